@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS outbox_messages_updated_at_trigger ON outbox_messages;
+DROP TABLE IF EXISTS outbox_messages;
@@ -0,0 +1,31 @@
+-- 予約に関するメール送信を確実に行うための outbox テーブル
+-- 予約の作成・終了と同じトランザクションで書き込み、
+-- バックグラウンドの dispatcher が送信・再試行する
+CREATE TABLE IF NOT EXISTS outbox_messages (
+    outbox_message_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(32) NOT NULL,
+    status VARCHAR(32) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    -- 送信時点で予約が削除されていても本文を組み立てられるよう、必要な値を複製して持つ
+    reservation_id UUID NOT NULL,
+    space_id UUID NOT NULL,
+    space_name VARCHAR(255) NOT NULL,
+    user_name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    reminder_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    reservation_start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    reservation_end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    sent_at TIMESTAMP(3) WITH TIME ZONE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE INDEX IF NOT EXISTS outbox_messages_pending_idx
+    ON outbox_messages (next_attempt_at)
+    WHERE status = 'pending';
+
+CREATE TRIGGER outbox_messages_updated_at_trigger
+    BEFORE UPDATE ON outbox_messages FOR EACH ROW
+    EXECUTE PROCEDURE set_updated_at();
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS reservations_due_reminder_idx;
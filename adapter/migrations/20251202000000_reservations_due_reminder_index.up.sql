@@ -0,0 +1,5 @@
+-- リマインダー送信の対象となる予約を、全件走査せずに取得するためのインデックス
+-- 送信済みの予約は対象外とし、インデックスを小さく保つ
+CREATE INDEX IF NOT EXISTS reservations_due_reminder_idx
+    ON reservations (reminder_at)
+    WHERE NOT reminder_is_already;
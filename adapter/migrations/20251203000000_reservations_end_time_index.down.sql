@@ -0,0 +1 @@
+DROP INDEX IF EXISTS reservations_end_time_idx;
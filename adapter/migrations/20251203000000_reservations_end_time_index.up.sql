@@ -0,0 +1,3 @@
+-- 終了時刻を過ぎた予約を全件走査せずに取得するためのインデックス
+CREATE INDEX IF NOT EXISTS reservations_end_time_idx
+    ON reservations (reservation_end_time);
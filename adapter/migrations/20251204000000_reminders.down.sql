@@ -0,0 +1,45 @@
+ALTER TABLE reservations
+    ADD COLUMN reminder_is_already BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN reminder_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3);
+
+ALTER TABLE returned_reservations
+    ADD COLUMN reminder_is_already BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN reminder_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3);
+
+-- 複数あるリマインダーのうち、最も早いものを元の列に戻す
+UPDATE reservations AS r
+SET
+    reminder_at = rm.remind_at,
+    reminder_is_already = rm.is_sent
+FROM (
+    SELECT DISTINCT ON (reservation_id) reservation_id, remind_at, is_sent
+    FROM reminders
+    ORDER BY reservation_id, remind_at ASC
+) AS rm
+WHERE r.reservation_id = rm.reservation_id;
+
+UPDATE returned_reservations AS r
+SET
+    reminder_at = rm.remind_at,
+    reminder_is_already = rm.is_sent
+FROM (
+    SELECT DISTINCT ON (reservation_id) reservation_id, remind_at, is_sent
+    FROM reminders
+    ORDER BY reservation_id, remind_at ASC
+) AS rm
+WHERE r.reservation_id = rm.reservation_id;
+
+CREATE INDEX IF NOT EXISTS reservations_due_reminder_idx
+    ON reservations (reminder_at)
+    WHERE NOT reminder_is_already;
+
+DELETE FROM outbox_messages WHERE reminder_at IS NULL AND kind = 'reminder';
+UPDATE outbox_messages SET reminder_at = reservation_start_time WHERE reminder_at IS NULL;
+ALTER TABLE outbox_messages
+    ALTER COLUMN reminder_at SET NOT NULL;
+
+DROP TRIGGER IF EXISTS reminders_updated_at_trigger ON reminders;
+DROP TABLE IF EXISTS reminders;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS reminder_lead_minutes;
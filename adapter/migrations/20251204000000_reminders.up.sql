@@ -0,0 +1,61 @@
+-- 予約ごとに複数のリマインダーを持てるようにする
+-- 利用者ごとの既定のリマインダー（予約開始の何分前に送るか）をユーザーに持たせる
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS reminder_lead_minutes INT[] NOT NULL DEFAULT '{60}';
+
+-- 予約は終了時に returned_reservations へ移るため、reservation_id に外部キーは張らない
+CREATE TABLE IF NOT EXISTS reminders (
+    reminder_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reservation_id UUID NOT NULL,
+    lead_minutes INT NOT NULL,
+    remind_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    is_sent BOOLEAN NOT NULL DEFAULT FALSE,
+    sent_at TIMESTAMP(3) WITH TIME ZONE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    UNIQUE (reservation_id, lead_minutes)
+);
+
+CREATE INDEX IF NOT EXISTS reminders_due_idx
+    ON reminders (remind_at)
+    WHERE NOT is_sent;
+
+CREATE INDEX IF NOT EXISTS reminders_reservation_id_idx
+    ON reminders (reservation_id);
+
+CREATE TRIGGER reminders_updated_at_trigger
+    BEFORE UPDATE ON reminders FOR EACH ROW
+    EXECUTE PROCEDURE set_updated_at();
+
+-- 既存の予約のリマインダーを移す
+-- 予約終了済みのものは、送信済みのリマインダーのみ履歴として残す
+INSERT INTO reminders (reservation_id, lead_minutes, remind_at, is_sent)
+SELECT
+    reservation_id,
+    GREATEST(EXTRACT(EPOCH FROM (reservation_start_time - reminder_at)) / 60, 0)::INT,
+    reminder_at,
+    reminder_is_already
+FROM reservations;
+
+INSERT INTO reminders (reservation_id, lead_minutes, remind_at, is_sent)
+SELECT
+    reservation_id,
+    GREATEST(EXTRACT(EPOCH FROM (reservation_start_time - reminder_at)) / 60, 0)::INT,
+    reminder_at,
+    TRUE
+FROM returned_reservations
+WHERE reminder_is_already;
+
+DROP INDEX IF EXISTS reservations_due_reminder_idx;
+
+ALTER TABLE reservations
+    DROP COLUMN reminder_at,
+    DROP COLUMN reminder_is_already;
+
+ALTER TABLE returned_reservations
+    DROP COLUMN reminder_at,
+    DROP COLUMN reminder_is_already;
+
+-- リマインダー以外の通知ではリマインダーの送信時刻を持たない
+ALTER TABLE outbox_messages
+    ALTER COLUMN reminder_at DROP NOT NULL;
@@ -0,0 +1,18 @@
+-- 予約時間の変更履歴
+-- 予約は終了時に returned_reservations へ移るため、reservation_id に外部キーは張らない
+CREATE TABLE IF NOT EXISTS reservation_changes (
+    reservation_change_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reservation_id UUID NOT NULL,
+    changed_by UUID NOT NULL,
+    previous_start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    previous_end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    new_start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    new_end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    changed_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    FOREIGN KEY (changed_by) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS reservation_changes_reservation_id_idx
+    ON reservation_changes (reservation_id);
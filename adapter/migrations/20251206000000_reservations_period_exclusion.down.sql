@@ -0,0 +1,2 @@
+ALTER TABLE reservations DROP CONSTRAINT IF EXISTS reservations_space_period_excl;
+ALTER TABLE reservations DROP COLUMN IF EXISTS period;
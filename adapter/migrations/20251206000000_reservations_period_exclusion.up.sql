@@ -0,0 +1,14 @@
+-- 同じスペースの予約時間帯が重ならないことをデータベースで保証する
+-- アプリケーション側の重複チェックをすり抜けた同時実行の予約も、この制約で弾かれる
+-- 既存のデータに重なる予約がある場合は、制約の追加に失敗するため先に解消しておくこと
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- 予約時間帯を開始を含み終了を含まない範囲として持つ
+-- 終了時刻と次の予約の開始時刻が同じ場合は重なりとみなさない
+ALTER TABLE reservations
+    ADD COLUMN IF NOT EXISTS period TSTZRANGE
+    GENERATED ALWAYS AS (tstzrange(reservation_start_time, reservation_end_time, '[)')) STORED;
+
+ALTER TABLE reservations
+    ADD CONSTRAINT reservations_space_period_excl
+    EXCLUDE USING gist (space_id WITH =, period WITH &&);
@@ -0,0 +1,9 @@
+ALTER TABLE reservations DROP CONSTRAINT IF EXISTS reservations_space_period_excl;
+ALTER TABLE reservations
+    ADD CONSTRAINT reservations_space_period_excl
+    EXCLUDE USING gist (space_id WITH =, period WITH &&);
+
+DROP INDEX IF EXISTS reservations_series_idx;
+ALTER TABLE returned_reservations DROP COLUMN IF EXISTS reservation_series_id;
+ALTER TABLE reservations DROP COLUMN IF EXISTS reservation_series_id;
+DROP TABLE IF EXISTS reservation_series;
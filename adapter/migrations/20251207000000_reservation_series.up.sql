@@ -0,0 +1,41 @@
+-- 繰り返し予約
+-- 各回の予約は reservations に 1 件ずつ作成し、reservation_series_id で紐づける
+CREATE TABLE IF NOT EXISTS reservation_series (
+    reservation_series_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    space_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    frequency VARCHAR(16) NOT NULL,
+    repeat_interval INT NOT NULL,
+    -- 曜日は ISO 8601 の番号（月曜日 = 1 〜 日曜日 = 7）で持つ
+    by_weekday INT[] NOT NULL DEFAULT '{}',
+    occurrence_count INT,
+    until TIMESTAMP(3) WITH TIME ZONE,
+    reservation_start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    reservation_end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+ALTER TABLE reservations
+    ADD COLUMN IF NOT EXISTS reservation_series_id UUID
+    REFERENCES reservation_series(reservation_series_id) ON DELETE SET NULL;
+-- 予約終了済みの予約からも、どの繰り返し予約の回だったか分かるようにする
+ALTER TABLE returned_reservations
+    ADD COLUMN IF NOT EXISTS reservation_series_id UUID;
+
+CREATE INDEX IF NOT EXISTS reservations_series_idx
+    ON reservations (reservation_series_id, reservation_start_time)
+    WHERE reservation_series_id IS NOT NULL;
+
+-- 繰り返し予約の複数の回をまとめてずらす際に、更新途中で一時的に回どうしが重なっても
+-- コミット時点で重なっていなければよいよう、制約の確認を遅延できるようにしておく
+ALTER TABLE reservations DROP CONSTRAINT IF EXISTS reservations_space_period_excl;
+ALTER TABLE reservations
+    ADD CONSTRAINT reservations_space_period_excl
+    EXCLUDE USING gist (space_id WITH =, period WITH &&)
+    DEFERRABLE INITIALLY IMMEDIATE;
@@ -0,0 +1,4 @@
+DROP FUNCTION IF EXISTS space_is_open(UUID, TIMESTAMPTZ, TIMESTAMPTZ);
+DROP TABLE IF EXISTS space_blackouts;
+DROP TABLE IF EXISTS space_opening_hours;
+ALTER TABLE spaces DROP COLUMN IF EXISTS timezone;
@@ -0,0 +1,62 @@
+-- スペースごとのタイムゾーン。営業時間はこのタイムゾーンの時刻として解釈する
+ALTER TABLE spaces
+    ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Tokyo';
+
+-- 曜日ごとの営業時間
+-- 1 つの曜日に複数の時間帯を持てる（昼休みを挟む場合など）
+-- スペースに営業時間が 1 件もない場合は、終日予約できるものとする
+CREATE TABLE IF NOT EXISTS space_opening_hours (
+    space_id UUID NOT NULL,
+    -- ISO 8601 の番号（月曜日 = 1 〜 日曜日 = 7）
+    weekday INT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+    -- 0 時からの経過分。close_minutes は 24:00 を表す 1440 まで指定できる
+    open_minutes INT NOT NULL,
+    close_minutes INT NOT NULL,
+    PRIMARY KEY (space_id, weekday, open_minutes),
+    CHECK (0 <= open_minutes AND open_minutes < close_minutes AND close_minutes <= 1440),
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+-- 清掃やメンテナンスなどで予約を受け付けない期間
+CREATE TABLE IF NOT EXISTS space_blackouts (
+    space_blackout_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    space_id UUID NOT NULL,
+    start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    created_by UUID NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    CHECK (start_time < end_time),
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS space_blackouts_period_idx
+    ON space_blackouts USING gist (space_id, tstzrange(start_time, end_time, '[)'));
+
+-- 指定の時間帯がスペースの営業時間内に収まっているかを返す
+-- 営業時間は開始時刻の日付（スペースのタイムゾーン）の 1 つの時間帯に収まっている必要がある
+CREATE OR REPLACE FUNCTION space_is_open(
+    p_space_id UUID,
+    p_start_time TIMESTAMPTZ,
+    p_end_time TIMESTAMPTZ
+) RETURNS BOOLEAN AS $$
+    SELECT NOT EXISTS (SELECT 1 FROM space_opening_hours WHERE space_id = p_space_id)
+        OR EXISTS (
+            SELECT 1
+            FROM spaces AS s
+            INNER JOIN space_opening_hours AS h ON h.space_id = s.space_id
+            WHERE s.space_id = p_space_id
+              AND h.weekday = EXTRACT(ISODOW FROM p_start_time AT TIME ZONE s.timezone)
+              AND p_start_time >= ((p_start_time AT TIME ZONE s.timezone)::date
+                  + make_interval(mins => h.open_minutes)) AT TIME ZONE s.timezone
+              AND p_end_time <= ((p_start_time AT TIME ZONE s.timezone)::date
+                  + make_interval(mins => h.close_minutes)) AT TIME ZONE s.timezone
+        );
+$$ LANGUAGE sql STABLE;
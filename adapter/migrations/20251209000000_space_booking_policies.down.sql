@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS space_booking_policies_space_role_idx;
+DROP INDEX IF EXISTS space_booking_policies_space_idx;
+DROP TABLE IF EXISTS space_booking_policies;
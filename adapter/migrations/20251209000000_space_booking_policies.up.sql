@@ -0,0 +1,26 @@
+-- スペースごとの予約のルール
+-- role_id が NULL の行はスペースのルール、それ以外はそのロールのユーザーに使うルール
+-- 値が NULL の項目は制限しない
+CREATE TABLE IF NOT EXISTS space_booking_policies (
+    space_booking_policy_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    space_id UUID NOT NULL,
+    role_id UUID,
+    min_duration_minutes INT CHECK (min_duration_minutes > 0),
+    max_duration_minutes INT CHECK (max_duration_minutes > 0),
+    slot_granularity_minutes INT CHECK (slot_granularity_minutes > 0),
+    max_days_in_advance INT CHECK (max_days_in_advance > 0),
+    max_active_reservations INT CHECK (max_active_reservations > 0),
+    max_reservations_per_week INT CHECK (max_reservations_per_week > 0),
+    CHECK (min_duration_minutes <= max_duration_minutes),
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE,
+    FOREIGN KEY (role_id) REFERENCES roles(role_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS space_booking_policies_space_idx
+    ON space_booking_policies (space_id) WHERE role_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS space_booking_policies_space_role_idx
+    ON space_booking_policies (space_id, role_id) WHERE role_id IS NOT NULL;
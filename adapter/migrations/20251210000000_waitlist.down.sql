@@ -0,0 +1,11 @@
+DELETE FROM outbox_messages WHERE reservation_id IS NULL;
+ALTER TABLE outbox_messages
+    DROP COLUMN IF EXISTS claim_expires_at,
+    DROP COLUMN IF EXISTS claim_token,
+    ALTER COLUMN reservation_id SET NOT NULL;
+
+DROP TRIGGER IF EXISTS waitlist_entries_updated_at_trigger ON waitlist_entries;
+DROP INDEX IF EXISTS waitlist_entries_offer_expires_at_idx;
+DROP INDEX IF EXISTS waitlist_entries_waiting_idx;
+DROP INDEX IF EXISTS waitlist_entries_active_idx;
+DROP TABLE IF EXISTS waitlist_entries;
@@ -0,0 +1,49 @@
+-- 予約の入っている時間帯のキャンセル待ち
+-- 予約は終了時に returned_reservations へ移るため、reservation_id に外部キーは張らない
+CREATE TABLE IF NOT EXISTS waitlist_entries (
+    waitlist_entry_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    space_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    reservation_start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    reservation_end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+    -- 空きが出たときの扱い（offer: 確定用のリンクを送る, auto_book: そのまま予約する）
+    fulfillment VARCHAR(16) NOT NULL,
+    -- waiting, offered, booked, expired, cancelled
+    status VARCHAR(16) NOT NULL DEFAULT 'waiting',
+    claim_token VARCHAR(64) UNIQUE,
+    offer_expires_at TIMESTAMP(3) WITH TIME ZONE,
+    reservation_id UUID,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    CHECK (reservation_start_time < reservation_end_time),
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+-- 同じユーザーが同じ時間帯に重複して並ばないようにする
+CREATE UNIQUE INDEX IF NOT EXISTS waitlist_entries_active_idx
+    ON waitlist_entries (space_id, user_id, reservation_start_time, reservation_end_time)
+    WHERE status IN ('waiting', 'offered');
+
+CREATE INDEX IF NOT EXISTS waitlist_entries_waiting_idx
+    ON waitlist_entries (space_id, created_at)
+    WHERE status = 'waiting';
+
+CREATE INDEX IF NOT EXISTS waitlist_entries_offer_expires_at_idx
+    ON waitlist_entries (offer_expires_at)
+    WHERE status = 'offered';
+
+CREATE TRIGGER waitlist_entries_updated_at_trigger
+    BEFORE UPDATE ON waitlist_entries FOR EACH ROW
+    EXECUTE PROCEDURE set_updated_at();
+
+-- キャンセル待ちの案内は予約がない状態で送るため、reservation_id を NULL にできるようにし、
+-- 確定用のトークンとその期限を持たせる
+ALTER TABLE outbox_messages
+    ALTER COLUMN reservation_id DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS claim_token VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS claim_expires_at TIMESTAMP(3) WITH TIME ZONE;
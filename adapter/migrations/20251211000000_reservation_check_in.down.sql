@@ -0,0 +1,15 @@
+ALTER TABLE space_booking_policies
+    DROP COLUMN IF EXISTS max_recent_no_shows;
+
+DROP INDEX IF EXISTS returned_reservations_no_show_idx;
+
+ALTER TABLE returned_reservations
+    DROP COLUMN IF EXISTS is_no_show,
+    DROP COLUMN IF EXISTS checked_in_at;
+
+ALTER TABLE reservations
+    DROP COLUMN IF EXISTS checked_in_at;
+
+ALTER TABLE spaces
+    DROP COLUMN IF EXISTS check_in_code,
+    DROP COLUMN IF EXISTS check_in_grace_minutes;
@@ -0,0 +1,22 @@
+-- 予約の利用開始時のチェックイン
+-- check_in_grace_minutes が NULL のスペースはチェックインを必須としない
+-- check_in_code が NULL のスペースはコードなしでチェックインできる
+ALTER TABLE spaces
+    ADD COLUMN IF NOT EXISTS check_in_grace_minutes INT CHECK (check_in_grace_minutes > 0),
+    ADD COLUMN IF NOT EXISTS check_in_code VARCHAR(32);
+
+ALTER TABLE reservations
+    ADD COLUMN IF NOT EXISTS checked_in_at TIMESTAMP(3) WITH TIME ZONE;
+
+-- チェックインがないまま猶予時間を過ぎて解放された予約は is_no_show を TRUE にする
+ALTER TABLE returned_reservations
+    ADD COLUMN IF NOT EXISTS checked_in_at TIMESTAMP(3) WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS is_no_show BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS returned_reservations_no_show_idx
+    ON returned_reservations (user_id, reservation_start_time)
+    WHERE is_no_show;
+
+-- 直近のチェックインしなかった回数が上限に達したユーザーの予約を制限する
+ALTER TABLE space_booking_policies
+    ADD COLUMN IF NOT EXISTS max_recent_no_shows INT CHECK (max_recent_no_shows > 0);
@@ -0,0 +1,59 @@
+CREATE TABLE IF NOT EXISTS returned_reservations (
+    reservation_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    space_id UUID NOT NULL,
+    is_cancel BOOLEAN NOT NULL,
+    reserved_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    returned_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    reservation_start_time TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    reservation_end_time TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    reservation_series_id UUID,
+    checked_in_at TIMESTAMP(3) WITH TIME ZONE,
+    is_no_show BOOLEAN NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (user_id) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE,
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS returned_reservations_no_show_idx
+    ON returned_reservations (user_id, reservation_start_time)
+    WHERE is_no_show;
+
+INSERT INTO returned_reservations
+(reservation_id, user_id, space_id, is_cancel, reserved_at, returned_at,
+reservation_start_time, reservation_end_time, reservation_series_id, checked_in_at, is_no_show)
+SELECT
+    reservation_id, user_id, space_id,
+    status IN ('cancelled_by_user', 'cancelled_by_admin', 'cancelled'),
+    reserved_at,
+    COALESCE(completed_at, cancelled_at, no_show_at, reservation_end_time),
+    reservation_start_time, reservation_end_time, reservation_series_id, checked_in_at,
+    status = 'no_show'
+FROM reservations
+WHERE status NOT IN ('pending', 'confirmed', 'checked_in');
+
+DELETE FROM reservations
+WHERE status NOT IN ('pending', 'confirmed', 'checked_in');
+
+DROP INDEX IF EXISTS reservations_no_show_idx;
+DROP INDEX IF EXISTS reservations_status_idx;
+
+DROP INDEX IF EXISTS reservations_end_time_idx;
+CREATE INDEX IF NOT EXISTS reservations_end_time_idx
+    ON reservations (reservation_end_time);
+
+ALTER TABLE reservations DROP CONSTRAINT IF EXISTS reservations_space_period_excl;
+ALTER TABLE reservations
+    ADD CONSTRAINT reservations_space_period_excl
+    EXCLUDE USING gist (space_id WITH =, period WITH &&)
+    DEFERRABLE INITIALLY IMMEDIATE;
+
+ALTER TABLE reservations
+    DROP COLUMN IF EXISTS no_show_at,
+    DROP COLUMN IF EXISTS cancelled_at,
+    DROP COLUMN IF EXISTS completed_at,
+    DROP COLUMN IF EXISTS confirmed_at,
+    DROP COLUMN IF EXISTS status;
@@ -0,0 +1,68 @@
+-- 予約の状態を reservations と returned_reservations の 2 つのテーブルと is_cancel で表すのをやめ、
+-- reservations の status で表す。状態が変わった日時は、状態ごとの列に記録する
+--   pending            : 承認待ち
+--   confirmed          : 予約確定
+--   checked_in         : チェックイン済み
+--   completed          : 利用終了
+--   cancelled_by_user  : 予約したユーザーによるキャンセル
+--   cancelled_by_admin : スペースの停止など、管理する側によるキャンセル
+--   cancelled          : これまでの記録から移したキャンセル。誰がキャンセルしたかは記録されていない
+--   no_show            : チェックインがないまま猶予時間を過ぎたため解放
+ALTER TABLE reservations
+    ADD COLUMN IF NOT EXISTS status VARCHAR(32) NOT NULL DEFAULT 'confirmed'
+        CHECK (status IN ('pending', 'confirmed', 'checked_in', 'completed',
+                          'cancelled_by_user', 'cancelled_by_admin', 'cancelled', 'no_show')),
+    ADD COLUMN IF NOT EXISTS confirmed_at TIMESTAMP(3) WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS completed_at TIMESTAMP(3) WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMP(3) WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS no_show_at TIMESTAMP(3) WITH TIME ZONE;
+
+UPDATE reservations
+SET status = CASE WHEN checked_in_at IS NULL THEN 'confirmed' ELSE 'checked_in' END,
+    confirmed_at = reserved_at;
+
+-- 時間帯の重なりは、まだ終わっていない予約の間でのみ禁止する
+-- 終わった予約は互いに、また終わっていない予約とも重なりうるため、移す前に条件を付け直す
+ALTER TABLE reservations DROP CONSTRAINT IF EXISTS reservations_space_period_excl;
+ALTER TABLE reservations
+    ADD CONSTRAINT reservations_space_period_excl
+    EXCLUDE USING gist (space_id WITH =, period WITH &&)
+    WHERE (status IN ('pending', 'confirmed', 'checked_in'))
+    DEFERRABLE INITIALLY IMMEDIATE;
+
+-- 予約終了済みの予約を移す
+-- これまでの記録には誰が終わらせたかが残っていないため、
+-- 予約開始前にキャンセルしたものは操作した人を決めないキャンセル、
+-- 予約開始後に終了したものは利用終了として扱う
+INSERT INTO reservations
+(reservation_id, space_id, user_id, reserved_at, reservation_start_time, reservation_end_time,
+reservation_series_id, checked_in_at, status, confirmed_at, completed_at, cancelled_at, no_show_at)
+SELECT
+    reservation_id, space_id, user_id, reserved_at, reservation_start_time, reservation_end_time,
+    reservation_series_id, checked_in_at,
+    CASE
+        WHEN is_no_show THEN 'no_show'
+        WHEN is_cancel AND returned_at < reservation_start_time THEN 'cancelled'
+        ELSE 'completed'
+    END,
+    reserved_at,
+    CASE WHEN NOT is_no_show AND NOT (is_cancel AND returned_at < reservation_start_time)
+        THEN returned_at END,
+    CASE WHEN NOT is_no_show AND is_cancel AND returned_at < reservation_start_time
+        THEN returned_at END,
+    CASE WHEN is_no_show THEN returned_at END
+FROM returned_reservations;
+
+DROP INDEX IF EXISTS reservations_end_time_idx;
+CREATE INDEX IF NOT EXISTS reservations_end_time_idx
+    ON reservations (reservation_end_time)
+    WHERE status IN ('pending', 'confirmed', 'checked_in');
+
+CREATE INDEX IF NOT EXISTS reservations_status_idx
+    ON reservations (status, reservation_start_time);
+
+CREATE INDEX IF NOT EXISTS reservations_no_show_idx
+    ON reservations (user_id, reservation_start_time)
+    WHERE status = 'no_show';
+
+DROP TABLE IF EXISTS returned_reservations;
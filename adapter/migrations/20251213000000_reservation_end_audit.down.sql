@@ -0,0 +1,8 @@
+ALTER TABLE outbox_messages
+    DROP COLUMN IF EXISTS end_reason,
+    DROP COLUMN IF EXISTS end_source;
+
+ALTER TABLE reservations
+    DROP COLUMN IF EXISTS end_reason,
+    DROP COLUMN IF EXISTS end_source,
+    DROP COLUMN IF EXISTS ended_by;
@@ -0,0 +1,23 @@
+-- 予約を終わらせた操作を記録する
+--   ended_by   : 操作したユーザー。バックグラウンドの処理で終わった場合は NULL
+--   end_source : 操作の出どころ
+--     user               : 予約したユーザー本人
+--     admin              : 予約したユーザー以外（管理者）
+--     space_deactivation : スペースの停止
+--     watcher            : バックグラウンドの処理（利用終了・チェックインしなかった予約の解放など）
+--   end_reason : 操作したユーザーが入力した理由
+ALTER TABLE reservations
+    ADD COLUMN IF NOT EXISTS ended_by UUID
+        REFERENCES users(user_id) ON UPDATE CASCADE ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS end_source VARCHAR(32)
+        CHECK (end_source IN ('user', 'admin', 'space_deactivation', 'watcher')),
+    ADD COLUMN IF NOT EXISTS end_reason TEXT;
+
+-- これまでの記録からは誰が終わらせたかを判別できないため、
+-- バックグラウンドの処理でしか起こらないチェックインしなかった予約の解放のみ埋めておく
+UPDATE reservations SET end_source = 'watcher' WHERE status = 'no_show';
+
+-- 通知の本文に含めるため、outbox にも複製する
+ALTER TABLE outbox_messages
+    ADD COLUMN IF NOT EXISTS end_source VARCHAR(32),
+    ADD COLUMN IF NOT EXISTS end_reason TEXT;
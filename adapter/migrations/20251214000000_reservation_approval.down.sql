@@ -0,0 +1,19 @@
+-- 却下・期限切れになった予約は、管理する側によるキャンセルとして残す
+UPDATE reservations
+SET status = 'cancelled_by_admin',
+    cancelled_at = COALESCE(rejected_at, expired_at)
+WHERE status IN ('rejected', 'expired');
+
+ALTER TABLE reservations
+    DROP COLUMN IF EXISTS expired_at,
+    DROP COLUMN IF EXISTS rejected_at,
+    DROP COLUMN IF EXISTS reviewed_by,
+    DROP CONSTRAINT IF EXISTS reservations_status_check,
+    ADD CONSTRAINT reservations_status_check
+        CHECK (status IN ('pending', 'confirmed', 'checked_in', 'completed',
+                          'cancelled_by_user', 'cancelled_by_admin', 'cancelled', 'no_show'));
+
+DROP TABLE IF EXISTS space_approvers;
+
+ALTER TABLE spaces
+    DROP COLUMN IF EXISTS requires_approval;
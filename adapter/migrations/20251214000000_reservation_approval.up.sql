@@ -0,0 +1,33 @@
+-- 承認が必要なスペースの予約は承認待ち（pending）として作成し、承認者が承認すると確定する
+--   requires_approval : 予約に承認が必要か
+--   space_approvers   : 予約を承認・却下できるユーザー
+ALTER TABLE spaces
+    ADD COLUMN IF NOT EXISTS requires_approval BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS space_approvers (
+    space_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    PRIMARY KEY (space_id, user_id),
+    FOREIGN KEY (space_id) REFERENCES spaces(space_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+-- 承認待ちの予約が終わる状態として、次の 2 つを加える
+--   rejected : 承認者による却下
+--   expired  : 予約開始時刻までに承認・却下されなかったため期限切れ
+-- reviewed_by には、承認・却下したユーザーを記録する
+ALTER TABLE reservations
+    DROP CONSTRAINT IF EXISTS reservations_status_check,
+    ADD CONSTRAINT reservations_status_check
+        CHECK (status IN ('pending', 'confirmed', 'checked_in', 'completed',
+                          'cancelled_by_user', 'cancelled_by_admin', 'cancelled', 'no_show',
+                          'rejected', 'expired')),
+    ADD COLUMN IF NOT EXISTS reviewed_by UUID
+        REFERENCES users(user_id) ON UPDATE CASCADE ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS rejected_at TIMESTAMP(3) WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS expired_at TIMESTAMP(3) WITH TIME ZONE;
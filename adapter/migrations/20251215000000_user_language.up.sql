@@ -0,0 +1,8 @@
+-- 通知メールに使う言語をユーザーごとに持つ
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS language VARCHAR(8) NOT NULL DEFAULT 'ja'
+        CHECK (language IN ('ja', 'en'));
+
+-- 送信時に宛先のユーザーの言語が変わっていても、積んだ時点の言語で送る
+ALTER TABLE outbox_messages
+    ADD COLUMN IF NOT EXISTS language VARCHAR(8) NOT NULL DEFAULT 'ja';
@@ -0,0 +1,5 @@
+ALTER TABLE outbox_messages
+    DROP COLUMN IF EXISTS timezone,
+    DROP COLUMN IF EXISTS space_equipment,
+    DROP COLUMN IF EXISTS space_address;
+ALTER TABLE users DROP COLUMN IF EXISTS timezone;
@@ -0,0 +1,9 @@
+-- 通知メールの日時を表示するタイムゾーンをユーザーごとに持つ
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Tokyo';
+
+-- メールの本文に載せるスペースの所在地と設備、宛先のユーザーのタイムゾーンも、積んだ時点の値で送る
+ALTER TABLE outbox_messages
+    ADD COLUMN IF NOT EXISTS space_address VARCHAR(255) NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS space_equipment VARCHAR(255) NOT NULL DEFAULT '',
+    ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Tokyo';
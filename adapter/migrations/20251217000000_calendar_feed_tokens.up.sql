@@ -0,0 +1,11 @@
+-- カレンダーアプリから予定表（iCalendar）を購読するための、ユーザーごとのトークン
+-- 購読する URL に含めて使うため、Authorization ヘッダがなくても予定表を取得できる
+-- 再発行すると以前のトークンは使えなくなる
+CREATE TABLE IF NOT EXISTS calendar_feed_tokens (
+    user_id UUID PRIMARY KEY,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    FOREIGN KEY (user_id) REFERENCES users(user_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
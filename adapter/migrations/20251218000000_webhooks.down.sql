@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS webhook_deliveries_updated_at_trigger ON webhook_deliveries;
+DROP INDEX IF EXISTS webhook_deliveries_webhook_id_idx;
+DROP INDEX IF EXISTS webhook_deliveries_pending_idx;
+DROP TABLE IF EXISTS webhook_deliveries;
+DROP TRIGGER IF EXISTS webhooks_updated_at_trigger ON webhooks;
+DROP TABLE IF EXISTS webhooks;
@@ -0,0 +1,51 @@
+-- 予約・スペースの状態の変化を外部のツール（ドアロックの制御、サイネージなど）に知らせる webhook の送信先
+CREATE TABLE IF NOT EXISTS webhooks (
+    webhook_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    url VARCHAR(2048) NOT NULL,
+    -- ペイロードの署名（HMAC-SHA256）に使う鍵。登録時に生成する
+    secret VARCHAR(64) NOT NULL,
+    -- 送る出来事（reservation.created など）の一覧
+    events VARCHAR(32)[] NOT NULL,
+    description VARCHAR(255) NOT NULL DEFAULT '',
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE TRIGGER webhooks_updated_at_trigger
+    BEFORE UPDATE ON webhooks FOR EACH ROW
+    EXECUTE PROCEDURE set_updated_at();
+
+-- webhook の送信と、その結果の記録
+-- 予約の変更と同じトランザクションで書き込み、バックグラウンドの dispatcher が送信・再試行する
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    webhook_delivery_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    webhook_id UUID NOT NULL,
+    event VARCHAR(32) NOT NULL,
+    -- 送信時点で予約が削除されていても送れるよう、出来事が起きた時点の値を複製して持つ
+    payload JSONB NOT NULL,
+    -- pending, delivered, dead
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    -- 最後に受け取ったレスポンスの HTTP ステータス
+    response_status INT,
+    next_attempt_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    delivered_at TIMESTAMP(3) WITH TIME ZONE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(webhook_id)
+      ON UPDATE CASCADE
+      ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_pending_idx
+    ON webhook_deliveries (next_attempt_at)
+    WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_webhook_id_idx
+    ON webhook_deliveries (webhook_id, created_at);
+
+CREATE TRIGGER webhook_deliveries_updated_at_trigger
+    BEFORE UPDATE ON webhook_deliveries FOR EACH ROW
+    EXECUTE PROCEDURE set_updated_at();
@@ -0,0 +1,11 @@
+-- 所有者によるキャンセルは、管理する側によるキャンセルとして残す
+UPDATE reservations
+SET status = 'cancelled_by_admin'
+WHERE status = 'cancelled_by_owner';
+
+ALTER TABLE reservations
+    DROP CONSTRAINT IF EXISTS reservations_status_check,
+    ADD CONSTRAINT reservations_status_check
+        CHECK (status IN ('pending', 'confirmed', 'checked_in', 'completed',
+                          'cancelled_by_user', 'cancelled_by_admin', 'cancelled', 'no_show',
+                          'rejected', 'expired'));
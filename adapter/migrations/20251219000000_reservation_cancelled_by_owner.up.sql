@@ -0,0 +1,8 @@
+-- スペースの所有者がスペースを停止したことによるキャンセルを、管理者によるキャンセルと分けて記録する
+--   cancelled_by_owner : スペースの所有者によるキャンセル
+ALTER TABLE reservations
+    DROP CONSTRAINT IF EXISTS reservations_status_check,
+    ADD CONSTRAINT reservations_status_check
+        CHECK (status IN ('pending', 'confirmed', 'checked_in', 'completed',
+                          'cancelled_by_user', 'cancelled_by_admin', 'cancelled_by_owner',
+                          'cancelled', 'no_show', 'rejected', 'expired'));
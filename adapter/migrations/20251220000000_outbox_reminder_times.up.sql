@@ -0,0 +1,3 @@
+-- 予約の通知に添付する予定のアラームとして、積んだ時点の予約のリマインダーの送信時刻を持つ
+ALTER TABLE outbox_messages
+    ADD COLUMN IF NOT EXISTS reminder_times TIMESTAMP(3) WITH TIME ZONE[] NOT NULL DEFAULT '{}';
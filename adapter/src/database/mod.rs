@@ -3,10 +3,7 @@ use shared::{
     error::{AppError, AppResult}
 };
 use sqlx::{postgres::PgConnectOptions, PgPool};
-use kernel::model::id::{ReservationId};
-use uuid::Uuid;
 pub mod model;
-use sqlx::postgres::PgQueryResult;
 
 
 fn make_pg_connect_options(cfg: &DatabaseConfig) -> PgConnectOptions {
@@ -32,25 +29,32 @@ impl ConnectionPool {
     pub async fn begin(&self) -> AppResult<sqlx::Transaction<'_, sqlx::Postgres>> {
         self.0.begin().await.map_err(AppError::TransactionError)
     }
+}
+
+pub fn connect_database_with(cfg: &DatabaseConfig) -> ConnectionPool {
+    ConnectionPool(PgPool::connect_lazy_with(make_pg_connect_options(cfg)))
+}
 
-    pub async fn mark_reminder_as_done(&self, reservation_id: Uuid) -> AppResult<PgQueryResult> {
-        let result = sqlx::query(
-            r#"
-            UPDATE reservations
-            SET reminder_is_already = TRUE
-            WHERE reservation_id = $1
-            "#,
-        )
-        .bind(reservation_id)
-        .execute(self.inner_ref())
-        .await
-        .map_err(AppError::DbQueryError)?;
-
-        Ok(result)
+// SERIALIZABLE のトランザクションが他のトランザクションと競合して失敗した
+const SQLSTATE_SERIALIZATION_FAILURE: &str = "40001";
+// 排他制約（EXCLUDE）に違反した
+const SQLSTATE_EXCLUSION_VIOLATION: &str = "23P01";
+
+fn sqlstate(e: &AppError) -> Option<std::borrow::Cow<'_, str>> {
+    match e {
+        AppError::SpecificOperationError(sqlx::Error::Database(db))
+        | AppError::TransactionError(sqlx::Error::Database(db))
+        | AppError::DbQueryError(sqlx::Error::Database(db)) => db.code(),
+        _ => None,
     }
+}
 
+// やり直せば成功する可能性のある、直列化の失敗かどうか
+pub(crate) fn is_serialization_failure(e: &AppError) -> bool {
+    sqlstate(e).is_some_and(|code| code == SQLSTATE_SERIALIZATION_FAILURE)
 }
 
-pub fn connect_database_with(cfg: &DatabaseConfig) -> ConnectionPool {
-    ConnectionPool(PgPool::connect_lazy_with(make_pg_connect_options(cfg)))
+// 排他制約の違反かどうか
+pub(crate) fn is_exclusion_violation(e: &AppError) -> bool {
+    sqlstate(e).is_some_and(|code| code == SQLSTATE_EXCLUSION_VIOLATION)
 }
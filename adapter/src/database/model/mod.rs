@@ -1,4 +1,8 @@
 pub mod space;
 pub mod auth;
 pub mod user;
-pub mod reservation;
\ No newline at end of file
+pub mod reservation;
+pub mod outbox;
+pub mod reminder;pub mod waitlist;
+
+pub mod webhook;
\ No newline at end of file
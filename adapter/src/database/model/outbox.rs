@@ -0,0 +1,115 @@
+use chrono::{DateTime, Local};
+use kernel::model::{
+    id::{OutboxMessageId, ReservationId, SpaceId},
+    notification::ReservationNotification,
+    outbox::OutboxMessage,
+};
+use shared::error::AppError;
+
+pub struct OutboxMessageRow {
+    pub outbox_message_id: OutboxMessageId,
+    pub kind: String,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+    pub sent_at: Option<DateTime<Local>>,
+    pub reservation_id: Option<ReservationId>,
+    pub space_id: SpaceId,
+    pub space_name: String,
+    pub space_address: String,
+    pub space_equipment: String,
+    pub user_name: String,
+    pub email: String,
+    pub reminder_at: Option<DateTime<Local>>,
+    pub reminder_times: Vec<DateTime<Local>>,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub claim_token: Option<String>,
+    pub claim_expires_at: Option<DateTime<Local>>,
+    pub end_source: Option<String>,
+    pub end_reason: Option<String>,
+    pub language: String,
+    pub timezone: String,
+}
+
+// kind と status は文字列で保存しているため、変換に失敗する可能性がある
+impl TryFrom<OutboxMessageRow> for OutboxMessage {
+    type Error = AppError;
+    fn try_from(value: OutboxMessageRow) -> Result<Self, Self::Error> {
+        let OutboxMessageRow {
+            outbox_message_id,
+            kind,
+            status,
+            attempts,
+            last_error,
+            next_attempt_at,
+            created_at,
+            sent_at,
+            reservation_id,
+            space_id,
+            space_name,
+            space_address,
+            space_equipment,
+            user_name,
+            email,
+            reminder_at,
+            reminder_times,
+            reservation_start_time,
+            reservation_end_time,
+            claim_token,
+            claim_expires_at,
+            end_source,
+            end_reason,
+            language,
+            timezone,
+        } = value;
+        Ok(OutboxMessage {
+            outbox_message_id,
+            kind: kind
+                .parse()
+                .map_err(|_| AppError::ConversionEntityError(format!("unknown kind: {kind}")))?,
+            status: status
+                .parse()
+                .map_err(|_| AppError::ConversionEntityError(format!("unknown status: {status}")))?,
+            attempts,
+            last_error,
+            next_attempt_at,
+            created_at,
+            sent_at,
+            notification: ReservationNotification {
+                reservation_id,
+                space_id,
+                space_name,
+                space_address,
+                space_equipment,
+                user_name,
+                email,
+                reminder_at,
+                reminder_times,
+                reservation_start_time,
+                reservation_end_time,
+                claim_token,
+                claim_expires_at,
+                end_source: end_source
+                    .map(|source| {
+                        source.parse().map_err(|_| {
+                            AppError::ConversionEntityError(format!("unknown end source: {source}"))
+                        })
+                    })
+                    .transpose()?,
+                end_reason,
+                language: language.parse().map_err(|_| {
+                    AppError::ConversionEntityError(format!("unknown language: {language}"))
+                })?,
+                timezone,
+            },
+        })
+    }
+}
+
+pub struct PaginatedOutboxMessageRow {
+    pub total: i64,
+    pub outbox_message_id: OutboxMessageId,
+}
@@ -0,0 +1,31 @@
+use kernel::model::{
+    id::{ReminderId, ReservationId},
+    reminder::Reminder,
+};
+use sqlx::types::chrono::{DateTime, Local};
+
+pub struct ReminderRow {
+    pub reminder_id: ReminderId,
+    pub reservation_id: ReservationId,
+    pub lead_minutes: i32,
+    pub remind_at: DateTime<Local>,
+    pub is_sent: bool,
+}
+
+impl From<ReminderRow> for Reminder {
+    fn from(value: ReminderRow) -> Self {
+        let ReminderRow {
+            reminder_id,
+            reservation_id: _,
+            lead_minutes,
+            remind_at,
+            is_sent,
+        } = value;
+        Reminder {
+            reminder_id,
+            lead_minutes,
+            remind_at,
+            is_sent,
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use kernel::model::{
-    reservation::{Reservation, ReservationSpace},
-    id::{SpaceId, ReservationId, UserId},
+    reminder::Reminder,
+    reservation::{
+        series::{Frequency, Recurrence, ReservationSeries},
+        status::{ReservationEnding, ReservationStatus},
+        Reservation, ReservationSpace,
+    },
+    id::{SpaceId, ReservationId, ReservationSeriesId, UserId},
 };
+use shared::error::{AppError, AppResult};
 use sqlx::types::chrono::{DateTime, Local};
 
 // 貸し出し状態を確認するための型
@@ -10,13 +16,34 @@ use sqlx::types::chrono::{DateTime, Local};
 // 蔵書が貸出中でない場合は reservation_id も user_id も None
 pub struct ReservationStateRow {
     pub space_id: SpaceId,
-    pub reminder_is_already:bool,
-    pub reminder_at:DateTime<Local>,
     pub reservation_id: ReservationId,
     pub user_id:UserId,
 }
 
-// 予約中の一覧を取得する際に使う型
+// 予約の変更・キャンセル時に行ロックを取った予約
+pub struct LockedReservationRow {
+    pub reservation_id: ReservationId,
+    pub user_id: UserId,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub reservation_series_id: Option<ReservationSeriesId>,
+    pub status: String,
+}
+
+impl LockedReservationRow {
+    pub fn status(&self) -> AppResult<ReservationStatus> {
+        ReservationStatus::try_from(self.status.as_str())
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+// 予約を検索する際に、条件に合う件数とページの範囲の予約 ID を取得するための型
+pub struct PaginatedReservationRow {
+    pub total: i64,
+    pub reservation_id: ReservationId,
+}
+
+// 予約の一覧を取得する際に使う型
 pub struct ReservationRow {
     pub reservation_id: ReservationId,
     pub space_id: SpaceId,
@@ -26,18 +53,30 @@ pub struct ReservationRow {
     pub reservation_start_time: DateTime<Local>,
     pub reservation_end_time: DateTime<Local>,
     pub reserved_at: DateTime<Local>,
-    pub reminder_at: DateTime<Local>,
-    pub reminder_is_already:bool,
     pub space_name: String,
     pub is_active: bool,
     pub capacity: i32,
     pub equipment: String,
     pub address: String,
+    pub reservation_series_id: Option<ReservationSeriesId>,
+    pub status: String,
+    pub confirmed_at: Option<DateTime<Local>>,
+    pub checked_in_at: Option<DateTime<Local>>,
+    pub completed_at: Option<DateTime<Local>>,
+    pub cancelled_at: Option<DateTime<Local>>,
+    pub no_show_at: Option<DateTime<Local>>,
+    pub rejected_at: Option<DateTime<Local>>,
+    pub expired_at: Option<DateTime<Local>>,
+    pub reviewed_by: Option<UserId>,
+    pub ended_by: Option<UserId>,
+    pub end_source: Option<String>,
+    pub end_reason: Option<String>,
 }
 
-
-impl From<ReservationRow> for Reservation {
-    fn from(value: ReservationRow) -> Self {
+// リマインダーは別のテーブルから取得するため、
+// From トレイトの実装の代わりに、引数をとる into_reservation メソッドを定義し実装する
+impl ReservationRow {
+    pub fn into_reservation(self, reminders: Vec<Reminder>) -> AppResult<Reservation> {
         let ReservationRow {
             reservation_id,
             space_id,
@@ -46,25 +85,50 @@ impl From<ReservationRow> for Reservation {
             email,
             reservation_start_time,
             reservation_end_time,
-            reminder_at,
             reserved_at,
-            reminder_is_already,
             space_name,
             is_active,
             capacity,
             equipment,
             address,
-        } = value;
-        Reservation {
-            reservation_id: reservation_id,
+            reservation_series_id,
+            status,
+            confirmed_at,
+            checked_in_at,
+            completed_at,
+            cancelled_at,
+            no_show_at,
+            rejected_at,
+            expired_at,
+            reviewed_by,
+            ended_by,
+            end_source,
+            end_reason,
+        } = self;
+        let status = ReservationStatus::try_from(status.as_str())
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        // 出どころが記録されている場合のみ、終わらせた操作の記録とする
+        let ending = end_source
+            .map(|source| {
+                source
+                    .parse()
+                    .map(|source| ReservationEnding {
+                        ended_by,
+                        source,
+                        reason: end_reason,
+                    })
+                    .map_err(|_| {
+                        AppError::ConversionEntityError(format!("unknown end source: {source}"))
+                    })
+            })
+            .transpose()?;
+        Ok(Reservation {
+            reservation_id,
             reserved_by: user_id,
             user_name,
             email,
             reserved_at,
-            reminder_at,
-            reminder_is_already,
-            // 未返却なので、returned_at は None を入れる
-            returned_at: None,
+            status,
             reservation_start_time,
             reservation_end_time,
             space: ReservationSpace {
@@ -75,72 +139,78 @@ impl From<ReservationRow> for Reservation {
                 equipment,
                 address,
             },
-        }
+            reminders,
+            reservation_series_id,
+            confirmed_at,
+            checked_in_at,
+            completed_at,
+            cancelled_at,
+            no_show_at,
+            rejected_at,
+            expired_at,
+            reviewed_by,
+            ending,
+        })
     }
 }
 
-// 予約終了済みの予約一覧を取得する際に使う型
-pub struct ReturnedReservationRow {
-    pub reservation_id: ReservationId,
+// 繰り返し予約を取得する際に使う型
+pub struct ReservationSeriesRow {
+    pub reservation_series_id: ReservationSeriesId,
     pub space_id: SpaceId,
     pub user_id: UserId,
-    pub user_name: String,
-    pub email: String,
-    pub is_cancel: bool,
-    pub reminder_is_already: bool,
-    pub reserved_at: DateTime<Local>,
-    pub reminder_at: DateTime<Local>,
-    pub returned_at: DateTime<Local>,
-    pub reservation_start_time:DateTime<Local>,
-    pub reservation_end_time:DateTime<Local>,
-    pub space_name: String,
-    pub is_active: bool,
-    pub capacity: i32,
-    pub equipment: String,
-    pub address: String,
+    pub frequency: String,
+    pub repeat_interval: i32,
+    pub by_weekday: Vec<i32>,
+    pub occurrence_count: Option<i32>,
+    pub until: Option<DateTime<Local>>,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub created_at: DateTime<Local>,
 }
 
-impl From<ReturnedReservationRow> for Reservation {
-    fn from(value: ReturnedReservationRow) -> Self {
-        let ReturnedReservationRow {
-            reservation_id,
+// 予約中の回は別のクエリで取得するため、引数をとる into_series メソッドを定義する
+impl ReservationSeriesRow {
+    pub fn into_series(self, reservations: Vec<Reservation>) -> AppResult<ReservationSeries> {
+        let ReservationSeriesRow {
+            reservation_series_id,
             space_id,
             user_id,
-            user_name,
-            email,
-            is_cancel: _,
-            reminder_is_already,
-            reserved_at,
-            reminder_at,
-            returned_at,
+            frequency,
+            repeat_interval,
+            by_weekday,
+            occurrence_count,
+            until,
             reservation_start_time,
             reservation_end_time,
-            space_name,
-            is_active,
-            capacity,
-            equipment,
-            address,
-        } = value;
-        Reservation {
-            reservation_id: reservation_id,
+            created_at,
+        } = self;
+        let frequency = Frequency::try_from(frequency.as_str())
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        let by_weekday = by_weekday
+            .into_iter()
+            .map(|n| {
+                u8::try_from(n - 1)
+                    .ok()
+                    .and_then(|n| chrono::Weekday::try_from(n).ok())
+                    .ok_or_else(|| AppError::ConversionEntityError(format!("invalid weekday: {n}")))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(ReservationSeries {
+            reservation_series_id,
+            space_id,
             reserved_by: user_id,
-            user_name,
-            email,
-            reminder_is_already,
-            reserved_at,
-            reminder_at,
-            // 返却済みなので returned_at には日時データが入る
-            returned_at: Some(returned_at),
+            recurrence: Recurrence {
+                frequency,
+                interval: repeat_interval,
+                by_weekday,
+                count: occurrence_count,
+                until,
+            },
             reservation_start_time,
             reservation_end_time,
-            space: ReservationSpace {
-                space_id,
-                space_name,
-                is_active,
-                capacity,
-                equipment,
-                address,
-            },
-        }
+            created_at,
+            reservations,
+        })
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,9 @@
-use kernel::model::{id::{SpaceId,UserId,ReservationId},
+use kernel::model::{id::{SpaceBlackoutId, SpaceId,UserId,ReservationId},
     user::{SpaceOwner,ReservationUser}, 
-    space::{Space,Reservation}};
+    role::Role,
+    space::{approval::ApprovalPolicy, check_in::CheckInPolicy, policy::{BookingPolicy, SpaceBookingPolicy}, schedule::{OpeningHours, SpaceBlackout}, Space,Reservation}};
+use std::str::FromStr;
+use shared::error::AppError;
 
 pub struct SpaceRow {
     pub space_id: SpaceId,
@@ -12,6 +15,10 @@ pub struct SpaceRow {
     pub equipment: String,
     pub address: String,
     pub owned_by:UserId,
+    pub timezone: String,
+    pub check_in_grace_minutes: Option<i32>,
+    pub check_in_code: Option<String>,
+    pub requires_approval: bool,
 }
 use chrono::{DateTime, Local};
 
@@ -27,6 +34,10 @@ impl From<SpaceRow> for Space {
             address,
             owner_name,
             owned_by,
+            timezone,
+            check_in_grace_minutes,
+            check_in_code,
+            requires_approval,
         } = value;
         Space {
             space_id,
@@ -38,16 +49,34 @@ impl From<SpaceRow> for Space {
             address,
             owner: SpaceOwner {
                 owner_id:owned_by,
-                owner_name:owner_name,
+                owner_name,
             },
             reservation: None, // ★ 追加
+            timezone,
+            // 営業時間と予約のルール、承認者は別のテーブルから取得するため、この変換では空にしておく
+            opening_hours: Vec::new(),
+            booking_policy: Default::default(),
+            check_in_policy: CheckInPolicy {
+                grace_minutes: check_in_grace_minutes,
+                code: check_in_code,
+            },
+            approval_policy: ApprovalPolicy {
+                requires_approval,
+                approvers: Vec::new(),
+            },
         }
     }
 }
 
 // From トレイトの実装の代わりに、引数をとる into_space メソッドを定義し実装する
 impl SpaceRow {
-    pub fn into_space(self, reservation: Option<Reservation>) -> Space {
+    pub fn into_space(
+        self,
+        reservation: Option<Reservation>,
+        opening_hours: Vec<OpeningHours>,
+        booking_policy: SpaceBookingPolicy,
+        approvers: Vec<UserId>,
+    ) -> Space {
         let SpaceRow {
             space_id,
             space_name,
@@ -58,9 +87,13 @@ impl SpaceRow {
             address,
             owned_by,
             owner_name,
+            timezone,
+            check_in_grace_minutes,
+            check_in_code,
+            requires_approval,
         } = self;
         Space {
-            space_id: space_id,
+            space_id,
             space_name,
             is_active,
             description,
@@ -69,9 +102,20 @@ impl SpaceRow {
             address,
             owner: SpaceOwner{
                 owner_id:owned_by,
-                owner_name:owner_name,
+                owner_name,
             },
             reservation,
+            timezone,
+            opening_hours,
+            booking_policy,
+            check_in_policy: CheckInPolicy {
+                grace_minutes: check_in_grace_minutes,
+                code: check_in_code,
+            },
+            approval_policy: ApprovalPolicy {
+                requires_approval,
+                approvers,
+            },
         }
     }
 }
@@ -104,10 +148,119 @@ impl From<SpaceReservationRow> for Reservation {
         Reservation {
             reservation_id,
             reserved_by: ReservationUser {
-                user_id: user_id,
-                user_name: user_name,
+                user_id,
+                user_name,
             },
             reserved_at,
         }
     }
-}
\ No newline at end of file
+}
+
+// 営業時間を取得する際に使う型
+pub struct OpeningHoursRow {
+    pub space_id: SpaceId,
+    pub weekday: i32,
+    pub open_minutes: i32,
+    pub close_minutes: i32,
+}
+
+impl TryFrom<OpeningHoursRow> for OpeningHours {
+    type Error = AppError;
+
+    fn try_from(value: OpeningHoursRow) -> Result<Self, Self::Error> {
+        let OpeningHoursRow {
+            space_id: _,
+            weekday,
+            open_minutes,
+            close_minutes,
+        } = value;
+        // 曜日は ISO 8601 の番号（月曜日 = 1 〜 日曜日 = 7）で持つ
+        let weekday = u8::try_from(weekday - 1)
+            .ok()
+            .and_then(|n| chrono::Weekday::try_from(n).ok())
+            .ok_or_else(|| AppError::ConversionEntityError(format!("invalid weekday: {weekday}")))?;
+        Ok(OpeningHours {
+            weekday,
+            open_minutes,
+            close_minutes,
+        })
+    }
+}
+
+// 予約を受け付けない期間を取得する際に使う型
+pub struct SpaceBlackoutRow {
+    pub space_blackout_id: SpaceBlackoutId,
+    pub start_time: DateTime<Local>,
+    pub end_time: DateTime<Local>,
+    pub reason: String,
+}
+
+impl From<SpaceBlackoutRow> for SpaceBlackout {
+    fn from(value: SpaceBlackoutRow) -> Self {
+        let SpaceBlackoutRow {
+            space_blackout_id,
+            start_time,
+            end_time,
+            reason,
+        } = value;
+        SpaceBlackout {
+            space_blackout_id,
+            start_time,
+            end_time,
+            reason,
+        }
+    }
+}
+
+// 予約のルールを取得する際に使う型
+// role_name が NULL の行はスペースのルール
+pub struct BookingPolicyRow {
+    pub space_id: SpaceId,
+    pub role_name: Option<String>,
+    pub min_duration_minutes: Option<i32>,
+    pub max_duration_minutes: Option<i32>,
+    pub slot_granularity_minutes: Option<i32>,
+    pub max_days_in_advance: Option<i32>,
+    pub max_active_reservations: Option<i32>,
+    pub max_reservations_per_week: Option<i32>,
+    pub max_recent_no_shows: Option<i32>,
+}
+
+impl BookingPolicyRow {
+    pub fn into_policy(self) -> Result<(Option<Role>, BookingPolicy), AppError> {
+        let BookingPolicyRow {
+            space_id: _,
+            role_name,
+            min_duration_minutes,
+            max_duration_minutes,
+            slot_granularity_minutes,
+            max_days_in_advance,
+            max_active_reservations,
+            max_reservations_per_week,
+            max_recent_no_shows,
+        } = self;
+        let role = role_name
+            .map(|role_name| {
+                Role::from_str(role_name.as_str())
+                    .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+            })
+            .transpose()?;
+        Ok((
+            role,
+            BookingPolicy {
+                min_duration_minutes,
+                max_duration_minutes,
+                slot_granularity_minutes,
+                max_days_in_advance,
+                max_active_reservations,
+                max_reservations_per_week,
+                max_recent_no_shows,
+            },
+        ))
+    }
+}
+
+pub struct SpaceApproverRow {
+    pub space_id: SpaceId,
+    pub user_id: UserId,
+}
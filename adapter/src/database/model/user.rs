@@ -1,5 +1,6 @@
 use kernel::model::{id::UserId, role::Role, user::User};
 use shared::error::AppError;
+use shared::i18n::Language;
 use sqlx::types::chrono::{DateTime, Local};
 use std::str::FromStr;
 
@@ -8,6 +9,9 @@ pub struct UserRow {
     pub user_name: String,
     pub email: String,
     pub role_name: String,
+    pub reminder_lead_minutes: Vec<i32>,
+    pub language: String,
+    pub timezone: String,
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
 }
@@ -20,14 +24,21 @@ impl TryFrom<UserRow> for User {
             user_name,
             email,
             role_name,
+            reminder_lead_minutes,
+            language,
+            timezone,
             ..
         } = value;
         Ok(User {
-            user_id: user_id,
+            user_id,
             user_name,
             email,
             role: Role::from_str(role_name.as_str())
                 .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            reminder_lead_minutes,
+            language: Language::from_str(language.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            timezone,
         })
     }
 }
\ No newline at end of file
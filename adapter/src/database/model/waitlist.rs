@@ -0,0 +1,69 @@
+use kernel::model::{
+    id::{ReservationId, SpaceId, UserId, WaitlistEntryId},
+    waitlist::WaitlistEntry,
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Local};
+
+pub struct WaitlistEntryRow {
+    pub waitlist_entry_id: WaitlistEntryId,
+    pub space_id: SpaceId,
+    pub space_name: String,
+    pub user_id: UserId,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub fulfillment: String,
+    pub status: String,
+    pub offer_expires_at: Option<DateTime<Local>>,
+    pub reservation_id: Option<ReservationId>,
+    pub created_at: DateTime<Local>,
+}
+
+// fulfillment と status は文字列で保存しているため、変換に失敗する可能性がある
+impl TryFrom<WaitlistEntryRow> for WaitlistEntry {
+    type Error = AppError;
+    fn try_from(value: WaitlistEntryRow) -> Result<Self, Self::Error> {
+        let WaitlistEntryRow {
+            waitlist_entry_id,
+            space_id,
+            space_name,
+            user_id,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment,
+            status,
+            offer_expires_at,
+            reservation_id,
+            created_at,
+        } = value;
+        Ok(WaitlistEntry {
+            waitlist_entry_id,
+            space_id,
+            space_name,
+            user_id,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment: fulfillment.parse().map_err(|_| {
+                AppError::ConversionEntityError(format!("unknown fulfillment: {fulfillment}"))
+            })?,
+            status: status
+                .parse()
+                .map_err(|_| AppError::ConversionEntityError(format!("unknown status: {status}")))?,
+            offer_expires_at,
+            reservation_id,
+            created_at,
+        })
+    }
+}
+
+// 空きが出た時間帯の案内の対象として、行ロックを取ったキャンセル待ち
+pub struct LockedWaitlistEntryRow {
+    pub waitlist_entry_id: WaitlistEntryId,
+    pub space_id: SpaceId,
+    pub user_id: UserId,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub fulfillment: String,
+    pub status: String,
+    pub offer_expires_at: Option<DateTime<Local>>,
+}
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Local};
+use kernel::model::{
+    id::{WebhookDeliveryId, WebhookId},
+    webhook::{Webhook, WebhookDelivery, WebhookDispatch, WebhookEvent},
+};
+use shared::error::{AppError, AppResult};
+
+pub struct WebhookRow {
+    pub webhook_id: WebhookId,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub description: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+// events は文字列で保存しているため、変換に失敗する可能性がある
+impl TryFrom<WebhookRow> for Webhook {
+    type Error = AppError;
+    fn try_from(value: WebhookRow) -> Result<Self, Self::Error> {
+        let WebhookRow {
+            webhook_id,
+            url,
+            secret,
+            events,
+            description,
+            is_active,
+            created_at,
+            updated_at,
+        } = value;
+        Ok(Webhook {
+            webhook_id,
+            url,
+            secret,
+            events: events
+                .iter()
+                .map(|event| parse_event(event))
+                .collect::<AppResult<_>>()?,
+            description,
+            is_active,
+            created_at,
+            updated_at,
+        })
+    }
+}
+
+pub struct WebhookDeliveryRow {
+    pub webhook_delivery_id: WebhookDeliveryId,
+    pub webhook_id: WebhookId,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub response_status: Option<i32>,
+    pub next_attempt_at: DateTime<Local>,
+    pub delivered_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
+
+impl TryFrom<WebhookDeliveryRow> for WebhookDelivery {
+    type Error = AppError;
+    fn try_from(value: WebhookDeliveryRow) -> Result<Self, Self::Error> {
+        let WebhookDeliveryRow {
+            webhook_delivery_id,
+            webhook_id,
+            event,
+            payload,
+            status,
+            attempts,
+            last_error,
+            response_status,
+            next_attempt_at,
+            delivered_at,
+            created_at,
+        } = value;
+        Ok(WebhookDelivery {
+            webhook_delivery_id,
+            webhook_id,
+            event: parse_event(&event)?,
+            payload,
+            status: status
+                .parse()
+                .map_err(|_| AppError::ConversionEntityError(format!("unknown status: {status}")))?,
+            attempts,
+            last_error,
+            response_status,
+            next_attempt_at,
+            delivered_at,
+            created_at,
+        })
+    }
+}
+
+pub struct PaginatedWebhookDeliveryRow {
+    pub total: i64,
+    pub webhook_delivery_id: WebhookDeliveryId,
+}
+
+// 送信するために取得した webhook の送信と、その送信先
+pub struct WebhookDispatchRow {
+    pub webhook_delivery_id: WebhookDeliveryId,
+    pub webhook_id: WebhookId,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub response_status: Option<i32>,
+    pub next_attempt_at: DateTime<Local>,
+    pub delivered_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+    pub url: String,
+    pub secret: String,
+}
+
+impl TryFrom<WebhookDispatchRow> for WebhookDispatch {
+    type Error = AppError;
+    fn try_from(value: WebhookDispatchRow) -> Result<Self, Self::Error> {
+        let WebhookDispatchRow {
+            webhook_delivery_id,
+            webhook_id,
+            event,
+            payload,
+            status,
+            attempts,
+            last_error,
+            response_status,
+            next_attempt_at,
+            delivered_at,
+            created_at,
+            url,
+            secret,
+        } = value;
+        let delivery = WebhookDeliveryRow {
+            webhook_delivery_id,
+            webhook_id,
+            event,
+            payload,
+            status,
+            attempts,
+            last_error,
+            response_status,
+            next_attempt_at,
+            delivered_at,
+            created_at,
+        }
+        .try_into()?;
+        Ok(WebhookDispatch {
+            delivery,
+            url,
+            secret,
+        })
+    }
+}
+
+fn parse_event(event: &str) -> AppResult<WebhookEvent> {
+    event
+        .parse()
+        .map_err(|_| AppError::ConversionEntityError(format!("unknown event: {event}")))
+}
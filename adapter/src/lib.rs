@@ -1,3 +1,6 @@
 pub mod database;
+pub mod notifier;
 pub mod repository;
-pub mod redis;
\ No newline at end of file
+pub mod redis;
+pub mod scheduler;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,136 @@
+use super::template::MailTemplates;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use kernel::{
+    model::notification::{NotificationKind, ReservationNotification},
+    notifier::Notifier,
+};
+use lettre::message::Mailbox;
+use reqwest::Client;
+use shared::{
+    config::{GmailConfig, GoogleAuthFlow},
+    error::{AppError, AppResult},
+};
+use std::sync::Arc;
+use yup_oauth2::{
+    authenticator::DefaultAuthenticator, InstalledFlowAuthenticator, InstalledFlowReturnMethod,
+    ServiceAccountAuthenticator,
+};
+
+const GMAIL_SEND_URL: &str = "https://gmail.googleapis.com/gmail/v1/users/me/messages/send";
+const GMAIL_SEND_SCOPE: &str = "https://www.googleapis.com/auth/gmail.send";
+
+// GmailConfig から Gmail API 用の認証器を作る
+// 認証器はトークンをキャッシュし、期限切れの際は自動で更新する
+pub async fn build_gmail_authenticator(
+    config: &GmailConfig,
+    sender: &Mailbox,
+) -> AppResult<DefaultAuthenticator> {
+    let auth = match config.flow {
+        GoogleAuthFlow::Installed => {
+            let secret = yup_oauth2::read_application_secret(&config.secret_path)
+                .await
+                .map_err(|e| {
+                    AppError::ExternalServiceError(format!(
+                        "failed to read Gmail client secret ({}): {e}",
+                        config.secret_path
+                    ))
+                })?;
+            let builder =
+                InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::Interactive);
+            match &config.token_cache_path {
+                Some(path) => builder.persist_tokens_to_disk(path).build().await?,
+                None => builder.build().await?,
+            }
+        }
+        GoogleAuthFlow::ServiceAccount => {
+            let key = yup_oauth2::read_service_account_key(&config.secret_path)
+                .await
+                .map_err(|e| {
+                    AppError::ExternalServiceError(format!(
+                        "failed to read Gmail service account key ({}): {e}",
+                        config.secret_path
+                    ))
+                })?;
+            // ドメイン全体の委任により、送信者アドレスのユーザーとして送信する
+            let builder =
+                ServiceAccountAuthenticator::builder(key).subject(sender.email.to_string());
+            match &config.token_cache_path {
+                Some(path) => builder.persist_tokens_to_disk(path).build().await?,
+                None => builder.build().await?,
+            }
+        }
+    };
+    Ok(auth)
+}
+
+// Gmail REST API を使ってメールを送信する Notifier
+pub struct GmailNotifier {
+    client: Client,
+    sender: Mailbox,
+    templates: Arc<MailTemplates>,
+    authenticator: Arc<DefaultAuthenticator>,
+}
+
+impl GmailNotifier {
+    pub fn new(
+        sender: Mailbox,
+        templates: Arc<MailTemplates>,
+        authenticator: Arc<DefaultAuthenticator>,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            sender,
+            templates,
+            authenticator,
+        }
+    }
+
+    async fn access_token(&self) -> AppResult<String> {
+        let token = self.authenticator.token(&[GMAIL_SEND_SCOPE]).await?;
+        token
+            .token()
+            .map(str::to_string)
+            .ok_or_else(|| AppError::ExternalServiceError("Gmail access token is empty".into()))
+    }
+}
+
+#[async_trait]
+impl Notifier for GmailNotifier {
+    async fn notify(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<()> {
+        let message = self
+            .templates
+            .render_content(kind, notification)?
+            .into_message(&self.sender)?;
+        let raw = general_purpose::URL_SAFE_NO_PAD.encode(message.formatted());
+        let access_token = self.access_token().await?;
+
+        let res = self
+            .client
+            .post(GMAIL_SEND_URL)
+            .bearer_auth(access_token)
+            .json(&serde_json::json!({ "raw": raw }))
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalServiceError(format!("Gmail error: {e}")))?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(AppError::ExternalServiceError(format!(
+                "Gmail error: {status} {text}"
+            )));
+        }
+
+        tracing::info!(
+            kind = kind.as_ref(),
+            reservation_id = notification.reservation_id.map(tracing::field::display),
+            "Gmail sent"
+        );
+        Ok(())
+    }
+}
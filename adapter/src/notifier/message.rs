@@ -0,0 +1,578 @@
+use chrono::{DateTime, Datelike, Local, Weekday};
+use chrono_tz::Tz;
+use kernel::model::{
+    calendar::{Calendar, CalendarEvent},
+    notification::{NotificationKind, ReservationNotification, DEFAULT_TIMEZONE},
+    reservation::status::ReservationEndSource,
+};
+use lettre::{
+    message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart},
+    Message,
+};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::Language,
+};
+
+// 送信手段によらない、通知メールの中身を表す型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MailContent {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    // HTML のテンプレートがある通知では、本文の HTML 版も送る
+    pub html: Option<String>,
+    // 予約が確定したことを知らせる通知では、予約を予定として取り込める iCalendar を添付する
+    pub calendar: Option<String>,
+}
+
+// 添付する iCalendar のファイル名
+const CALENDAR_FILE_NAME: &str = "reservation.ics";
+
+impl MailContent {
+    // テンプレートが用意されていない通知の、テキストのみの件名と本文を組み立てる
+    // waitlist_claim_url はキャンセル待ちの案内で、予約を確定するためのリンクに使う
+    // 件名と本文は、宛先のユーザーが設定した言語で組み立てる
+    pub fn new(
+        kind: NotificationKind,
+        n: &ReservationNotification,
+        waitlist_claim_url: &str,
+    ) -> Self {
+        let claim_url = claim_url(waitlist_claim_url, n);
+        let (subject, body) = match n.language {
+            Language::Ja => japanese(kind, n, &claim_url),
+            Language::En => english(kind, n, &claim_url),
+        };
+        Self {
+            to: n.email.clone(),
+            subject: subject.into(),
+            body,
+            html: None,
+            calendar: calendar_attachment(kind, n),
+        }
+    }
+
+    // RFC 5322 形式のメッセージに変換する
+    // HTML 版がある場合は、テキストと HTML の multipart/alternative とする
+    // iCalendar を添付する場合は、本文と添付ファイルの multipart/mixed とする
+    pub fn into_message(self, sender: &Mailbox) -> AppResult<Message> {
+        let to: Mailbox = self
+            .to
+            .parse()
+            .map_err(|e| AppError::ConversionEntityError(format!("invalid address: {e}")))?;
+        let builder = Message::builder()
+            .from(sender.clone())
+            .to(to)
+            .subject(self.subject);
+        match (self.html, self.calendar) {
+            (Some(html), None) => {
+                builder.multipart(MultiPart::alternative_plain_html(self.body, html))
+            }
+            (None, None) => builder.header(ContentType::TEXT_PLAIN).body(self.body),
+            (Some(html), Some(calendar)) => builder.multipart(
+                MultiPart::mixed()
+                    .multipart(MultiPart::alternative_plain_html(self.body, html))
+                    .singlepart(calendar_part(calendar)?),
+            ),
+            (None, Some(calendar)) => builder.multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(self.body))
+                    .singlepart(calendar_part(calendar)?),
+            ),
+        }
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+// 予約が確定したことを知らせる通知（受付・承認・キャンセル待ちからの自動予約）に添付する iCalendar
+pub(super) fn calendar_attachment(
+    kind: NotificationKind,
+    n: &ReservationNotification,
+) -> Option<String> {
+    if !matches!(
+        kind,
+        NotificationKind::Confirmation
+            | NotificationKind::Approved
+            | NotificationKind::WaitlistBooked
+    ) {
+        return None;
+    }
+    let event = CalendarEvent::from_notification(n)?;
+    Some(Calendar::new(n.space_name.clone(), vec![event]).to_ics())
+}
+
+fn calendar_part(calendar: String) -> AppResult<SinglePart> {
+    let content_type = ContentType::parse("text/calendar; charset=utf-8; method=PUBLISH")
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+    Ok(Attachment::new(CALENDAR_FILE_NAME.into()).body(calendar, content_type))
+}
+
+// 日本語の件名と本文
+fn japanese(
+    kind: NotificationKind,
+    n: &ReservationNotification,
+    claim_url: &str,
+) -> (&'static str, String) {
+    let tz = parse_timezone(&n.timezone);
+    let start = format_datetime(n.reservation_start_time, tz, Language::Ja);
+    let end = format_datetime(n.reservation_end_time, tz, Language::Ja);
+    match kind {
+            NotificationKind::Reminder => (
+                "remind mail",
+                format!(
+                    "{}さん {} の予約開始の{}前です。予約時間：{} 〜 {}",
+                    n.user_name,
+                    n.space_name,
+                    format_lead_time(
+                    Language::Ja,
+                        n.reminder_at
+                            .map(|at| n.reservation_start_time - at)
+                            .unwrap_or_default()
+                    ),
+                    start,
+                    end
+                ),
+            ),
+            NotificationKind::Confirmation => (
+                "confirm mail",
+                format!(
+                    "{}さん {} の予約を受け付けました。予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+            NotificationKind::Reschedule => (
+                "reschedule mail",
+                format!(
+                    "{}さん {} の予約時間を変更しました。変更後の予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+            NotificationKind::Cancellation => (
+                "cancel mail",
+                format!(
+                    "{}さん {}予約時間：{} 〜 {}{}",
+                    n.user_name,
+                    match n.end_source {
+                        Some(ReservationEndSource::User) => {
+                            format!("{} の予約をキャンセルしました。", n.space_name)
+                        }
+                        Some(ReservationEndSource::Admin) => {
+                            format!("{} のご予約は管理者によりキャンセルされました。", n.space_name)
+                        }
+                        _ => format!(
+                            "{} が使えなくなりました。ご予約はキャンセルになります。",
+                            n.space_name
+                        ),
+                    },
+                    start,
+                    end,
+                    format_reason("キャンセル理由", n.end_reason.as_deref())
+                ),
+            ),
+            NotificationKind::Return => (
+                "return mail",
+                format!(
+                    "{}さん {} の予約を{}終了しました。予約時間：{} 〜 {}{}",
+                    n.user_name,
+                    n.space_name,
+                    match n.end_source {
+                        Some(ReservationEndSource::Admin) => "管理者により",
+                        _ => "",
+                    },
+                    start,
+                    end,
+                    format_reason("理由", n.end_reason.as_deref())
+                ),
+            ),
+            NotificationKind::WaitlistOffer => (
+                "waitlist offer mail",
+                format!(
+                    "{}さん キャンセル待ちをしていた {} に空きが出ました。予約時間：{} 〜 {}\n\
+                     {} までに次のリンクから予約を確定してください。期限を過ぎると次の方にご案内します。\n\
+                     {}",
+                    n.user_name,
+                    n.space_name,
+                    start,
+                    end,
+                    format_claim_expires_at(n, tz, Language::Ja),
+                    claim_url
+                ),
+            ),
+            NotificationKind::WaitlistBooked => (
+                "waitlist booked mail",
+                format!(
+                    "{}さん キャンセル待ちをしていた {} に空きが出たため、予約しました。予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+            NotificationKind::NoShow => (
+                "no-show mail",
+                format!(
+                    "{}さん {} の予約はチェックインがなかったため解放しました。予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+            NotificationKind::ApprovalRequest => (
+                "approval request mail",
+                format!(
+                    "{}さん {} に承認待ちの予約があります。予約時間：{} 〜 {}\n\
+                     予約開始時刻までに承認または却下してください。予約 ID：{}",
+                    n.user_name,
+                    n.space_name,
+                    start,
+                    end,
+                    n.reservation_id.map(|id| id.to_string()).unwrap_or_default()
+                ),
+            ),
+            NotificationKind::ApprovalPending => (
+                "approval pending mail",
+                format!(
+                    "{}さん {} の予約を承認待ちとして受け付けました。承認されると予約が確定します。予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+            NotificationKind::Approved => (
+                "approved mail",
+                format!(
+                    "{}さん {} の予約が承認され、確定しました。予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+            NotificationKind::Rejected => (
+                "rejected mail",
+                format!(
+                    "{}さん {} の予約は承認されませんでした。予約時間：{} 〜 {}{}",
+                    n.user_name,
+                    n.space_name,
+                    start,
+                    end,
+                    format_reason("理由", n.end_reason.as_deref())
+                ),
+            ),
+            NotificationKind::ApprovalExpired => (
+                "approval expired mail",
+                format!(
+                    "{}さん {} の予約は予約開始時刻までに承認されなかったため、取り消しました。予約時間：{} 〜 {}",
+                    n.user_name, n.space_name, start, end
+                ),
+            ),
+        }
+}
+
+// 英語の件名と本文
+fn english(
+    kind: NotificationKind,
+    n: &ReservationNotification,
+    claim_url: &str,
+) -> (&'static str, String) {
+    let tz = parse_timezone(&n.timezone);
+    let start = format_datetime(n.reservation_start_time, tz, Language::En);
+    let end = format_datetime(n.reservation_end_time, tz, Language::En);
+    match kind {
+        NotificationKind::Reminder => (
+            "Reservation reminder",
+            format!(
+                "Hello {}, your reservation for {} starts in {}. Reservation time: {} - {}",
+                n.user_name,
+                n.space_name,
+                format_lead_time(
+                    Language::En,
+                    n.reminder_at
+                        .map(|at| n.reservation_start_time - at)
+                        .unwrap_or_default()
+                ),
+                start,
+                end
+            ),
+        ),
+        NotificationKind::Confirmation => (
+            "Reservation received",
+            format!(
+                "Hello {}, your reservation for {} has been received. Reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+        NotificationKind::Reschedule => (
+            "Reservation rescheduled",
+            format!(
+                "Hello {}, your reservation for {} has been rescheduled. \
+                 New reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+        NotificationKind::Cancellation => (
+            "Reservation cancelled",
+            format!(
+                "Hello {}, {} Reservation time: {} - {}{}",
+                n.user_name,
+                match n.end_source {
+                    Some(ReservationEndSource::User) => {
+                        format!("your reservation for {} has been cancelled.", n.space_name)
+                    }
+                    Some(ReservationEndSource::Admin) => format!(
+                        "your reservation for {} has been cancelled by an administrator.",
+                        n.space_name
+                    ),
+                    _ => format!(
+                        "{} is no longer available, so your reservation has been cancelled.",
+                        n.space_name
+                    ),
+                },
+                start,
+                end,
+                format_reason_en("Reason for cancellation", n.end_reason.as_deref())
+            ),
+        ),
+        NotificationKind::Return => (
+            "Reservation ended",
+            format!(
+                "Hello {}, your reservation for {} has been ended{}. Reservation time: {} - {}{}",
+                n.user_name,
+                n.space_name,
+                match n.end_source {
+                    Some(ReservationEndSource::Admin) => " by an administrator",
+                    _ => "",
+                },
+                start,
+                end,
+                format_reason_en("Reason", n.end_reason.as_deref())
+            ),
+        ),
+        NotificationKind::WaitlistOffer => (
+            "A waitlisted time slot is now available",
+            format!(
+                "Hello {}, a time slot you were waiting for at {} is now available. \
+                 Reservation time: {} - {}\n\
+                 Please confirm the reservation from the link below by {}. \
+                 After that, the slot will be offered to the next person.\n\
+                 {}",
+                n.user_name,
+                n.space_name,
+                start,
+                end,
+                format_claim_expires_at(n, tz, Language::En),
+                claim_url
+            ),
+        ),
+        NotificationKind::WaitlistBooked => (
+            "Waitlisted reservation booked",
+            format!(
+                "Hello {}, a time slot you were waiting for at {} became available \
+                 and has been booked for you. Reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+        NotificationKind::NoShow => (
+            "Reservation released",
+            format!(
+                "Hello {}, your reservation for {} has been released because there was no check-in. \
+                 Reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+        NotificationKind::ApprovalRequest => (
+            "Reservation awaiting your approval",
+            format!(
+                "Hello {}, a reservation for {} is awaiting approval. Reservation time: {} - {}\n\
+                 Please approve or reject it before it starts. Reservation ID: {}",
+                n.user_name,
+                n.space_name,
+                start,
+                end,
+                n.reservation_id.map(|id| id.to_string()).unwrap_or_default()
+            ),
+        ),
+        NotificationKind::ApprovalPending => (
+            "Reservation awaiting approval",
+            format!(
+                "Hello {}, your reservation for {} has been received and is awaiting approval. \
+                 It will be confirmed once approved. Reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+        NotificationKind::Approved => (
+            "Reservation approved",
+            format!(
+                "Hello {}, your reservation for {} has been approved and confirmed. \
+                 Reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+        NotificationKind::Rejected => (
+            "Reservation rejected",
+            format!(
+                "Hello {}, your reservation for {} was not approved. Reservation time: {} - {}{}",
+                n.user_name,
+                n.space_name,
+                start,
+                end,
+                format_reason_en("Reason", n.end_reason.as_deref())
+            ),
+        ),
+        NotificationKind::ApprovalExpired => (
+            "Reservation approval expired",
+            format!(
+                "Hello {}, your reservation for {} was withdrawn because it was not approved \
+                 before it started. Reservation time: {} - {}",
+                n.user_name, n.space_name, start, end
+            ),
+        ),
+    }
+}
+
+// キャンセル待ちの案内に載せる、予約を確定するためのリンク
+pub(super) fn claim_url(waitlist_claim_url: &str, n: &ReservationNotification) -> String {
+    format!(
+        "{}/{}",
+        waitlist_claim_url.trim_end_matches('/'),
+        n.claim_token.as_deref().unwrap_or_default()
+    )
+}
+
+pub(super) fn format_claim_expires_at(
+    n: &ReservationNotification,
+    tz: Tz,
+    language: Language,
+) -> String {
+    n.claim_expires_at
+        .map(|at| format_datetime(at, tz, language))
+        .unwrap_or_default()
+}
+
+// 宛先のユーザーのタイムゾーン。解釈できない場合は既定のタイムゾーンとする
+pub(super) fn parse_timezone(timezone: &str) -> Tz {
+    timezone.parse().unwrap_or_else(|_| {
+        tracing::warn!(timezone, "unknown timezone; using the default timezone");
+        DEFAULT_TIMEZONE.parse().unwrap_or(Tz::UTC)
+    })
+}
+
+// 日時を「2025年12月15日(月) 10:00」「Mon, Dec 15, 2025 10:00」のように表す
+pub(super) fn format_datetime(at: DateTime<Local>, tz: Tz, language: Language) -> String {
+    let at = at.with_timezone(&tz);
+    match language {
+        Language::Ja => format!(
+            "{}({}) {}",
+            at.format("%Y年%-m月%-d日"),
+            weekday_ja(at.weekday()),
+            at.format("%H:%M")
+        ),
+        Language::En => at.format("%a, %b %-d, %Y %H:%M").to_string(),
+    }
+}
+
+// 予約時間を「2025年12月15日(月) 10:00 〜 11:00 (JST)」のように表す
+// 日をまたぐ予約では、終了時刻にも日付を付ける
+pub(super) fn format_period(
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+    tz: Tz,
+    language: Language,
+) -> String {
+    let (local_start, local_end) = (start.with_timezone(&tz), end.with_timezone(&tz));
+    let end_label = if local_start.date_naive() == local_end.date_naive() {
+        local_end.format("%H:%M").to_string()
+    } else {
+        match language {
+            Language::Ja => format!(
+                "{}({}) {}",
+                local_end.format("%-m月%-d日"),
+                weekday_ja(local_end.weekday()),
+                local_end.format("%H:%M")
+            ),
+            Language::En => local_end.format("%a, %b %-d %H:%M").to_string(),
+        }
+    };
+    let separator = match language {
+        Language::Ja => "〜",
+        Language::En => "-",
+    };
+    format!(
+        "{} {separator} {end_label} ({})",
+        format_datetime(start, tz, language),
+        local_start.format("%Z")
+    )
+}
+
+fn weekday_ja(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "月",
+        Weekday::Tue => "火",
+        Weekday::Wed => "水",
+        Weekday::Thu => "木",
+        Weekday::Fri => "金",
+        Weekday::Sat => "土",
+        Weekday::Sun => "日",
+    }
+}
+
+// 理由が入力されている場合は、本文の末尾に 1 行追加する
+fn format_reason(label: &str, reason: Option<&str>) -> String {
+    reason
+        .map(|reason| format!("\n{label}：{reason}"))
+        .unwrap_or_default()
+}
+
+// リマインダーを予約開始のどれくらい前に送るかを「1日」「2時間」「15分」
+// 「1 day」「2 hours」「15 minutes」のように表す
+pub(super) fn format_lead_time(language: Language, lead: chrono::Duration) -> String {
+    let minutes = lead.num_minutes();
+    let (value, unit) = if minutes >= 60 * 24 && minutes % (60 * 24) == 0 {
+        (minutes / (60 * 24), 0)
+    } else if minutes >= 60 && minutes % 60 == 0 {
+        (minutes / 60, 1)
+    } else {
+        (minutes, 2)
+    };
+    match language {
+        Language::Ja => format!("{value}{}", ["日", "時間", "分"][unit]),
+        Language::En => {
+            let unit = ["day", "hour", "minute"][unit];
+            if value == 1 {
+                format!("{value} {unit}")
+            } else {
+                format!("{value} {unit}s")
+            }
+        }
+    }
+}
+
+fn format_reason_en(label: &str, reason: Option<&str>) -> String {
+    reason
+        .map(|reason| format!("\n{label}: {reason}"))
+        .unwrap_or_default()
+}
+
+pub fn parse_sender(sender: &str) -> AppResult<Mailbox> {
+    sender
+        .parse()
+        .map_err(|e| AppError::ConversionEntityError(format!("invalid sender address: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_period() {
+        let tz: Tz = "Asia/Tokyo".parse().unwrap();
+        let start = tz
+            .with_ymd_and_hms(2025, 12, 15, 10, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+        assert_eq!(
+            format_period(start, start + chrono::Duration::hours(1), tz, Language::Ja),
+            "2025年12月15日(月) 10:00 〜 11:00 (JST)"
+        );
+        assert_eq!(
+            format_period(start, start + chrono::Duration::hours(16), tz, Language::En),
+            "Mon, Dec 15, 2025 10:00 - Tue, Dec 16 02:00 (JST)"
+        );
+        // 予約時間はユーザーのタイムゾーンで表示する
+        assert_eq!(
+            format_datetime(start, Tz::UTC, Language::En),
+            "Mon, Dec 15, 2025 01:00"
+        );
+    }
+}
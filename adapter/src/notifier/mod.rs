@@ -0,0 +1,5 @@
+pub mod gmail;
+pub mod message;
+pub mod sink;
+pub mod smtp;
+pub mod template;
@@ -0,0 +1,218 @@
+use super::{message::MailContent, template::MailTemplates};
+use async_trait::async_trait;
+use kernel::{
+    model::notification::{NotificationKind, ReservationNotification},
+    notifier::Notifier,
+};
+use lettre::message::Mailbox;
+use shared::error::AppResult;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::io::AsyncWriteExt;
+
+// 送信するはずだったメールをファイルに追記する Notifier
+// Google の認証情報や SMTP サーバーがないローカル環境で使う
+pub struct FileNotifier {
+    path: PathBuf,
+    sender: Mailbox,
+    templates: Arc<MailTemplates>,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>, sender: Mailbox, templates: Arc<MailTemplates>) -> Self {
+        Self {
+            path: path.into(),
+            sender,
+            templates,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn notify(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<()> {
+        let message = self
+            .templates
+            .render_content(kind, notification)?
+            .into_message(&self.sender)?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(&message.formatted()).await?;
+        file.write_all(b"\r\n\r\n").await?;
+        Ok(())
+    }
+}
+
+// 送信するはずだったメールをメモリ上に保持する Notifier
+// テストで送信内容を検証するために使う
+pub struct InMemoryNotifier {
+    sent: Mutex<Vec<MailContent>>,
+    templates: Arc<MailTemplates>,
+}
+
+impl InMemoryNotifier {
+    pub fn new(templates: Arc<MailTemplates>) -> Self {
+        Self {
+            sent: Mutex::default(),
+            templates,
+        }
+    }
+
+    pub fn sent(&self) -> Vec<MailContent> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Notifier for InMemoryNotifier {
+    async fn notify(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<()> {
+        let content = self.templates.render_content(kind, notification)?;
+        tracing::info!(
+            kind = kind.as_ref(),
+            to = %content.to,
+            subject = %content.subject,
+            "mail stored in memory"
+        );
+        self.sent.lock().unwrap().push(content);
+        Ok(())
+    }
+}
+
+// メール送信が無効な場合に使う Notifier
+// 通知内容はログに出すだけで、どこにも送らない
+#[derive(Default)]
+pub struct DisabledNotifier;
+
+#[async_trait]
+impl Notifier for DisabledNotifier {
+    async fn notify(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<()> {
+        tracing::debug!(
+            kind = kind.as_ref(),
+            reservation_id = notification.reservation_id.map(tracing::field::display),
+            "mail is disabled; notification skipped"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Local};
+    use kernel::model::{
+        id::{ReservationId, SpaceId},
+        reservation::status::ReservationEndSource,
+    };
+    use crate::notifier::message::parse_sender;
+    use shared::i18n::Language;
+
+    #[tokio::test]
+    async fn test_in_memory_notifier_keeps_sent_mail() -> anyhow::Result<()> {
+        let start = Local::now() + Duration::hours(2);
+        let notification = ReservationNotification {
+            reservation_id: Some(ReservationId::new()),
+            space_id: SpaceId::new(),
+            space_name: "meeting room1".into(),
+            space_address: "東京都千代田区丸の内1-1-1".into(),
+            space_equipment: "プロジェクター".into(),
+            user_name: "common user".into(),
+            email: "user@example.com".into(),
+            reminder_at: Some(start - Duration::hours(1)),
+            reminder_times: vec![start - Duration::hours(1)],
+            reservation_start_time: start,
+            reservation_end_time: start + Duration::hours(1),
+            claim_token: None,
+            claim_expires_at: None,
+            end_source: Some(ReservationEndSource::SpaceDeactivation),
+            end_reason: Some("設備点検のため".into()),
+            language: Language::Ja,
+            timezone: "Asia/Tokyo".into(),
+        };
+
+        let templates = MailTemplates::load(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/templates/mail"),
+            "http://localhost:8080/api/v1/waitlist/claims".into(),
+        )?;
+        let notifier = InMemoryNotifier::new(Arc::new(templates));
+        notifier.send_confirmation(&notification).await?;
+        notifier.send_cancellation(&notification).await?;
+        notifier.send_reminder(&notification).await?;
+
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[0].to, "user@example.com");
+        assert!(sent[0].subject.starts_with("【予約受付】meeting room1"));
+        assert!(sent[1].subject.starts_with("【予約キャンセル】meeting room1"));
+        assert!(sent[1].body.contains("meeting room1"));
+        assert!(sent[1].body.contains("キャンセル理由：設備点検のため"));
+        assert!(sent[2].body.contains("予約開始の1時間前"));
+        // 所在地と設備、ユーザーのタイムゾーンでの日時を載せ、HTML 版も送る
+        assert!(sent[0].body.contains("■ 所在地：東京都千代田区丸の内1-1-1"));
+        assert!(sent[0].body.contains("■ 設備：プロジェクター"));
+        assert!(sent[0].body.contains("(JST)"));
+        assert!(sent[0]
+            .html
+            .as_deref()
+            .is_some_and(|html| html.contains("<html lang=\"ja\">")));
+        // 予約受付の通知にのみ、予約を予定として取り込める iCalendar を添付する
+        let reservation_id = notification.reservation_id.unwrap();
+        assert!(sent[0]
+            .calendar
+            .as_deref()
+            .is_some_and(|ics| ics.contains(&format!("UID:reservation-{reservation_id}\r\n"))));
+        // 予約のリマインダーの送信時刻を、予定の通知として含める
+        assert!(sent[0]
+            .calendar
+            .as_deref()
+            .is_some_and(|ics| ics.contains("BEGIN:VALARM\r\n")));
+        assert!(sent[1].calendar.is_none());
+        let sender = parse_sender("noreply@example.com")?;
+        let message = String::from_utf8(sent[0].clone().into_message(&sender)?.formatted())?;
+        assert!(message.contains("multipart/mixed"));
+        assert!(message.contains("text/calendar"));
+        assert!(message.contains("filename=\"reservation.ics\""));
+
+        // ユーザーの言語が英語の場合は、英語の件名と本文で送る
+        let notification = ReservationNotification {
+            language: Language::En,
+            end_reason: Some("equipment inspection".into()),
+            timezone: "UTC".into(),
+            ..notification
+        };
+        notifier.send_cancellation(&notification).await?;
+        notifier.send_reminder(&notification).await?;
+
+        let sent = notifier.sent();
+        assert!(sent[3].subject.starts_with("Reservation cancelled: meeting room1"));
+        assert!(sent[3].body.contains("(UTC)"));
+        assert!(sent[3]
+            .body
+            .contains("Reason for cancellation: equipment inspection"));
+        assert!(sent[4].body.contains("starts in 1 hour."));
+
+        // テンプレートのない通知は、テキストのみで送る
+        notifier.send_no_show(&notification).await?;
+        let sent = notifier.sent();
+        assert_eq!(sent[5].subject, "Reservation released");
+        assert!(sent[5].html.is_none());
+
+        Ok(())
+    }
+}
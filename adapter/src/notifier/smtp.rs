@@ -0,0 +1,75 @@
+use super::template::MailTemplates;
+use async_trait::async_trait;
+use kernel::{
+    model::notification::{NotificationKind, ReservationNotification},
+    notifier::Notifier,
+};
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Tokio1Executor,
+};
+use shared::{
+    config::SmtpConfig,
+    error::{AppError, AppResult},
+};
+use std::sync::Arc;
+
+// SMTP サーバー経由でメールを送信する Notifier
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    sender: Mailbox,
+    templates: Arc<MailTemplates>,
+}
+
+impl SmtpNotifier {
+    pub fn new(
+        config: &SmtpConfig,
+        sender: Mailbox,
+        templates: Arc<MailTemplates>,
+    ) -> AppResult<Self> {
+        // 認証情報が設定されている場合のみ STARTTLS + 認証で接続し、
+        // それ以外はローカルの開発用サーバーを想定して平文で接続する
+        let transport = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                    .map_err(|e| AppError::ExternalServiceError(e.to_string()))?
+                    .port(config.port)
+                    .credentials(Credentials::new(username.clone(), password.clone()))
+                    .build()
+            }
+            _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+                .port(config.port)
+                .build(),
+        };
+        Ok(Self {
+            transport,
+            sender,
+            templates,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn notify(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<()> {
+        let message = self
+            .templates
+            .render_content(kind, notification)?
+            .into_message(&self.sender)?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::ExternalServiceError(format!("SMTP error: {e}")))?;
+
+        tracing::info!(
+            kind = kind.as_ref(),
+            reservation_id = notification.reservation_id.map(tracing::field::display),
+            "SMTP mail sent"
+        );
+        Ok(())
+    }
+}
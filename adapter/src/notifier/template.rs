@@ -0,0 +1,244 @@
+use super::message::{
+    calendar_attachment, claim_url, format_claim_expires_at, format_datetime, format_lead_time,
+    format_period, parse_timezone, MailContent,
+};
+use kernel::{
+    model::notification::{
+        NotificationKind, RenderedMail, ReservationNotification, DEFAULT_TIMEZONE,
+    },
+    notifier::MailRenderer,
+};
+use minijinja::Environment;
+use serde::Serialize;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::Language,
+};
+use std::{collections::HashSet, path::Path};
+use strum::IntoEnumIterator;
+
+// 通知ごとのテンプレートのファイル名
+// テンプレートは {言語}/{通知の種類}/ 以下に置く（例: ja/reminder/body.html）
+// dir と {言語}/ の直下に置いたファイルは、レイアウトなどの共通部品として読み込む
+const SUBJECT_TEMPLATE: &str = "subject.txt";
+const TEXT_TEMPLATE: &str = "body.txt";
+const HTML_TEMPLATE: &str = "body.html";
+
+// 起動時にテンプレートのファイルを読み込み、通知の件名と本文を組み立てる
+// テンプレートがない通知は、MailContent::new のテキストのみの本文で送る
+pub struct MailTemplates {
+    env: Environment<'static>,
+    // テンプレートが用意されている言語と通知の種類の組
+    templated: HashSet<(Language, NotificationKind)>,
+    waitlist_claim_url: String,
+}
+
+impl MailTemplates {
+    // dir 以下のテンプレートをすべて読み込む
+    // 読み込んだテンプレートは見本の通知で一度組み立て、誤りがあれば起動時にエラーとする
+    pub fn load(dir: impl AsRef<Path>, waitlist_claim_url: String) -> AppResult<Self> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(AppError::MailTemplateError(format!(
+                "template directory not found: {}",
+                dir.display()
+            )));
+        }
+
+        let mut env = Environment::new();
+        // 制御構文だけの行が、テキストの本文に空行として残らないようにする
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        add_partials(&mut env, dir, None)?;
+        let mut templated = HashSet::new();
+        for language in Language::iter() {
+            if dir.join(language.as_ref()).is_dir() {
+                add_partials(&mut env, dir, Some(language))?;
+            }
+            for kind in NotificationKind::iter() {
+                let prefix = template_prefix(language, kind);
+                if !dir.join(&prefix).is_dir() {
+                    continue;
+                }
+                add_template(&mut env, dir, format!("{prefix}/{SUBJECT_TEMPLATE}"))?;
+                add_template(&mut env, dir, format!("{prefix}/{TEXT_TEMPLATE}"))?;
+                let html = format!("{prefix}/{HTML_TEMPLATE}");
+                if dir.join(&html).is_file() {
+                    add_template(&mut env, dir, html)?;
+                }
+                templated.insert((language, kind));
+            }
+        }
+
+        let templates = Self {
+            env,
+            templated,
+            waitlist_claim_url,
+        };
+        for &(language, kind) in &templates.templated {
+            let sample = ReservationNotification::sample(kind, language, DEFAULT_TIMEZONE);
+            templates.render_content(kind, &sample)?;
+        }
+        tracing::info!(
+            dir = %dir.display(),
+            templates = templates.templated.len(),
+            "mail templates loaded"
+        );
+        Ok(templates)
+    }
+
+    // 宛先のユーザーの言語のテンプレートで、通知の件名と本文を組み立てる
+    pub fn render_content(
+        &self,
+        kind: NotificationKind,
+        n: &ReservationNotification,
+    ) -> AppResult<MailContent> {
+        if !self.templated.contains(&(n.language, kind)) {
+            return Ok(MailContent::new(kind, n, &self.waitlist_claim_url));
+        }
+        let prefix = template_prefix(n.language, kind);
+        let context = MailContext::new(n, &self.waitlist_claim_url);
+        let subject = self.render_template(&format!("{prefix}/{SUBJECT_TEMPLATE}"), &context)?;
+        let body = self.render_template(&format!("{prefix}/{TEXT_TEMPLATE}"), &context)?;
+        let html = format!("{prefix}/{HTML_TEMPLATE}");
+        let html = match self.env.get_template(&html) {
+            Ok(_) => Some(self.render_template(&html, &context)?),
+            Err(_) => None,
+        };
+        Ok(MailContent {
+            to: n.email.clone(),
+            // 件名は 1 行にまとめる
+            subject: subject.split_whitespace().collect::<Vec<_>>().join(" "),
+            body,
+            html,
+            calendar: calendar_attachment(kind, n),
+        })
+    }
+
+    fn render_template(&self, name: &str, context: &MailContext) -> AppResult<String> {
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(|e| AppError::MailTemplateError(format!("failed to render {name}: {e:#}")))
+    }
+}
+
+impl MailRenderer for MailTemplates {
+    fn render(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<RenderedMail> {
+        let MailContent {
+            subject,
+            body,
+            html,
+            ..
+        } = self.render_content(kind, notification)?;
+        Ok(RenderedMail {
+            subject,
+            text: body,
+            html,
+        })
+    }
+}
+
+fn template_prefix(language: Language, kind: NotificationKind) -> String {
+    format!("{}/{}", language.as_ref(), kind.as_ref())
+}
+
+// ディレクトリ直下のファイルを、共通部品のテンプレートとして読み込む
+fn add_partials(
+    env: &mut Environment<'static>,
+    dir: &Path,
+    language: Option<Language>,
+) -> AppResult<()> {
+    let prefix = language.map(|l| format!("{}/", l.as_ref())).unwrap_or_default();
+    let entries = std::fs::read_dir(dir.join(&prefix)).map_err(|e| {
+        AppError::MailTemplateError(format!("failed to read {}: {e}", dir.display()))
+    })?;
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        if file_name.ends_with(".html") || file_name.ends_with(".txt") {
+            add_template(env, dir, format!("{prefix}{file_name}"))?;
+        }
+    }
+    Ok(())
+}
+
+// テンプレートの名前は dir からの相対パスとする
+// 名前が .html で終わるテンプレートでは、値が自動で HTML エスケープされる
+fn add_template(env: &mut Environment<'static>, dir: &Path, name: String) -> AppResult<()> {
+    let path = dir.join(&name);
+    let source = std::fs::read_to_string(&path).map_err(|e| {
+        AppError::MailTemplateError(format!("failed to read {}: {e}", path.display()))
+    })?;
+    env.add_template_owned(name.clone(), source)
+        .map_err(|e| AppError::MailTemplateError(format!("failed to parse {name}: {e:#}")))
+}
+
+// テンプレートから参照できる値
+// 日時は宛先のユーザーのタイムゾーンと言語で、表示用の文字列にしておく
+#[derive(Serialize)]
+struct MailContext<'a> {
+    language: &'a str,
+    user_name: &'a str,
+    space_name: &'a str,
+    space_address: &'a str,
+    space_equipment: &'a str,
+    start: String,
+    end: String,
+    period: String,
+    timezone: &'static str,
+    // リマインダーを予約開始のどれくらい前に送るか
+    lead_time: Option<String>,
+    // 予約を終わらせた操作の出どころ（user, admin, space_deactivation など）と理由
+    end_source: Option<&'a str>,
+    end_reason: Option<&'a str>,
+    reservation_id: Option<String>,
+    // キャンセル待ちの案内で、予約を確定するためのリンクとその期限
+    claim_url: Option<String>,
+    claim_expires_at: Option<String>,
+}
+
+impl<'a> MailContext<'a> {
+    fn new(n: &'a ReservationNotification, waitlist_claim_url: &str) -> Self {
+        let tz = parse_timezone(&n.timezone);
+        Self {
+            language: n.language.as_ref(),
+            user_name: &n.user_name,
+            space_name: &n.space_name,
+            space_address: &n.space_address,
+            space_equipment: &n.space_equipment,
+            start: format_datetime(n.reservation_start_time, tz, n.language),
+            end: format_datetime(n.reservation_end_time, tz, n.language),
+            period: format_period(
+                n.reservation_start_time,
+                n.reservation_end_time,
+                tz,
+                n.language,
+            ),
+            timezone: tz.name(),
+            lead_time: n
+                .reminder_at
+                .map(|at| format_lead_time(n.language, n.reservation_start_time - at)),
+            end_source: n.end_source.as_ref().map(|source| source.as_ref()),
+            end_reason: n.end_reason.as_deref(),
+            reservation_id: n.reservation_id.map(|id| id.to_string()),
+            claim_url: n
+                .claim_token
+                .as_ref()
+                .map(|_| claim_url(waitlist_claim_url, n)),
+            claim_expires_at: n
+                .claim_expires_at
+                .map(|_| format_claim_expires_at(n, tz, n.language)),
+        }
+    }
+}
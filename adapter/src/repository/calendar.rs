@@ -0,0 +1,149 @@
+use crate::database::ConnectionPool;
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::model::{calendar::CalendarFeedToken, id::UserId};
+use kernel::repository::calendar::CalendarFeedRepository;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+#[derive(new)]
+pub struct CalendarFeedRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl CalendarFeedRepository for CalendarFeedRepositoryImpl {
+    async fn issue_token(&self, user_id: UserId) -> AppResult<CalendarFeedToken> {
+        // トークンは URL に含めるため、推測されにくい乱数を 16 進数の文字列にする
+        let row = sqlx::query!(
+            r#"
+                INSERT INTO calendar_feed_tokens (user_id, token)
+                VALUES ($1, encode(gen_random_bytes(24), 'hex'))
+                ON CONFLICT (user_id) DO UPDATE
+                SET
+                    token = EXCLUDED.token,
+                    created_at = CURRENT_TIMESTAMP(3)
+                RETURNING token, created_at
+            "#,
+            user_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(CalendarFeedToken {
+            token: row.token,
+            created_at: row.created_at.into(),
+        })
+    }
+
+    async fn find_token(&self, user_id: UserId) -> AppResult<Option<CalendarFeedToken>> {
+        let row = sqlx::query!(
+            r#"
+                SELECT token, created_at
+                FROM calendar_feed_tokens
+                WHERE user_id = $1
+            "#,
+            user_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.map(|row| CalendarFeedToken {
+            token: row.token,
+            created_at: row.created_at.into(),
+        }))
+    }
+
+    async fn revoke_token(&self, user_id: UserId) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM calendar_feed_tokens
+                WHERE user_id = $1
+            "#,
+            user_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::CalendarFeedTokenNotFound,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn find_user_id_by_token(&self, token: &str) -> AppResult<Option<UserId>> {
+        let row = sqlx::query!(
+            r#"
+                SELECT user_id
+                FROM calendar_feed_tokens
+                WHERE token = $1
+            "#,
+            token
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.map(|row| UserId::from(row.user_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::user::UserRepositoryImpl;
+    use kernel::model::user::event::CreateUser;
+    use kernel::repository::user::UserRepository;
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_calendar_feed_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        sqlx::query!(r#"INSERT INTO roles(role_name) VALUES ('Admin'), ('User');"#)
+            .execute(db.inner_ref())
+            .await?;
+        let user = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Test User".into(),
+                email: "test@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let repo = CalendarFeedRepositoryImpl::new(db);
+
+        assert!(repo.find_token(user.user_id).await?.is_none());
+
+        // 発行したトークンでユーザーを引ける
+        let first = repo.issue_token(user.user_id).await?;
+        assert_eq!(first.token.len(), 48);
+        assert_eq!(
+            repo.find_user_id_by_token(&first.token).await?,
+            Some(user.user_id)
+        );
+
+        // 再発行すると以前のトークンは使えなくなる
+        let second = repo.issue_token(user.user_id).await?;
+        assert_ne!(first.token, second.token);
+        assert!(repo.find_user_id_by_token(&first.token).await?.is_none());
+        assert_eq!(
+            repo.find_token(user.user_id).await?.map(|t| t.token),
+            Some(second.token.clone())
+        );
+
+        // 取り消すとトークンは使えなくなる
+        repo.revoke_token(user.user_id).await?;
+        assert!(repo.find_user_id_by_token(&second.token).await?.is_none());
+        assert!(matches!(
+            repo.revoke_token(user.user_id).await,
+            Err(AppError::EntityNotFound(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use crate::database::{
+    model::outbox::{OutboxMessageRow, PaginatedOutboxMessageRow},
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use derive_new::new;
+use kernel::model::{
+    id::{OutboxMessageId, ReservationId, WaitlistEntryId},
+    list::PaginatedList,
+    notification::NotificationKind,
+    outbox::{event::RecordOutboxFailure, OutboxListOptions, OutboxMessage, OutboxStatus},
+};
+use kernel::repository::outbox::OutboxRepository;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+#[derive(new)]
+pub struct OutboxRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl OutboxRepository for OutboxRepositoryImpl {
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Local>,
+    ) -> AppResult<Vec<OutboxMessage>> {
+        // 送信時刻を迎えたメッセージを古い順に取得し、
+        // next_attempt_at を lease_until まで進めることで、送信中に他の dispatcher が
+        // 同じメッセージを取得しないようにする。
+        // 送信の途中でプロセスが落ちた場合も、lease_until を過ぎれば再び送信対象となる。
+        // 試行回数はここで加算しておき、送信結果の記録に失敗しても回数が失われないようにする
+        let rows = sqlx::query_as!(
+            OutboxMessageRow,
+            r#"
+                UPDATE outbox_messages
+                SET
+                    attempts = attempts + 1,
+                    next_attempt_at = $2
+                WHERE outbox_message_id IN (
+                    SELECT outbox_message_id
+                    FROM outbox_messages
+                    WHERE status = 'pending'
+                      AND next_attempt_at <= CURRENT_TIMESTAMP
+                    ORDER BY next_attempt_at ASC
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING
+                    outbox_message_id,
+                    kind,
+                    status,
+                    attempts,
+                    last_error,
+                    next_attempt_at,
+                    created_at,
+                    sent_at AS "sent_at: DateTime<Local>",
+                    reservation_id AS "reservation_id: ReservationId",
+                    space_id,
+                    space_name,
+                    space_address,
+                    space_equipment,
+                    user_name,
+                    email,
+                    reminder_at AS "reminder_at: DateTime<Local>",
+                    reminder_times AS "reminder_times: Vec<DateTime<Local>>",
+                    reservation_start_time,
+                    reservation_end_time,
+                    claim_token,
+                    claim_expires_at AS "claim_expires_at: DateTime<Local>",
+                    end_source,
+                    end_reason,
+                    language,
+                    timezone
+            "#,
+            limit,
+            lease_until,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        rows.into_iter().map(OutboxMessage::try_from).collect()
+    }
+
+    async fn mark_sent(&self, outbox_message_id: OutboxMessageId) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE outbox_messages
+                SET
+                    status = $2,
+                    sent_at = CURRENT_TIMESTAMP(3),
+                    last_error = NULL
+                WHERE outbox_message_id = $1
+            "#,
+            outbox_message_id as _,
+            OutboxStatus::Sent.as_ref(),
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::OutboxMessageNotFound {
+                    outbox_message_id: outbox_message_id.to_string(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    async fn record_failure(&self, event: RecordOutboxFailure) -> AppResult<()> {
+        let RecordOutboxFailure {
+            outbox_message_id,
+            error,
+            next_attempt_at,
+        } = event;
+        // 再試行する場合は次回の送信時刻を、しない場合は dead を記録する
+        let (status, next_attempt_at) = match next_attempt_at {
+            Some(at) => (OutboxStatus::Pending, Some(at)),
+            None => (OutboxStatus::Dead, None),
+        };
+        let res = sqlx::query!(
+            r#"
+                UPDATE outbox_messages
+                SET
+                    status = $2,
+                    last_error = $3,
+                    next_attempt_at = COALESCE($4, next_attempt_at)
+                WHERE outbox_message_id = $1
+            "#,
+            outbox_message_id as _,
+            status.as_ref(),
+            error,
+            next_attempt_at,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::OutboxMessageNotFound {
+                    outbox_message_id: outbox_message_id.to_string(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    async fn find_all(
+        &self,
+        options: OutboxListOptions,
+    ) -> AppResult<PaginatedList<OutboxMessage>> {
+        let OutboxListOptions {
+            status,
+            limit,
+            offset,
+        } = options;
+        let status = status.map(|s| s.as_ref().to_string());
+
+        let rows: Vec<PaginatedOutboxMessageRow> = sqlx::query_as!(
+            PaginatedOutboxMessageRow,
+            r#"
+                SELECT
+                COUNT(*) OVER() AS "total!",
+                o.outbox_message_id AS outbox_message_id
+                FROM outbox_messages AS o
+                WHERE $1::VARCHAR IS NULL OR o.status = $1
+                ORDER BY o.created_at DESC
+                LIMIT $2
+                OFFSET $3
+            "#,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let total = rows.first().map(|r| r.total).unwrap_or_default(); // レコードが 1 つもないときは total も 0 にする
+        let outbox_message_ids = rows
+            .into_iter()
+            .map(|r| r.outbox_message_id)
+            .collect::<Vec<OutboxMessageId>>();
+
+        let items = sqlx::query_as!(
+            OutboxMessageRow,
+            r#"
+                SELECT
+                    outbox_message_id,
+                    kind,
+                    status,
+                    attempts,
+                    last_error,
+                    next_attempt_at,
+                    created_at,
+                    sent_at AS "sent_at: DateTime<Local>",
+                    reservation_id AS "reservation_id: ReservationId",
+                    space_id,
+                    space_name,
+                    space_address,
+                    space_equipment,
+                    user_name,
+                    email,
+                    reminder_at AS "reminder_at: DateTime<Local>",
+                    reminder_times AS "reminder_times: Vec<DateTime<Local>>",
+                    reservation_start_time,
+                    reservation_end_time,
+                    claim_token,
+                    claim_expires_at AS "claim_expires_at: DateTime<Local>",
+                    end_source,
+                    end_reason,
+                    language,
+                    timezone
+                FROM outbox_messages
+                WHERE outbox_message_id IN (SELECT * FROM UNNEST($1::uuid[]))
+                ORDER BY created_at DESC
+            "#,
+            &outbox_message_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(OutboxMessage::try_from)
+        .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn requeue(&self, outbox_message_id: OutboxMessageId) -> AppResult<()> {
+        // dead になったメッセージのみを対象とし、試行回数を数え直す
+        let res = sqlx::query!(
+            r#"
+                UPDATE outbox_messages
+                SET
+                    status = $2,
+                    attempts = 0,
+                    next_attempt_at = CURRENT_TIMESTAMP(3)
+                WHERE outbox_message_id = $1
+                  AND status = $3
+            "#,
+            outbox_message_id as _,
+            OutboxStatus::Pending.as_ref(),
+            OutboxStatus::Dead.as_ref(),
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::RetryableOutboxMessageNotFound {
+                    outbox_message_id: outbox_message_id.to_string(),
+                },
+            ));
+        }
+        Ok(())
+    }
+}
+
+// 予約に関する通知を outbox に積む
+// 予約の作成・終了と同じトランザクション内で呼び出すことで、
+// 予約の変更が確定した場合にのみ通知が送られるようにする。
+// 本文の組み立てに必要な値はこの時点の予約から複製する
+// 予約の終了・キャンセルの通知は、終わらせた操作の記録を含めるため、状態の変更の後に呼び出す
+pub(crate) async fn enqueue_reservation_notification(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    kind: NotificationKind,
+    reservation_id: ReservationId,
+) -> AppResult<()> {
+    enqueue_notification(tx, kind, reservation_id, None).await
+}
+
+// リマインダーを outbox に積む
+pub(crate) async fn enqueue_reminder_notification(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    reservation_id: ReservationId,
+    remind_at: DateTime<Local>,
+) -> AppResult<()> {
+    enqueue_notification(tx, NotificationKind::Reminder, reservation_id, Some(remind_at)).await
+}
+
+// 承認待ちの予約の承認依頼を、スペースの承認者それぞれに宛てて outbox に積む
+// 宛先は承認者だが、本文の組み立てに必要な予約の値はこの時点の予約から複製する
+pub(crate) async fn enqueue_approval_request_notification(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    reservation_id: ReservationId,
+) -> AppResult<()> {
+    let res = sqlx::query!(
+        r#"
+            INSERT INTO outbox_messages
+            (kind, reservation_id, space_id, space_name, space_address, space_equipment,
+            user_name, email, reservation_start_time, reservation_end_time, language, timezone)
+            SELECT $2, r.reservation_id, r.space_id, s.space_name, s.address, s.equipment,
+            u.user_name, u.email, r.reservation_start_time, r.reservation_end_time, u.language,
+            u.timezone
+            FROM reservations AS r
+            INNER JOIN spaces AS s ON r.space_id = s.space_id
+            INNER JOIN space_approvers AS a ON r.space_id = a.space_id
+            INNER JOIN users AS u ON a.user_id = u.user_id
+            WHERE r.reservation_id = $1
+        "#,
+        reservation_id as _,
+        NotificationKind::ApprovalRequest.as_ref(),
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if res.rows_affected() < 1 {
+        return Err(AppError::NoRowsAffectedError(
+            "No outbox_messages record has been created".into(),
+        ));
+    }
+    Ok(())
+}
+
+// キャンセル待ちの案内を outbox に積む
+// 予約はまだないため、本文の組み立てに必要な値はキャンセル待ちから複製する
+pub(crate) async fn enqueue_waitlist_offer_notification(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    waitlist_entry_id: WaitlistEntryId,
+) -> AppResult<()> {
+    let res = sqlx::query!(
+        r#"
+            INSERT INTO outbox_messages
+            (kind, space_id, space_name, space_address, space_equipment, user_name, email,
+            reservation_start_time, reservation_end_time, claim_token, claim_expires_at, language,
+            timezone)
+            SELECT $2, w.space_id, s.space_name, s.address, s.equipment, u.user_name, u.email,
+            w.reservation_start_time, w.reservation_end_time, w.claim_token, w.offer_expires_at,
+            u.language, u.timezone
+            FROM waitlist_entries AS w
+            INNER JOIN spaces AS s ON w.space_id = s.space_id
+            INNER JOIN users AS u ON w.user_id = u.user_id
+            WHERE w.waitlist_entry_id = $1
+              AND w.claim_token IS NOT NULL
+        "#,
+        waitlist_entry_id as _,
+        NotificationKind::WaitlistOffer.as_ref(),
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if res.rows_affected() < 1 {
+        return Err(AppError::NoRowsAffectedError(
+            "No outbox_messages record has been created".into(),
+        ));
+    }
+    Ok(())
+}
+
+async fn enqueue_notification(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    kind: NotificationKind,
+    reservation_id: ReservationId,
+    reminder_at: Option<DateTime<Local>>,
+) -> AppResult<()> {
+    let res = sqlx::query!(
+        r#"
+            INSERT INTO outbox_messages
+            (kind, reservation_id, space_id, space_name, space_address, space_equipment,
+            user_name, email, reminder_at, reminder_times, reservation_start_time,
+            reservation_end_time, end_source, end_reason, language, timezone)
+            SELECT $2, r.reservation_id, r.space_id, s.space_name, s.address, s.equipment,
+            u.user_name, u.email, $3,
+            ARRAY(
+                SELECT m.remind_at FROM reminders AS m
+                WHERE m.reservation_id = r.reservation_id
+                ORDER BY m.remind_at
+            ),
+            r.reservation_start_time, r.reservation_end_time,
+            r.end_source, r.end_reason, u.language, u.timezone
+            FROM reservations AS r
+            INNER JOIN spaces AS s ON r.space_id = s.space_id
+            INNER JOIN users AS u ON r.user_id = u.user_id
+            WHERE r.reservation_id = $1
+        "#,
+        reservation_id as _,
+        kind.as_ref(),
+        reminder_at,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if res.rows_affected() < 1 {
+        return Err(AppError::NoRowsAffectedError(
+            "No outbox_messages record has been created".into(),
+        ));
+    }
+    Ok(())
+}
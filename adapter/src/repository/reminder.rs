@@ -0,0 +1,289 @@
+use crate::database::ConnectionPool;
+use crate::repository::outbox::enqueue_reminder_notification;
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use derive_new::new;
+use kernel::model::id::{ReminderId, ReservationId};
+use kernel::repository::reminder::ReminderRepository;
+use shared::error::{AppError, AppResult};
+
+#[derive(new)]
+pub struct ReminderRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ReminderRepository for ReminderRepositoryImpl {
+    async fn enqueue_due(&self, limit: i64) -> AppResult<usize> {
+        let mut tx = self.db.begin().await?;
+
+        // 送信時刻を迎えた未送信のリマインダーを取得し、行ロックをかける
+        // 他のインスタンスがロック中のリマインダーは読み飛ばすため、同じリマインダーが二重に積まれることはない
+        // 利用停止中のスペースの予約と、確定していない予約は対象外とする
+        let due = sqlx::query!(
+            r#"
+                SELECT rm.reminder_id, rm.reservation_id, rm.remind_at
+                FROM reminders AS rm
+                INNER JOIN reservations AS r ON rm.reservation_id = r.reservation_id
+                INNER JOIN spaces AS s ON r.space_id = s.space_id
+                WHERE rm.remind_at <= CURRENT_TIMESTAMP
+                  AND NOT rm.is_sent
+                  AND s.is_active
+                  AND r.status IN ('confirmed', 'checked_in')
+                ORDER BY rm.remind_at ASC
+                LIMIT $1
+                FOR UPDATE OF rm SKIP LOCKED
+            "#,
+            limit,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if due.is_empty() {
+            return Ok(0);
+        }
+
+        let mut reminder_ids = Vec::with_capacity(due.len());
+        for row in due {
+            enqueue_reminder_notification(
+                &mut tx,
+                ReservationId::from(row.reservation_id),
+                row.remind_at.into(),
+            )
+            .await?;
+            reminder_ids.push(ReminderId::from(row.reminder_id));
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE reminders
+                SET
+                    is_sent = TRUE,
+                    sent_at = CURRENT_TIMESTAMP(3)
+                WHERE reminder_id IN (SELECT * FROM UNNEST($1::uuid[]))
+            "#,
+            &reminder_ids as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(reminder_ids.len())
+    }
+
+    async fn find_next_due_at(&self) -> AppResult<Option<DateTime<Local>>> {
+        let row = sqlx::query!(
+            r#"
+                SELECT MIN(rm.remind_at) AS next_due_at
+                FROM reminders AS rm
+                INNER JOIN reservations AS r ON rm.reservation_id = r.reservation_id
+                INNER JOIN spaces AS s ON r.space_id = s.space_id
+                WHERE NOT rm.is_sent
+                  AND s.is_active
+                  AND r.status IN ('confirmed', 'checked_in')
+            "#,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.next_due_at.map(DateTime::<Local>::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{
+        reservation::ReservationRepositoryImpl, space::SpaceRepositoryImpl,
+        user::UserRepositoryImpl,
+    };
+    use kernel::model::{
+        id::{SpaceId, UserId},
+        reservation::{
+            event::{CreateReservation, UpdateReturned},
+            status::{ReservationEndSource, ReservationStatus},
+        },
+        space::{
+            approval::ApprovalPolicy,
+            event::{CreateSpace, UpdateApprovalPolicy},
+        },
+        user::event::CreateUser,
+    };
+    use kernel::repository::{
+        reservation::ReservationRepository, space::SpaceRepository, user::UserRepository,
+    };
+    use chrono::Timelike;
+
+    // テスト用のユーザーとスペースを作成する
+    async fn setup(db: &ConnectionPool) -> anyhow::Result<(UserId, SpaceId)> {
+        sqlx::query!(r#"INSERT INTO roles(role_name) VALUES ('Admin'), ('User');"#)
+            .execute(db.inner_ref())
+            .await?;
+        let user = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Test User".into(),
+                email: "test@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        space_repo
+            .create(
+                CreateSpace {
+                    space_name: "Test SpaceName".into(),
+                    is_active: true,
+                    description: "Test Description".into(),
+                    capacity: 5,
+                    equipment: "Test Equipment".into(),
+                    address: "Test Address".into(),
+                },
+                user.user_id,
+            )
+            .await?;
+        let space_id = space_repo
+            .find_all(kernel::model::space::SpaceListOptions {
+                limit: 1,
+                offset: 0,
+            })
+            .await?
+            .items[0]
+            .space_id;
+        Ok((user.user_id, space_id))
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_enqueue_due_reminders_once(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+
+        // 1 日前のリマインダーは送信時刻が過ぎているため登録されない
+        let start = Local::now() + chrono::Duration::hours(2);
+        let reservation_repo = ReservationRepositoryImpl::new(db.clone());
+        let reservation_id = reservation_repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start,
+                start + chrono::Duration::hours(1),
+                vec![60 * 24, 60, 15],
+            ))
+            .await?;
+        let reservation = reservation_repo.find_by_id(reservation_id).await?;
+        let leads = reservation
+            .reminders
+            .iter()
+            .map(|r| r.lead_minutes)
+            .collect::<Vec<_>>();
+        assert_eq!(leads, vec![60, 15]);
+
+        // 1 時間前のリマインダーの送信時刻を過ぎたことにする
+        sqlx::query!(
+            "UPDATE reminders SET remind_at = CURRENT_TIMESTAMP - INTERVAL '1 minute' WHERE lead_minutes = 60"
+        )
+        .execute(&pool)
+        .await?;
+
+        let repo = ReminderRepositoryImpl::new(db);
+        assert_eq!(repo.enqueue_due(10).await?, 1);
+        assert_eq!(repo.enqueue_due(10).await?, 0);
+
+        let queued = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM outbox_messages WHERE kind = 'reminder'"#
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(queued, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_enqueue_due_skips_unconfirmed_reservations(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let reservation_repo = ReservationRepositoryImpl::new(db.clone());
+
+        // データベースはミリ秒までしか保持しないため、続けて取る予約の境目がずれないよう秒単位にそろえる
+        let start = (Local::now() + chrono::Duration::hours(2))
+            .with_nanosecond(0)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        let reserve = |offset: i32| {
+            CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start + hour * offset,
+                start + hour * (offset + 1),
+                vec![60],
+            )
+        };
+        let confirmed = reservation_repo.create(reserve(0)).await?;
+
+        // キャンセルした予約
+        let cancelled = reservation_repo.create(reserve(1)).await?;
+        reservation_repo
+            .update_returned(UpdateReturned::new(
+                cancelled,
+                space_id,
+                user_id,
+                ReservationEndSource::User,
+                None,
+                ReservationStatus::CancelledByUser,
+                Local::now(),
+                start + hour,
+                start + hour * 2,
+                None,
+            ))
+            .await?;
+
+        // 承認待ちの予約
+        SpaceRepositoryImpl::new(db.clone())
+            .update_approval_policy(UpdateApprovalPolicy {
+                space_id,
+                approval_policy: ApprovalPolicy {
+                    requires_approval: true,
+                    approvers: vec![user_id],
+                },
+                requested_user: user_id,
+            })
+            .await?;
+        let pending = reservation_repo.create(reserve(2)).await?;
+        assert_eq!(
+            reservation_repo.find_by_id(pending).await?.status,
+            ReservationStatus::Pending
+        );
+
+        // すべてのリマインダーの送信時刻を過ぎたことにする
+        sqlx::query!("UPDATE reminders SET remind_at = CURRENT_TIMESTAMP - INTERVAL '1 minute'")
+            .execute(&pool)
+            .await?;
+
+        // 確定した予約のリマインダーのみを積む
+        let repo = ReminderRepositoryImpl::new(db);
+        assert_eq!(repo.enqueue_due(10).await?, 1);
+        assert_eq!(repo.enqueue_due(10).await?, 0);
+
+        let queued = sqlx::query_scalar!(
+            r#"
+                SELECT reservation_id AS "reservation_id!"
+                FROM outbox_messages
+                WHERE kind = 'reminder'
+            "#
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(queued, vec![confirmed.raw()]);
+
+        Ok(())
+    }
+}
@@ -1,17 +1,65 @@
 use crate::database::{
-    model::reservation::{ReservationRow, ReservationStateRow, ReturnedReservationRow},
+    is_exclusion_violation, is_serialization_failure,
+    model::{
+        reminder::ReminderRow,
+        space::BookingPolicyRow,
+        reservation::{
+            LockedReservationRow, PaginatedReservationRow, ReservationRow,
+            ReservationSeriesRow,
+        },
+    },
     ConnectionPool,
 };
+use crate::repository::outbox::enqueue_reservation_notification;
+use crate::repository::webhook::{enqueue_reservation_webhook, enqueue_space_webhook};
 use async_trait::async_trait;
+use chrono::{DateTime, Local, Weekday};
 
 use derive_new::new;
+use kernel::model::notification::NotificationKind;
+use kernel::model::webhook::WebhookEvent;
+use kernel::model::reminder::Reminder;
 use kernel::model::reservation::{
-    event::{CreateReservation, UpdateReturned},
-    Reservation,
+    event::{
+        CancelReservationSeries, CheckInReservation, CreateReservation, CreateReservationSeries,
+        DeactivateAllSpaces, DeactivateSpace, ReviewReservation, UpdateReservation,
+        UpdateReservationSeries, UpdateReturned,
+    },
+    series::{
+        CreatedReservationSeries, ReservationSeries, SeriesConflictPolicy, SeriesScope,
+        SkippedOccurrence,
+    },
+    status::{ReservationEndSource, ReservationEnding, ReservationStatus},
+    Reservation, ReservationListOptions,
+};
+use kernel::model::list::{PaginatedList, SortOrder};
+use kernel::model::space::{
+    check_in::{CheckInPolicy, NO_SHOW_LOOKBACK_DAYS},
+    policy::{BookingPolicy, BookingRequest},
+    schedule::format_minutes,
+};
+use kernel::model::id::{SpaceId, ReservationId, ReservationSeriesId, UserId, WaitlistEntryId};
+use kernel::model::waitlist::{
+    event::{CancelWaitlistEntry, ClaimWaitlistOffer, CreateWaitlistEntry},
+    WaitlistEntry,
 };
-use kernel::model::id::{SpaceId, ReservationId, UserId};
 use kernel::repository::reservation::ReservationRepository;
-use shared::error::{AppError, AppResult};
+use shared::{
+    error::{AppError, AppResult, ErrorCode},
+    i18n::ErrorMessage,
+};
+use std::collections::HashMap;
+use std::future::Future;
+
+mod approval;
+mod waitlist;
+
+// archive_ended で取る advisory lock のキー
+// 他の用途の advisory lock と重ならない値にしておく
+const ARCHIVE_ENDED_LOCK_KEY: i64 = 0x7265_7365_7276_0001;
+
+// 直列化に失敗したトランザクションをやり直す回数の上限
+const MAX_SERIALIZATION_RETRIES: usize = 3;
 
 #[derive(new)]
 pub struct ReservationRepositoryImpl {
@@ -22,140 +70,95 @@ pub struct ReservationRepositoryImpl {
 impl ReservationRepository for ReservationRepositoryImpl {
     // 予約操作を行う
     async fn create(&self, event: CreateReservation) -> AppResult<ReservationId> {
-        let mut tx = self.db.begin().await?;
-
-        // トランザクション分離レベルを SERIALIZABLE に設定する
-        self.set_transaction_serializable(&mut tx).await?;
-
-
-        // -----------------------------
-        // ① 開始 < 終了 チェック
-        // -----------------------------
-        if event.reservation_start_time >= event.reservation_end_time {
-            return Err(AppError::UnprocessableEntity(
-                "予約開始時刻は予約終了時刻より前である必要があります。".into(),
-            ));
-        }
-        // -----------------------------
-        // ② 開始時刻が現在より未来であることのチェック
-        // -----------------------------
-        let now = chrono::Local::now();
-
-        if event.reservation_start_time <= now {
-            return Err(AppError::UnprocessableEntity(
-                "予約開始時刻は現在時刻より後である必要があります。".into(),
-            ));
-        }
-        // 事前のチェックとして、以下を調べる。
-        // - 指定のスペース ID をもつスペースが存在するか
-        // - 存在した場合、その時間帯は予約中ではないか
-        //
-        // 上記の両方が Yes だった場合、このブロック以降の処理に進む
-        {
-            //
-        // ① スペースの存在確認 ＋ is_active チェック
-        //
-        let space_row = sqlx::query!(
-            r#"
-            SELECT space_id, is_active
-            FROM spaces
-            WHERE space_id = $1
-            "#,
-            event.space_id as _
+        self.retry_on_conflict(
+            event.space_id,
+            &[(event.reservation_start_time, event.reservation_end_time)],
+            &[],
+            || self.try_create(&event),
         )
-        .fetch_optional(&mut *tx)
         .await
-        .map_err(AppError::SpecificOperationError)?;
+    }
 
-        let space = match space_row {
-            None => {
-                return Err(AppError::EntityNotFound(format!(
-                    "スペース（{}）が見つかりませんでした。",
-                    event.space_id
-                )))
-            }
-            Some(s) => s,
-        };
+    // 予約時間を変更する
+    async fn update(&self, event: UpdateReservation) -> AppResult<()> {
+        self.retry_on_conflict(
+            event.space_id,
+            &[(event.reservation_start_time, event.reservation_end_time)],
+            &[event.reservation_id],
+            || self.try_update(&event),
+        )
+        .await
+    }
 
-        if !space.is_active {
-            return Err(AppError::UnprocessableEntity(format!(
-                "スペース（{}）は現在利用できません（is_active = false）",
-                event.space_id
-            )));
-        }
+    // 繰り返し予約を作成する
+    async fn create_series(
+        &self,
+        event: CreateReservationSeries,
+    ) -> AppResult<CreatedReservationSeries> {
+        let occurrences = event
+            .recurrence
+            .occurrences(event.reservation_start_time, event.reservation_end_time)?;
+        self.retry_on_conflict(event.space_id, &occurrences, &[], || {
+            self.try_create_series(&event, &occurrences)
+        })
+        .await
+    }
 
-        //
-        // ② 希望予約時間帯が既存予約と重なっていないか確認
-        //    重複条件：
-        //        existing.start < new.end AND new.start < existing.end
-        //
-        let overlap = sqlx::query!(
-            r#"
-            SELECT reservation_id
-            FROM reservations
-            WHERE space_id = $1
-              AND reservation_start_time < $3
-              AND $2 < reservation_end_time
-            LIMIT 1;
-            "#,
-            event.space_id as _,
-            event.reservation_start_time,
-            event.reservation_end_time,
+    // 繰り返し予約の予約時間を、指定の範囲の回についてまとめて変更する
+    async fn update_series(&self, event: UpdateReservationSeries) -> AppResult<()> {
+        self.retry_on_conflict(
+            event.space_id,
+            &[(event.reservation_start_time, event.reservation_end_time)],
+            &[event.reservation_id],
+            || self.try_update_series(&event),
         )
-        .fetch_optional(&mut *tx)
         .await
-        .map_err(AppError::SpecificOperationError)?;
+    }
 
-        if overlap.is_some() {
-            return Err(AppError::UnprocessableEntity(format!(
-                "スペース（{}）は指定時間帯にすでに予約が存在します。",
-                event.space_id
-            )));
-        }
+    // 繰り返し予約を、指定の範囲の回についてまとめてキャンセルする
+    async fn cancel_series(&self, event: CancelReservationSeries) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
 
-        //
-        // ここまでのチェックを通過すれば予約を作成する
-        //
-        }
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
 
-        // 予約処理を行う、すなわち reservations テーブルにレコードを追加する
-        let reservation_id = ReservationId::new();
-        let res = sqlx::query!(
-            r#"
-                INSERT INTO reservations
-                (reservation_id, space_id, user_id, reserved_at,
-                reservation_start_time,reservation_end_time,
-                reminder_at,reminder_is_already)
-                VALUES ($1, $2, $3, $4,$5,$6,$7,$8)
-                ;
-            "#,
-            reservation_id as _,
-            event.space_id as _,
-            event.reserved_by as _,
-            event.reserved_at,
-            event.reservation_start_time,
-            event.reservation_end_time,
-            event.reminder_at,
-            event.reminder_is_already,
-        )
-        .execute(&mut *tx)
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        let anchor = self
+            .lock_reservation(&mut tx, event.reservation_id, event.space_id)
+            .await?;
+        let targets = self
+            .lock_series_targets(&mut tx, event.reservation_id, &anchor, event.scope)
+            .await?;
 
-        if res.rows_affected() < 1 {
-            return Err(AppError::NoRowsAffectedError(
-                "No reservation record has been created".into(),
-            ));
+        let ending = ReservationEnding {
+            ended_by: Some(event.requested_by),
+            source: event.source,
+            reason: event.reason,
+        };
+        for target in &targets {
+            self.transition_status(
+                &mut tx,
+                target.reservation_id,
+                event.source.cancelled_status(),
+                event.cancelled_at,
+                Some(&ending),
+            )
+            .await?;
         }
 
-        tx.commit().await.map_err(AppError::TransactionError)?;
+        // 回ごとに通知すると件数が多くなるため、キャンセルの通知は指定した回の分のみ積む
+        enqueue_reservation_notification(
+            &mut tx,
+            NotificationKind::Cancellation,
+            event.reservation_id,
+        )
+        .await?;
 
-    
+        tx.commit().await.map_err(AppError::TransactionError)?;
 
-        Ok(reservation_id)
+        Ok(())
     }
 
-    // 予約終了操作を行う
+    // 予約を利用終了またはキャンセルの状態にする
     async fn update_returned(&self, event: UpdateReturned) -> AppResult<()> {
         let mut tx = self.db.begin().await?;
 
@@ -189,10 +192,9 @@ impl ReservationRepository for ReservationRepositoryImpl {
             .map_err(AppError::SpecificOperationError)?;
     
             if space_row.is_none() {
-                return Err(AppError::EntityNotFound(format!(
-                    "スペース（{}）が見つかりませんでした。",
-                    event.space_id
-                )));
+                return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                    space_id: event.space_id.to_string(),
+                }));
             }
     
             //
@@ -203,6 +205,8 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 SELECT reservation_id, user_id, reservation_end_time
                 FROM reservations
                 WHERE reservation_id = $1 AND space_id = $2
+                  AND status IN ('pending', 'confirmed', 'checked_in')
+                FOR UPDATE
                 "#,
                 event.reservation_id as _,
                 event.space_id as _
@@ -211,11 +215,13 @@ impl ReservationRepository for ReservationRepositoryImpl {
             .await
             .map_err(AppError::SpecificOperationError)?;
     
-            let Some(res_row) = existing_reservation else {
-                return Err(AppError::EntityNotFound(format!(
-                    "予約（ID={}）がスペース（{}）に存在しません。",
-                    event.reservation_id, event.space_id
-                )));
+            let Some(_res_row) = existing_reservation else {
+                return Err(AppError::EntityNotFound(
+                    ErrorMessage::ReservationNotFoundInSpace {
+                        reservation_id: event.reservation_id.to_string(),
+                        space_id: event.space_id.to_string(),
+                    },
+                ));
             };
     
             //
@@ -230,64 +236,403 @@ impl ReservationRepository for ReservationRepositoryImpl {
             // }
         }
 
-        // データベース上の予約終了操作として、
-        // reservations テーブルにある該当予約 ID のレコードを、
-        // returned_at を追加して returned_reservations テーブルに INSERT する
-        // is_cancel は event.is_cancel をそのまま使用
-        let res = sqlx::query!(
+        // 利用終了またはキャンセルの状態にし、終わらせた操作を記録する
+        if event.status.is_active() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidEndStatus {
+                    status: event.status.as_ref().into(),
+                },
+            ));
+        }
+        let ending = ReservationEnding {
+            ended_by: Some(event.returned_by),
+            source: event.source,
+            reason: event.reason,
+        };
+        self.transition_status(
+            &mut tx,
+            event.reservation_id,
+            event.status,
+            event.returned_at,
+            Some(&ending),
+        )
+        .await?;
+
+        // 予約終了の通知は、状態の変更と同じトランザクションで outbox に積んでおく
+        if let Some(kind) = event.notification {
+            enqueue_reservation_notification(&mut tx, kind, event.reservation_id).await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // スペースを停止し、そのスペースの終わっていない予約をすべてキャンセルする
+    // 途中で失敗した場合に、停止したのに一部の予約だけが残ることのないよう、
+    // 停止・キャンセル・通知をまとめて 1 つのトランザクションで行う
+    async fn deactivate_space(&self, event: DeactivateSpace) -> AppResult<usize> {
+        if !event.status.is_cancelled() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidDeactivationStatus {
+                    status: event.status.as_ref().into(),
+                },
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+        let ending = ReservationEnding {
+            ended_by: Some(event.requested_by),
+            source: ReservationEndSource::SpaceDeactivation,
+            reason: event.reason,
+        };
+        let cancelled = self
+            .deactivate_space_in(
+                &mut tx,
+                event.space_id,
+                event.status,
+                &ending,
+                event.deactivated_at,
+            )
+            .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(cancelled)
+    }
+
+    // すべてのスペースを停止し、すべての終わっていない予約をキャンセルする
+    // 一部のスペースだけが停止したままにならないよう、すべてのスペースを 1 つのトランザクションで停止する
+    async fn deactivate_all_spaces(&self, event: DeactivateAllSpaces) -> AppResult<usize> {
+        if !event.status.is_cancelled() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidDeactivationStatus {
+                    status: event.status.as_ref().into(),
+                },
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        // 処理中にスペースが削除されないよう、すべてのスペースの行ロックを先に取る
+        let space_ids = sqlx::query_scalar!(
+            r#"
+                SELECT space_id AS "space_id: SpaceId"
+                FROM spaces
+                ORDER BY space_id ASC
+                FOR UPDATE
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let ending = ReservationEnding {
+            ended_by: Some(event.requested_by),
+            source: ReservationEndSource::SpaceDeactivation,
+            reason: event.reason,
+        };
+        let mut cancelled = 0;
+        for space_id in space_ids {
+            cancelled += self
+                .deactivate_space_in(
+                    &mut tx,
+                    space_id,
+                    event.status,
+                    &ending,
+                    event.deactivated_at,
+                )
+                .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(cancelled)
+    }
+
+    // 終了時刻を過ぎた予約を利用終了の状態にする
+    async fn archive_ended(&self, limit: i64) -> AppResult<usize> {
+        let mut tx = self.db.begin().await?;
+
+        // 複数のインスタンスで同時に実行しても二重に処理しないよう、
+        // トランザクションの間だけ有効な advisory lock を取る。
+        // 取れなかった場合は他のインスタンスが処理中なので何もしない
+        let locked = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_xact_lock($1) AS "locked!""#,
+            ARCHIVE_ENDED_LOCK_KEY,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !locked {
+            return Ok(0);
+        }
+
+        // 終了時刻を過ぎた、予約確定またはチェックイン済みの予約のみを取得する
+        // 承認待ちのまま終了時刻を過ぎた予約は、承認の処理に任せる
+        // 利用者による予約終了操作と競合しないよう、行ロックも取っておく
+        let ended = sqlx::query!(
             r#"
-                INSERT INTO returned_reservations
-                (reservation_id, space_id, user_id, reserved_at, 
-                returned_at,reservation_start_time,reservation_end_time,reminder_at,
-                is_cancel,reminder_is_already)
-                SELECT reservation_id, space_id, user_id, reserved_at, $2,
-                reservation_start_time,reservation_end_time,reminder_at,$3,reminder_is_already
+                SELECT reservation_id
                 FROM reservations
-                WHERE reservation_id = $1
-                ;
+                WHERE reservation_end_time <= CURRENT_TIMESTAMP
+                  AND status IN ('confirmed', 'checked_in')
+                ORDER BY reservation_end_time ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
             "#,
-            event.reservation_id as _,
-            event.returned_at,
-            event.is_cancel, 
+            limit,
         )
-        .execute(&mut *tx)
+        .fetch_all(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
-        if res.rows_affected() < 1 {
-            return Err(AppError::NoRowsAffectedError(
-                "No returned_reservations record has been created".into(),
-            ));
+        let completed_at = chrono::Local::now();
+        let ending = ReservationEnding::watcher();
+        for row in &ended {
+            self.transition_status(
+                &mut tx,
+                row.reservation_id.into(),
+                ReservationStatus::Completed,
+                completed_at,
+                Some(&ending),
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(ended.len())
+    }
+
+    // スペースの承認者のみが、承認待ちの予約を承認・却下できる
+    async fn review(&self, event: ReviewReservation) -> AppResult<()> {
+        self.try_review(event).await
+    }
+
+    // 承認されないまま予約開始時刻を過ぎた予約は、時間帯を押さえたままにしないよう期限切れにする
+    async fn expire_pending_reviews(&self, limit: i64) -> AppResult<usize> {
+        self.try_expire_pending_reviews(limit).await
+    }
+
+    // 予約したユーザー本人が、スペースの設定に従ってチェックインする
+    async fn check_in(&self, event: CheckInReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let current = self
+            .lock_reservation(&mut tx, event.reservation_id, event.space_id)
+            .await?;
+        // チェックインできるのは予約したユーザー本人のみ
+        if current.user_id != event.requested_by {
+            return Err(AppError::ForbiddenOperation);
         }
 
-        // 上記処理が成功したら reservations テーブルから該当予約 ID のレコードを削除する
-        let delete_res = sqlx::query!(
+        let row = sqlx::query!(
             r#"
-                DELETE FROM reservations WHERE reservation_id = $1;
+                SELECT
+                    s.check_in_grace_minutes,
+                    s.check_in_code,
+                    r.checked_in_at AS "checked_in_at: DateTime<Local>"
+                FROM reservations AS r
+                INNER JOIN spaces AS s ON s.space_id = r.space_id
+                WHERE r.reservation_id = $1
             "#,
-            event.reservation_id as _,
+            event.reservation_id as _
         )
-        .execute(&mut *tx)
+        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
-        if delete_res.rows_affected() < 1 {
-            return Err(AppError::NoRowsAffectedError(
-                "No reservation record has been deleted".into(),
+        if let Some(checked_in_at) = row.checked_in_at {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::AlreadyCheckedIn {
+                    checked_in_at,
+                },
             ));
         }
+        current.status()?.transition_to(ReservationStatus::CheckedIn)?;
+        let policy = CheckInPolicy {
+            grace_minutes: row.check_in_grace_minutes,
+            code: row.check_in_code,
+        };
+        policy.verify(
+            event.checked_in_at,
+            current.reservation_start_time,
+            current.reservation_end_time,
+            event.code.as_deref(),
+        )?;
+
+        self.transition_status(
+            &mut tx,
+            event.reservation_id,
+            ReservationStatus::CheckedIn,
+            event.checked_in_at,
+            None,
+        )
+        .await?;
 
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
     }
 
-    // すべての未予約終了の予約情報を取得する
-    async fn find_unreturned_all(&self) -> AppResult<Vec<Reservation>> {
-        // reservations テーブルにあるレコードを全件抽出する
-        // spaces テーブルと INNER JOIN し、スペースの情報も一緒に抽出する
-        // 出力するレコードは、予約日の古い順に並べる
-        sqlx::query_as!(
+    // チェックインが必須のスペースで、猶予時間を過ぎてもチェックインのない予約を解放する
+    // 解放した予約は no_show の状態として履歴に残し、予約のルールで回数を数える
+    async fn release_no_shows(&self, limit: i64) -> AppResult<usize> {
+        let mut tx = self.db.begin().await?;
+
+        // 終了時刻を過ぎた予約は archive_ended で利用終了の状態にするため、ここでは扱わない
+        // 利用者のチェックインと競合しないよう、行ロックも取っておく
+        let overdue = sqlx::query!(
+            r#"
+                SELECT r.reservation_id
+                FROM reservations AS r
+                INNER JOIN spaces AS s ON s.space_id = r.space_id
+                WHERE s.check_in_grace_minutes IS NOT NULL
+                  AND r.status = 'confirmed'
+                  AND r.reservation_start_time
+                      + make_interval(mins => s.check_in_grace_minutes) <= CURRENT_TIMESTAMP
+                  AND r.reservation_end_time > CURRENT_TIMESTAMP
+                ORDER BY r.reservation_start_time ASC
+                LIMIT $1
+                FOR UPDATE OF r SKIP LOCKED
+            "#,
+            limit,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let released_at = chrono::Local::now();
+        let ending = ReservationEnding::watcher();
+        for row in &overdue {
+            let reservation_id = row.reservation_id.into();
+            self.transition_status(
+                &mut tx,
+                reservation_id,
+                ReservationStatus::NoShow,
+                released_at,
+                Some(&ending),
+            )
+            .await?;
+            enqueue_reservation_notification(&mut tx, NotificationKind::NoShow, reservation_id)
+                .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(overdue.len())
+    }
+
+    // 予約の入っている時間帯のキャンセル待ちに登録する
+    async fn join_waitlist(&self, event: CreateWaitlistEntry) -> AppResult<WaitlistEntryId> {
+        self.try_join_waitlist(&event).await
+    }
+
+    // ユーザー ID に紐づくキャンセル待ちを、登録の新しい順に取得する
+    async fn find_waitlist_by_user_id(&self, user_id: UserId) -> AppResult<Vec<WaitlistEntry>> {
+        self.find_waitlist_entries_by_user_id(user_id).await
+    }
+
+    // キャンセル待ちを取り下げる
+    async fn cancel_waitlist_entry(&self, event: CancelWaitlistEntry) -> AppResult<()> {
+        self.try_cancel_waitlist_entry(&event).await
+    }
+
+    // 案内された時間帯の予約を確定する
+    async fn claim_waitlist_offer(&self, event: ClaimWaitlistOffer) -> AppResult<ReservationId> {
+        let (space_id, reservation_start_time, reservation_end_time) =
+            self.find_offered_period(&event.claim_token).await?;
+        self.retry_on_conflict(
+            space_id,
+            &[(reservation_start_time, reservation_end_time)],
+            &[],
+            || self.try_claim_waitlist_offer(&event),
+        )
+        .await
+    }
+
+    // 期限を過ぎた案内と、希望の時間帯を過ぎたキャンセル待ちを期限切れにする
+    async fn expire_waitlist_entries(&self, limit: i64) -> AppResult<usize> {
+        self.try_expire_waitlist_entries(limit).await
+    }
+
+    // 終わった予約も含めて、条件に合う予約を並べ替えて取得する
+    async fn find_all(
+        &self,
+        options: ReservationListOptions,
+    ) -> AppResult<PaginatedList<Reservation>> {
+        let ReservationListOptions {
+            space_id,
+            user_id,
+            from,
+            to,
+            statuses,
+            is_cancel,
+            sort,
+            order,
+            limit,
+            offset,
+        } = options;
+        let statuses = statuses.iter().map(|s| s.as_ref().to_string()).collect::<Vec<_>>();
+
+        // 並べ替えの基準が同じ予約は、予約 ID の順に並べてページの境界を安定させる
+        let rows: Vec<PaginatedReservationRow> = sqlx::query_as!(
+            PaginatedReservationRow,
+            r#"
+                SELECT
+                COUNT(*) OVER() AS "total!",
+                r.reservation_id
+                FROM reservations AS r
+                WHERE ($1::uuid IS NULL OR r.space_id = $1)
+                  AND ($2::uuid IS NULL OR r.user_id = $2)
+                  AND ($3::timestamptz IS NULL OR r.reservation_end_time > $3)
+                  AND ($4::timestamptz IS NULL OR r.reservation_start_time < $4)
+                  AND (cardinality($5::varchar[]) = 0 OR r.status = ANY($5))
+                  AND ($6::bool IS NULL
+                       OR (r.status IN ('cancelled_by_user', 'cancelled_by_admin',
+                                        'cancelled_by_owner', 'cancelled')) = $6)
+                ORDER BY
+                  CASE WHEN $8 THEN
+                    CASE $7::varchar
+                      WHEN 'end_time' THEN r.reservation_end_time
+                      WHEN 'reserved_at' THEN r.reserved_at
+                      ELSE r.reservation_start_time
+                    END
+                  END DESC,
+                  CASE WHEN NOT $8 THEN
+                    CASE $7::varchar
+                      WHEN 'end_time' THEN r.reservation_end_time
+                      WHEN 'reserved_at' THEN r.reserved_at
+                      ELSE r.reservation_start_time
+                    END
+                  END ASC,
+                  r.reservation_id ASC
+                LIMIT $9
+                OFFSET $10
+            "#,
+            space_id as Option<SpaceId>,
+            user_id as Option<UserId>,
+            from,
+            to,
+            &statuses,
+            is_cancel,
+            sort.as_ref(),
+            order == SortOrder::Desc,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let total = rows.first().map(|r| r.total).unwrap_or_default(); // レコードが 1 つもないときは total も 0 にする
+        let reservation_ids = rows
+            .into_iter()
+            .map(|r| r.reservation_id)
+            .collect::<Vec<ReservationId>>();
+
+        let rows = sqlx::query_as!(
             ReservationRow,
             r#"
                 SELECT
@@ -299,8 +644,19 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 r.reservation_start_time,
                 r.reservation_end_time,
                 r.reserved_at,
-                r.reminder_at,
-                r.reminder_is_already,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
                 s.space_name,
                 s.is_active,
                 s.capacity,
@@ -308,47 +664,38 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 s.address
                 FROM reservations AS r
                 INNER JOIN spaces AS s ON r.space_id = s.space_id
-                INNER JOIN users AS u ON r.user_id  = u.user_id
-                ORDER BY r.reserved_at ASC
-                ;
+                INNER JOIN users AS u ON r.user_id = u.user_id
+                WHERE r.reservation_id = ANY($1)
             "#,
+            &reservation_ids as _
         )
         .fetch_all(self.db.inner_ref())
         .await
-        .map(|rows| rows.into_iter().map(Reservation::from).collect())
-        .map_err(AppError::SpecificOperationError)
-    }
-
-    // reminder_is_alreadyを更新する
-    async fn update_reminder_is_already(&self,reservation_id:ReservationId,reminder_is_already:bool) -> AppResult<()> {
-        // reservations テーブルの該当データのreminder_is_alreadyを更新する
-        let res = sqlx::query!(
-            r#"
-                UPDATE reservations
-                SET
-                    reminder_is_already = $1
-                WHERE reservation_id = $2
-            "#,
-            reminder_is_already,
-            reservation_id as _
-            )
-        .execute(self.db.inner_ref())
-        .await
         .map_err(AppError::SpecificOperationError)?;
-        if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified reservation not found".into()));
-        }
-
-        Ok(())
-    }
 
+        // 2 つ目のクエリは順序を保証しないため、1 つ目のクエリの順に並べ直す
+        let mut items = self.attach_reminders(rows).await?;
+        let position = reservation_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect::<HashMap<_, _>>();
+        items.sort_by_key(|r| position.get(&r.reservation_id).copied());
 
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
 
-    // ユーザー ID に紐づく未予約終了の予約情報を取得する
-    async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Reservation>> {
-        // find_unreturned_all の SQL に
-        // ユーザー ID で絞り込む WHERE 句を追加したものである
-        sqlx::query_as!(
+    // すべての終わっていない予約を取得する
+    async fn find_unreturned_all(&self) -> AppResult<Vec<Reservation>> {
+        // reservations テーブルから、終わっていない状態のレコードを全件抽出する
+        // spaces テーブルと INNER JOIN し、スペースの情報も一緒に抽出する
+        // 出力するレコードは、予約日の古い順に並べる
+        let rows = sqlx::query_as!(
             ReservationRow,
             r#"
                 SELECT
@@ -360,8 +707,19 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 r.reservation_start_time,
                 r.reservation_end_time,
                 r.reserved_at,
-                r.reminder_at,
-                r.reminder_is_already,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
                 s.space_name,
                 s.is_active,
                 s.capacity,
@@ -370,76 +728,129 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 FROM reservations AS r
                 INNER JOIN spaces AS s ON r.space_id = s.space_id
                 INNER JOIN users AS u ON r.user_id  = u.user_id
-                WHERE r.user_id = $1
+                WHERE r.status IN ('pending', 'confirmed', 'checked_in')
                 ORDER BY r.reserved_at ASC
                 ;
             "#,
-            user_id as _
         )
         .fetch_all(self.db.inner_ref())
         .await
-        .map(|rows| rows.into_iter().map(Reservation::from).collect())
-        .map_err(AppError::SpecificOperationError)
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.attach_reminders(rows).await
     }
 
-    // スペースの予約履歴（予約終了済みも含む）を取得する
-    async fn find_history_by_space_id(&self, space_id: SpaceId) -> AppResult<Vec<Reservation>> {
-        // このメソッドでは、予約中・予約終了済みの両方を取得して
-        // スペースに対する予約履歴の一覧として返す必要がある。
-        // そのため、未予約終了の予約情報と予約終了済みの予約情報をそれぞれ取得し、
-        // 未予約終了の予約情報があれば Vec に挿入して返す、という実装とする。
-        // 未予約終了の予約情報を取得
-        let reservation: Option<Reservation> = self.find_unreturned_by_space_id(space_id).await?;
-        // 予約終了済みの予約情報を取得
-        let mut reservation_histories: Vec<Reservation> = sqlx::query_as!(
-            ReturnedReservationRow,
+    // ユーザー ID に紐づく終わっていない予約を取得する
+    async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Reservation>> {
+        // find_unreturned_all の SQL に
+        // ユーザー ID で絞り込む WHERE 句を追加したものである
+        let rows = sqlx::query_as!(
+            ReservationRow,
             r#"
                 SELECT
-                rr.reservation_id,
-                rr.space_id,
-                rr.user_id,
+                r.reservation_id,
+                r.space_id,
+                r.user_id,
                 u.user_name,
                 u.email,
-                rr.is_cancel,
-                rr.reminder_is_already,
-                rr.reserved_at,
-                rr.reminder_at,
-                rr.returned_at,
-                rr.reservation_start_time,
-                rr.reservation_end_time,
+                r.reservation_start_time,
+                r.reservation_end_time,
+                r.reserved_at,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
                 s.space_name,
                 s.is_active,
                 s.capacity,
                 s.equipment,
                 s.address
-                FROM returned_reservations AS rr
-                INNER JOIN spaces AS s ON rr.space_id = s.space_id
-                INNER JOIN users AS u ON rr.user_id = u.user_id
-                WHERE s.space_id = $1
-                ORDER BY rr.reserved_at DESC
+                FROM reservations AS r
+                INNER JOIN spaces AS s ON r.space_id = s.space_id
+                INNER JOIN users AS u ON r.user_id  = u.user_id
+                WHERE r.user_id = $1
+                  AND r.status IN ('pending', 'confirmed', 'checked_in')
+                ORDER BY r.reserved_at ASC
+                ;
             "#,
-            space_id as _
+            user_id as _
         )
         .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(Reservation::from)
-        .collect();
+        .map_err(AppError::SpecificOperationError)?;
 
-        // 予約中である場合は予約終了済みの履歴の先頭に追加する
-        if let Some(co) = reservation {
-            reservation_histories.insert(0, co);
-        }
+        self.attach_reminders(rows).await
+    }
+
+    // スペースの予約履歴（終わった予約も含む）を、予約日時の新しい順に取得する
+    async fn find_history_by_space_id(
+        &self,
+        space_id: SpaceId,
+        statuses: &[ReservationStatus],
+    ) -> AppResult<Vec<Reservation>> {
+        // statuses が空の場合は、すべての状態の予約を取得する
+        let statuses = statuses.iter().map(|s| s.as_ref().to_string()).collect::<Vec<_>>();
+        let rows = sqlx::query_as!(
+            ReservationRow,
+            r#"
+                SELECT
+                r.reservation_id,
+                r.space_id,
+                r.user_id,
+                u.user_name,
+                u.email,
+                r.reservation_start_time,
+                r.reservation_end_time,
+                r.reserved_at,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
+                s.space_name,
+                s.is_active,
+                s.capacity,
+                s.equipment,
+                s.address
+                FROM reservations AS r
+                INNER JOIN spaces AS s ON r.space_id = s.space_id
+                INNER JOIN users AS u ON r.user_id = u.user_id
+                WHERE r.space_id = $1
+                  AND (cardinality($2::varchar[]) = 0 OR r.status = ANY($2))
+                ORDER BY r.reserved_at DESC
+            "#,
+            space_id as _,
+            &statuses
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
 
-        Ok(reservation_histories)
+        self.attach_reminders(rows).await
     }
 
-    // スペースの予約（予約終了済みを含まない）を取得する
+    // スペースの終わっていない予約を取得する
     async fn find_reservations_by_space_id(&self, space_id: SpaceId) -> AppResult<Vec<Reservation>> {
         // このメソッドでは、予約中を取得して
         // スペースに対する予約の一覧として返す必要がある。
-        let row : Vec<Reservation>= sqlx::query_as!(
+        let rows = sqlx::query_as!(
             ReservationRow,
             r#"
                 SELECT
@@ -451,8 +862,19 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 r.reservation_start_time,
                 r.reservation_end_time,
                 r.reserved_at,
-                r.reminder_at,
-                r.reminder_is_already,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
                 s.space_name,
                 s.is_active,
                 s.capacity,
@@ -462,23 +884,20 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 INNER JOIN spaces AS s ON r.space_id = s.space_id
                 INNER JOIN users AS u ON r.user_id  = u.user_id
                 WHERE r.space_id = $1
+                  AND r.status IN ('pending', 'confirmed', 'checked_in')
                 ;
             "#,
             space_id as _
         )
         .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(Reservation::from)
-        .collect();
-        
-    
-        Ok(row.into())
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.attach_reminders(rows).await
     }
 
     async fn find_by_id(&self, reservation_id: ReservationId) -> AppResult<Reservation>{
-        let row : Reservation= sqlx::query_as!(
+        let row = sqlx::query_as!(
             ReservationRow,
             r#"
                 SELECT
@@ -490,8 +909,19 @@ impl ReservationRepository for ReservationRepositoryImpl {
                 r.reservation_start_time,
                 r.reservation_end_time,
                 r.reserved_at,
-                r.reminder_at,
-                r.reminder_is_already,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
                 s.space_name,
                 s.is_active,
                 s.capacity,
@@ -505,35 +935,59 @@ impl ReservationRepository for ReservationRepositoryImpl {
             "#,
             reservation_id as _
         )
-        .fetch_one(self.db.inner_ref())
+        .fetch_optional(self.db.inner_ref())
         .await
-        .map(Reservation::from)
-        .map_err(AppError::SpecificOperationError)?;
-        
-    
-        Ok(row.into())
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::ReservationNotFound {
+                reservation_id: reservation_id.to_string(),
+            })
+        })?;
+
+        let reminders = self
+            .find_reminders(&[reservation_id])
+            .await?
+            .remove(&reservation_id)
+            .unwrap_or_default();
+
+        row.into_reservation(reminders)
     }
-}
 
-impl ReservationRepositoryImpl {
-    // create, update_returned メソッドでのトランザクションを利用するにあたり
-    // トランザクション分離レベルを SERIALIZABLE にするために
-    // 内部的に使うメソッド
-    async fn set_transaction_serializable(
+    async fn find_series_by_id(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-    ) -> AppResult<()> {
-        sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
-            .execute(&mut **tx)
-            .await
-            .map_err(AppError::SpecificOperationError)?;
-        Ok(())
-    }
+        reservation_series_id: ReservationSeriesId,
+    ) -> AppResult<ReservationSeries> {
+        let series = sqlx::query_as!(
+            ReservationSeriesRow,
+            r#"
+                SELECT
+                reservation_series_id,
+                space_id,
+                user_id,
+                frequency,
+                repeat_interval,
+                by_weekday,
+                occurrence_count,
+                until AS "until: DateTime<Local>",
+                reservation_start_time,
+                reservation_end_time,
+                created_at
+                FROM reservation_series
+                WHERE reservation_series_id = $1
+            "#,
+            reservation_series_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::ReservationSeriesNotFound {
+                reservation_series_id: reservation_series_id.to_string(),
+            })
+        })?;
 
-    // find_history_by_space_id で未予約終了の予約情報を取得するために
-    // 内部的に使うメソッド
-    async fn find_unreturned_by_space_id(&self, space_id: SpaceId) -> AppResult<Option<Reservation>> {
-        let res = sqlx::query_as!(
+        // 予約中の回を開始時刻の早い順に取得する
+        let rows = sqlx::query_as!(
             ReservationRow,
             r#"
                 SELECT
@@ -545,8 +999,19 @@ impl ReservationRepositoryImpl {
                 r.reservation_start_time,
                 r.reservation_end_time,
                 r.reserved_at,
-                r.reminder_at,
-                r.reminder_is_already,
+                r.reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                r.status,
+                r.confirmed_at AS "confirmed_at: DateTime<Local>",
+                r.checked_in_at AS "checked_in_at: DateTime<Local>",
+                r.completed_at AS "completed_at: DateTime<Local>",
+                r.cancelled_at AS "cancelled_at: DateTime<Local>",
+                r.no_show_at AS "no_show_at: DateTime<Local>",
+                r.rejected_at AS "rejected_at: DateTime<Local>",
+                r.expired_at AS "expired_at: DateTime<Local>",
+                r.reviewed_by AS "reviewed_by: UserId",
+                r.ended_by AS "ended_by: UserId",
+                r.end_source,
+                r.end_reason,
                 s.space_name,
                 s.is_active,
                 s.capacity,
@@ -555,15 +1020,2825 @@ impl ReservationRepositoryImpl {
                 FROM reservations AS r
                 INNER JOIN spaces AS s ON r.space_id = s.space_id
                 INNER JOIN users AS u ON r.user_id  = u.user_id
-                WHERE s.space_id = $1
+                WHERE r.reservation_series_id = $1
+                  AND r.status IN ('pending', 'confirmed', 'checked_in')
+                ORDER BY r.reservation_start_time ASC
             "#,
-            space_id as _,
+            reservation_series_id as _
         )
-        .fetch_optional(self.db.inner_ref())
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        series.into_series(self.attach_reminders(rows).await?)
+    }
+}
+
+impl ReservationRepositoryImpl {
+    // スペースを停止し、そのスペースの終わっていない予約をキャンセルして、キャンセルした件数を返す
+    // スペースを先に停止しておくことで、キャンセルで空いた時間帯がキャンセル待ちのユーザーに案内されないようにする
+    async fn deactivate_space_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        space_id: SpaceId,
+        status: ReservationStatus,
+        ending: &ReservationEnding,
+        deactivated_at: DateTime<Local>,
+    ) -> AppResult<usize> {
+        let was_active = sqlx::query_scalar!(
+            r#"
+                UPDATE spaces AS s
+                SET is_active = FALSE
+                FROM (
+                    SELECT space_id, is_active
+                    FROM spaces
+                    WHERE space_id = $1
+                    FOR UPDATE
+                ) AS prev
+                WHERE s.space_id = prev.space_id
+                RETURNING prev.is_active
+            "#,
+            space_id as _
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })
+        })?;
+        if was_active {
+            enqueue_space_webhook(tx, WebhookEvent::SpaceDeactivated, space_id).await?;
+        }
+
+        let reservation_ids = sqlx::query_scalar!(
+            r#"
+                SELECT reservation_id AS "reservation_id: ReservationId"
+                FROM reservations
+                WHERE space_id = $1
+                  AND status IN ('pending', 'confirmed', 'checked_in')
+                ORDER BY reservation_start_time ASC
+                FOR UPDATE
+            "#,
+            space_id as _
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        for &reservation_id in &reservation_ids {
+            self.transition_status(
+                tx,
+                reservation_id,
+                status,
+                deactivated_at,
+                Some(ending),
+            )
+            .await?;
+            // キャンセルの通知は outbox に積まれ、バックグラウンドで送信される
+            enqueue_reservation_notification(tx, NotificationKind::Cancellation, reservation_id)
+                .await?;
+        }
+
+        Ok(reservation_ids.len())
+    }
+
+    // create の 1 回分の試行
+    async fn try_create(&self, event: &CreateReservation) -> AppResult<ReservationId> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
+
+        // 予約できる時間帯かどうかを確認する
+        self.check_reservable(
+            &mut tx,
+            event.space_id,
+            event.reservation_start_time,
+            event.reservation_end_time,
+            &[],
+        )
+        .await?;
+        self.check_booking_policy(
+            &mut tx,
+            event.space_id,
+            event.reserved_by,
+            event.reservation_start_time,
+            event.reservation_end_time,
+            &[],
+        )
+        .await?;
+        self.check_waitlist_hold(
+            &mut tx,
+            event.space_id,
+            event.reserved_by,
+            event.reservation_start_time,
+            event.reservation_end_time,
+        )
+        .await?;
+
+        let reservation_id = ReservationId::new();
+        let status = self
+            .insert_reservation(&mut tx, reservation_id, event, None)
+            .await?;
+
+        // 予約受付の通知を同じトランザクションで outbox に積む
+        self.enqueue_created_notification(
+            &mut tx,
+            NotificationKind::Confirmation,
+            reservation_id,
+            status,
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(reservation_id)
+    }
+
+    // update の 1 回分の試行
+    async fn try_update(&self, event: &UpdateReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
+
+        // 変更対象の予約が指定のスペースに存在するか確認し、行ロックを取る
+        let current = self
+            .lock_reservation(&mut tx, event.reservation_id, event.space_id)
+            .await?;
+
+        // すでに始まっている予約は変更できない
+        if current.reservation_start_time <= chrono::Local::now() {
+            return Err(AppError::rule_violation(
+                ErrorCode::ReservationAlreadyStarted,
+                ErrorMessage::ReservationAlreadyStarted {
+                    reservation_id: event.reservation_id.to_string(),
+                },
+            ));
+        }
+
+        // 予約作成時と同じチェックを、変更対象の予約自身を除いて行う
+        self.check_reservable(
+            &mut tx,
+            event.space_id,
+            event.reservation_start_time,
+            event.reservation_end_time,
+            &[event.reservation_id],
+        )
+        .await?;
+        // ルールは変更したユーザーではなく、予約したユーザーに対して確認する
+        self.check_booking_policy(
+            &mut tx,
+            event.space_id,
+            current.user_id,
+            event.reservation_start_time,
+            event.reservation_end_time,
+            &[event.reservation_id],
+        )
+        .await?;
+        self.check_waitlist_hold(
+            &mut tx,
+            event.space_id,
+            current.user_id,
+            event.reservation_start_time,
+            event.reservation_end_time,
+        )
+        .await?;
+
+        self.reschedule(
+            &mut tx,
+            event.reservation_id,
+            event.requested_by,
+            (current.reservation_start_time, current.reservation_end_time),
+            (event.reservation_start_time, event.reservation_end_time),
+        )
+        .await?;
+
+        // 予約時間の変更の通知を同じトランザクションで outbox に積む
+        enqueue_reservation_notification(
+            &mut tx,
+            NotificationKind::Reschedule,
+            event.reservation_id,
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // create_series の 1 回分の試行
+    async fn try_create_series(
+        &self,
+        event: &CreateReservationSeries,
+        occurrences: &[(DateTime<Local>, DateTime<Local>)],
+    ) -> AppResult<CreatedReservationSeries> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
+
+        let reservation_series_id = ReservationSeriesId::new();
+        let recurrence = &event.recurrence;
+        let by_weekday = recurrence
+            .by_weekday
+            .iter()
+            .map(|weekday| weekday.number_from_monday() as i32)
+            .collect::<Vec<_>>();
+        sqlx::query!(
+            r#"
+                INSERT INTO reservation_series
+                (reservation_series_id, space_id, user_id, frequency, repeat_interval,
+                by_weekday, occurrence_count, until,
+                reservation_start_time, reservation_end_time)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            reservation_series_id as _,
+            event.space_id as _,
+            event.reserved_by as _,
+            recurrence.frequency.as_ref(),
+            recurrence.interval,
+            &by_weekday,
+            recurrence.count,
+            recurrence.until,
+            event.reservation_start_time,
+            event.reservation_end_time,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 各回を 1 件の予約として作成する
+        // 先に作成した回も重なりの確認の対象になるため、回どうしが重なる場合も検出できる
+        let mut reservation_ids = Vec::new();
+        let mut status = ReservationStatus::Confirmed;
+        let mut skipped = Vec::new();
+        for &(reservation_start_time, reservation_end_time) in occurrences {
+            match self
+                .check_reservable(
+                    &mut tx,
+                    event.space_id,
+                    reservation_start_time,
+                    reservation_end_time,
+                    &[],
+                )
+                .await
+            {
+                Ok(()) => {}
+                Err(AppError::Conflict {
+                    conflicting_reservation_id: Some(conflicting),
+                    ..
+                }) if event.conflict_policy == SeriesConflictPolicy::SkipConflicts => {
+                    skipped.push(SkippedOccurrence {
+                        reservation_start_time,
+                        reservation_end_time,
+                        conflicting_reservation_id: conflicting.into(),
+                    });
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+            // 先に作成した回も件数の上限に数える
+            self.check_booking_policy(
+                &mut tx,
+                event.space_id,
+                event.reserved_by,
+                reservation_start_time,
+                reservation_end_time,
+                &[],
+            )
+            .await?;
+            self.check_waitlist_hold(
+                &mut tx,
+                event.space_id,
+                event.reserved_by,
+                reservation_start_time,
+                reservation_end_time,
+            )
+            .await?;
+
+            let reservation_id = ReservationId::new();
+            let occurrence = CreateReservation::new(
+                event.space_id,
+                event.reserved_by,
+                event.reserved_at,
+                reservation_start_time,
+                reservation_end_time,
+                event.reminder_lead_minutes.clone(),
+            );
+            // すべての回が同じスペースの予約のため、承認待ちになるかどうかも各回で同じになる
+            status = self
+                .insert_reservation(
+                    &mut tx,
+                    reservation_id,
+                    &occurrence,
+                    Some(reservation_series_id),
+                )
+                .await?;
+            reservation_ids.push(reservation_id);
+        }
+
+        let Some(&first) = reservation_ids.first() else {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::AllOccurrencesConflict,
+            ));
+        };
+
+        // 回ごとに通知すると件数が多くなるため、予約受付の通知は最初の回の分のみ積む
+        // 承認待ちの場合、承認者は scope を指定してまとめて承認・却下できる
+        self.enqueue_created_notification(&mut tx, NotificationKind::Confirmation, first, status)
+            .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(CreatedReservationSeries {
+            reservation_series_id,
+            reservation_ids,
+            skipped,
+        })
+    }
+
+    // update_series の 1 回分の試行
+    async fn try_update_series(&self, event: &UpdateReservationSeries) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
+
+        // 複数の回をまとめてずらすと、更新の途中で回どうしが一時的に重なることがある
+        // 重なりはコミット時にまとめて確認する
+        sqlx::query!("SET CONSTRAINTS reservations_space_period_excl DEFERRED")
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+        let anchor = self
+            .lock_reservation(&mut tx, event.reservation_id, event.space_id)
+            .await?;
+
+        // すでに始まっている回は変更できない
+        if anchor.reservation_start_time <= chrono::Local::now() {
+            return Err(AppError::rule_violation(
+                ErrorCode::ReservationAlreadyStarted,
+                ErrorMessage::ReservationAlreadyStarted {
+                    reservation_id: event.reservation_id.to_string(),
+                },
+            ));
+        }
+
+        // 指定した回の変更量を、対象のすべての回に同じだけ適用する
+        let start_offset = event.reservation_start_time - anchor.reservation_start_time;
+        let end_offset = event.reservation_end_time - anchor.reservation_end_time;
+        let targets = self
+            .lock_series_targets(&mut tx, event.reservation_id, &anchor, event.scope)
+            .await?;
+        let target_ids = targets
+            .iter()
+            .map(|target| target.reservation_id)
+            .collect::<Vec<_>>();
+
+        for target in &targets {
+            let new_period = (
+                target.reservation_start_time + start_offset,
+                target.reservation_end_time + end_offset,
+            );
+            // 一緒にずらす回との重なりは、コミット時に排他制約で確認する
+            self.check_reservable(&mut tx, event.space_id, new_period.0, new_period.1, &target_ids)
+                .await?;
+            self.check_booking_policy(
+                &mut tx,
+                event.space_id,
+                target.user_id,
+                new_period.0,
+                new_period.1,
+                &target_ids,
+            )
+            .await?;
+            self.check_waitlist_hold(
+                &mut tx,
+                event.space_id,
+                target.user_id,
+                new_period.0,
+                new_period.1,
+            )
+            .await?;
+            self.reschedule(
+                &mut tx,
+                target.reservation_id,
+                event.requested_by,
+                (target.reservation_start_time, target.reservation_end_time),
+                new_period,
+            )
+            .await?;
+        }
+
+        // 回ごとに通知すると件数が多くなるため、予約時間の変更の通知は指定した回の分のみ積む
+        enqueue_reservation_notification(
+            &mut tx,
+            NotificationKind::Reschedule,
+            event.reservation_id,
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // SERIALIZABLE のトランザクションで予約の時間帯を確定させる操作を実行する
+    // - 直列化に失敗した場合は MAX_SERIALIZATION_RETRIES 回までやり直す
+    // - やり直しても失敗した場合や、排他制約に違反した場合は Conflict を返す
+    //   periods は競合相手を探す時間帯、exclude_reservation_ids は競合相手から除く予約
+    async fn retry_on_conflict<T, F, Fut>(
+        &self,
+        space_id: SpaceId,
+        periods: &[(DateTime<Local>, DateTime<Local>)],
+        exclude_reservation_ids: &[ReservationId],
+        op: F,
+    ) -> AppResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = AppResult<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            match op().await {
+                Err(e) if is_serialization_failure(&e) && retries < MAX_SERIALIZATION_RETRIES => {
+                    retries += 1;
+                    tracing::debug!(retries, "retrying serializable transaction");
+                }
+                Err(e) if is_serialization_failure(&e) || is_exclusion_violation(&e) => {
+                    // トランザクションは巻き戻っているため、コミット済みの予約から競合相手を探す
+                    let mut conflicting = None;
+                    for &(reservation_start_time, reservation_end_time) in periods {
+                        conflicting = find_overlapping_reservation(
+                            self.db.inner_ref(),
+                            space_id,
+                            reservation_start_time,
+                            reservation_end_time,
+                            exclude_reservation_ids,
+                        )
+                        .await?;
+                        if conflicting.is_some() {
+                            break;
+                        }
+                    }
+                    return Err(AppError::Conflict {
+                        message: match conflicting {
+                            Some(_) => ErrorMessage::ReservationOverlap {
+                                space_id: space_id.to_string(),
+                            },
+                            None => ErrorMessage::ConcurrentOperation,
+                        },
+                        conflicting_reservation_id: conflicting.map(ReservationId::raw),
+                    });
+                }
+                res => return res,
+            }
+        }
+    }
+
+    // 予約を reservations テーブルに追加し、リマインダーを登録する
+    async fn insert_reservation(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: ReservationId,
+        event: &CreateReservation,
+        reservation_series_id: Option<ReservationSeriesId>,
+    ) -> AppResult<ReservationStatus> {
+        // 予約処理を行う、すなわち reservations テーブルにレコードを追加する
+        // 承認が必要なスペースでは承認待ち、それ以外は予約確定の状態で作成する
+        let status = sqlx::query_scalar!(
+            r#"
+                INSERT INTO reservations
+                (reservation_id, space_id, user_id, reserved_at,
+                reservation_start_time,reservation_end_time,
+                reservation_series_id, status, confirmed_at)
+                SELECT $1, s.space_id, $3, $4::timestamptz, $5, $6, $7,
+                    CASE WHEN s.requires_approval THEN 'pending' ELSE 'confirmed' END,
+                    CASE WHEN s.requires_approval THEN NULL ELSE $4::timestamptz END
+                FROM spaces AS s
+                WHERE s.space_id = $2
+                RETURNING status;
+            "#,
+            reservation_id as _,
+            event.space_id as _,
+            event.reserved_by as _,
+            event.reserved_at,
+            event.reservation_start_time,
+            event.reservation_end_time,
+            reservation_series_id as _,
+        )
+        .fetch_optional(&mut **tx)
         .await
         .map_err(AppError::SpecificOperationError)?
-        .map(Reservation::from);
+        .ok_or_else(|| {
+            AppError::NoRowsAffectedError("No reservation record has been created".into())
+        })?;
+        let status = ReservationStatus::try_from(status.as_str())
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+
+        // リマインダーを登録する
+        // 承認待ちの予約のリマインダーは、承認されるまで送らない
+        self.insert_reminders(
+            tx,
+            reservation_id,
+            event.reservation_start_time,
+            &event.reminder_lead_minutes,
+        )
+        .await?;
+
+        // 通知メールは繰り返し予約の最初の回の分のみ積むが、webhook は回ごとに積む
+        enqueue_reservation_webhook(tx, WebhookEvent::ReservationCreated, reservation_id).await?;
 
-        Ok(res)
+        Ok(status)
     }
-}
\ No newline at end of file
+
+    // 終わっていない予約が指定のスペースに存在するか確認し、行ロックを取る
+    async fn lock_reservation(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: ReservationId,
+        space_id: SpaceId,
+    ) -> AppResult<LockedReservationRow> {
+        let current = sqlx::query_as!(
+            LockedReservationRow,
+            r#"
+                SELECT
+                reservation_id,
+                user_id,
+                reservation_start_time,
+                reservation_end_time,
+                reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                status
+                FROM reservations
+                WHERE reservation_id = $1 AND space_id = $2
+                  AND status IN ('pending', 'confirmed', 'checked_in')
+                FOR UPDATE
+            "#,
+            reservation_id as _,
+            space_id as _
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        current.ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::ReservationNotFoundInSpace {
+                reservation_id: reservation_id.to_string(),
+                space_id: space_id.to_string(),
+            })
+        })
+    }
+
+    // 繰り返し予約の変更・キャンセルの対象となる回を、開始時刻の早い順に取得し行ロックを取る
+    // 指定した回以外は、まだ始まっていない回のみを対象とする
+    async fn lock_series_targets(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: ReservationId,
+        anchor: &LockedReservationRow,
+        scope: SeriesScope,
+    ) -> AppResult<Vec<LockedReservationRow>> {
+        let reservation_series_id = match (scope, anchor.reservation_series_id) {
+            (SeriesScope::This, _) => None,
+            (_, Some(reservation_series_id)) => Some(reservation_series_id),
+            (_, None) => {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::NotReservationSeries {
+                        reservation_id: reservation_id.to_string(),
+                    },
+                ))
+            }
+        };
+
+        sqlx::query_as!(
+            LockedReservationRow,
+            r#"
+                SELECT
+                reservation_id,
+                user_id,
+                reservation_start_time,
+                reservation_end_time,
+                reservation_series_id AS "reservation_series_id: ReservationSeriesId",
+                status
+                FROM reservations
+                WHERE (reservation_id = $1
+                       OR (reservation_series_id = $2
+                           AND reservation_start_time > CURRENT_TIMESTAMP
+                           AND ($3 OR reservation_start_time >= $4)))
+                  AND status IN ('pending', 'confirmed', 'checked_in')
+                ORDER BY reservation_start_time ASC
+                FOR UPDATE
+            "#,
+            reservation_id as _,
+            reservation_series_id as _,
+            scope == SeriesScope::All,
+            anchor.reservation_start_time,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    // 予約時間を変更し、変更履歴の記録とリマインダーの再登録を行う
+    async fn reschedule(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: ReservationId,
+        changed_by: UserId,
+        (previous_start_time, previous_end_time): (DateTime<Local>, DateTime<Local>),
+        (new_start_time, new_end_time): (DateTime<Local>, DateTime<Local>),
+    ) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET
+                    reservation_start_time = $2,
+                    reservation_end_time = $3
+                WHERE reservation_id = $1
+            "#,
+            reservation_id as _,
+            new_start_time,
+            new_end_time,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No reservation record has been updated".into(),
+            ));
+        }
+
+        // 変更履歴を記録する
+        sqlx::query!(
+            r#"
+                INSERT INTO reservation_changes
+                (reservation_id, changed_by, previous_start_time, previous_end_time,
+                new_start_time, new_end_time)
+                VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            reservation_id as _,
+            changed_by as _,
+            previous_start_time,
+            previous_end_time,
+            new_start_time,
+            new_end_time,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // リマインダーは同じタイミング（予約開始の何分前か）のまま、送信時刻を計算し直す
+        // 変更前の時刻で送信済みのものも、変更後の時刻に合わせて送り直す
+        let reminder_lead_minutes = sqlx::query_scalar!(
+            r#"
+                DELETE FROM reminders WHERE reservation_id = $1
+                RETURNING lead_minutes
+            "#,
+            reservation_id as _,
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.insert_reminders(tx, reservation_id, new_start_time, &reminder_lead_minutes)
+            .await?;
+
+        enqueue_reservation_webhook(tx, WebhookEvent::ReservationUpdated, reservation_id).await
+    }
+
+    // create, update_returned メソッドでのトランザクションを利用するにあたり
+    // トランザクション分離レベルを SERIALIZABLE にするために
+    // 内部的に使うメソッド
+    async fn set_transaction_serializable(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> AppResult<()> {
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    // スペースの予約のルールに合っているかを確認する
+    // ルールは予約するユーザーのロールに対するものがあればそれを、なければスペースのものを使う
+    // 件数の上限には、exclude_reservation_ids の予約を数えない
+    async fn check_booking_policy(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        space_id: SpaceId,
+        user_id: UserId,
+        reservation_start_time: DateTime<Local>,
+        reservation_end_time: DateTime<Local>,
+        exclude_reservation_ids: &[ReservationId],
+    ) -> AppResult<()> {
+        let row = sqlx::query_as!(
+            BookingPolicyRow,
+            r#"
+            SELECT
+                p.space_id,
+                r.role_name AS "role_name?",
+                p.min_duration_minutes,
+                p.max_duration_minutes,
+                p.slot_granularity_minutes,
+                p.max_days_in_advance,
+                p.max_active_reservations,
+                p.max_reservations_per_week,
+                p.max_recent_no_shows
+            FROM space_booking_policies AS p
+            LEFT JOIN roles AS r ON r.role_id = p.role_id
+            WHERE p.space_id = $1
+              AND (p.role_id IS NULL
+                   OR p.role_id = (SELECT role_id FROM users WHERE user_id = $2))
+            ORDER BY p.role_id IS NULL ASC
+            LIMIT 1
+            "#,
+            space_id as _,
+            user_id as _
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let Some(row) = row else {
+            return Ok(());
+        };
+        let (_, policy) = row.into_policy()?;
+        if policy == BookingPolicy::default() {
+            return Ok(());
+        }
+
+        // 時刻の区切りと週の区切りは、スペースのタイムゾーンで判定する
+        // 週の予約数には、終わった予約も含める（キャンセルされたものは除く）
+        // チェックインしなかった回数は、すべてのスペースの予約について数える
+        let stats = sqlx::query!(
+            r#"
+            SELECT
+                EXTRACT(EPOCH FROM ($3 AT TIME ZONE s.timezone)::time)::bigint
+                    AS "start_seconds_of_day!",
+                (
+                    SELECT COUNT(*)
+                    FROM reservations AS r
+                    WHERE r.space_id = s.space_id
+                      AND r.user_id = $2
+                      AND r.status IN ('pending', 'confirmed', 'checked_in')
+                      AND r.reservation_end_time > CURRENT_TIMESTAMP
+                      AND r.reservation_id <> ALL($4)
+                ) AS "active_reservations!",
+                (
+                    SELECT COUNT(*)
+                    FROM reservations AS r
+                    WHERE r.space_id = s.space_id
+                      AND r.user_id = $2
+                      AND r.status NOT IN ('cancelled_by_user', 'cancelled_by_admin',
+                                           'cancelled_by_owner', 'cancelled')
+                      AND r.reservation_id <> ALL($4)
+                      AND date_trunc('week', r.reservation_start_time AT TIME ZONE s.timezone)
+                          = date_trunc('week', $3 AT TIME ZONE s.timezone)
+                ) AS "reservations_in_week!",
+                (
+                    SELECT COUNT(*)
+                    FROM reservations AS r
+                    WHERE r.user_id = $2
+                      AND r.status = 'no_show'
+                      AND r.reservation_start_time > CURRENT_TIMESTAMP - make_interval(days => $5::int)
+                ) AS "recent_no_shows!"
+            FROM spaces AS s
+            WHERE s.space_id = $1
+            "#,
+            space_id as _,
+            user_id as _,
+            reservation_start_time,
+            exclude_reservation_ids as _,
+            NO_SHOW_LOOKBACK_DAYS as i32
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        policy.check(&BookingRequest {
+            reservation_start_time,
+            reservation_end_time,
+            now: chrono::Local::now(),
+            start_seconds_of_day: stats.start_seconds_of_day,
+            active_reservations: stats.active_reservations,
+            reservations_in_week: stats.reservations_in_week,
+            recent_no_shows: stats.recent_no_shows,
+        })
+    }
+
+    // create, update で共通の事前チェック
+    // 以下をすべて満たす場合のみ Ok を返す
+    // - 予約開始時刻が予約終了時刻より前である
+    // - 予約開始時刻が現在より未来である
+    // - 指定のスペース ID をもつスペースが存在し、利用可能である
+    // - その時間帯に他の予約がない（exclude_reservation_ids に指定した予約は除く）
+    async fn check_reservable(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        space_id: SpaceId,
+        reservation_start_time: DateTime<Local>,
+        reservation_end_time: DateTime<Local>,
+        exclude_reservation_ids: &[ReservationId],
+    ) -> AppResult<()> {
+        // -----------------------------
+        // ① 開始 < 終了 チェック
+        // -----------------------------
+        if reservation_start_time >= reservation_end_time {
+            return Err(AppError::rule_violation(
+                ErrorCode::InvalidReservationPeriod,
+                ErrorMessage::InvalidReservationPeriod,
+            ));
+        }
+        // -----------------------------
+        // ② 開始時刻が現在より未来であることのチェック
+        // -----------------------------
+        let now = chrono::Local::now();
+
+        if reservation_start_time <= now {
+            return Err(AppError::rule_violation(
+                ErrorCode::ReservationStartInPast,
+                ErrorMessage::ReservationStartInPast,
+            ));
+        }
+
+        //
+        // ③ スペースの存在確認 ＋ is_active チェック
+        //
+        let space_row = sqlx::query!(
+            r#"
+            SELECT space_id, is_active
+            FROM spaces
+            WHERE space_id = $1
+            "#,
+            space_id as _
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let space = match space_row {
+            None => {
+                return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                    space_id: space_id.to_string(),
+                }))
+            }
+            Some(s) => s,
+        };
+
+        if !space.is_active {
+            return Err(AppError::rule_violation(
+                ErrorCode::SpaceInactive,
+                ErrorMessage::SpaceInactive {
+                    space_id: space_id.to_string(),
+                },
+            ));
+        }
+
+        //
+        // ④ 予約を受け付けない期間と重なっていないか確認
+        //
+        let blackout = sqlx::query!(
+            r#"
+            SELECT start_time, end_time, reason
+            FROM space_blackouts
+            WHERE space_id = $1
+              AND tstzrange(start_time, end_time, '[)') && tstzrange($2, $3, '[)')
+            ORDER BY start_time ASC
+            LIMIT 1
+            "#,
+            space_id as _,
+            reservation_start_time,
+            reservation_end_time
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if let Some(blackout) = blackout {
+            return Err(AppError::rule_violation(
+                ErrorCode::SpaceBlackout,
+                ErrorMessage::SpaceBlackout {
+                    space_id: space_id.to_string(),
+                    start: blackout.start_time.into(),
+                    end: blackout.end_time.into(),
+                    reason: blackout.reason,
+                },
+            ));
+        }
+
+        //
+        // ⑤ 営業時間内に収まっているか確認
+        //    営業時間はスペースのタイムゾーンで、予約開始日の曜日のものを使う
+        //
+        let is_open = sqlx::query_scalar!(
+            r#"SELECT space_is_open($1, $2, $3) AS "is_open!""#,
+            space_id as _,
+            reservation_start_time,
+            reservation_end_time
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if !is_open {
+            let rows = sqlx::query!(
+                r#"
+                SELECT
+                    s.timezone,
+                    EXTRACT(ISODOW FROM $2 AT TIME ZONE s.timezone)::int AS "weekday!",
+                    h.open_minutes AS "open_minutes?",
+                    h.close_minutes AS "close_minutes?"
+                FROM spaces AS s
+                LEFT JOIN space_opening_hours AS h
+                  ON h.space_id = s.space_id
+                 AND h.weekday = EXTRACT(ISODOW FROM $2 AT TIME ZONE s.timezone)
+                WHERE s.space_id = $1
+                ORDER BY h.open_minutes ASC
+                "#,
+                space_id as _,
+                reservation_start_time
+            )
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+            let timezone = rows.first().map(|r| r.timezone.clone()).unwrap_or_default();
+            let weekday = rows
+                .first()
+                .and_then(|r| u8::try_from(r.weekday - 1).ok())
+                .and_then(|n| Weekday::try_from(n).ok())
+                .unwrap_or(Weekday::Mon);
+            let opening_hours = rows
+                .into_iter()
+                .filter_map(|r| {
+                    Some((
+                        format_minutes(r.open_minutes?),
+                        format_minutes(r.close_minutes?),
+                    ))
+                })
+                .collect::<Vec<_>>();
+            return Err(AppError::rule_violation(
+                ErrorCode::OutsideOpeningHours,
+                ErrorMessage::OutsideOpeningHours {
+                    space_id: space_id.to_string(),
+                    weekday,
+                    opening_hours,
+                    timezone,
+                },
+            ));
+        }
+
+        //
+        // ⑥ 希望予約時間帯が既存予約と重なっていないか確認
+        //    予約の変更時は、変更対象の予約自身との重なりは無視する
+        //
+        let overlap = find_overlapping_reservation(
+            &mut **tx,
+            space_id,
+            reservation_start_time,
+            reservation_end_time,
+            exclude_reservation_ids,
+        )
+        .await?;
+
+        if let Some(conflicting) = overlap {
+            return Err(AppError::Conflict {
+                message: ErrorMessage::ReservationOverlap {
+                    space_id: space_id.to_string(),
+                },
+                conflicting_reservation_id: Some(conflicting.raw()),
+            });
+        }
+
+        Ok(())
+    }
+
+    // 予約の状態を変える処理で共通の処理
+    // reservations テーブルにある該当予約 ID のレコードを、
+    // 予約の状態を next に変え、その状態になった日時として at を記録する
+    // 終わった状態にする場合は ending に終わらせた操作を渡し、あわせて記録する
+    // 終わった状態にした場合は、空いた時間帯をキャンセル待ちのユーザーに案内する
+    async fn transition_status(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: ReservationId,
+        next: ReservationStatus,
+        at: DateTime<Local>,
+        ending: Option<&ReservationEnding>,
+    ) -> AppResult<()> {
+        let current = sqlx::query_scalar!(
+            r#"
+                SELECT status
+                FROM reservations
+                WHERE reservation_id = $1
+                FOR UPDATE
+            "#,
+            reservation_id as _,
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::ReservationNotFound {
+                reservation_id: reservation_id.to_string(),
+            })
+        })?;
+        let current = ReservationStatus::try_from(current.as_str())
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+        current.transition_to(next)?;
+
+        let updated = sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET
+                    status = $2::varchar,
+                    confirmed_at = CASE WHEN $2 = 'confirmed' THEN $3 ELSE confirmed_at END,
+                    checked_in_at = CASE WHEN $2 = 'checked_in' THEN $3 ELSE checked_in_at END,
+                    completed_at = CASE WHEN $2 = 'completed' THEN $3 ELSE completed_at END,
+                    cancelled_at = CASE
+                        WHEN $2 IN ('cancelled_by_user', 'cancelled_by_admin', 'cancelled_by_owner')
+                            THEN $3
+                        ELSE cancelled_at
+                    END,
+                    no_show_at = CASE WHEN $2 = 'no_show' THEN $3 ELSE no_show_at END,
+                    rejected_at = CASE WHEN $2 = 'rejected' THEN $3 ELSE rejected_at END,
+                    expired_at = CASE WHEN $2 = 'expired' THEN $3 ELSE expired_at END,
+                    ended_by = COALESCE($4, ended_by),
+                    end_source = COALESCE($5, end_source),
+                    end_reason = COALESCE($6, end_reason)
+                WHERE reservation_id = $1
+                RETURNING space_id, reservation_start_time, reservation_end_time;
+            "#,
+            reservation_id as _,
+            next.as_ref(),
+            at,
+            ending.and_then(|e| e.ended_by) as _,
+            ending.map(|e| e.source.as_ref()),
+            ending.and_then(|e| e.reason.as_deref()),
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        enqueue_reservation_webhook(tx, WebhookEvent::for_status(next), reservation_id).await?;
+
+        if next.is_active() {
+            return Ok(());
+        }
+
+        // 未送信のリマインダーは送る必要がなくなるため削除する
+        // 送信済みのものは履歴として残しておく
+        sqlx::query!(
+            r#"
+                DELETE FROM reminders WHERE reservation_id = $1 AND NOT is_sent;
+            "#,
+            reservation_id as _,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 空いた時間帯をキャンセル待ちのユーザーに案内する
+        // 終了時刻を過ぎた予約の場合は、案内できる時間帯がないため何もしない
+        self.offer_released_slot(
+            tx,
+            updated.space_id.into(),
+            updated.reservation_start_time.into(),
+            updated.reservation_end_time.into(),
+        )
+        .await
+    }
+
+    // 予約開始時刻から lead_minutes 分前をリマインダーの送信時刻として登録する
+    // 送信時刻がすでに過ぎているものは、予約受付の通知と重なるため登録しない
+    async fn insert_reminders(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        reservation_id: ReservationId,
+        reservation_start_time: DateTime<Local>,
+        reminder_lead_minutes: &[i32],
+    ) -> AppResult<()> {
+        let now = Local::now();
+        let (lead_minutes, remind_at): (Vec<i32>, Vec<DateTime<Local>>) = reminder_lead_minutes
+            .iter()
+            .map(|&lead| {
+                (
+                    lead,
+                    reservation_start_time - chrono::Duration::minutes(lead as i64),
+                )
+            })
+            .filter(|(_, remind_at)| *remind_at > now)
+            .unzip();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO reminders (reminder_id, reservation_id, lead_minutes, remind_at)
+                SELECT gen_random_uuid(), $1, lead_minutes, remind_at
+                FROM UNNEST($2::int[], $3::timestamptz[]) AS t(lead_minutes, remind_at)
+                ON CONFLICT (reservation_id, lead_minutes) DO NOTHING
+            "#,
+            reservation_id as _,
+            &lead_minutes,
+            &remind_at as _,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(())
+    }
+
+    // 予約 ID ごとのリマインダーを、送信時刻の早い順に取得する
+    async fn find_reminders(
+        &self,
+        reservation_ids: &[ReservationId],
+    ) -> AppResult<HashMap<ReservationId, Vec<Reminder>>> {
+        let rows = sqlx::query_as!(
+            ReminderRow,
+            r#"
+                SELECT
+                reminder_id,
+                reservation_id,
+                lead_minutes,
+                remind_at,
+                is_sent
+                FROM reminders
+                WHERE reservation_id = ANY($1)
+                ORDER BY remind_at ASC
+            "#,
+            reservation_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut reminders: HashMap<ReservationId, Vec<Reminder>> = HashMap::new();
+        for row in rows {
+            reminders
+                .entry(row.reservation_id)
+                .or_default()
+                .push(Reminder::from(row));
+        }
+        Ok(reminders)
+    }
+
+    // 予約中の予約一覧にリマインダーを付けて返す
+    async fn attach_reminders(&self, rows: Vec<ReservationRow>) -> AppResult<Vec<Reservation>> {
+        let reservation_ids = rows.iter().map(|r| r.reservation_id).collect::<Vec<_>>();
+        let mut reminders = self.find_reminders(&reservation_ids).await?;
+        rows
+            .into_iter()
+            .map(|row| {
+                let reminders = reminders.remove(&row.reservation_id).unwrap_or_default();
+                row.into_reservation(reminders)
+            })
+            .collect()
+    }
+}
+
+// 指定のスペースで、指定の時間帯と重なる予約を 1 件探す
+// 重複条件は reservations_space_period_excl 制約と同じく、
+// 開始を含み終了を含まない範囲どうしが重なることとする
+async fn find_overlapping_reservation<'e>(
+    executor: impl sqlx::PgExecutor<'e>,
+    space_id: SpaceId,
+    reservation_start_time: DateTime<Local>,
+    reservation_end_time: DateTime<Local>,
+    exclude_reservation_ids: &[ReservationId],
+) -> AppResult<Option<ReservationId>> {
+    let reservation_id = sqlx::query_scalar!(
+        r#"
+            SELECT reservation_id
+            FROM reservations
+            WHERE space_id = $1
+              AND status IN ('pending', 'confirmed', 'checked_in')
+              AND period && tstzrange($2, $3, '[)')
+              AND reservation_id <> ALL($4)
+            LIMIT 1
+        "#,
+        space_id as _,
+        reservation_start_time,
+        reservation_end_time,
+        exclude_reservation_ids as _,
+    )
+    .fetch_optional(executor)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(reservation_id.map(ReservationId::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{space::SpaceRepositoryImpl, user::UserRepositoryImpl};
+    use kernel::model::{
+        reservation::{
+            series::{Frequency, Recurrence},
+            status::{ReservationEndSource, ReservationOutcome, ReviewDecision},
+        },
+        role::Role,
+        space::{
+            approval::ApprovalPolicy,
+            check_in::CheckInPolicy,
+            event::{
+                CreateSpace, CreateSpaceBlackout, UpdateApprovalPolicy, UpdateBookingPolicy,
+                UpdateCheckInPolicy, UpdateOpeningHours,
+            },
+            policy::{RoleBookingPolicy, SpaceBookingPolicy},
+            schedule::OpeningHours,
+            SpaceListOptions,
+        },
+        reservation::ReservationSortKey,
+        user::event::CreateUser,
+        waitlist::{WaitlistFulfillment, WaitlistStatus},
+    };
+    use kernel::repository::{space::SpaceRepository, user::UserRepository};
+    use chrono::Timelike;
+
+    // テスト用のユーザーとスペースを作成する
+    async fn setup(db: &ConnectionPool) -> anyhow::Result<(UserId, SpaceId)> {
+        sqlx::query!(r#"INSERT INTO roles(role_name) VALUES ('Admin'), ('User');"#)
+            .execute(db.inner_ref())
+            .await?;
+        let user = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Test User".into(),
+                email: "test@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        space_repo
+            .create(
+                CreateSpace {
+                    space_name: "Test SpaceName".into(),
+                    is_active: true,
+                    description: "Test Description".into(),
+                    capacity: 5,
+                    equipment: "Test Equipment".into(),
+                    address: "Test Address".into(),
+                },
+                user.user_id,
+            )
+            .await?;
+        let space_id = space_repo
+            .find_all(SpaceListOptions {
+                limit: 1,
+                offset: 0,
+            })
+            .await?
+            .items[0]
+            .space_id;
+        Ok((user.user_id, space_id))
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_update_reservation(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+
+        let repo = ReservationRepositoryImpl::new(db);
+        // データベースはミリ秒までしか保持しないため、比較しやすいよう秒単位にそろえる
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        let first = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start,
+                start + hour,
+                vec![60],
+            ))
+            .await?;
+        let second = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start + hour * 3,
+                start + hour * 4,
+                vec![],
+            ))
+            .await?;
+
+        // 自分自身と重なる時間帯への変更はできる
+        repo.update(UpdateReservation::new(
+            first,
+            space_id,
+            user_id,
+            start + hour / 2,
+            start + hour * 2,
+        ))
+        .await?;
+        let updated = repo.find_by_id(first).await?;
+        assert_eq!(updated.reservation_start_time, start + hour / 2);
+        assert_eq!(updated.reminders.len(), 1);
+        assert_eq!(updated.reminders[0].remind_at, start - hour / 2);
+
+        // 存在しない予約は見つからないエラーになる
+        assert!(matches!(
+            repo.find_by_id(ReservationId::new()).await,
+            Err(AppError::EntityNotFound(_))
+        ));
+
+        // 他の予約と重なる時間帯への変更はできず、重なった予約の ID が返る
+        let res = repo
+            .update(UpdateReservation::new(
+                first,
+                space_id,
+                user_id,
+                start + hour * 2,
+                start + hour * 4,
+            ))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::Conflict {
+                conflicting_reservation_id: Some(id),
+                ..
+            }) if id == second.raw()
+        ));
+
+        // 終了時刻と開始時刻が接する時間帯は重なりとみなさない
+        repo.update(UpdateReservation::new(
+            first,
+            space_id,
+            user_id,
+            start + hour * 2,
+            start + hour * 3,
+        ))
+        .await?;
+
+        // アプリケーション側のチェックを経ずに書き込んでも、排他制約で弾かれる
+        let res = sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET reservation_end_time = $2
+                WHERE reservation_id = $1
+            "#,
+            first as _,
+            start + hour * 3 + hour / 2,
+        )
+        .execute(&pool)
+        .await
+        .map_err(AppError::SpecificOperationError);
+        assert!(res.is_err_and(|e| is_exclusion_violation(&e)));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_create_reservation_conflict(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let (user_id, space_id) = setup(&db).await?;
+
+        let repo = ReservationRepositoryImpl::new(db);
+        let start = Local::now() + chrono::Duration::days(1);
+        let hour = chrono::Duration::hours(1);
+        let existing = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start,
+                start + hour,
+                vec![],
+            ))
+            .await?;
+
+        // 既存の予約と重なる予約は作成できず、重なった予約の ID が返る
+        let res = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start + hour / 2,
+                start + hour * 2,
+                vec![],
+            ))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::Conflict {
+                conflicting_reservation_id: Some(id),
+                ..
+            }) if id == existing.raw()
+        ));
+
+        // 同じ時間帯への予約を同時に作成しても、成功するのは 1 件のみ
+        let concurrent_start = start + hour * 3;
+        let event = || {
+            CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                concurrent_start,
+                concurrent_start + hour,
+                vec![],
+            )
+        };
+        let (first, second) = tokio::join!(repo.create(event()), repo.create(event()));
+        let (created, conflict) = match (first, second) {
+            (Ok(id), Err(e)) | (Err(e), Ok(id)) => (id, e),
+            res => anyhow::bail!("exactly one reservation must be created: {res:?}"),
+        };
+        assert!(matches!(
+            conflict,
+            AppError::Conflict {
+                conflicting_reservation_id: Some(id),
+                ..
+            } if id == created.raw()
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_reservation_series(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        let day = chrono::Duration::days(1);
+        // 3 回目と重なる予約を先に入れておく
+        let existing = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start + day * 2,
+                start + day * 2 + hour,
+                vec![],
+            ))
+            .await?;
+
+        let series = |conflict_policy| {
+            CreateReservationSeries::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start,
+                start + hour,
+                Recurrence {
+                    frequency: Frequency::Daily,
+                    interval: 1,
+                    by_weekday: vec![],
+                    count: Some(4),
+                    until: None,
+                },
+                conflict_policy,
+                vec![],
+            )
+        };
+
+        // 1 回でも重なると全体を作成しない
+        let res = repo
+            .create_series(series(SeriesConflictPolicy::AllOrNothing))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict { .. })));
+
+        // 重なった回のみ飛ばして作成する
+        let created = repo
+            .create_series(series(SeriesConflictPolicy::SkipConflicts))
+            .await?;
+        assert_eq!(created.reservation_ids.len(), 3);
+        assert_eq!(created.skipped.len(), 1);
+        assert_eq!(created.skipped[0].conflicting_reservation_id, existing);
+
+        // 2 回目以降を 30 分遅らせる
+        let second = created.reservation_ids[1];
+        repo.update_series(UpdateReservationSeries::new(
+            second,
+            space_id,
+            user_id,
+            SeriesScope::ThisAndFollowing,
+            start + day + hour / 2,
+            start + day + hour + hour / 2,
+        ))
+        .await?;
+        let found = repo
+            .find_series_by_id(created.reservation_series_id)
+            .await?;
+        let starts = found
+            .reservations
+            .iter()
+            .map(|r| r.reservation_start_time)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            starts,
+            vec![start, start + day + hour / 2, start + day * 3 + hour / 2]
+        );
+
+        // すべての回をキャンセルする
+        repo.cancel_series(CancelReservationSeries::new(
+            second,
+            space_id,
+            user_id,
+            SeriesScope::All,
+            ReservationEndSource::User,
+            None,
+            Local::now(),
+        ))
+        .await?;
+        let found = repo
+            .find_series_by_id(created.reservation_series_id)
+            .await?;
+        assert!(found.reservations.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_opening_hours_and_blackouts(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let (user_id, space_id) = setup(&db).await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        let repo = ReservationRepositoryImpl::new(db);
+
+        // 毎日 9:00〜18:00（UTC）のみ予約できるようにする
+        space_repo
+            .update_opening_hours(UpdateOpeningHours {
+                space_id,
+                timezone: "UTC".into(),
+                opening_hours: [
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                    Weekday::Sat,
+                    Weekday::Sun,
+                ]
+                .into_iter()
+                .map(|weekday| OpeningHours {
+                    weekday,
+                    open_minutes: 9 * 60,
+                    close_minutes: 18 * 60,
+                })
+                .collect(),
+                requested_user: user_id,
+            })
+            .await?;
+        let at = |h: u32| -> DateTime<Local> {
+            (chrono::Utc::now() + chrono::Duration::days(1))
+                .date_naive()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+                .and_utc()
+                .into()
+        };
+        let reserve = |start, end| {
+            CreateReservation::new(space_id, user_id, Local::now(), start, end, vec![])
+        };
+
+        // 営業時間外の予約は、その曜日の営業時間を示して断る
+        let res = repo.create(reserve(at(8), at(10))).await;
+        assert!(matches!(
+            res,
+            Err(AppError::RuleViolation { code: ErrorCode::OutsideOpeningHours, ref message })
+                if message.to_string().contains("09:00〜18:00")
+        ));
+        repo.create(reserve(at(9), at(10))).await?;
+
+        // 利用停止の期間と重なる予約は、その理由を示して断る
+        space_repo
+            .create_blackout(CreateSpaceBlackout {
+                space_id,
+                start_time: at(12),
+                end_time: at(14),
+                reason: "清掃".into(),
+                requested_user: user_id,
+            })
+            .await?;
+        let res = repo.create(reserve(at(13), at(15))).await;
+        assert!(matches!(
+            res,
+            Err(AppError::RuleViolation { code: ErrorCode::SpaceBlackout, ref message })
+                if message.to_string().contains("清掃")
+        ));
+        repo.create(reserve(at(14), at(15))).await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_booking_policy(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let (user_id, space_id) = setup(&db).await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let policy = BookingPolicy {
+            min_duration_minutes: Some(30),
+            max_duration_minutes: Some(120),
+            slot_granularity_minutes: Some(30),
+            max_days_in_advance: Some(7),
+            max_active_reservations: Some(1),
+            max_reservations_per_week: None,
+            max_recent_no_shows: None,
+        };
+        let update_policy = |role_overrides| UpdateBookingPolicy {
+            space_id,
+            booking_policy: SpaceBookingPolicy {
+                policy: policy.clone(),
+                role_overrides,
+            },
+            requested_user: user_id,
+        };
+        space_repo.update_booking_policy(update_policy(vec![])).await?;
+        let found = space_repo.find_by_id(space_id).await?.unwrap();
+        assert_eq!(found.booking_policy.policy, policy);
+
+        // スペースのタイムゾーン（Asia/Tokyo）でも 30 分単位の区切りになるよう、UTC の正時を使う
+        let at = |h: u32, m: u32| -> DateTime<Local> {
+            (chrono::Utc::now() + chrono::Duration::days(1))
+                .date_naive()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+                .and_utc()
+                .into()
+        };
+        let reserve = |start, end| {
+            CreateReservation::new(space_id, user_id, Local::now(), start, end, vec![])
+        };
+        let violated_rule = |res: AppResult<ReservationId>| match res {
+            Err(AppError::RuleViolation {
+                code: ErrorCode::BookingPolicyViolation,
+                message,
+            }) => message.to_string(),
+            res => panic!("unexpected result: {res:?}"),
+        };
+
+        assert!(violated_rule(repo.create(reserve(at(10, 0), at(10, 15))).await)
+            .contains("最短予約時間"));
+        assert!(violated_rule(repo.create(reserve(at(10, 10), at(11, 10))).await)
+            .contains("予約時間の単位"));
+        let first = repo.create(reserve(at(10, 0), at(11, 0))).await?;
+        assert!(violated_rule(repo.create(reserve(at(12, 0), at(13, 0))).await)
+            .contains("同時に持てる予約数"));
+
+        // 予約時間の変更にもルールを適用する
+        let res = repo
+            .update(UpdateReservation::new(
+                first,
+                space_id,
+                user_id,
+                at(13, 0),
+                at(16, 0),
+            ))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::RuleViolation { code: ErrorCode::BookingPolicyViolation, ref message })
+                if message.to_string().contains("最長予約時間")
+        ));
+
+        // ロールごとのルールがある場合は、スペースのルールの代わりに使う
+        space_repo
+            .update_booking_policy(update_policy(vec![RoleBookingPolicy {
+                role: Role::User,
+                policy: BookingPolicy {
+                    max_active_reservations: Some(2),
+                    ..policy.clone()
+                },
+            }]))
+            .await?;
+        repo.create(reserve(at(12, 0), at(13, 0))).await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_waitlist(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (owner_id, space_id) = setup(&db).await?;
+        let user_repo = UserRepositoryImpl::new(db.clone());
+        let mut waiting_ids = Vec::new();
+        for (user_name, email) in [("Offer User", "offer@example.com"), ("Auto User", "auto@example.com")] {
+            let user = user_repo
+                .create(CreateUser {
+                    user_name: user_name.into(),
+                    email: email.into(),
+                    password: "test_password".into(),
+                })
+                .await?;
+            waiting_ids.push(user.user_id);
+        }
+        let (offer_user, auto_user) = (waiting_ids[0], waiting_ids[1]);
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let end = start + chrono::Duration::hours(1);
+        let reservation_id = repo
+            .create(CreateReservation::new(space_id, owner_id, Local::now(), start, end, vec![]))
+            .await?;
+
+        // 予約の入っていない時間帯はキャンセル待ちにできない
+        let res = repo
+            .join_waitlist(CreateWaitlistEntry::new(
+                space_id,
+                offer_user,
+                end,
+                end + chrono::Duration::hours(1),
+                WaitlistFulfillment::Offer,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        repo.join_waitlist(CreateWaitlistEntry::new(
+            space_id,
+            offer_user,
+            start,
+            end,
+            WaitlistFulfillment::Offer,
+        ))
+        .await?;
+        let res = repo
+            .join_waitlist(CreateWaitlistEntry::new(
+                space_id,
+                offer_user,
+                start,
+                end,
+                WaitlistFulfillment::Offer,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::Conflict { .. })));
+        repo.join_waitlist(CreateWaitlistEntry::new(
+            space_id,
+            auto_user,
+            start,
+            end,
+            WaitlistFulfillment::AutoBook,
+        ))
+        .await?;
+
+        // 予約のキャンセルで空いた時間帯は、先に並んだユーザーに案内する
+        repo.update_returned(UpdateReturned::new(
+            reservation_id,
+            space_id,
+            owner_id,
+            ReservationEndSource::User,
+            None,
+            ReservationStatus::CancelledByUser,
+            Local::now(),
+            start,
+            end,
+            None,
+        ))
+        .await?;
+        let offered = repo.find_waitlist_by_user_id(offer_user).await?;
+        assert_eq!(offered[0].status, WaitlistStatus::Offered);
+        let claim_token = sqlx::query_scalar!(
+            r#"
+                SELECT claim_token AS "claim_token!"
+                FROM outbox_messages
+                WHERE kind = 'waitlist_offer' AND reservation_id IS NULL
+            "#
+        )
+        .fetch_one(&pool)
+        .await?;
+
+        // 案内の期限までは、他のユーザーはその時間帯を予約できない
+        let res = repo
+            .create(CreateReservation::new(space_id, auto_user, Local::now(), start, end, vec![]))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::Conflict {
+                conflicting_reservation_id: None,
+                ..
+            })
+        ));
+
+        // 案内の期限を過ぎると、次に並んでいるユーザーのために自動で予約する
+        sqlx::query!(
+            "UPDATE waitlist_entries SET offer_expires_at = CURRENT_TIMESTAMP - INTERVAL '1 minute' WHERE status = 'offered'"
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(repo.expire_waitlist_entries(10).await?, 1);
+        let booked = repo.find_waitlist_by_user_id(auto_user).await?;
+        assert_eq!(booked[0].status, WaitlistStatus::Booked);
+        let booked_reservation = repo.find_by_id(booked[0].reservation_id.unwrap()).await?;
+        assert_eq!(booked_reservation.reserved_by, auto_user);
+
+        let res = repo
+            .claim_waitlist_offer(ClaimWaitlistOffer::new(
+                claim_token.clone(),
+                offer_user,
+                Local::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 自動で予約した分がキャンセルされると、改めて並んだユーザーが案内から予約を確定できる
+        let entry_id = repo
+            .join_waitlist(CreateWaitlistEntry::new(
+                space_id,
+                offer_user,
+                start,
+                end,
+                WaitlistFulfillment::Offer,
+            ))
+            .await?;
+        repo.update_returned(UpdateReturned::new(
+            booked_reservation.reservation_id,
+            space_id,
+            auto_user,
+            ReservationEndSource::User,
+            None,
+            ReservationStatus::CancelledByUser,
+            Local::now(),
+            start,
+            end,
+            None,
+        ))
+        .await?;
+        let claim_token = sqlx::query_scalar!(
+            r#"SELECT claim_token AS "claim_token!" FROM waitlist_entries WHERE waitlist_entry_id = $1"#,
+            entry_id as _
+        )
+        .fetch_one(&pool)
+        .await?;
+        let res = repo
+            .claim_waitlist_offer(ClaimWaitlistOffer::new(
+                claim_token.clone(),
+                owner_id,
+                Local::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        let claimed = repo
+            .claim_waitlist_offer(ClaimWaitlistOffer::new(claim_token, offer_user, Local::now()))
+            .await?;
+        assert_eq!(repo.find_by_id(claimed).await?.reserved_by, offer_user);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_waitlist_skips_ineligible_entries(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let (owner_id, space_id) = setup(&db).await?;
+        let user_repo = UserRepositoryImpl::new(db.clone());
+        let mut waiting_ids = Vec::new();
+        for (user_name, email) in [("Busy User", "busy@example.com"), ("Auto User", "auto@example.com")] {
+            let user = user_repo
+                .create(CreateUser {
+                    user_name: user_name.into(),
+                    email: email.into(),
+                    password: "test_password".into(),
+                })
+                .await?;
+            waiting_ids.push(user.user_id);
+        }
+        let (busy_user, auto_user) = (waiting_ids[0], waiting_ids[1]);
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        let reservation_id = repo
+            .create(CreateReservation::new(
+                space_id,
+                owner_id,
+                Local::now(),
+                start,
+                start + hour,
+                vec![],
+            ))
+            .await?;
+        repo.create(CreateReservation::new(
+            space_id,
+            busy_user,
+            Local::now(),
+            start + hour * 2,
+            start + hour * 3,
+            vec![],
+        ))
+        .await?;
+        for user_id in [busy_user, auto_user] {
+            repo.join_waitlist(CreateWaitlistEntry::new(
+                space_id,
+                user_id,
+                start,
+                start + hour,
+                WaitlistFulfillment::AutoBook,
+            ))
+            .await?;
+        }
+
+        // 先に並んだユーザーは、同時に持てる予約数を超えるため予約できない
+        space_repo
+            .update_booking_policy(UpdateBookingPolicy {
+                space_id,
+                booking_policy: SpaceBookingPolicy {
+                    policy: BookingPolicy {
+                        max_active_reservations: Some(1),
+                        ..BookingPolicy::default()
+                    },
+                    role_overrides: vec![],
+                },
+                requested_user: owner_id,
+            })
+            .await?;
+
+        // ルールに合わないユーザーがいても予約のキャンセルは失敗せず、次に並んでいるユーザーが予約できる
+        repo.update_returned(UpdateReturned::new(
+            reservation_id,
+            space_id,
+            owner_id,
+            ReservationEndSource::User,
+            None,
+            ReservationStatus::CancelledByUser,
+            Local::now(),
+            start,
+            start + hour,
+            None,
+        ))
+        .await?;
+        let waiting = repo.find_waitlist_by_user_id(busy_user).await?;
+        assert_eq!(waiting[0].status, WaitlistStatus::Waiting);
+        let booked = repo.find_waitlist_by_user_id(auto_user).await?;
+        assert_eq!(booked[0].status, WaitlistStatus::Booked);
+        let booked_reservation = repo.find_by_id(booked[0].reservation_id.unwrap()).await?;
+        assert_eq!(booked_reservation.reserved_by, auto_user);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_check_in_and_no_show(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let other_user = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Other User".into(),
+                email: "other@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let check_in_policy = CheckInPolicy {
+            grace_minutes: Some(15),
+            code: Some("ROOM42".into()),
+        };
+        space_repo
+            .update_check_in_policy(UpdateCheckInPolicy {
+                space_id,
+                check_in_policy: check_in_policy.clone(),
+                requested_user: user_id,
+            })
+            .await?;
+        let found = space_repo.find_by_id(space_id).await?.unwrap();
+        assert_eq!(found.check_in_policy, check_in_policy);
+
+        let now = Local::now().with_nanosecond(0).unwrap();
+        let reserve = |start, end| CreateReservation::new(space_id, user_id, now, start, end, vec![]);
+        let check_in = |reservation_id, requested_by, code: &str| {
+            CheckInReservation::new(
+                reservation_id,
+                space_id,
+                requested_by,
+                Some(code.into()),
+                Local::now(),
+            )
+        };
+
+        // 予約開始の少し前からチェックインでき、コードが違う場合や本人以外はチェックインできない
+        let attended = repo
+            .create(reserve(
+                now + chrono::Duration::minutes(10),
+                now + chrono::Duration::minutes(40),
+            ))
+            .await?;
+        let res = repo.check_in(check_in(attended, user_id, "WRONG1")).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = repo
+            .check_in(check_in(attended, other_user.user_id, "ROOM42"))
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+        repo.check_in(check_in(attended, user_id, "ROOM42")).await?;
+        assert!(repo.find_by_id(attended).await?.checked_in_at.is_some());
+        let res = repo.check_in(check_in(attended, user_id, "ROOM42")).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 猶予時間を過ぎてもチェックインのない予約は解放し、チェックインしなかったものとして残す
+        let missed = repo
+            .create(reserve(
+                now + chrono::Duration::hours(3),
+                now + chrono::Duration::hours(4),
+            ))
+            .await?;
+        sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET reservation_start_time = CURRENT_TIMESTAMP - INTERVAL '1 hour',
+                    reservation_end_time = CURRENT_TIMESTAMP + INTERVAL '5 minutes'
+                WHERE reservation_id = $1
+            "#,
+            missed as _
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(repo.release_no_shows(10).await?, 1);
+        assert!(repo.find_by_id(attended).await.is_ok());
+        let history = repo
+            .find_history_by_space_id(space_id, &[ReservationStatus::NoShow])
+            .await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].reservation_id, missed);
+        assert!(history[0].no_show_at.is_some() && history[0].checked_in_at.is_none());
+        let notified = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM outbox_messages WHERE kind = 'no_show' AND reservation_id = $1"#,
+            missed as _
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(notified, 1);
+
+        // チェックインしなかった回数が上限に達したユーザーは予約できない
+        space_repo
+            .update_booking_policy(UpdateBookingPolicy {
+                space_id,
+                booking_policy: SpaceBookingPolicy {
+                    policy: BookingPolicy {
+                        max_recent_no_shows: Some(1),
+                        ..Default::default()
+                    },
+                    role_overrides: vec![],
+                },
+                requested_user: user_id,
+            })
+            .await?;
+        let res = repo
+            .create(reserve(
+                now + chrono::Duration::days(1),
+                now + chrono::Duration::days(1) + chrono::Duration::hours(1),
+            ))
+            .await;
+        assert!(matches!(
+            res,
+            Err(AppError::RuleViolation { code: ErrorCode::BookingPolicyViolation, ref message })
+                if message.to_string().contains("チェックインしなかった回数")
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_reservation_status(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let end = start + chrono::Duration::hours(1);
+        let cancelled = repo
+            .create(CreateReservation::new(space_id, user_id, Local::now(), start, end, vec![]))
+            .await?;
+        let found = repo.find_by_id(cancelled).await?;
+        assert_eq!(found.status, ReservationStatus::Confirmed);
+        assert!(found.confirmed_at.is_some() && found.ended_at().is_none());
+
+        // 利用終了・キャンセルにできるのは終わっていない予約のみ
+        let cancel = |reservation_id, status| {
+            UpdateReturned::new(
+                reservation_id,
+                space_id,
+                user_id,
+                ReservationEndSource::User,
+                None,
+                status,
+                Local::now(),
+                start,
+                end,
+                None,
+            )
+        };
+        let res = repo
+            .update_returned(cancel(cancelled, ReservationStatus::CheckedIn))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        repo.update_returned(cancel(cancelled, ReservationStatus::CancelledByUser))
+            .await?;
+        let res = repo
+            .update_returned(cancel(cancelled, ReservationStatus::Completed))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // キャンセルした予約は時間帯を押さえないため、同じ時間帯を予約し直せる
+        let rebooked = repo
+            .create(CreateReservation::new(space_id, user_id, Local::now(), start, end, vec![]))
+            .await?;
+        let active = repo.find_reservations_by_space_id(space_id).await?;
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].reservation_id, rebooked);
+
+        let history = repo.find_history_by_space_id(space_id, &[]).await?;
+        assert_eq!(history.len(), 2);
+        let history = repo
+            .find_history_by_space_id(
+                space_id,
+                &[
+                    ReservationStatus::CancelledByUser,
+                    ReservationStatus::CancelledByAdmin,
+                ],
+            )
+            .await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].reservation_id, cancelled);
+        assert!(history[0].cancelled_at.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_reservation_end_audit(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let end = start + chrono::Duration::hours(1);
+        let reservation_id = repo
+            .create(CreateReservation::new(space_id, user_id, Local::now(), start, end, vec![]))
+            .await?;
+
+        // スペースの停止によるキャンセルとして、操作したユーザーと理由を記録する
+        repo.update_returned(UpdateReturned::new(
+            reservation_id,
+            space_id,
+            user_id,
+            ReservationEndSource::SpaceDeactivation,
+            Some("設備点検のため".into()),
+            ReservationStatus::CancelledByAdmin,
+            Local::now(),
+            start,
+            end,
+            Some(NotificationKind::Cancellation),
+        ))
+        .await?;
+
+        let history = repo.find_history_by_space_id(space_id, &[]).await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, ReservationStatus::CancelledByAdmin);
+        assert_eq!(
+            history[0].ending,
+            Some(ReservationEnding {
+                ended_by: Some(user_id),
+                source: ReservationEndSource::SpaceDeactivation,
+                reason: Some("設備点検のため".into()),
+            })
+        );
+
+        // キャンセルの通知にも、出どころと理由を複製しておく
+        let outbox = sqlx::query!(
+            r#"SELECT end_source, end_reason FROM outbox_messages WHERE reservation_id = $1 AND kind = 'cancellation'"#,
+            reservation_id as _,
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(outbox.end_source.as_deref(), Some("space_deactivation"));
+        assert_eq!(outbox.end_reason.as_deref(), Some("設備点検のため"));
+
+        // まだ終わっていない予約には記録がない
+        let rebooked = repo
+            .create(CreateReservation::new(space_id, user_id, Local::now(), start, end, vec![]))
+            .await?;
+        assert!(repo.find_by_id(rebooked).await?.ending.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_archive_ended(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db);
+
+        // データベースはミリ秒までしか保持しないため、続けて取る予約の境目がずれないよう秒単位にそろえる
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        let ended = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start,
+                start + hour,
+                vec![],
+            ))
+            .await?;
+        let active = repo
+            .create(CreateReservation::new(
+                space_id,
+                user_id,
+                Local::now(),
+                start + hour,
+                start + hour * 2,
+                vec![],
+            ))
+            .await?;
+        // 過去の時間帯の予約は作成できないため、作成後に終了済みの時間帯へ書き換える
+        let now = Local::now();
+        sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET reservation_start_time = $2, reservation_end_time = $3
+                WHERE reservation_id = $1
+            "#,
+            ended as _,
+            now - hour * 2,
+            now - hour,
+        )
+        .execute(&pool)
+        .await?;
+
+        // 他のインスタンスが advisory lock を持っている間は何もしない
+        let mut other = pool.begin().await?;
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", ARCHIVE_ENDED_LOCK_KEY)
+            .execute(&mut *other)
+            .await?;
+        assert_eq!(repo.archive_ended(10).await?, 0);
+        assert_eq!(
+            repo.find_by_id(ended).await?.status,
+            ReservationStatus::Confirmed
+        );
+        other.rollback().await?;
+
+        // ロックが解放されると、終了時刻を過ぎた予約のみを利用終了にする
+        assert_eq!(repo.archive_ended(10).await?, 1);
+        assert_eq!(
+            repo.find_by_id(ended).await?.status,
+            ReservationStatus::Completed
+        );
+        assert_eq!(
+            repo.find_by_id(active).await?.status,
+            ReservationStatus::Confirmed
+        );
+        assert_eq!(repo.archive_ended(10).await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_deactivate_space(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db.clone());
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        let mut reservation_ids = Vec::new();
+        for i in 0..2 {
+            reservation_ids.push(
+                repo.create(CreateReservation::new(
+                    space_id,
+                    user_id,
+                    Local::now(),
+                    start + hour * (i * 2),
+                    start + hour * (i * 2 + 1),
+                    vec![],
+                ))
+                .await?,
+            );
+        }
+
+        // キャンセル以外の状態は指定できず、スペースも停止しない
+        let res = repo
+            .deactivate_space(DeactivateSpace::new(
+                space_id,
+                user_id,
+                ReservationStatus::Completed,
+                None,
+                Local::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let space_repo = SpaceRepositoryImpl::new(db);
+        assert!(space_repo.find_by_id(space_id).await?.unwrap().is_active);
+
+        // 所有者による停止では、すべての予約を所有者によるキャンセルにし、通知を積む
+        let cancelled = repo
+            .deactivate_space(DeactivateSpace::new(
+                space_id,
+                user_id,
+                ReservationStatus::CancelledByOwner,
+                Some("改装のため".into()),
+                Local::now(),
+            ))
+            .await?;
+        assert_eq!(cancelled, 2);
+        assert!(!space_repo.find_by_id(space_id).await?.unwrap().is_active);
+        for reservation_id in &reservation_ids {
+            let reservation = repo.find_by_id(*reservation_id).await?;
+            assert_eq!(reservation.status, ReservationStatus::CancelledByOwner);
+            assert_eq!(
+                reservation.ending,
+                Some(ReservationEnding {
+                    ended_by: Some(user_id),
+                    source: ReservationEndSource::SpaceDeactivation,
+                    reason: Some("改装のため".into()),
+                })
+            );
+        }
+        let notified = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM outbox_messages WHERE reservation_id = ANY($1) AND kind = 'cancellation'"#,
+            &reservation_ids as _,
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(notified, 2);
+
+        // 停止済みのスペースを再び停止しても、キャンセルする予約はない
+        let cancelled = repo
+            .deactivate_space(DeactivateSpace::new(
+                space_id,
+                user_id,
+                ReservationStatus::CancelledByAdmin,
+                None,
+                Local::now(),
+            ))
+            .await?;
+        assert_eq!(cancelled, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_deactivate_all_spaces(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, first_space_id) = setup(&db).await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        space_repo
+            .create(
+                CreateSpace {
+                    space_name: "Second SpaceName".into(),
+                    is_active: true,
+                    description: "Test Description".into(),
+                    capacity: 5,
+                    equipment: "Test Equipment".into(),
+                    address: "Test Address".into(),
+                },
+                user_id,
+            )
+            .await?;
+        let mut space_ids =
+            sqlx::query_scalar!(r#"SELECT space_id AS "space_id: SpaceId" FROM spaces"#)
+                .fetch_all(&pool)
+                .await?;
+        space_ids.sort_by_key(|space_id| space_id.raw());
+        assert!(space_ids.contains(&first_space_id));
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let start = (Local::now() + chrono::Duration::days(1))
+            .with_nanosecond(0)
+            .unwrap();
+        let mut reservation_ids = Vec::new();
+        for &space_id in &space_ids {
+            reservation_ids.push(
+                repo.create(CreateReservation::new(
+                    space_id,
+                    user_id,
+                    Local::now(),
+                    start,
+                    start + chrono::Duration::hours(1),
+                    vec![],
+                ))
+                .await?,
+            );
+        }
+        let deactivate_all = || {
+            DeactivateAllSpaces::new(
+                user_id,
+                ReservationStatus::CancelledByAdmin,
+                Some("全館点検のため".into()),
+                Local::now(),
+            )
+        };
+
+        // 後から処理するスペースで失敗した場合は、先に処理したスペースも停止しない
+        sqlx::query(
+            r#"
+                CREATE FUNCTION fail_cancellation() RETURNS trigger AS $$
+                BEGIN
+                    RAISE EXCEPTION 'cancellation failed';
+                END;
+                $$ LANGUAGE plpgsql
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(&format!(
+            "CREATE TRIGGER fail_cancellation BEFORE UPDATE ON reservations FOR EACH ROW
+             WHEN (OLD.space_id = '{}') EXECUTE FUNCTION fail_cancellation()",
+            space_ids[1].raw()
+        ))
+        .execute(&pool)
+        .await?;
+        assert!(repo.deactivate_all_spaces(deactivate_all()).await.is_err());
+        for &space_id in &space_ids {
+            assert!(space_repo.find_by_id(space_id).await?.unwrap().is_active);
+        }
+        for &reservation_id in &reservation_ids {
+            assert_eq!(
+                repo.find_by_id(reservation_id).await?.status,
+                ReservationStatus::Confirmed
+            );
+        }
+        sqlx::query("DROP TRIGGER fail_cancellation ON reservations")
+            .execute(&pool)
+            .await?;
+
+        // すべてのスペースを停止し、すべての予約をキャンセルする
+        assert_eq!(repo.deactivate_all_spaces(deactivate_all()).await?, 2);
+        for &space_id in &space_ids {
+            assert!(!space_repo.find_by_id(space_id).await?.unwrap().is_active);
+        }
+        for &reservation_id in &reservation_ids {
+            let reservation = repo.find_by_id(reservation_id).await?;
+            assert_eq!(reservation.status, ReservationStatus::CancelledByAdmin);
+            let reason = reservation.ending.and_then(|ending| ending.reason);
+            assert_eq!(reason.as_deref(), Some("全館点検のため"));
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_reservation_approval(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let approver = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Approver".into(),
+                email: "approver@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        let repo = ReservationRepositoryImpl::new(db);
+
+        // 承認が必要な場合は、承認者が 1 人以上必要
+        let res = space_repo
+            .update_approval_policy(UpdateApprovalPolicy {
+                space_id,
+                approval_policy: ApprovalPolicy {
+                    requires_approval: true,
+                    approvers: vec![],
+                },
+                requested_user: user_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let approval_policy = ApprovalPolicy {
+            requires_approval: true,
+            approvers: vec![approver.user_id],
+        };
+        space_repo
+            .update_approval_policy(UpdateApprovalPolicy {
+                space_id,
+                approval_policy: approval_policy.clone(),
+                requested_user: user_id,
+            })
+            .await?;
+        let found = space_repo.find_by_id(space_id).await?.unwrap();
+        assert_eq!(found.approval_policy, approval_policy);
+
+        let now = Local::now().with_nanosecond(0).unwrap();
+        let reserve = |hours: i64| {
+            CreateReservation::new(
+                space_id,
+                user_id,
+                now,
+                now + chrono::Duration::hours(hours),
+                now + chrono::Duration::hours(hours + 1),
+                vec![],
+            )
+        };
+        let review = |reservation_id, requested_by, decision, reason: Option<&str>| {
+            ReviewReservation::new(
+                reservation_id,
+                space_id,
+                requested_by,
+                SeriesScope::This,
+                decision,
+                reason.map(String::from),
+                Local::now(),
+            )
+        };
+
+        // 承認待ちとして作成し、時間帯は押さえておく
+        let approved = repo.create(reserve(2)).await?;
+        let found = repo.find_by_id(approved).await?;
+        assert_eq!(found.status, ReservationStatus::Pending);
+        assert!(found.confirmed_at.is_none());
+        let res = repo.create(reserve(2)).await;
+        assert!(matches!(res, Err(AppError::Conflict { .. })));
+
+        // 承認者には承認依頼を、予約したユーザーには承認待ちであることを通知する
+        let kinds = sqlx::query!(
+            r#"SELECT kind, email FROM outbox_messages WHERE reservation_id = $1 ORDER BY kind"#,
+            approved as _,
+        )
+        .fetch_all(&pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.kind, row.email))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("approval_pending".to_string(), "test@example.com".to_string()),
+                ("approval_request".to_string(), "approver@example.com".to_string()),
+            ]
+        );
+
+        // 承認できるのは承認者のみ
+        let res = repo
+            .review(review(approved, user_id, ReviewDecision::Approve, None))
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+        repo.review(review(approved, approver.user_id, ReviewDecision::Approve, None))
+            .await?;
+        let found = repo.find_by_id(approved).await?;
+        assert_eq!(found.status, ReservationStatus::Confirmed);
+        assert_eq!(found.reviewed_by, Some(approver.user_id));
+        assert!(found.confirmed_at.is_some());
+        let res = repo
+            .review(review(approved, approver.user_id, ReviewDecision::Reject, None))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 却下した予約は、理由とあわせて履歴に残し、時間帯を空ける
+        let rejected = repo.create(reserve(4)).await?;
+        repo.review(review(
+            rejected,
+            approver.user_id,
+            ReviewDecision::Reject,
+            Some("利用目的を確認できないため"),
+        ))
+        .await?;
+        let found = repo.find_by_id(rejected).await?;
+        assert_eq!(found.status, ReservationStatus::Rejected);
+        assert!(found.rejected_at.is_some());
+        assert_eq!(
+            found.ending.and_then(|ending| ending.reason).as_deref(),
+            Some("利用目的を確認できないため")
+        );
+        repo.create(reserve(4)).await?;
+
+        // 予約開始時刻までに承認・却下されなかった予約は期限切れにする
+        let unreviewed = repo.create(reserve(6)).await?;
+        sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET reservation_start_time = CURRENT_TIMESTAMP - INTERVAL '2 hours',
+                    reservation_end_time = CURRENT_TIMESTAMP - INTERVAL '1 hour'
+                WHERE reservation_id = $1
+            "#,
+            unreviewed as _
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(repo.expire_pending_reviews(10).await?, 1);
+        let found = repo.find_by_id(unreviewed).await?;
+        assert_eq!(found.status, ReservationStatus::Expired);
+        assert_eq!(
+            found.ending.map(|ending| ending.source),
+            Some(ReservationEndSource::Watcher)
+        );
+        assert_eq!(repo.expire_pending_reviews(10).await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_find_all_reservations(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let now = Local::now().with_nanosecond(0).unwrap();
+        let mut reservation_ids = Vec::new();
+        for hours in [2, 4, 6] {
+            let start = now + chrono::Duration::hours(hours);
+            let end = start + chrono::Duration::hours(1);
+            reservation_ids.push(
+                repo.create(CreateReservation::new(space_id, user_id, now, start, end, vec![]))
+                    .await?,
+            );
+        }
+        // 2 番目の予約はキャンセルし、終わった予約として残す
+        let start = now + chrono::Duration::hours(4);
+        repo.update_returned(UpdateReturned::new(
+            reservation_ids[1],
+            space_id,
+            user_id,
+            ReservationEndSource::User,
+            None,
+            ReservationStatus::CancelledByUser,
+            Local::now(),
+            start,
+            start + chrono::Duration::hours(1),
+            None,
+        ))
+        .await?;
+
+        let options = |limit, offset| ReservationListOptions {
+            space_id: Some(space_id),
+            user_id: None,
+            from: None,
+            to: None,
+            statuses: vec![],
+            is_cancel: None,
+            sort: ReservationSortKey::StartTime,
+            order: SortOrder::Asc,
+            limit,
+            offset,
+        };
+        let ids = |list: &PaginatedList<Reservation>| {
+            list.items.iter().map(|r| r.reservation_id).collect::<Vec<_>>()
+        };
+
+        // 終わった予約も含めて、ページの範囲のみを取得する
+        let page = repo.find_all(options(2, 0)).await?;
+        assert_eq!(page.total, 3);
+        assert_eq!(ids(&page), reservation_ids[..2]);
+        let page = repo.find_all(options(2, 2)).await?;
+        assert_eq!(page.total, 3);
+        assert_eq!(ids(&page), reservation_ids[2..]);
+
+        // 並び順を逆にする
+        let page = repo
+            .find_all(ReservationListOptions {
+                order: SortOrder::Desc,
+                ..options(10, 0)
+            })
+            .await?;
+        assert_eq!(
+            ids(&page),
+            reservation_ids.iter().rev().copied().collect::<Vec<_>>()
+        );
+
+        // キャンセルの有無と状態で絞り込む
+        let page = repo
+            .find_all(ReservationListOptions {
+                is_cancel: Some(true),
+                ..options(10, 0)
+            })
+            .await?;
+        assert_eq!(ids(&page), vec![reservation_ids[1]]);
+        let page = repo
+            .find_all(ReservationListOptions {
+                statuses: vec![ReservationStatus::Confirmed],
+                ..options(10, 0)
+            })
+            .await?;
+        assert_eq!(ids(&page), vec![reservation_ids[0], reservation_ids[2]]);
+
+        // 期間に重なる予約に絞り込む
+        let page = repo
+            .find_all(ReservationListOptions {
+                from: Some(now + chrono::Duration::hours(5)),
+                to: Some(now + chrono::Duration::hours(7)),
+                ..options(10, 0)
+            })
+            .await?;
+        assert_eq!(ids(&page), vec![reservation_ids[2]]);
+
+        // 条件に合う予約がない場合は total も 0 にする
+        let page = repo
+            .find_all(ReservationListOptions {
+                user_id: Some(UserId::new()),
+                ..options(10, 0)
+            })
+            .await?;
+        assert_eq!(page.total, 0);
+        assert!(page.items.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_find_reservation_history_by_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let (user_id, space_id) = setup(&db).await?;
+        let repo = ReservationRepositoryImpl::new(db);
+
+        let now = Local::now().with_nanosecond(0).unwrap();
+        let mut reservation_ids = Vec::new();
+        for hours in [2, 4, 6] {
+            let start = now + chrono::Duration::hours(hours);
+            let end = start + chrono::Duration::hours(1);
+            reservation_ids.push(
+                repo.create(CreateReservation::new(space_id, user_id, now, start, end, vec![]))
+                    .await?,
+            );
+        }
+        // 1 番目の予約は利用者がキャンセルし、2 番目の予約は終了時刻の経過で自動的に終わらせる
+        let start = now + chrono::Duration::hours(2);
+        repo.update_returned(UpdateReturned::new(
+            reservation_ids[0],
+            space_id,
+            user_id,
+            ReservationEndSource::User,
+            None,
+            ReservationStatus::CancelledByUser,
+            Local::now(),
+            start,
+            start + chrono::Duration::hours(1),
+            None,
+        ))
+        .await?;
+        sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET reservation_start_time = CURRENT_TIMESTAMP - INTERVAL '2 hours',
+                    reservation_end_time = CURRENT_TIMESTAMP - INTERVAL '1 hour'
+                WHERE reservation_id = $1
+            "#,
+            reservation_ids[1] as _
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(repo.archive_ended(10).await?, 1);
+
+        // 終わった予約のみを、予約開始時刻の新しい順に取得する
+        let history = repo
+            .find_all(ReservationListOptions {
+                space_id: None,
+                user_id: Some(user_id),
+                from: None,
+                to: None,
+                statuses: ReservationStatus::ENDED.to_vec(),
+                is_cancel: None,
+                sort: ReservationSortKey::StartTime,
+                order: SortOrder::Desc,
+                limit: 10,
+                offset: 0,
+            })
+            .await?;
+        assert_eq!(history.total, 2);
+        let outcomes = history
+            .items
+            .iter()
+            .map(|r| (r.reservation_id, r.outcome()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                (reservation_ids[0], Some(ReservationOutcome::Cancelled)),
+                (reservation_ids[1], Some(ReservationOutcome::AutoEnded)),
+            ]
+        );
+
+        Ok(())
+    }
+}
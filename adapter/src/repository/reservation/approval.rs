@@ -0,0 +1,197 @@
+use super::ReservationRepositoryImpl;
+use crate::repository::outbox::{
+    enqueue_approval_request_notification, enqueue_reservation_notification,
+};
+use kernel::model::{
+    id::ReservationId,
+    notification::NotificationKind,
+    reservation::{
+        event::ReviewReservation,
+        status::{ReservationEndSource, ReservationEnding, ReservationStatus, ReviewDecision},
+    },
+};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+impl ReservationRepositoryImpl {
+    // 予約受付の通知を outbox に積む
+    // 承認待ちとして作成した予約は、kind の代わりに承認待ちとして受け付けたことを通知し、
+    // スペースの承認者に承認を依頼する
+    pub(super) async fn enqueue_created_notification(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        kind: NotificationKind,
+        reservation_id: ReservationId,
+        status: ReservationStatus,
+    ) -> AppResult<()> {
+        if status != ReservationStatus::Pending {
+            return enqueue_reservation_notification(tx, kind, reservation_id).await;
+        }
+        enqueue_reservation_notification(tx, NotificationKind::ApprovalPending, reservation_id)
+            .await?;
+        // 承認者が退会などでいなくなっている場合は、承認されることがないため予約を受け付けない
+        enqueue_approval_request_notification(tx, reservation_id)
+            .await
+            .map_err(|e| match e {
+                AppError::NoRowsAffectedError(_) => {
+                    AppError::UnprocessableEntity(ErrorMessage::NoApprovers)
+                }
+                e => e,
+            })
+    }
+
+    // 承認待ちの予約を承認・却下する
+    pub(super) async fn try_review(&self, event: ReviewReservation) -> AppResult<()> {
+        let ReviewReservation {
+            reservation_id,
+            space_id,
+            requested_by,
+            scope,
+            decision,
+            reason,
+            reviewed_at,
+        } = event;
+
+        let mut tx = self.db.begin().await?;
+
+        let anchor = self
+            .lock_reservation(&mut tx, reservation_id, space_id)
+            .await?;
+
+        let is_approver = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM space_approvers
+                    WHERE space_id = $1 AND user_id = $2
+                ) AS "is_approver!"
+            "#,
+            space_id as _,
+            requested_by as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !is_approver {
+            return Err(AppError::ForbiddenOperation);
+        }
+
+        if anchor.status()? != ReservationStatus::Pending {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::NotPendingReview {
+                    reservation_id: reservation_id.to_string(),
+                },
+            ));
+        }
+        // 予約開始時刻を過ぎた承認待ちの予約は、期限切れとして扱う
+        if anchor.reservation_start_time <= reviewed_at {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::ReviewAfterStart {
+                    reservation_id: reservation_id.to_string(),
+                },
+            ));
+        }
+
+        // 繰り返し予約の場合、scope の範囲の回のうち承認待ちのものにも同じ判断を適用する
+        let targets = self
+            .lock_series_targets(&mut tx, reservation_id, &anchor, scope)
+            .await?;
+        // 却下は、承認者による管理する側の操作として記録する
+        let ending = match decision {
+            ReviewDecision::Approve => None,
+            ReviewDecision::Reject => Some(ReservationEnding {
+                ended_by: Some(requested_by),
+                source: ReservationEndSource::Admin,
+                reason,
+            }),
+        };
+        let mut reviewed = Vec::new();
+        for target in &targets {
+            if target.status()? != ReservationStatus::Pending {
+                continue;
+            }
+            self.transition_status(
+                &mut tx,
+                target.reservation_id,
+                decision.next_status(),
+                reviewed_at,
+                ending.as_ref(),
+            )
+            .await?;
+            reviewed.push(target.reservation_id);
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE reservations
+                SET reviewed_by = $2
+                WHERE reservation_id = ANY($1)
+            "#,
+            &reviewed as _,
+            requested_by as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 回ごとに通知すると件数が多くなるため、結果の通知は指定した回の分のみ積む
+        let kind = match decision {
+            ReviewDecision::Approve => NotificationKind::Approved,
+            ReviewDecision::Reject => NotificationKind::Rejected,
+        };
+        enqueue_reservation_notification(&mut tx, kind, reservation_id).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // 予約開始時刻を過ぎても承認・却下されていない予約を期限切れにする
+    // 期限切れにした時間帯は、キャンセル待ちのユーザーに案内される
+    pub(super) async fn try_expire_pending_reviews(&self, limit: i64) -> AppResult<usize> {
+        let mut tx = self.db.begin().await?;
+
+        // 承認者の操作と競合しないよう、行ロックも取っておく
+        let overdue = sqlx::query!(
+            r#"
+                SELECT reservation_id
+                FROM reservations
+                WHERE status = 'pending'
+                  AND reservation_start_time <= CURRENT_TIMESTAMP
+                ORDER BY reservation_start_time ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            "#,
+            limit,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let expired_at = chrono::Local::now();
+        let ending = ReservationEnding::watcher();
+        for row in &overdue {
+            let reservation_id = row.reservation_id.into();
+            self.transition_status(
+                &mut tx,
+                reservation_id,
+                ReservationStatus::Expired,
+                expired_at,
+                Some(&ending),
+            )
+            .await?;
+            enqueue_reservation_notification(
+                &mut tx,
+                NotificationKind::ApprovalExpired,
+                reservation_id,
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(overdue.len())
+    }
+}
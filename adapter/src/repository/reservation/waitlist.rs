@@ -0,0 +1,698 @@
+use super::{find_overlapping_reservation, ReservationRepositoryImpl};
+use crate::database::model::waitlist::{LockedWaitlistEntryRow, WaitlistEntryRow};
+use crate::repository::outbox::enqueue_waitlist_offer_notification;
+use chrono::{DateTime, Local};
+use kernel::model::{
+    id::{ReservationId, SpaceId, UserId, WaitlistEntryId},
+    notification::NotificationKind,
+    reservation::{event::CreateReservation, status::ReservationStatus},
+    waitlist::{
+        event::{CancelWaitlistEntry, ClaimWaitlistOffer, CreateWaitlistEntry},
+        WaitlistEntry, WaitlistFulfillment, WaitlistStatus, WAITLIST_OFFER_HOLD_MINUTES,
+    },
+};
+use shared::{
+    error::{AppError, AppResult, ErrorCode},
+    i18n::ErrorMessage,
+};
+use sqlx::Acquire;
+
+impl ReservationRepositoryImpl {
+    // キャンセル待ちに登録する
+    // 予約の入っていない時間帯は、キャンセル待ちではなくそのまま予約してもらう
+    pub(super) async fn try_join_waitlist(
+        &self,
+        event: &CreateWaitlistEntry,
+    ) -> AppResult<WaitlistEntryId> {
+        let CreateWaitlistEntry {
+            space_id,
+            requested_by,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment,
+        } = *event;
+
+        if reservation_start_time >= reservation_end_time {
+            return Err(AppError::rule_violation(
+                ErrorCode::InvalidReservationPeriod,
+                ErrorMessage::InvalidReservationPeriod,
+            ));
+        }
+        if reservation_start_time <= Local::now() {
+            return Err(AppError::rule_violation(
+                ErrorCode::ReservationStartInPast,
+                ErrorMessage::ReservationStartInPast,
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        let space = sqlx::query!(
+            r#"
+                SELECT is_active
+                FROM spaces
+                WHERE space_id = $1
+            "#,
+            space_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })
+        })?;
+        if !space.is_active {
+            return Err(AppError::rule_violation(
+                ErrorCode::SpaceInactive,
+                ErrorMessage::SpaceInactive {
+                    space_id: space_id.to_string(),
+                },
+            ));
+        }
+
+        let own_reservation = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM reservations
+                    WHERE space_id = $1
+                      AND user_id = $2
+                      AND status IN ('pending', 'confirmed', 'checked_in')
+                      AND period && tstzrange($3, $4, '[)')
+                ) AS "exists!"
+            "#,
+            space_id as _,
+            requested_by as _,
+            reservation_start_time,
+            reservation_end_time
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if own_reservation {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::WaitlistOwnReservation,
+            ));
+        }
+
+        let overlap = find_overlapping_reservation(
+            &mut *tx,
+            space_id,
+            reservation_start_time,
+            reservation_end_time,
+            &[],
+        )
+        .await?;
+        let held = self
+            .check_waitlist_hold(
+                &mut tx,
+                space_id,
+                requested_by,
+                reservation_start_time,
+                reservation_end_time,
+            )
+            .await
+            .is_err();
+        if overlap.is_none() && !held {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::WaitlistSlotAvailable,
+            ));
+        }
+
+        let duplicated = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1
+                    FROM waitlist_entries
+                    WHERE space_id = $1
+                      AND user_id = $2
+                      AND reservation_start_time = $3
+                      AND reservation_end_time = $4
+                      AND status IN ('waiting', 'offered')
+                ) AS "exists!"
+            "#,
+            space_id as _,
+            requested_by as _,
+            reservation_start_time,
+            reservation_end_time
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if duplicated {
+            return Err(AppError::Conflict {
+                message: ErrorMessage::AlreadyOnWaitlist,
+                conflicting_reservation_id: None,
+            });
+        }
+
+        let waitlist_entry_id = WaitlistEntryId::new();
+        sqlx::query!(
+            r#"
+                INSERT INTO waitlist_entries
+                (waitlist_entry_id, space_id, user_id,
+                reservation_start_time, reservation_end_time, fulfillment)
+                VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            waitlist_entry_id as _,
+            space_id as _,
+            requested_by as _,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment.as_ref(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(waitlist_entry_id)
+    }
+
+    pub(super) async fn find_waitlist_entries_by_user_id(
+        &self,
+        user_id: UserId,
+    ) -> AppResult<Vec<WaitlistEntry>> {
+        sqlx::query_as!(
+            WaitlistEntryRow,
+            r#"
+                SELECT
+                w.waitlist_entry_id,
+                w.space_id,
+                s.space_name,
+                w.user_id,
+                w.reservation_start_time,
+                w.reservation_end_time,
+                w.fulfillment,
+                w.status,
+                w.offer_expires_at AS "offer_expires_at: DateTime<Local>",
+                w.reservation_id AS "reservation_id: ReservationId",
+                w.created_at
+                FROM waitlist_entries AS w
+                INNER JOIN spaces AS s ON w.space_id = s.space_id
+                WHERE w.user_id = $1
+                ORDER BY w.created_at DESC
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(WaitlistEntry::try_from)
+        .collect()
+    }
+
+    pub(super) async fn try_cancel_waitlist_entry(
+        &self,
+        event: &CancelWaitlistEntry,
+    ) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 登録したユーザー本人のもののみ取り下げられる
+        let entry = sqlx::query_as!(
+            LockedWaitlistEntryRow,
+            r#"
+                SELECT
+                waitlist_entry_id,
+                space_id,
+                user_id,
+                reservation_start_time,
+                reservation_end_time,
+                fulfillment,
+                status,
+                offer_expires_at AS "offer_expires_at: DateTime<Local>"
+                FROM waitlist_entries
+                WHERE waitlist_entry_id = $1 AND user_id = $2
+                FOR UPDATE
+            "#,
+            event.waitlist_entry_id as _,
+            event.requested_by as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::WaitlistEntryNotFound {
+                waitlist_entry_id: event.waitlist_entry_id.to_string(),
+            })
+        })?;
+
+        let status = parse_status(&entry.status)?;
+        if !status.is_active() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::WaitlistEntryClosed {
+                    waitlist_entry_id: event.waitlist_entry_id.to_string(),
+                },
+            ));
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE waitlist_entries
+                SET status = $2, offer_expires_at = NULL
+                WHERE waitlist_entry_id = $1
+            "#,
+            event.waitlist_entry_id as _,
+            WaitlistStatus::Cancelled.as_ref(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 案内中だった時間帯は、次に待っているユーザーに案内する
+        if status == WaitlistStatus::Offered {
+            self.offer_released_slot(
+                &mut tx,
+                entry.space_id,
+                entry.reservation_start_time,
+                entry.reservation_end_time,
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // claim_waitlist_offer で競合相手を探すための、案内中の時間帯を取得する
+    pub(super) async fn find_offered_period(
+        &self,
+        claim_token: &str,
+    ) -> AppResult<(SpaceId, DateTime<Local>, DateTime<Local>)> {
+        let row = sqlx::query!(
+            r#"
+                SELECT
+                space_id AS "space_id: SpaceId",
+                reservation_start_time,
+                reservation_end_time
+                FROM waitlist_entries
+                WHERE claim_token = $1
+            "#,
+            claim_token
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(offer_not_found)?;
+        Ok((
+            row.space_id,
+            row.reservation_start_time.into(),
+            row.reservation_end_time.into(),
+        ))
+    }
+
+    // claim_waitlist_offer の 1 回分の試行
+    pub(super) async fn try_claim_waitlist_offer(
+        &self,
+        event: &ClaimWaitlistOffer,
+    ) -> AppResult<ReservationId> {
+        let mut tx = self.db.begin().await?;
+
+        // トランザクション分離レベルを SERIALIZABLE に設定する
+        self.set_transaction_serializable(&mut tx).await?;
+
+        // 案内されたユーザー本人のみ確定できる
+        // 他のユーザーにはトークンの存在を明かさない
+        let entry = sqlx::query_as!(
+            LockedWaitlistEntryRow,
+            r#"
+                SELECT
+                waitlist_entry_id,
+                space_id,
+                user_id,
+                reservation_start_time,
+                reservation_end_time,
+                fulfillment,
+                status,
+                offer_expires_at AS "offer_expires_at: DateTime<Local>"
+                FROM waitlist_entries
+                WHERE claim_token = $1 AND user_id = $2
+                FOR UPDATE
+            "#,
+            event.claim_token,
+            event.requested_by as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(offer_not_found)?;
+
+        if parse_status(&entry.status)? != WaitlistStatus::Offered {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::WaitlistOfferUsed,
+            ));
+        }
+        if let Some(expires_at) = entry.offer_expires_at {
+            if expires_at <= event.claimed_at {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::WaitlistOfferExpired {
+                        expires_at,
+                    },
+                ));
+            }
+        }
+
+        self.check_reservable(
+            &mut tx,
+            entry.space_id,
+            entry.reservation_start_time,
+            entry.reservation_end_time,
+            &[],
+        )
+        .await?;
+        self.check_booking_policy(
+            &mut tx,
+            entry.space_id,
+            entry.user_id,
+            entry.reservation_start_time,
+            entry.reservation_end_time,
+            &[],
+        )
+        .await?;
+
+        let (reservation_id, status) = self
+            .book_waitlist_entry(&mut tx, &entry, event.claimed_at)
+            .await?;
+
+        // 予約受付の通知を同じトランザクションで outbox に積む
+        self.enqueue_created_notification(
+            &mut tx,
+            NotificationKind::Confirmation,
+            reservation_id,
+            status,
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(reservation_id)
+    }
+
+    // 期限を過ぎた案内と、希望の時間帯を過ぎたキャンセル待ちを期限切れにする
+    // 期限を過ぎた案内の時間帯は、次に待っているユーザーに案内する
+    pub(super) async fn try_expire_waitlist_entries(&self, limit: i64) -> AppResult<usize> {
+        let mut tx = self.db.begin().await?;
+
+        let passed = sqlx::query!(
+            r#"
+                UPDATE waitlist_entries
+                SET status = $2, offer_expires_at = NULL
+                WHERE waitlist_entry_id IN (
+                    SELECT waitlist_entry_id
+                    FROM waitlist_entries
+                    WHERE status IN ('waiting', 'offered')
+                      AND reservation_start_time <= CURRENT_TIMESTAMP
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+            "#,
+            limit,
+            WaitlistStatus::Expired.as_ref(),
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .rows_affected() as usize;
+
+        let expired = sqlx::query!(
+            r#"
+                UPDATE waitlist_entries
+                SET status = $2
+                WHERE waitlist_entry_id IN (
+                    SELECT waitlist_entry_id
+                    FROM waitlist_entries
+                    WHERE status = 'offered'
+                      AND offer_expires_at <= CURRENT_TIMESTAMP
+                    ORDER BY offer_expires_at ASC
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING
+                space_id AS "space_id: SpaceId",
+                reservation_start_time,
+                reservation_end_time
+            "#,
+            limit,
+            WaitlistStatus::Expired.as_ref(),
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        for row in &expired {
+            self.offer_released_slot(
+                &mut tx,
+                row.space_id,
+                row.reservation_start_time.into(),
+                row.reservation_end_time.into(),
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(passed + expired.len())
+    }
+
+    // 予約の終了・キャンセルや案内の期限切れで空いた時間帯を、キャンセル待ちのユーザーに登録の古い順に案内する
+    // 空いた時間帯と重なる時間帯を待っているユーザーのうち、
+    // 希望の時間帯全体がまだ予約できないユーザーやルールに合わないユーザーは、そのまま待ち続けてもらう
+    pub(super) async fn offer_released_slot(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        space_id: SpaceId,
+        released_start_time: DateTime<Local>,
+        released_end_time: DateTime<Local>,
+    ) -> AppResult<()> {
+        let candidates = sqlx::query_as!(
+            LockedWaitlistEntryRow,
+            r#"
+                SELECT
+                waitlist_entry_id,
+                space_id,
+                user_id,
+                reservation_start_time,
+                reservation_end_time,
+                fulfillment,
+                status,
+                offer_expires_at AS "offer_expires_at: DateTime<Local>"
+                FROM waitlist_entries
+                WHERE space_id = $1
+                  AND status = 'waiting'
+                  AND reservation_start_time > CURRENT_TIMESTAMP
+                  AND tstzrange(reservation_start_time, reservation_end_time, '[)')
+                      && tstzrange($2, $3, '[)')
+                ORDER BY created_at ASC
+                FOR UPDATE SKIP LOCKED
+            "#,
+            space_id as _,
+            released_start_time,
+            released_end_time
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        for entry in &candidates {
+            // 1 人分の処理が途中で失敗しても他のユーザーの処理に影響しないよう、セーブポイントを置く
+            let mut savepoint = tx.begin().await.map_err(AppError::TransactionError)?;
+            match self.fulfill_waitlist_entry(&mut savepoint, entry).await {
+                Ok(()) => savepoint.commit().await.map_err(AppError::TransactionError)?,
+                Err(
+                    AppError::UnprocessableEntity(_)
+                    | AppError::EntityNotFound(_)
+                    | AppError::RuleViolation { .. }
+                    | AppError::Conflict { .. },
+                ) => savepoint
+                    .rollback()
+                    .await
+                    .map_err(AppError::TransactionError)?,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    // キャンセル待ちの希望の時間帯が予約できる場合に、希望に応じて予約するか案内を送る
+    async fn fulfill_waitlist_entry(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        entry: &LockedWaitlistEntryRow,
+    ) -> AppResult<()> {
+        self.check_reservable(
+            tx,
+            entry.space_id,
+            entry.reservation_start_time,
+            entry.reservation_end_time,
+            &[],
+        )
+        .await?;
+        self.check_waitlist_hold(
+            tx,
+            entry.space_id,
+            entry.user_id,
+            entry.reservation_start_time,
+            entry.reservation_end_time,
+        )
+        .await?;
+        self.check_booking_policy(
+            tx,
+            entry.space_id,
+            entry.user_id,
+            entry.reservation_start_time,
+            entry.reservation_end_time,
+            &[],
+        )
+        .await?;
+
+        let fulfillment = entry.fulfillment.parse::<WaitlistFulfillment>().map_err(|_| {
+            AppError::ConversionEntityError(format!("unknown fulfillment: {}", entry.fulfillment))
+        })?;
+        match fulfillment {
+            WaitlistFulfillment::AutoBook => {
+                let (reservation_id, status) = self
+                    .book_waitlist_entry(tx, entry, Local::now())
+                    .await?;
+                self.enqueue_created_notification(
+                    tx,
+                    NotificationKind::WaitlistBooked,
+                    reservation_id,
+                    status,
+                )
+                .await?;
+            }
+            WaitlistFulfillment::Offer => {
+                // 確定用のトークンは推測できないよう、十分な長さの乱数にする
+                sqlx::query!(
+                    r#"
+                        UPDATE waitlist_entries
+                        SET
+                            status = $2,
+                            claim_token = encode(gen_random_bytes(24), 'hex'),
+                            offer_expires_at = CURRENT_TIMESTAMP(3) + make_interval(mins => $3)
+                        WHERE waitlist_entry_id = $1
+                    "#,
+                    entry.waitlist_entry_id as _,
+                    WaitlistStatus::Offered.as_ref(),
+                    WAITLIST_OFFER_HOLD_MINUTES as i32,
+                )
+                .execute(&mut **tx)
+                .await
+                .map_err(AppError::SpecificOperationError)?;
+                enqueue_waitlist_offer_notification(tx, entry.waitlist_entry_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // キャンセル待ちの希望の時間帯で予約し、キャンセル待ちを予約済みにする
+    // リマインダーはユーザーの既定値を使う
+    // 作成した予約の ID と、作成時の状態（承認が必要なスペースでは承認待ち）を返す
+    async fn book_waitlist_entry(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        entry: &LockedWaitlistEntryRow,
+        reserved_at: DateTime<Local>,
+    ) -> AppResult<(ReservationId, ReservationStatus)> {
+        let reminder_lead_minutes = sqlx::query_scalar!(
+            r#"
+                SELECT reminder_lead_minutes
+                FROM users
+                WHERE user_id = $1
+            "#,
+            entry.user_id as _
+        )
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let reservation_id = ReservationId::new();
+        let event = CreateReservation::new(
+            entry.space_id,
+            entry.user_id,
+            reserved_at,
+            entry.reservation_start_time,
+            entry.reservation_end_time,
+            reminder_lead_minutes,
+        );
+        let status = self
+            .insert_reservation(tx, reservation_id, &event, None)
+            .await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE waitlist_entries
+                SET status = $2, reservation_id = $3, offer_expires_at = NULL
+                WHERE waitlist_entry_id = $1
+            "#,
+            entry.waitlist_entry_id as _,
+            WaitlistStatus::Booked.as_ref(),
+            reservation_id as _,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok((reservation_id, status))
+    }
+
+    // 他のユーザーに案内中の時間帯と重なっていないか確認する
+    // 案内の期限までは、案内されたユーザー以外は予約できない
+    pub(super) async fn check_waitlist_hold(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        space_id: SpaceId,
+        user_id: UserId,
+        reservation_start_time: DateTime<Local>,
+        reservation_end_time: DateTime<Local>,
+    ) -> AppResult<()> {
+        let offer_expires_at = sqlx::query_scalar!(
+            r#"
+                SELECT offer_expires_at AS "offer_expires_at!: DateTime<Local>"
+                FROM waitlist_entries
+                WHERE space_id = $1
+                  AND user_id <> $2
+                  AND status = 'offered'
+                  AND offer_expires_at > CURRENT_TIMESTAMP
+                  AND tstzrange(reservation_start_time, reservation_end_time, '[)')
+                      && tstzrange($3, $4, '[)')
+                ORDER BY offer_expires_at DESC
+                LIMIT 1
+            "#,
+            space_id as _,
+            user_id as _,
+            reservation_start_time,
+            reservation_end_time
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if let Some(offer_expires_at) = offer_expires_at {
+            return Err(AppError::Conflict {
+                message: ErrorMessage::OfferedToWaitlist {
+                    space_id: space_id.to_string(),
+                    offer_expires_at,
+                },
+                conflicting_reservation_id: None,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn parse_status(status: &str) -> AppResult<WaitlistStatus> {
+    status
+        .parse()
+        .map_err(|_| AppError::ConversionEntityError(format!("unknown status: {status}")))
+}
+
+fn offer_not_found() -> AppError {
+    AppError::EntityNotFound(ErrorMessage::WaitlistOfferNotFound)
+}
@@ -2,21 +2,36 @@ use async_trait::async_trait;
 use derive_new::new;
 use kernel::model::{
     space::Reservation,
-    id::{SpaceId, UserId},
+    id::{SpaceBlackoutId, SpaceId, UserId},
     {space::event::DeleteSpace, list::PaginatedList},
 };
 use kernel::{
     model::space::{
-        event::{CreateSpace, UpdateSpace},
+        availability::{
+            AvailabilityOptions, AvailabilityWindow, AvailableSpaceOptions, SpaceAvailability,
+        },
+        event::{
+            CreateSpace, CreateSpaceBlackout, DeleteSpaceBlackout, UpdateApprovalPolicy,
+            UpdateBookingPolicy, UpdateCheckInPolicy, UpdateOpeningHours, UpdateSpace,
+        },
+        policy::{BookingPolicy, RoleBookingPolicy, SpaceBookingPolicy},
+        schedule::{validate_opening_hours, OpeningHours, SpaceBlackout},
         Space, SpaceListOptions,
     },
     repository::space::SpaceRepository,
 };
-use crate::database::model::space::SpaceReservationRow;
+use crate::database::model::space::{
+    BookingPolicyRow, OpeningHoursRow, SpaceApproverRow, SpaceBlackoutRow, SpaceReservationRow,
+};
 use crate::database::ConnectionPool;
 use crate::database::model::space::{SpaceRow, PaginatedSpaceRow};
+use crate::repository::webhook::enqueue_space_webhook;
+use kernel::model::webhook::WebhookEvent;
 use std::collections::HashMap;
-use shared::error::{AppError, AppResult};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
 
 #[derive(new)]
 pub struct SpaceRepositoryImpl {
@@ -63,7 +78,11 @@ impl SpaceRepository for SpaceRepositoryImpl {
                 s.equipment,
                 s.address,
                 s.user_id AS owned_by,
-                u.user_name AS owner_name
+                u.user_name AS owner_name,
+                s.timezone,
+                s.check_in_grace_minutes,
+                s.check_in_code,
+                s.requires_approval
                 FROM spaces AS s
                 INNER JOIN users AS u ON s.user_id  = u.user_id
                 ;
@@ -98,49 +117,184 @@ impl SpaceRepository for SpaceRepositoryImpl {
         let total = rows.first().map(|r| r.total).unwrap_or_default(); // レコードが 1 つもないときは total も 0 にする
         let space_ids = rows.into_iter().map(|r| r.space_id).collect::<Vec<SpaceId>>();
 
-        let rows: Vec<SpaceRow> = sqlx::query_as!(
-            SpaceRow,
+        let items = self.find_spaces_by_ids(&space_ids).await?;
+
+        Ok(PaginatedList {
+                    total,
+                    limit,
+                    offset,
+                    items,
+                })
+
+    }
+
+    async fn find_availability(
+        &self,
+        space_id: SpaceId,
+        options: AvailabilityOptions,
+    ) -> AppResult<SpaceAvailability> {
+        let AvailabilityWindow { from, to } = options.window;
+        let space = sqlx::query!(
             r#"
                 SELECT
-                    s.space_id AS space_id,
-                    s.space_name AS space_name,
-                    s.is_active AS is_active,
-                    s.description AS description,
-                    s.capacity AS capacity,
-                    s.equipment AS equipment,
-                    s.address AS address,
-                    u.user_id AS owned_by,
-                    u.user_name AS owner_name
+                    s.is_active,
+                    EXISTS (
+                        SELECT 1 FROM space_opening_hours AS h WHERE h.space_id = s.space_id
+                    ) AS "has_opening_hours!"
                 FROM spaces AS s
-                INNER JOIN users AS u USING(user_id)
-                WHERE s.space_id IN (SELECT * FROM UNNEST($1::uuid[]))
+                WHERE s.space_id = $1
+            "#,
+            space_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })
+        })?;
+
+        // 利用できないスペースには空き時間がない
+        let free_intervals = if space.is_active {
+            // 予約を受け付けない期間も、予約済みの時間帯と同じく空きから除く
+            let reserved = sqlx::query!(
+                r#"
+                    SELECT
+                        reservation_start_time AS "start_time!",
+                        reservation_end_time AS "end_time!"
+                    FROM reservations
+                    WHERE space_id = $1
+                      AND status IN ('pending', 'confirmed', 'checked_in')
+                      AND period && tstzrange($2, $3, '[)')
+                    UNION ALL
+                    SELECT start_time, end_time
+                    FROM space_blackouts
+                    WHERE space_id = $1
+                      AND tstzrange(start_time, end_time, '[)') && tstzrange($2, $3, '[)')
+                    ORDER BY 1 ASC
+                "#,
+                space_id as _,
+                from,
+                to,
+            )
+            .fetch_all(self.db.inner_ref())
+            .await
+            .map_err(AppError::SpecificOperationError)?
+            .into_iter()
+            .map(|r| (r.start_time.into(), r.end_time.into()))
+            .collect::<Vec<_>>();
+
+            // 営業時間がある場合は、期間内の各日の営業時間帯をスペースのタイムゾーンで求める
+            let opening = if space.has_opening_hours {
+                Some(
+                    sqlx::query!(
+                        r#"
+                            SELECT
+                                (d.day::date + make_interval(mins => h.open_minutes))
+                                    AT TIME ZONE s.timezone AS "open_time!",
+                                (d.day::date + make_interval(mins => h.close_minutes))
+                                    AT TIME ZONE s.timezone AS "close_time!"
+                            FROM spaces AS s
+                            INNER JOIN space_opening_hours AS h ON h.space_id = s.space_id
+                            CROSS JOIN generate_series(
+                                ($2 AT TIME ZONE s.timezone)::date,
+                                ($3 AT TIME ZONE s.timezone)::date,
+                                interval '1 day'
+                            ) AS d(day)
+                            WHERE s.space_id = $1
+                              AND h.weekday = EXTRACT(ISODOW FROM d.day)
+                            ORDER BY 1 ASC
+                        "#,
+                        space_id as _,
+                        from,
+                        to,
+                    )
+                    .fetch_all(self.db.inner_ref())
+                    .await
+                    .map_err(AppError::SpecificOperationError)?
+                    .into_iter()
+                    .map(|r| (r.open_time.into(), r.close_time.into()))
+                    .collect::<Vec<_>>(),
+                )
+            } else {
+                None
+            };
+            options.free_intervals(chrono::Local::now(), &reserved, opening.as_deref())
+        } else {
+            Vec::new()
+        };
+
+        Ok(SpaceAvailability {
+            space_id,
+            window: options.window,
+            slot: options.slot,
+            free_intervals,
+        })
+    }
+
+    async fn find_available(
+        &self,
+        options: AvailableSpaceOptions,
+    ) -> AppResult<PaginatedList<Space>> {
+        let AvailableSpaceOptions {
+            window: AvailabilityWindow { from, to },
+            capacity,
+            equipment,
+            limit,
+            offset,
+        } = options;
+        // 設備は部分一致で、指定したものをすべて含むスペースに絞り込む
+        let rows: Vec<PaginatedSpaceRow> = sqlx::query_as!(
+            PaginatedSpaceRow,
+            r#"
+                SELECT
+                COUNT(*) OVER() AS "total!",
+                s.space_id AS space_id
+                FROM spaces AS s
+                WHERE s.is_active
+                  AND ($3::int IS NULL OR s.capacity >= $3)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM UNNEST($4::text[]) AS e(keyword)
+                      WHERE strpos(lower(s.equipment), lower(e.keyword)) = 0
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM reservations AS r
+                      WHERE r.space_id = s.space_id
+                        AND r.status IN ('pending', 'confirmed', 'checked_in')
+                        AND r.period && tstzrange($1, $2, '[)')
+                  )
+                  AND NOT EXISTS (
+                      SELECT 1 FROM space_blackouts AS b
+                      WHERE b.space_id = s.space_id
+                        AND tstzrange(b.start_time, b.end_time, '[)') && tstzrange($1, $2, '[)')
+                  )
+                  AND space_is_open(s.space_id, $1, $2)
                 ORDER BY s.created_at DESC
+                LIMIT $5
+                OFFSET $6
             "#,
-            &space_ids as _
+            from,
+            to,
+            capacity,
+            &equipment,
+            limit,
+            offset
         )
         .fetch_all(self.db.inner_ref())
         .await
         .map_err(AppError::SpecificOperationError)?;
 
-
-        let space_ids = rows.iter().map(|space| space.space_id).collect::<Vec<_>>();
-        let mut reservations = self.find_reservations(&space_ids).await?;
-        let items = rows
-            .into_iter()
-            .map(|row| {
-                let reservation = reservations.remove(&row.space_id);
-                row.into_space(reservation)
-            })
-            .collect();
-
+        let total = rows.first().map(|r| r.total).unwrap_or_default(); // レコードが 1 つもないときは total も 0 にする
+        let space_ids = rows.into_iter().map(|r| r.space_id).collect::<Vec<SpaceId>>();
+        let items = self.find_spaces_by_ids(&space_ids).await?;
 
         Ok(PaginatedList {
-                    total,
-                    limit,
-                    offset,
-                    items,
-                })
-
+            total,
+            limit,
+            offset,
+            items,
+        })
     }
 
     async fn find_by_id(&self, space_id: SpaceId) -> AppResult<Option<Space>> {
@@ -156,7 +310,11 @@ impl SpaceRepository for SpaceRepositoryImpl {
                     s.equipment AS equipment,
                     s.address AS address, 
                     u.user_id AS owned_by,
-                    u.user_name AS owner_name
+                    u.user_name AS owner_name,
+                    s.timezone AS timezone,
+                    s.check_in_grace_minutes,
+                    s.check_in_code,
+                    s.requires_approval
                 FROM spaces AS s
                 INNER JOIN users AS u USING(user_id)
                 WHERE s.space_id = $1
@@ -170,19 +328,41 @@ impl SpaceRepository for SpaceRepositoryImpl {
          match row {
             Some(r) => {
                 let reservation = self.find_reservations(&[r.space_id]).await?.remove(&r.space_id);
-                Ok(Some(r.into_space(reservation)))
+                let opening_hours = self
+                    .find_opening_hours(&[r.space_id])
+                    .await?
+                    .remove(&r.space_id)
+                    .unwrap_or_default();
+                let booking_policy = self
+                    .find_booking_policies(&[r.space_id])
+                    .await?
+                    .remove(&r.space_id)
+                    .unwrap_or_default();
+                let approvers = self
+                    .find_approvers(&[r.space_id])
+                    .await?
+                    .remove(&r.space_id)
+                    .unwrap_or_default();
+                Ok(Some(r.into_space(
+                    reservation,
+                    opening_hours,
+                    booking_policy,
+                    approvers,
+                )))
             }
             None => Ok(None),
         }
 
     }
-    // update は SQL の UPDATE 文に当てはめているだけであるが、
-    // 内容を変更できるのは所有者のみとするため、
-    // SQL クエリの WHERE 条件を space_id と user_id の複合条件としている。
+    // update は SQL の UPDATE 文に当てはめているだけである
+    // 管理者は他のユーザーのスペースも変更できるため、所有者での絞り込みは行わない
+    // 操作できるかどうかは、呼び出し側で AuthorizationPolicy により確認しておくこと
     async fn update(&self, event: UpdateSpace) -> AppResult<()> {
-        let res = sqlx::query!(
+        let mut tx = self.db.begin().await?;
+        // 停止したことを webhook で知らせるため、更新前の is_active も返す
+        let was_active = sqlx::query_scalar!(
             r#"
-                UPDATE spaces
+                UPDATE spaces AS s
                 SET
                     space_name = $1,
                     is_active = $2,
@@ -190,8 +370,14 @@ impl SpaceRepository for SpaceRepositoryImpl {
                     capacity = $4,
                     equipment = $5,
                     address = $6
-                WHERE space_id = $7
-                AND user_id = $8
+                FROM (
+                    SELECT space_id, is_active
+                    FROM spaces
+                    WHERE space_id = $7
+                    FOR UPDATE
+                ) AS prev
+                WHERE s.space_id = prev.space_id
+                RETURNING prev.is_active
             "#,
             event.space_name,
             event.is_active,
@@ -199,70 +385,558 @@ impl SpaceRepository for SpaceRepositoryImpl {
             event.capacity,
             event.equipment,
             event.address,
-            event.space_id as _,
-            event.requested_user as _
+            event.space_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            })
+        })?;
+        notify_if_deactivated(&mut tx, &event, was_active).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // is_activeを更新する
+    // 管理者は他のユーザーのスペースも停止できるため、所有者での絞り込みは行わない
+    // 操作できるかどうかは、呼び出し側で AuthorizationPolicy により確認しておくこと
+    async fn update_is_active(&self, event: UpdateSpace) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+        let was_active = sqlx::query_scalar!(
+            r#"
+                UPDATE spaces AS s
+                SET
+                    is_active = $1
+                FROM (
+                    SELECT space_id, is_active
+                    FROM spaces
+                    WHERE space_id = $2
+                    FOR UPDATE
+                ) AS prev
+                WHERE s.space_id = prev.space_id
+                RETURNING prev.is_active
+            "#,
+            event.is_active,
+            event.space_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            })
+        })?;
+        notify_if_deactivated(&mut tx, &event, was_active).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+
+    // update と同様に、操作できるかどうかは呼び出し側で確認しておくこと
+    async fn delete(&self, event: DeleteSpace) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM spaces
+                WHERE space_id = $1
+            "#,
+            event.space_id as _
         )
         .execute(self.db.inner_ref())
         .await
         .map_err(AppError::SpecificOperationError)?;
+
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified space not found".into()));
+            return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            }));
         }
 
         Ok(())
     }
 
-    // is_activeを更新する
-    async fn update_is_active(&self, event: UpdateSpace) -> AppResult<()> {
+    // 営業時間は曜日ごとの差分ではなく、一覧を丸ごと置き換える
+    // スペースの変更と同様に、操作できるかどうかは呼び出し側で確認しておくこと
+    async fn update_opening_hours(&self, event: UpdateOpeningHours) -> AppResult<()> {
+        validate_opening_hours(&event.opening_hours)?;
+
+        let is_valid_timezone = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) AS "exists!""#,
+            event.timezone
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !is_valid_timezone {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidTimezone {
+                    timezone: event.timezone.clone(),
+                },
+            ));
+        }
+
+        let mut tx = self.db.begin().await?;
+
         let res = sqlx::query!(
             r#"
                 UPDATE spaces
-                SET
-                    is_active = $1
+                SET timezone = $1
                 WHERE space_id = $2
-                AND user_id = $3
             "#,
-            event.is_active,
+            event.timezone,
+            event.space_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            }));
+        }
+
+        sqlx::query!(
+            r#"DELETE FROM space_opening_hours WHERE space_id = $1"#,
+            event.space_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 曜日は ISO 8601 の番号（月曜日 = 1 〜 日曜日 = 7）で保存する
+        let (weekdays, (open_minutes, close_minutes)): (Vec<i32>, (Vec<i32>, Vec<i32>)) = event
+            .opening_hours
+            .iter()
+            .map(|h| {
+                (
+                    h.weekday.number_from_monday() as i32,
+                    (h.open_minutes, h.close_minutes),
+                )
+            })
+            .unzip();
+        sqlx::query!(
+            r#"
+                INSERT INTO space_opening_hours (space_id, weekday, open_minutes, close_minutes)
+                SELECT $1, *
+                FROM UNNEST($2::int[], $3::int[], $4::int[])
+            "#,
+            event.space_id as _,
+            &weekdays,
+            &open_minutes,
+            &close_minutes
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn find_blackouts(&self, space_id: SpaceId) -> AppResult<Vec<SpaceBlackout>> {
+        sqlx::query_as!(
+            SpaceBlackoutRow,
+            r#"
+                SELECT space_blackout_id, start_time, end_time, reason
+                FROM space_blackouts
+                WHERE space_id = $1
+                  AND end_time > CURRENT_TIMESTAMP
+                ORDER BY start_time ASC
+            "#,
+            space_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map(|rows| rows.into_iter().map(SpaceBlackout::from).collect())
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    // 既存の予約と重なる期間も登録できる。その予約はそのまま残るため、必要に応じて個別にキャンセルする
+    async fn create_blackout(&self, event: CreateSpaceBlackout) -> AppResult<SpaceBlackoutId> {
+        if event.start_time >= event.end_time {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidBlackoutPeriod,
+            ));
+        }
+        let space_blackout_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO space_blackouts (space_id, start_time, end_time, reason, created_by)
+                SELECT space_id, $3, $4, $5, $2
+                FROM spaces
+                WHERE space_id = $1
+                RETURNING space_blackout_id AS "space_blackout_id: SpaceBlackoutId"
+            "#,
             event.space_id as _,
-            event.requested_user as _
+            event.requested_user as _,
+            event.start_time,
+            event.end_time,
+            event.reason
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            })
+        })?;
+
+        Ok(space_blackout_id)
+    }
+
+    async fn delete_blackout(&self, event: DeleteSpaceBlackout) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM space_blackouts
+                WHERE space_blackout_id = $1
+                AND space_id = $2
+            "#,
+            event.space_blackout_id as _,
+            event.space_id as _
         )
         .execute(self.db.inner_ref())
         .await
         .map_err(AppError::SpecificOperationError)?;
+
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified space not found".into()));
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::SpaceBlackoutNotFound {
+                    space_blackout_id: event.space_blackout_id.to_string(),
+                },
+            ));
         }
 
         Ok(())
     }
 
+    // 予約のルールも、営業時間と同様に一覧を丸ごと置き換える
+    async fn update_booking_policy(&self, event: UpdateBookingPolicy) -> AppResult<()> {
+        event.booking_policy.validate()?;
 
-    // update と同様に、delete も所有者のみが行えるよう、
-    // SQL クエリの WHERE の条件には space_id と user_id の複合条件にしている。
-    async fn delete(&self, event: DeleteSpace) -> AppResult<()> {
-        let res = sqlx::query!(
+        let mut tx = self.db.begin().await?;
+
+        // スペースがあることを確認し、同時に置き換えられないよう行ロックを取る
+        let locked = sqlx::query!(
             r#"
-                DELETE FROM spaces
+                SELECT space_id
+                FROM spaces
                 WHERE space_id = $1
-                AND user_id = $2
+                FOR UPDATE
             "#,
-            event.space_id as _,
-            event.requested_user as _
+            event.space_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if locked.is_none() {
+            return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            }));
+        }
+
+        sqlx::query!(
+            r#"DELETE FROM space_booking_policies WHERE space_id = $1"#,
+            event.space_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let SpaceBookingPolicy {
+            policy,
+            role_overrides,
+        } = &event.booking_policy;
+        let policies = std::iter::once((None, policy)).chain(
+            role_overrides
+                .iter()
+                .map(|role_override| (Some(role_override.role.as_ref()), &role_override.policy)),
+        );
+        for (role_name, policy) in policies {
+            // スペースのルールは、すべての項目が制限なしなら保存しない
+            // ロールごとのルールは、制限なしであることにも意味があるため保存する
+            if role_name.is_none() && *policy == BookingPolicy::default() {
+                continue;
+            }
+            sqlx::query!(
+                r#"
+                    INSERT INTO space_booking_policies
+                    (space_id, role_id, min_duration_minutes, max_duration_minutes,
+                    slot_granularity_minutes, max_days_in_advance,
+                    max_active_reservations, max_reservations_per_week, max_recent_no_shows)
+                    VALUES (
+                        $1,
+                        (SELECT role_id FROM roles WHERE role_name = $2),
+                        $3, $4, $5, $6, $7, $8, $9
+                    )
+                "#,
+                event.space_id as _,
+                role_name,
+                policy.min_duration_minutes,
+                policy.max_duration_minutes,
+                policy.slot_granularity_minutes,
+                policy.max_days_in_advance,
+                policy.max_active_reservations,
+                policy.max_reservations_per_week,
+                policy.max_recent_no_shows,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    // チェックインの設定も、スペースの変更と同様に操作できるかどうかは呼び出し側で確認しておくこと
+    // 設定を変えても、すでにチェックインした予約はそのまま扱う
+    async fn update_check_in_policy(&self, event: UpdateCheckInPolicy) -> AppResult<()> {
+        event.check_in_policy.validate()?;
+
+        let res = sqlx::query!(
+            r#"
+                UPDATE spaces
+                SET
+                    check_in_grace_minutes = $1,
+                    check_in_code = $2
+                WHERE space_id = $3
+            "#,
+            event.check_in_policy.grace_minutes,
+            event.check_in_policy.code,
+            event.space_id as _
         )
         .execute(self.db.inner_ref())
         .await
         .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    // 承認の設定も、予約のルールと同様に承認者の一覧を丸ごと置き換える
+    // 設定を変えても、すでにある予約の状態はそのまま扱う
+    async fn update_approval_policy(&self, event: UpdateApprovalPolicy) -> AppResult<()> {
+        event.approval_policy.validate()?;
+
+        let mut tx = self.db.begin().await?;
 
+        // スペースがあることを確認し、同時に置き換えられないよう行ロックを取る
+        let res = sqlx::query!(
+            r#"
+                UPDATE spaces
+                SET requires_approval = $1
+                WHERE space_id = $2
+            "#,
+            event.approval_policy.requires_approval,
+            event.space_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified space not found".into()));
+            return Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: event.space_id.to_string(),
+            }));
+        }
+
+        let approvers = &event.approval_policy.approvers;
+        let existing = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM users WHERE user_id = ANY($1)"#,
+            approvers as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if existing != approvers.len() as i64 {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::UnknownApprover,
+            ));
         }
 
+        sqlx::query!(
+            r#"DELETE FROM space_approvers WHERE space_id = $1"#,
+            event.space_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                INSERT INTO space_approvers (space_id, user_id)
+                SELECT $1, user_id FROM UNNEST($2::uuid[]) AS t(user_id)
+            "#,
+            event.space_id as _,
+            approvers as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
 }
 
 
 impl SpaceRepositoryImpl {
+    // ページネーションで絞り込んだ space_id のスペースを、作成日時の新しい順に取得する
+    async fn find_spaces_by_ids(&self, space_ids: &[SpaceId]) -> AppResult<Vec<Space>> {
+        let rows: Vec<SpaceRow> = sqlx::query_as!(
+            SpaceRow,
+            r#"
+                SELECT
+                    s.space_id AS space_id,
+                    s.space_name AS space_name,
+                    s.is_active AS is_active,
+                    s.description AS description,
+                    s.capacity AS capacity,
+                    s.equipment AS equipment,
+                    s.address AS address,
+                    u.user_id AS owned_by,
+                    u.user_name AS owner_name,
+                    s.timezone AS timezone,
+                    s.check_in_grace_minutes,
+                    s.check_in_code,
+                    s.requires_approval
+                FROM spaces AS s
+                INNER JOIN users AS u USING(user_id)
+                WHERE s.space_id IN (SELECT * FROM UNNEST($1::uuid[]))
+                ORDER BY s.created_at DESC
+            "#,
+            space_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let space_ids = rows.iter().map(|space| space.space_id).collect::<Vec<_>>();
+        let mut reservations = self.find_reservations(&space_ids).await?;
+        let mut opening_hours = self.find_opening_hours(&space_ids).await?;
+        let mut booking_policies = self.find_booking_policies(&space_ids).await?;
+        let mut approvers = self.find_approvers(&space_ids).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let reservation = reservations.remove(&row.space_id);
+                let opening_hours = opening_hours.remove(&row.space_id).unwrap_or_default();
+                let booking_policy = booking_policies.remove(&row.space_id).unwrap_or_default();
+                let approvers = approvers.remove(&row.space_id).unwrap_or_default();
+                row.into_space(reservation, opening_hours, booking_policy, approvers)
+            })
+            .collect())
+    }
+
+    // 指定された space_id の予約のルールを、ロールごとのルールとあわせて返す
+    async fn find_booking_policies(
+        &self,
+        space_ids: &[SpaceId],
+    ) -> AppResult<HashMap<SpaceId, SpaceBookingPolicy>> {
+        let rows = sqlx::query_as!(
+            BookingPolicyRow,
+            r#"
+                SELECT
+                    p.space_id,
+                    r.role_name AS "role_name?",
+                    p.min_duration_minutes,
+                    p.max_duration_minutes,
+                    p.slot_granularity_minutes,
+                    p.max_days_in_advance,
+                    p.max_active_reservations,
+                    p.max_reservations_per_week,
+                    p.max_recent_no_shows
+                FROM space_booking_policies AS p
+                LEFT JOIN roles AS r ON r.role_id = p.role_id
+                WHERE p.space_id = ANY($1)
+                ORDER BY p.space_id, r.role_name NULLS FIRST
+            "#,
+            space_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<SpaceId, SpaceBookingPolicy> = HashMap::new();
+        for row in rows {
+            let booking_policy = res.entry(row.space_id).or_default();
+            match row.into_policy()? {
+                (None, policy) => booking_policy.policy = policy,
+                (Some(role), policy) => booking_policy
+                    .role_overrides
+                    .push(RoleBookingPolicy { role, policy }),
+            }
+        }
+        Ok(res)
+    }
+
+    // 指定された space_id の承認者を、登録した順に返す
+    async fn find_approvers(
+        &self,
+        space_ids: &[SpaceId],
+    ) -> AppResult<HashMap<SpaceId, Vec<UserId>>> {
+        let rows = sqlx::query_as!(
+            SpaceApproverRow,
+            r#"
+                SELECT space_id, user_id
+                FROM space_approvers
+                WHERE space_id = ANY($1)
+                ORDER BY space_id, created_at, user_id
+            "#,
+            space_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<SpaceId, Vec<UserId>> = HashMap::new();
+        for row in rows {
+            res.entry(row.space_id).or_default().push(row.user_id);
+        }
+        Ok(res)
+    }
+
+    // 指定された space_id の営業時間を、曜日・開始時刻の順に返す
+    async fn find_opening_hours(
+        &self,
+        space_ids: &[SpaceId],
+    ) -> AppResult<HashMap<SpaceId, Vec<OpeningHours>>> {
+        let rows = sqlx::query_as!(
+            OpeningHoursRow,
+            r#"
+                SELECT space_id, weekday, open_minutes, close_minutes
+                FROM space_opening_hours
+                WHERE space_id = ANY($1)
+                ORDER BY space_id, weekday, open_minutes
+            "#,
+            space_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<SpaceId, Vec<OpeningHours>> = HashMap::new();
+        for row in rows {
+            let space_id = row.space_id;
+            res.entry(space_id)
+                .or_default()
+                .push(OpeningHours::try_from(row)?);
+        }
+        Ok(res)
+    }
+
     // 指定された space_id が貸出中の場合に貸出情報を返すメソッドを追加する
     async fn find_reservations(&self, space_ids: &[SpaceId]) -> AppResult<HashMap<SpaceId, Reservation>> {
         let res = sqlx::query_as!(
@@ -277,6 +951,7 @@ impl SpaceRepositoryImpl {
                 FROM reservations AS r
                 INNER JOIN users AS u USING(user_id)
                 WHERE space_id = ANY($1)
+                  AND r.status IN ('pending', 'confirmed', 'checked_in')
                 ;
             "#,
             space_ids as _
@@ -292,11 +967,26 @@ impl SpaceRepositoryImpl {
     }
 }
 
+// 利用できたスペースを停止した場合に限り、停止したことを webhook で知らせる
+async fn notify_if_deactivated(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: &UpdateSpace,
+    was_active: bool,
+) -> AppResult<()> {
+    if was_active && event.is_active == Some(false) {
+        enqueue_space_webhook(tx, WebhookEvent::SpaceDeactivated, event.space_id).await?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repository::user::UserRepositoryImpl;
-    use kernel::{model::user::event::CreateUser, repository::user::UserRepository};
+    use crate::repository::{reservation::ReservationRepositoryImpl, user::UserRepositoryImpl};
+    use kernel::{
+        model::{reservation::event::CreateReservation, user::event::CreateUser},
+        repository::{reservation::ReservationRepository, user::UserRepository},
+    };
 
 
     #[sqlx::test]
@@ -306,22 +996,29 @@ mod tests {
         // テストコードのほうでもロールおよびユーザー情報を追加するコードを足した。
         // テストコードで、このようなデータベースにあらかじめデータを追加しておくために
         // fixture という機能が便利であるが、次章で解説するためここでは愚直な実装としておく。
-        sqlx::query!(r#"INSERT INTO roles(name) VALUES ('Admin'), ('User');"#)
+        sqlx::query!(r#"INSERT INTO roles(role_name) VALUES ('Admin'), ('User');"#)
             .execute(&pool)
             .await?;
         let user_repo = UserRepositoryImpl::new(ConnectionPool::new(pool.clone()));
         let repo = SpaceRepositoryImpl::new(ConnectionPool::new(pool));
+        let user = user_repo
+            .create(CreateUser {
+                user_name: "Test User".into(),
+                email: "test@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
 
         let space = CreateSpace {
             space_name: "Test SpaceName".into(),
-            is_active: true.into(),
+            is_active: true,
             description: "Test Description".into(),
-            capacity: 5.into(),
+            capacity: 5,
             equipment: "Test Equipment".into(),
             address: "Test Address".into(),
         };
 
-        repo.create(space,user.id).await?;
+        repo.create(space, user.user_id).await?;
         // find_all を実行するためには SpaceListOptions 型の値が必要なので作る。
         let options = SpaceListOptions {
             limit: 20,
@@ -331,12 +1028,12 @@ mod tests {
         let res = repo.find_all(options).await?;
         assert_eq!(res.items.len(), 1);
 
-        let space_id = res.items[0].id;
+        let space_id = res.items[0].space_id;
         let res = repo.find_by_id(space_id).await?;
         assert!(res.is_some());
 
         let Space {
-            id,
+            space_id: id,
             space_name,
             is_active,
             description,
@@ -348,13 +1045,111 @@ mod tests {
         } = res.unwrap();
         assert_eq!(id, space_id);
         assert_eq!(space_name, "Test SpaceName");
-        assert_eq!(is_active, true);
+        assert!(is_active);
         assert_eq!(description, "Test Description");
         assert_eq!(capacity, 5);
         assert_eq!(equipment, "Test Equipment");
         assert_eq!(address, "Test Address");
-        assert_eq!(owner.name, "Test User");
+        assert_eq!(owner.owner_name, "Test User");
+
 
+        Ok(())
+    }
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_find_available_spaces(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        sqlx::query!(r#"INSERT INTO roles(role_name) VALUES ('Admin'), ('User');"#)
+            .execute(&pool)
+            .await?;
+        let db = ConnectionPool::new(pool);
+        let user = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Test User".into(),
+                email: "test@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let repo = SpaceRepositoryImpl::new(db.clone());
+        for (space_name, capacity, equipment) in [
+            ("Small", 4, "Whiteboard"),
+            ("Large", 12, "Projector, Whiteboard"),
+            ("Hall", 30, "Projector"),
+        ] {
+            repo.create(
+                CreateSpace {
+                    space_name: space_name.into(),
+                    is_active: true,
+                    description: "Test Description".into(),
+                    capacity,
+                    equipment: equipment.into(),
+                    address: "Test Address".into(),
+                },
+                user.user_id,
+            )
+            .await?;
+        }
+        let spaces = repo
+            .find_all(SpaceListOptions {
+                limit: 20,
+                offset: 0,
+            })
+            .await?
+            .into_inner();
+        let space_id = |name: &str| {
+            spaces
+                .iter()
+                .find(|s| s.space_name == name)
+                .map(|s| s.space_id)
+                .unwrap()
+        };
+
+        // Hall の 10:00〜11:00 に予約を入れておく
+        let from = (chrono::Local::now() + chrono::Duration::days(1))
+            .date_naive()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .unwrap();
+        let hour = chrono::Duration::hours(1);
+        ReservationRepositoryImpl::new(db)
+            .create(CreateReservation::new(
+                space_id("Hall"),
+                user.user_id,
+                chrono::Local::now(),
+                from + hour,
+                from + hour * 2,
+                vec![],
+            ))
+            .await?;
+
+        let window = AvailabilityWindow::new(from, from + hour * 3)?;
+        let res = repo
+            .find_available(AvailableSpaceOptions {
+                window,
+                capacity: Some(10),
+                equipment: vec!["projector".into()],
+                limit: 20,
+                offset: 0,
+            })
+            .await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].space_name, "Large");
+
+        let availability = repo
+            .find_availability(
+                space_id("Hall"),
+                AvailabilityOptions::new(window, chrono::Duration::minutes(30))?,
+            )
+            .await?;
+        assert_eq!(
+            availability
+                .free_intervals
+                .iter()
+                .map(|i| (i.start, i.end))
+                .collect::<Vec<_>>(),
+            vec![(from, from + hour), (from + hour * 2, from + hour * 3)]
+        );
 
         Ok(())
     }
@@ -4,11 +4,16 @@ use derive_new::new;
 use kernel::model::id::UserId;
 use kernel::model::role::Role;
 use kernel::model::user::{
-    event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+    event::{
+        CreateUser, DeleteUser, UpdateUserLanguage, UpdateUserPassword,
+        UpdateUserReminderPreference, UpdateUserRole, UpdateUserTimezone,
+    },
     User,
 };
 use kernel::repository::user::UserRepository;
 use shared::error::{AppError, AppResult};
+use shared::i18n::{ErrorMessage, Language};
+use std::str::FromStr;
 
 #[derive(new)]
 pub struct UserRepositoryImpl {
@@ -26,6 +31,9 @@ impl UserRepository for UserRepositoryImpl {
                 u.user_name,
                 u.email,
                 r.role_name as role_name,
+                u.reminder_lead_minutes,
+                u.language,
+                u.timezone,
                 u.created_at,
                 u.updated_at
                 FROM users AS u
@@ -52,6 +60,9 @@ impl UserRepository for UserRepositoryImpl {
                     u.user_name,
                     u.email,
                     r.role_name as role_name,
+                    u.reminder_lead_minutes,
+                    u.language,
+                    u.timezone,
                     u.created_at,
                     u.updated_at
                 FROM users AS u
@@ -73,10 +84,12 @@ impl UserRepository for UserRepositoryImpl {
         let hashed_password = hash_password(&event.password)?;
         // ユーザーを追加するときは管理者ではなく一般のユーザー権限とする
         let role = Role::User;
+        // リマインダーと言語、タイムゾーンの既定値はデータベースの既定値を使うため、登録した値を返してもらう
         let res = sqlx::query!(
             r#"
                 INSERT INTO users(user_id,user_name, email, password_hash, role_id)
-                SELECT $1, $2, $3, $4, role_id FROM roles WHERE role_name = $5;
+                SELECT $1, $2, $3, $4, role_id FROM roles WHERE role_name = $5
+                RETURNING reminder_lead_minutes, language, timezone;
             "#,
             user_id as _,
             event.user_name,
@@ -84,19 +97,23 @@ impl UserRepository for UserRepositoryImpl {
             hashed_password,
             role.as_ref()
         )
-        .execute(self.db.inner_ref())
+        .fetch_optional(self.db.inner_ref())
         .await
         .map_err(AppError::SpecificOperationError)?;
-        if res.rows_affected() < 1 {
+        let Some(res) = res else {
             return Err(AppError::NoRowsAffectedError(
                 "No user has been created".into(),
             ));
-        }
+        };
         Ok(User {
-            user_id: user_id,
+            user_id,
             user_name: event.user_name,
             email: event.email,
             role,
+            reminder_lead_minutes: res.reminder_lead_minutes,
+            language: Language::from_str(&res.language)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            timezone: res.timezone,
         })
     }
 
@@ -146,7 +163,91 @@ impl UserRepository for UserRepositoryImpl {
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("Specified user not found".into()));
+            return Err(AppError::EntityNotFound(ErrorMessage::UserNotFound {
+                user_id: event.user_id.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    async fn update_reminder_preference(
+        &self,
+        event: UpdateUserReminderPreference,
+    ) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE users
+                SET reminder_lead_minutes = $2
+                WHERE user_id = $1
+            "#,
+            event.user_id as _,
+            &event.reminder_lead_minutes,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::UserNotFound {
+                user_id: event.user_id.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    async fn update_language(&self, event: UpdateUserLanguage) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE users
+                SET language = $2
+                WHERE user_id = $1
+            "#,
+            event.user_id as _,
+            event.language.as_ref(),
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::UserNotFound {
+                user_id: event.user_id.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    // スペースの営業時間と同様に、PostgreSQL が解釈できるタイムゾーンのみ受け付ける
+    async fn update_timezone(&self, event: UpdateUserTimezone) -> AppResult<()> {
+        let is_valid_timezone = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) AS "exists!""#,
+            event.timezone
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !is_valid_timezone {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidTimezone {
+                    timezone: event.timezone.clone(),
+                },
+            ));
+        }
+
+        let res = sqlx::query!(
+            r#"
+                UPDATE users
+                SET timezone = $2
+                WHERE user_id = $1
+            "#,
+            event.user_id as _,
+            event.timezone,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::UserNotFound {
+                user_id: event.user_id.to_string(),
+            }));
         }
         Ok(())
     }
@@ -163,7 +264,9 @@ impl UserRepository for UserRepositoryImpl {
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("Specified user not found".into()));
+            return Err(AppError::EntityNotFound(ErrorMessage::UserNotFound {
+                user_id: event.user_id.to_string(),
+            }));
         }
         Ok(())
     }
@@ -0,0 +1,748 @@
+use crate::database::{
+    model::webhook::{
+        PaginatedWebhookDeliveryRow, WebhookDeliveryRow, WebhookDispatchRow, WebhookRow,
+    },
+    ConnectionPool,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use derive_new::new;
+use kernel::model::{
+    id::{ReservationId, SpaceId, WebhookDeliveryId, WebhookId},
+    list::PaginatedList,
+    webhook::{
+        event::{CreateWebhook, DeleteWebhook, RecordWebhookFailure, UpdateWebhook},
+        Webhook, WebhookDelivery, WebhookDeliveryListOptions, WebhookDeliveryStatus,
+        WebhookDispatch, WebhookEvent,
+    },
+};
+use kernel::repository::webhook::WebhookRepository;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+#[derive(new)]
+pub struct WebhookRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl WebhookRepository for WebhookRepositoryImpl {
+    async fn create(&self, event: CreateWebhook) -> AppResult<Webhook> {
+        let events = event_names(&event.events);
+        // 署名に使う鍵は、推測されにくい乱数を 16 進数の文字列にする
+        let row = sqlx::query_as!(
+            WebhookRow,
+            r#"
+                INSERT INTO webhooks (url, secret, events, description)
+                VALUES ($1, encode(gen_random_bytes(32), 'hex'), $2, $3)
+                RETURNING
+                    webhook_id,
+                    url,
+                    secret,
+                    events,
+                    description,
+                    is_active,
+                    created_at,
+                    updated_at
+            "#,
+            event.url,
+            &events,
+            event.description,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        row.try_into()
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<Webhook>> {
+        let rows = sqlx::query_as!(
+            WebhookRow,
+            r#"
+                SELECT
+                    webhook_id,
+                    url,
+                    secret,
+                    events,
+                    description,
+                    is_active,
+                    created_at,
+                    updated_at
+                FROM webhooks
+                ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        rows.into_iter().map(Webhook::try_from).collect()
+    }
+
+    async fn find_by_id(&self, webhook_id: WebhookId) -> AppResult<Option<Webhook>> {
+        let row = sqlx::query_as!(
+            WebhookRow,
+            r#"
+                SELECT
+                    webhook_id,
+                    url,
+                    secret,
+                    events,
+                    description,
+                    is_active,
+                    created_at,
+                    updated_at
+                FROM webhooks
+                WHERE webhook_id = $1
+            "#,
+            webhook_id as _,
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        row.map(Webhook::try_from).transpose()
+    }
+
+    async fn update(&self, event: UpdateWebhook) -> AppResult<()> {
+        let events = event_names(&event.events);
+        let res = sqlx::query!(
+            r#"
+                UPDATE webhooks
+                SET
+                    url = $2,
+                    events = $3,
+                    description = $4,
+                    is_active = $5
+                WHERE webhook_id = $1
+            "#,
+            event.webhook_id as _,
+            event.url,
+            &events,
+            event.description,
+            event.is_active,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::WebhookNotFound {
+                webhook_id: event.webhook_id.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteWebhook) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM webhooks
+                WHERE webhook_id = $1
+            "#,
+            event.webhook_id as _,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(ErrorMessage::WebhookNotFound {
+                webhook_id: event.webhook_id.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    async fn create_ping(
+        &self,
+        webhook_id: WebhookId,
+        lease_until: DateTime<Local>,
+    ) -> AppResult<WebhookDispatch> {
+        // 呼び出し側ですぐに送信するため、1 回目の試行として積んでおく
+        // 送信結果を記録する前にプロセスが落ちた場合は、lease_until を過ぎてから dispatcher が送り直す
+        let row = sqlx::query_as!(
+            WebhookDispatchRow,
+            r#"
+                WITH inserted AS (
+                    INSERT INTO webhook_deliveries (webhook_id, event, payload, attempts, next_attempt_at)
+                    SELECT w.webhook_id, $2, jsonb_build_object(
+                        'webhookId', replace(w.webhook_id::text, '-', ''),
+                        'description', w.description
+                    ), 1, $3
+                    FROM webhooks AS w
+                    WHERE w.webhook_id = $1
+                    RETURNING *
+                )
+                SELECT
+                    d.webhook_delivery_id AS "webhook_delivery_id!",
+                    d.webhook_id AS "webhook_id!",
+                    d.event AS "event!",
+                    d.payload AS "payload!",
+                    d.status AS "status!",
+                    d.attempts AS "attempts!",
+                    d.last_error,
+                    d.response_status,
+                    d.next_attempt_at AS "next_attempt_at!",
+                    d.delivered_at AS "delivered_at: DateTime<Local>",
+                    d.created_at AS "created_at!",
+                    w.url,
+                    w.secret
+                FROM inserted AS d
+                INNER JOIN webhooks AS w ON d.webhook_id = w.webhook_id
+            "#,
+            webhook_id as _,
+            WebhookEvent::Ping.as_ref(),
+            lease_until,
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::WebhookNotFound {
+                webhook_id: webhook_id.to_string(),
+            })
+        })?;
+
+        row.try_into()
+    }
+
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Local>,
+    ) -> AppResult<Vec<WebhookDispatch>> {
+        // outbox と同様に、next_attempt_at を lease_until まで進めることで、
+        // 送信中に他の dispatcher が同じ送信を取得しないようにする
+        // 無効にした送信先への送信は、有効に戻すまで保留する
+        let rows = sqlx::query_as!(
+            WebhookDispatchRow,
+            r#"
+                WITH claimed AS (
+                    UPDATE webhook_deliveries
+                    SET
+                        attempts = attempts + 1,
+                        next_attempt_at = $2
+                    WHERE webhook_delivery_id IN (
+                        SELECT d.webhook_delivery_id
+                        FROM webhook_deliveries AS d
+                        INNER JOIN webhooks AS w ON d.webhook_id = w.webhook_id
+                        WHERE d.status = 'pending'
+                          AND d.next_attempt_at <= CURRENT_TIMESTAMP
+                          AND w.is_active
+                        ORDER BY d.next_attempt_at ASC
+                        LIMIT $1
+                        FOR UPDATE OF d SKIP LOCKED
+                    )
+                    RETURNING *
+                )
+                SELECT
+                    d.webhook_delivery_id AS "webhook_delivery_id!",
+                    d.webhook_id AS "webhook_id!",
+                    d.event AS "event!",
+                    d.payload AS "payload!",
+                    d.status AS "status!",
+                    d.attempts AS "attempts!",
+                    d.last_error,
+                    d.response_status,
+                    d.next_attempt_at AS "next_attempt_at!",
+                    d.delivered_at AS "delivered_at: DateTime<Local>",
+                    d.created_at AS "created_at!",
+                    w.url,
+                    w.secret
+                FROM claimed AS d
+                INNER JOIN webhooks AS w ON d.webhook_id = w.webhook_id
+                ORDER BY d.created_at ASC
+            "#,
+            limit,
+            lease_until,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        rows.into_iter().map(WebhookDispatch::try_from).collect()
+    }
+
+    async fn mark_delivered(
+        &self,
+        webhook_delivery_id: WebhookDeliveryId,
+        response_status: i32,
+    ) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE webhook_deliveries
+                SET
+                    status = $2,
+                    response_status = $3,
+                    delivered_at = CURRENT_TIMESTAMP(3),
+                    last_error = NULL
+                WHERE webhook_delivery_id = $1
+            "#,
+            webhook_delivery_id as _,
+            WebhookDeliveryStatus::Delivered.as_ref(),
+            response_status,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::WebhookDeliveryNotFound {
+                    webhook_delivery_id: webhook_delivery_id.to_string(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    async fn record_failure(&self, event: RecordWebhookFailure) -> AppResult<()> {
+        let RecordWebhookFailure {
+            webhook_delivery_id,
+            error,
+            response_status,
+            next_attempt_at,
+        } = event;
+        // 再試行する場合は次回の送信時刻を、しない場合は dead を記録する
+        let status = match next_attempt_at {
+            Some(_) => WebhookDeliveryStatus::Pending,
+            None => WebhookDeliveryStatus::Dead,
+        };
+        let res = sqlx::query!(
+            r#"
+                UPDATE webhook_deliveries
+                SET
+                    status = $2,
+                    last_error = $3,
+                    response_status = $4,
+                    next_attempt_at = COALESCE($5, next_attempt_at)
+                WHERE webhook_delivery_id = $1
+            "#,
+            webhook_delivery_id as _,
+            status.as_ref(),
+            error,
+            response_status,
+            next_attempt_at,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                ErrorMessage::WebhookDeliveryNotFound {
+                    webhook_delivery_id: webhook_delivery_id.to_string(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    async fn find_deliveries(
+        &self,
+        options: WebhookDeliveryListOptions,
+    ) -> AppResult<PaginatedList<WebhookDelivery>> {
+        let WebhookDeliveryListOptions {
+            webhook_id,
+            status,
+            limit,
+            offset,
+        } = options;
+        let status = status.map(|s| s.as_ref().to_string());
+
+        let rows: Vec<PaginatedWebhookDeliveryRow> = sqlx::query_as!(
+            PaginatedWebhookDeliveryRow,
+            r#"
+                SELECT
+                COUNT(*) OVER() AS "total!",
+                d.webhook_delivery_id AS webhook_delivery_id
+                FROM webhook_deliveries AS d
+                WHERE d.webhook_id = $1
+                  AND ($2::VARCHAR IS NULL OR d.status = $2)
+                ORDER BY d.created_at DESC
+                LIMIT $3
+                OFFSET $4
+            "#,
+            webhook_id as _,
+            status,
+            limit,
+            offset
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let total = rows.first().map(|r| r.total).unwrap_or_default(); // レコードが 1 つもないときは total も 0 にする
+        let webhook_delivery_ids = rows
+            .into_iter()
+            .map(|r| r.webhook_delivery_id)
+            .collect::<Vec<WebhookDeliveryId>>();
+
+        let items = sqlx::query_as!(
+            WebhookDeliveryRow,
+            r#"
+                SELECT
+                    webhook_delivery_id,
+                    webhook_id,
+                    event,
+                    payload,
+                    status,
+                    attempts,
+                    last_error,
+                    response_status,
+                    next_attempt_at,
+                    delivered_at AS "delivered_at: DateTime<Local>",
+                    created_at
+                FROM webhook_deliveries
+                WHERE webhook_delivery_id IN (SELECT * FROM UNNEST($1::uuid[]))
+                ORDER BY created_at DESC
+            "#,
+            &webhook_delivery_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(WebhookDelivery::try_from)
+        .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn find_delivery_by_id(
+        &self,
+        webhook_delivery_id: WebhookDeliveryId,
+    ) -> AppResult<Option<WebhookDelivery>> {
+        let row = sqlx::query_as!(
+            WebhookDeliveryRow,
+            r#"
+                SELECT
+                    webhook_delivery_id,
+                    webhook_id,
+                    event,
+                    payload,
+                    status,
+                    attempts,
+                    last_error,
+                    response_status,
+                    next_attempt_at,
+                    delivered_at AS "delivered_at: DateTime<Local>",
+                    created_at
+                FROM webhook_deliveries
+                WHERE webhook_delivery_id = $1
+            "#,
+            webhook_delivery_id as _,
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        row.map(WebhookDelivery::try_from).transpose()
+    }
+}
+
+// 予約の状態の変化を、その出来事を送る有効な webhook それぞれに宛てて積む
+// 予約の変更と同じトランザクション内で呼び出すことで、変更が確定した場合にのみ送られるようにする
+// ペイロードはこの時点の予約から組み立てる。ID は API のレスポンスと同じ形式（ハイフンなし）にする
+pub(crate) async fn enqueue_reservation_webhook(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: WebhookEvent,
+    reservation_id: ReservationId,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT w.webhook_id, $2, jsonb_build_object(
+                'reservationId', replace(r.reservation_id::text, '-', ''),
+                'reservationSeriesId', replace(r.reservation_series_id::text, '-', ''),
+                'spaceId', replace(r.space_id::text, '-', ''),
+                'spaceName', s.space_name,
+                'userId', replace(r.user_id::text, '-', ''),
+                'userName', u.user_name,
+                'status', r.status,
+                'reservationStartTime', r.reservation_start_time,
+                'reservationEndTime', r.reservation_end_time,
+                'endSource', r.end_source,
+                'endReason', r.end_reason
+            )
+            FROM reservations AS r
+            INNER JOIN spaces AS s ON r.space_id = s.space_id
+            INNER JOIN users AS u ON r.user_id = u.user_id
+            INNER JOIN webhooks AS w ON w.is_active AND $2::VARCHAR = ANY(w.events)
+            WHERE r.reservation_id = $1
+        "#,
+        reservation_id as _,
+        event.as_ref(),
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+// スペースの状態の変化を、その出来事を送る有効な webhook それぞれに宛てて積む
+pub(crate) async fn enqueue_space_webhook(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: WebhookEvent,
+    space_id: SpaceId,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT w.webhook_id, $2, jsonb_build_object(
+                'spaceId', replace(s.space_id::text, '-', ''),
+                'spaceName', s.space_name,
+                'address', s.address,
+                'isActive', s.is_active
+            )
+            FROM spaces AS s
+            INNER JOIN webhooks AS w ON w.is_active AND $2::VARCHAR = ANY(w.events)
+            WHERE s.space_id = $1
+        "#,
+        space_id as _,
+        event.as_ref(),
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+fn event_names(events: &[WebhookEvent]) -> Vec<String> {
+    events.iter().map(|e| e.as_ref().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{
+        reservation::ReservationRepositoryImpl, space::SpaceRepositoryImpl,
+        user::UserRepositoryImpl,
+    };
+    use kernel::model::{
+        reservation::{
+            event::{CreateReservation, UpdateReturned},
+            status::{ReservationEndSource, ReservationStatus},
+        },
+        space::{
+            event::{CreateSpace, UpdateSpace},
+            SpaceListOptions,
+        },
+        user::event::CreateUser,
+    };
+    use kernel::repository::{
+        reservation::ReservationRepository, space::SpaceRepository, user::UserRepository,
+    };
+
+    #[sqlx::test]
+    #[ignore]
+    async fn test_webhook_deliveries(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        sqlx::query!(r#"INSERT INTO roles(role_name) VALUES ('Admin'), ('User');"#)
+            .execute(db.inner_ref())
+            .await?;
+        let user = UserRepositoryImpl::new(db.clone())
+            .create(CreateUser {
+                user_name: "Test User".into(),
+                email: "test@example.com".into(),
+                password: "test_password".into(),
+            })
+            .await?;
+        let space_repo = SpaceRepositoryImpl::new(db.clone());
+        space_repo
+            .create(
+                CreateSpace {
+                    space_name: "Test SpaceName".into(),
+                    is_active: true,
+                    description: "Test Description".into(),
+                    capacity: 5,
+                    equipment: "Test Equipment".into(),
+                    address: "Test Address".into(),
+                },
+                user.user_id,
+            )
+            .await?;
+        let space_id = space_repo
+            .find_all(SpaceListOptions {
+                limit: 1,
+                offset: 0,
+            })
+            .await?
+            .items[0]
+            .space_id;
+
+        let repo = WebhookRepositoryImpl::new(db.clone());
+        let door_lock = repo
+            .create(CreateWebhook {
+                url: "http://localhost:9000/door-lock".into(),
+                events: vec![
+                    WebhookEvent::ReservationCreated,
+                    WebhookEvent::ReservationCancelled,
+                    WebhookEvent::SpaceDeactivated,
+                ],
+                description: "door lock".into(),
+            })
+            .await?;
+        assert_eq!(door_lock.secret.len(), 64);
+        let signage = repo
+            .create(CreateWebhook {
+                url: "http://localhost:9000/signage".into(),
+                events: vec![WebhookEvent::ReservationReturned],
+                description: "signage".into(),
+            })
+            .await?;
+        assert_eq!(repo.find_all().await?.len(), 2);
+
+        // 予約の作成・キャンセルとスペースの停止が、購読している webhook にのみ積まれる
+        let reservation_repo = ReservationRepositoryImpl::new(db.clone());
+        let now = Local::now();
+        let start = now + chrono::Duration::days(1);
+        let end = start + chrono::Duration::hours(1);
+        let reservation_id = reservation_repo
+            .create(CreateReservation::new(
+                space_id,
+                user.user_id,
+                now,
+                start,
+                end,
+                vec![],
+            ))
+            .await?;
+        reservation_repo
+            .update_returned(UpdateReturned::new(
+                reservation_id,
+                space_id,
+                user.user_id,
+                ReservationEndSource::User,
+                None,
+                ReservationStatus::CancelledByUser,
+                Local::now(),
+                start,
+                end,
+                None,
+            ))
+            .await?;
+        let deactivate = || UpdateSpace {
+            space_id,
+            space_name: None,
+            is_active: Some(false),
+            description: None,
+            capacity: None,
+            equipment: None,
+            address: None,
+            requested_user: user.user_id,
+        };
+        space_repo.update_is_active(deactivate()).await?;
+        // 停止済みのスペースを再び停止しても積まない
+        space_repo.update_is_active(deactivate()).await?;
+
+        let deliveries = |webhook_id, status| {
+            repo.find_deliveries(WebhookDeliveryListOptions {
+                webhook_id,
+                status,
+                limit: 10,
+                offset: 0,
+            })
+        };
+        let all = deliveries(door_lock.webhook_id, None).await?;
+        assert_eq!(all.total, 3);
+        let mut events = all.items.iter().map(|d| d.event).collect::<Vec<_>>();
+        events.sort_by_key(|e| e.as_ref().to_string());
+        assert_eq!(
+            events,
+            vec![
+                WebhookEvent::ReservationCancelled,
+                WebhookEvent::ReservationCreated,
+                WebhookEvent::SpaceDeactivated,
+            ]
+        );
+        let created = all
+            .items
+            .iter()
+            .find(|d| d.event == WebhookEvent::ReservationCreated)
+            .unwrap();
+        assert_eq!(created.payload["reservationId"], reservation_id.to_string());
+        assert_eq!(created.payload["spaceName"], "Test SpaceName");
+        assert_eq!(created.payload["status"], "confirmed");
+        assert_eq!(deliveries(signage.webhook_id, None).await?.total, 0);
+
+        // 取得した送信は lease の間、他の dispatcher から見えない
+        let lease_until = Local::now() + chrono::Duration::minutes(5);
+        let claimed = repo.claim_due(10, lease_until).await?;
+        assert_eq!(claimed.len(), 3);
+        assert!(claimed
+            .iter()
+            .all(|d| d.url == door_lock.url && d.secret == door_lock.secret));
+        assert!(claimed.iter().all(|d| d.delivery.attempts == 1));
+        assert!(repo.claim_due(10, lease_until).await?.is_empty());
+
+        repo.mark_delivered(claimed[0].delivery.webhook_delivery_id, 204)
+            .await?;
+        repo.record_failure(RecordWebhookFailure {
+            webhook_delivery_id: claimed[1].delivery.webhook_delivery_id,
+            error: "unexpected status: 500".into(),
+            response_status: Some(500),
+            next_attempt_at: None,
+        })
+        .await?;
+        let delivered = repo
+            .find_delivery_by_id(claimed[0].delivery.webhook_delivery_id)
+            .await?
+            .unwrap();
+        assert_eq!(delivered.status, WebhookDeliveryStatus::Delivered);
+        assert_eq!(delivered.response_status, Some(204));
+        assert!(delivered.delivered_at.is_some());
+        let dead = deliveries(
+            door_lock.webhook_id,
+            Some(WebhookDeliveryStatus::Dead),
+        )
+        .await?;
+        assert_eq!(dead.total, 1);
+        assert_eq!(dead.items[0].response_status, Some(500));
+        assert_eq!(
+            dead.items[0].last_error.as_deref(),
+            Some("unexpected status: 500")
+        );
+
+        // 試験送信は指定した webhook にのみ積まれ、dispatcher からは見えない
+        let ping = repo.create_ping(signage.webhook_id, lease_until).await?;
+        assert_eq!(ping.delivery.event, WebhookEvent::Ping);
+        assert_eq!(ping.url, signage.url);
+        assert!(repo.claim_due(10, lease_until).await?.is_empty());
+
+        // 無効にした webhook には積まない
+        repo.update(UpdateWebhook {
+            webhook_id: door_lock.webhook_id,
+            url: door_lock.url.clone(),
+            events: door_lock.events.clone(),
+            description: door_lock.description.clone(),
+            is_active: false,
+        })
+        .await?;
+        space_repo
+            .update_is_active(UpdateSpace {
+                is_active: Some(true),
+                ..deactivate()
+            })
+            .await?;
+        space_repo.update_is_active(deactivate()).await?;
+        assert_eq!(deliveries(door_lock.webhook_id, None).await?.total, 3);
+
+        // 削除すると送信の記録もあわせて削除される
+        repo.delete(DeleteWebhook {
+            webhook_id: door_lock.webhook_id,
+        })
+        .await?;
+        assert!(repo.find_by_id(door_lock.webhook_id).await?.is_none());
+        assert_eq!(deliveries(door_lock.webhook_id, None).await?.total, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod outbox;
+pub mod reminder;
+pub mod watcher;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,134 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use derive_new::new;
+use kernel::{
+    model::outbox::{event::RecordOutboxFailure, OutboxMessage},
+    notifier::Notifier,
+    repository::outbox::OutboxRepository,
+};
+use shared::{config::OutboxConfig, error::AppResult};
+
+// 取得したメッセージを他の dispatcher から見えなくしておく時間
+// 送信にかかる時間よりも十分長くしておく
+const LEASE_DURATION_SECS: i64 = 300;
+
+// outbox に積まれた通知を送信し、失敗した場合は間隔を空けて再試行する
+#[derive(new)]
+pub struct OutboxDispatcher {
+    outbox_repository: Arc<dyn OutboxRepository>,
+    notifier: Arc<dyn Notifier>,
+    config: OutboxConfig,
+}
+
+impl OutboxDispatcher {
+    pub async fn run(self) {
+        loop {
+            match self.dispatch_due().await {
+                // 一度に取得できる件数いっぱいまで送った場合は、続きをすぐに送る
+                Ok(count) if count as i64 >= self.config.batch_size => continue,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error.message = %e, "failed to dispatch outbox messages");
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.poll_interval_secs)).await;
+        }
+    }
+
+    // 送信時刻を迎えたメッセージを送信し、処理した件数を返す
+    async fn dispatch_due(&self) -> AppResult<usize> {
+        let lease_until = Local::now() + chrono::Duration::seconds(LEASE_DURATION_SECS);
+        let messages = self
+            .outbox_repository
+            .claim_due(self.config.batch_size, lease_until)
+            .await?;
+        let count = messages.len();
+        for message in messages {
+            self.dispatch(message).await?;
+        }
+        Ok(count)
+    }
+
+    async fn dispatch(&self, message: OutboxMessage) -> AppResult<()> {
+        let OutboxMessage {
+            outbox_message_id,
+            kind,
+            attempts,
+            notification,
+            ..
+        } = message;
+
+        let Err(e) = self.notifier.notify(kind, &notification).await else {
+            return self.outbox_repository.mark_sent(outbox_message_id).await;
+        };
+
+        // 試行回数が上限に達した場合は再試行せず dead とする
+        let next_attempt_at = (attempts < self.config.max_attempts)
+            .then(|| Local::now() + backoff(&self.config, attempts));
+        if next_attempt_at.is_none() {
+            tracing::error!(
+                %outbox_message_id,
+                kind = kind.as_ref(),
+                attempts,
+                error.message = %e,
+                "giving up sending notification"
+            );
+        } else {
+            tracing::warn!(
+                %outbox_message_id,
+                kind = kind.as_ref(),
+                attempts,
+                error.message = %e,
+                "failed to send notification, will retry"
+            );
+        }
+
+        self.outbox_repository
+            .record_failure(RecordOutboxFailure {
+                outbox_message_id,
+                error: e.to_string(),
+                next_attempt_at,
+            })
+            .await
+    }
+}
+
+// attempts 回目の送信に失敗した後、次の送信までの待ち時間
+fn backoff(config: &OutboxConfig, attempts: i32) -> chrono::Duration {
+    exponential_backoff(config.backoff_base_secs, config.backoff_max_secs, attempts)
+}
+
+// base, base * 2, base * 4, ... と増やし、max で頭打ちにする
+// webhook の再試行でも同じ間隔の空け方を使う
+pub(super) fn exponential_backoff(
+    base_secs: u64,
+    max_secs: u64,
+    attempts: i32,
+) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 31) as u32;
+    let secs = base_secs
+        .saturating_mul(2u64.saturating_pow(exponent))
+        .min(max_secs);
+    chrono::Duration::seconds(secs as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_until_max() {
+        let config = OutboxConfig {
+            max_attempts: 8,
+            backoff_base_secs: 30,
+            backoff_max_secs: 200,
+            poll_interval_secs: 10,
+            batch_size: 20,
+        };
+        let secs = (1..=5)
+            .map(|attempts| backoff(&config, attempts).num_seconds())
+            .collect::<Vec<_>>();
+        assert_eq!(secs, vec![30, 60, 120, 200, 200]);
+    }
+}
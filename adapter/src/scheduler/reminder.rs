@@ -0,0 +1,52 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use derive_new::new;
+use kernel::repository::reminder::ReminderRepository;
+use shared::config::ReminderConfig;
+
+// 他のインスタンスが処理中のリマインダーしか残っていない場合に、
+// 待ち時間が 0 のまま確認を繰り返さないための最小の待ち時間
+const MIN_WAIT: Duration = Duration::from_secs(1);
+
+// 送信時刻を迎えたリマインダーを outbox に積む
+// 実際のメール送信は OutboxDispatcher が行う
+#[derive(new)]
+pub struct ReminderScheduler {
+    reminder_repository: Arc<dyn ReminderRepository>,
+    config: ReminderConfig,
+}
+
+impl ReminderScheduler {
+    pub async fn run(self) {
+        let max_idle = Duration::from_secs(self.config.max_idle_secs);
+        loop {
+            match self.reminder_repository.enqueue_due(self.config.batch_size).await {
+                // 一度に処理できる件数いっぱいまで積んだ場合は、続きをすぐに処理する
+                Ok(count) if count as i64 >= self.config.batch_size => continue,
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "enqueued reminders"),
+                Err(e) => {
+                    tracing::error!(error.message = %e, "failed to enqueue reminders");
+                    tokio::time::sleep(max_idle).await;
+                    continue;
+                }
+            }
+
+            // 次のリマインダーの送信時刻まで待つ
+            // 待機中に追加・変更された予約があっても max_idle ごとに確認し直す
+            let wait = match self.reminder_repository.find_next_due_at().await {
+                Ok(Some(next_due_at)) => (next_due_at - Local::now())
+                    .to_std()
+                    .unwrap_or(Duration::ZERO)
+                    .clamp(MIN_WAIT, max_idle),
+                Ok(None) => max_idle,
+                Err(e) => {
+                    tracing::error!(error.message = %e, "failed to find next reminder");
+                    max_idle
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::{sync::Arc, time::Duration};
+
+use derive_new::new;
+use kernel::repository::reservation::ReservationRepository;
+use shared::config::WatcherConfig;
+
+// 終了時刻を過ぎた予約を利用終了に、チェックインのないまま猶予時間を過ぎた予約を no_show にし、
+// 予約開始時刻までに承認されなかった予約と、期限を過ぎたキャンセル待ちを期限切れにする
+// 複数のインスタンスで動かしても、同時に処理するのはいずれか 1 つだけになる
+#[derive(new)]
+pub struct ReservationEndWatcher {
+    reservation_repository: Arc<dyn ReservationRepository>,
+    config: WatcherConfig,
+}
+
+impl ReservationEndWatcher {
+    pub async fn run(self) {
+        loop {
+            let archived = self.archive_ended().await;
+            let released = self.release_no_shows().await;
+            let unreviewed = self.expire_pending_reviews().await;
+            let expired = self.expire_waitlist_entries().await;
+            // 一度に処理できる件数いっぱいまで処理した場合は、続きをすぐに処理する
+            if archived || released || unreviewed || expired {
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.interval_secs)).await;
+        }
+    }
+
+    // 処理できる件数いっぱいまで処理した場合に true を返す
+    async fn archive_ended(&self) -> bool {
+        match self
+            .reservation_repository
+            .archive_ended(self.config.batch_size)
+            .await
+        {
+            Ok(count) if count as i64 >= self.config.batch_size => true,
+            Ok(0) => false,
+            Ok(count) => {
+                tracing::info!(count, "archived ended reservations");
+                false
+            }
+            Err(e) => {
+                tracing::error!(error.message = %e, "failed to archive ended reservations");
+                false
+            }
+        }
+    }
+
+    // 解放した時間帯は、キャンセル待ちのユーザーに案内される
+    async fn release_no_shows(&self) -> bool {
+        match self
+            .reservation_repository
+            .release_no_shows(self.config.batch_size)
+            .await
+        {
+            Ok(count) if count as i64 >= self.config.batch_size => true,
+            Ok(0) => false,
+            Ok(count) => {
+                tracing::info!(count, "released no-show reservations");
+                false
+            }
+            Err(e) => {
+                tracing::error!(error.message = %e, "failed to release no-show reservations");
+                false
+            }
+        }
+    }
+
+    // 期限切れにした時間帯は、キャンセル待ちのユーザーに案内される
+    async fn expire_pending_reviews(&self) -> bool {
+        match self
+            .reservation_repository
+            .expire_pending_reviews(self.config.batch_size)
+            .await
+        {
+            Ok(count) if count as i64 >= self.config.batch_size => true,
+            Ok(0) => false,
+            Ok(count) => {
+                tracing::info!(count, "expired unreviewed reservations");
+                false
+            }
+            Err(e) => {
+                tracing::error!(error.message = %e, "failed to expire unreviewed reservations");
+                false
+            }
+        }
+    }
+
+    // 期限を過ぎた案内は、次に待っているユーザーに案内される
+    async fn expire_waitlist_entries(&self) -> bool {
+        match self
+            .reservation_repository
+            .expire_waitlist_entries(self.config.batch_size)
+            .await
+        {
+            Ok(count) if count as i64 >= self.config.batch_size => true,
+            Ok(0) => false,
+            Ok(count) => {
+                tracing::info!(count, "expired waitlist entries");
+                false
+            }
+            Err(e) => {
+                tracing::error!(error.message = %e, "failed to expire waitlist entries");
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use derive_new::new;
+use kernel::{
+    model::webhook::{event::RecordWebhookFailure, WebhookDispatch, WEBHOOK_LEASE_DURATION_SECS},
+    repository::webhook::WebhookRepository,
+    webhook::WebhookSender,
+};
+use shared::{config::WebhookConfig, error::AppResult};
+
+use super::outbox::exponential_backoff;
+
+// 積まれた webhook を送信し、失敗した場合は間隔を空けて再試行する
+#[derive(new)]
+pub struct WebhookDispatcher {
+    webhook_repository: Arc<dyn WebhookRepository>,
+    sender: Arc<dyn WebhookSender>,
+    config: WebhookConfig,
+}
+
+impl WebhookDispatcher {
+    pub async fn run(self) {
+        loop {
+            match self.dispatch_due().await {
+                // 一度に取得できる件数いっぱいまで送った場合は、続きをすぐに送る
+                Ok(count) if count as i64 >= self.config.batch_size => continue,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error.message = %e, "failed to dispatch webhooks");
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.poll_interval_secs)).await;
+        }
+    }
+
+    // 送信時刻を迎えた webhook を送信し、処理した件数を返す
+    async fn dispatch_due(&self) -> AppResult<usize> {
+        let lease_until = Local::now() + chrono::Duration::seconds(WEBHOOK_LEASE_DURATION_SECS);
+        let dispatches = self
+            .webhook_repository
+            .claim_due(self.config.batch_size, lease_until)
+            .await?;
+        let count = dispatches.len();
+        for dispatch in dispatches {
+            self.dispatch(dispatch).await?;
+        }
+        Ok(count)
+    }
+
+    async fn dispatch(&self, dispatch: WebhookDispatch) -> AppResult<()> {
+        let webhook_delivery_id = dispatch.delivery.webhook_delivery_id;
+        let event = dispatch.delivery.event;
+        let attempts = dispatch.delivery.attempts;
+
+        // 2xx 以外のステータスが返った場合も失敗として再試行する
+        let (error, response_status) = match self.sender.send(&dispatch).await {
+            Ok(status) if (200..300).contains(&status) => {
+                return self
+                    .webhook_repository
+                    .mark_delivered(webhook_delivery_id, status as i32)
+                    .await;
+            }
+            Ok(status) => (format!("unexpected status: {status}"), Some(status as i32)),
+            Err(e) => (e.to_string(), None),
+        };
+
+        // 試行回数が上限に達した場合は再試行せず dead とする
+        let next_attempt_at = (attempts < self.config.max_attempts).then(|| {
+            Local::now()
+                + exponential_backoff(
+                    self.config.backoff_base_secs,
+                    self.config.backoff_max_secs,
+                    attempts,
+                )
+        });
+        if next_attempt_at.is_none() {
+            tracing::error!(
+                %webhook_delivery_id,
+                event = event.as_ref(),
+                attempts,
+                error.message = %error,
+                "giving up sending webhook"
+            );
+        } else {
+            tracing::warn!(
+                %webhook_delivery_id,
+                event = event.as_ref(),
+                attempts,
+                error.message = %error,
+                "failed to send webhook, will retry"
+            );
+        }
+
+        self.webhook_repository
+            .record_failure(RecordWebhookFailure {
+                webhook_delivery_id,
+                error,
+                response_status,
+                next_attempt_at,
+            })
+            .await
+    }
+}
@@ -0,0 +1,240 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use kernel::{model::webhook::WebhookDispatch, webhook::WebhookSender};
+use reqwest::Client;
+use sha2::Sha256;
+use shared::error::{AppError, AppResult};
+use std::time::Duration;
+
+pub const WEBHOOK_ID_HEADER: &str = "X-Webhook-Id";
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Webhook-Event";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+// 受信側が検証できるよう、"{タイムスタンプ}.{本文}" を webhook の鍵で HMAC-SHA256 により署名する
+// タイムスタンプも署名に含めることで、古いリクエストの再送を受信側で弾けるようにする
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    format!(
+        "sha256={}",
+        hmac_sha256_hex(secret.as_bytes(), format!("{timestamp}.{body}").as_bytes())
+    )
+}
+
+fn hmac_sha256_hex(key: &[u8], data: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(data);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// 送信 1 件分を、webhook で送る JSON の本文にする
+pub fn build_body(dispatch: &WebhookDispatch) -> String {
+    let delivery = &dispatch.delivery;
+    serde_json::json!({
+        "id": delivery.webhook_delivery_id.to_string(),
+        "event": delivery.event.as_ref(),
+        "createdAt": delivery.created_at.to_rfc3339(),
+        "data": delivery.payload,
+    })
+    .to_string()
+}
+
+// 署名を付けた JSON を POST で送る WebhookSender
+pub struct HttpWebhookSender {
+    client: Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new(timeout_secs: u64) -> AppResult<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(timeout_secs))
+            .build()
+            .map_err(|e| {
+                AppError::ExternalServiceError(format!("failed to build webhook client: {e}"))
+            })?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, dispatch: &WebhookDispatch) -> AppResult<u16> {
+        let body = build_body(dispatch);
+        let timestamp = Utc::now().timestamp();
+        let signature = sign_payload(&dispatch.secret, timestamp, &body);
+
+        let res = self
+            .client
+            .post(&dispatch.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(
+                WEBHOOK_ID_HEADER,
+                dispatch.delivery.webhook_delivery_id.to_string(),
+            )
+            .header(WEBHOOK_EVENT_HEADER, dispatch.delivery.event.as_ref())
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalServiceError(format!("webhook error: {e}")))?;
+
+        let status = res.status().as_u16();
+        tracing::info!(
+            webhook_id = %dispatch.delivery.webhook_id,
+            webhook_delivery_id = %dispatch.delivery.webhook_delivery_id,
+            event = dispatch.delivery.event.as_ref(),
+            status,
+            "webhook sent"
+        );
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    use kernel::model::{
+        id::{WebhookDeliveryId, WebhookId},
+        webhook::{WebhookDelivery, WebhookDeliveryStatus, WebhookEvent},
+    };
+    use std::collections::HashMap;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    fn dispatch(url: String) -> WebhookDispatch {
+        WebhookDispatch {
+            delivery: WebhookDelivery {
+                webhook_delivery_id: WebhookDeliveryId::new(),
+                webhook_id: WebhookId::new(),
+                event: WebhookEvent::ReservationCreated,
+                payload: serde_json::json!({ "spaceName": "会議室A" }),
+                status: WebhookDeliveryStatus::Pending,
+                attempts: 1,
+                last_error: None,
+                response_status: None,
+                next_attempt_at: Local::now(),
+                delivered_at: None,
+                created_at: Local::now(),
+            },
+            url,
+            secret: "test-secret".into(),
+        }
+    }
+
+    // リクエストを 1 件だけ受け取り、ヘッダーと本文を返す HTTP のスタブ
+    async fn receive_one(
+        listener: TcpListener,
+        status_line: &str,
+    ) -> (HashMap<String, String>, String) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        let (head_len, content_length) = loop {
+            let n = stream.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                let head = String::from_utf8_lossy(&buf[..pos]).to_lowercase();
+                let content_length = head
+                    .lines()
+                    .find_map(|l| l.strip_prefix("content-length:"))
+                    .map(|v| v.trim().parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                break (pos + 4, content_length);
+            }
+        };
+        while buf.len() < head_len + content_length {
+            let n = stream.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        let head = String::from_utf8_lossy(&buf[..head_len]).to_string();
+        let headers = head
+            .lines()
+            .skip(1)
+            .filter_map(|l| l.split_once(':'))
+            .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+            .collect();
+        let body = String::from_utf8_lossy(&buf[head_len..]).to_string();
+        stream
+            .write_all(format!("{status_line}\r\ncontent-length: 0\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        (headers, body)
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        // RFC 4231 のテストケース 2
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            sign_payload("Jefe", 1700000000, "{}"),
+            format!("sha256={}", hmac_sha256_hex(b"Jefe", b"1700000000.{}"))
+        );
+        // タイムスタンプが異なれば署名も変わる
+        assert_ne!(
+            sign_payload("Jefe", 1, "body"),
+            sign_payload("Jefe", 2, "body")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_signed_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let server = tokio::spawn(receive_one(listener, "HTTP/1.1 204 No Content"));
+
+        let dispatch = dispatch(url);
+        let sender = HttpWebhookSender::new(5).unwrap();
+        let status = sender.send(&dispatch).await.unwrap();
+        assert_eq!(status, 204);
+
+        let (headers, body) = server.await.unwrap();
+        assert_eq!(headers["content-type"], "application/json");
+        assert_eq!(
+            headers["x-webhook-id"],
+            dispatch.delivery.webhook_delivery_id.to_string()
+        );
+        assert_eq!(headers["x-webhook-event"], "reservation.created");
+        let timestamp: i64 = headers["x-webhook-timestamp"].parse().unwrap();
+        assert_eq!(
+            headers["x-webhook-signature"],
+            sign_payload("test-secret", timestamp, &body)
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["event"], "reservation.created");
+        assert_eq!(json["data"]["spaceName"], "会議室A");
+    }
+
+    #[tokio::test]
+    async fn test_send_returns_error_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let server = tokio::spawn(receive_one(listener, "HTTP/1.1 500 Internal Server Error"));
+
+        // 2xx 以外のステータスもエラーにせずそのまま返す
+        let status = HttpWebhookSender::new(5)
+            .unwrap()
+            .send(&dispatch(url))
+            .await
+            .unwrap();
+        assert_eq!(status, 500);
+        server.await.unwrap();
+
+        // 接続できない場合はエラーとする
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(HttpWebhookSender::new(5)
+            .unwrap()
+            .send(&dispatch(url))
+            .await
+            .is_err());
+    }
+}
@@ -1,14 +1,16 @@
-use axum::extract::FromRequestParts;
+use axum::extract::{FromRequestParts, Query};
 use axum::http::request::Parts;
 use axum::{async_trait, RequestPartsExt};
 use axum_extra::headers::authorization::Bearer;
 use axum_extra::headers::Authorization;
 use axum_extra::TypedHeader;
+use kernel::authorization::{Action, Actor, AuthorizationPolicy};
 use kernel::model::auth::AccessToken;
 use kernel::model::id::UserId;
 use kernel::model::role::Role;
 use kernel::model::user::User;
 use registry::AppRegistry;
+use serde::Deserialize;
 use shared::error::AppError;
 
 // a) リクエストの前処理を実行後、handler に渡す構造体を定義
@@ -24,6 +26,16 @@ impl AuthorizedUser {
     pub fn is_admin(&self) -> bool {
         self.user.role == Role::Admin
     }
+    pub fn actor(&self) -> Actor {
+        Actor {
+            user_id: self.user.user_id,
+            role: self.user.role,
+        }
+    }
+    // 操作を行う権限がない場合は 403 を返す
+    pub fn authorize(&self, action: Action) -> Result<(), AppError> {
+        AuthorizationPolicy::authorize(self.actor(), action)
+    }
 }
 
 #[async_trait]
@@ -58,4 +70,49 @@ impl FromRequestParts<AppRegistry> for AuthorizedUser {
 
         Ok(Self { access_token, user })
     }
-}
\ No newline at end of file
+}
+
+// 予定表（iCalendar）を取得するユーザー
+// カレンダーアプリは Authorization ヘッダを送れないため、URL の token に指定した購読用のトークンでも認証する
+// token がない場合は、通常のアクセストークンで認証する
+pub struct CalendarFeedUser {
+    pub user: User,
+}
+
+#[derive(Deserialize)]
+struct CalendarFeedQuery {
+    token: Option<String>,
+}
+
+#[async_trait]
+impl FromRequestParts<AppRegistry> for CalendarFeedUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(query) = parts
+            .extract::<Query<CalendarFeedQuery>>()
+            .await
+            .map_err(|_| AppError::UnauthorizedError)?;
+        let Some(token) = query.token else {
+            let AuthorizedUser { user, .. } =
+                AuthorizedUser::from_request_parts(parts, registry).await?;
+            return Ok(Self { user });
+        };
+
+        let user_id = registry
+            .calendar_feed_repository()
+            .find_user_id_by_token(&token)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        let user = registry
+            .user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+
+        Ok(Self { user })
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    extractor::{AuthorizedUser, CalendarFeedUser},
+    model::calendar::CalendarFeedTokenResponse,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Local};
+use kernel::model::{
+    calendar::{Calendar, CalendarEvent, CALENDAR_FEED_LIMIT, CALENDAR_FEED_PAST_DAYS},
+    id::{SpaceId, UserId},
+    list::SortOrder,
+    reservation::{Reservation, ReservationListOptions, ReservationSortKey},
+};
+use registry::AppRegistry;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{ErrorMessage, Language},
+};
+
+/// 予定表を購読するためのトークンを取得する
+pub async fn show_calendar_feed_token(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CalendarFeedTokenResponse>> {
+    registry
+        .calendar_feed_repository()
+        .find_token(user.id())
+        .await?
+        .map(CalendarFeedTokenResponse::from)
+        .map(Json)
+        .ok_or_else(|| AppError::EntityNotFound(ErrorMessage::CalendarFeedTokenNotFound))
+}
+
+/// 予定表を購読するためのトークンを発行する
+/// 発行済みの場合は新しいトークンに置き換え、以前のトークンで購読している予定表は取得できなくなる
+pub async fn issue_calendar_feed_token(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<CalendarFeedTokenResponse>)> {
+    let token = registry
+        .calendar_feed_repository()
+        .issue_token(user.id())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(token.into())))
+}
+
+/// 予定表を購読するためのトークンを取り消す
+pub async fn revoke_calendar_feed_token(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .calendar_feed_repository()
+        .revoke_token(user.id())
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 自分の予約を iCalendar 形式の予定表で返す
+/// 終わった予約は、直近のものを取り消した予定（利用終了・キャンセルなど）として含める
+pub async fn show_my_reservation_calendar(
+    CalendarFeedUser { user }: CalendarFeedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    let reservations = find_calendar_reservations(&registry, None, Some(user.user_id)).await?;
+    let events = reservations
+        .iter()
+        .map(|r| CalendarEvent::from_reservation(r, r.space.space_name.clone()))
+        .collect();
+    let name = match user.language {
+        Language::Ja => format!("{}さんの予約", user.user_name),
+        Language::En => format!("{}'s reservations", user.user_name),
+    };
+
+    Ok(calendar_response(Calendar::new(name, events)))
+}
+
+/// スペースの予約を iCalendar 形式の予定表で返す
+/// 予定の件名は予約したユーザーの名前とする
+pub async fn show_space_calendar(
+    _user: CalendarFeedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    let space = registry
+        .space_repository()
+        .find_by_id(space_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })
+        })?;
+    let reservations = find_calendar_reservations(&registry, Some(space_id), None).await?;
+    let events = reservations
+        .iter()
+        .map(|r| CalendarEvent::from_reservation(r, r.user_name.clone()))
+        .collect();
+
+    Ok(calendar_response(Calendar::new(space.space_name, events)))
+}
+
+// 予定表に含める予約を、予約開始時刻の早い順に取得する
+// 件数の上限は終わった予約と今後の予約で別々に適用し、終わった予約が多くても今後の予約を落とさない
+async fn find_calendar_reservations(
+    registry: &AppRegistry,
+    space_id: Option<SpaceId>,
+    user_id: Option<UserId>,
+) -> AppResult<Vec<Reservation>> {
+    let now = Local::now();
+    let repository = registry.reservation_repository();
+    // 利用中のものを含む今後の予約は、開始の早い順に上限まで
+    let upcoming = repository
+        .find_all(calendar_feed_options(
+            space_id,
+            user_id,
+            Some(now),
+            None,
+            SortOrder::Asc,
+        ))
+        .await?
+        .into_inner();
+    // 直近に終わった予約は、終わりに近いものから上限まで
+    let mut reservations: Vec<Reservation> = repository
+        .find_all(calendar_feed_options(
+            space_id,
+            user_id,
+            Some(now - Duration::days(CALENDAR_FEED_PAST_DAYS)),
+            Some(now),
+            SortOrder::Desc,
+        ))
+        .await?
+        .into_inner()
+        .into_iter()
+        .filter(|r| r.reservation_end_time <= now)
+        .collect();
+    reservations.reverse();
+    reservations.extend(upcoming);
+    Ok(reservations)
+}
+
+fn calendar_feed_options(
+    space_id: Option<SpaceId>,
+    user_id: Option<UserId>,
+    from: Option<DateTime<Local>>,
+    to: Option<DateTime<Local>>,
+    order: SortOrder,
+) -> ReservationListOptions {
+    ReservationListOptions {
+        space_id,
+        user_id,
+        from,
+        to,
+        statuses: Vec::new(),
+        is_cancel: None,
+        sort: ReservationSortKey::StartTime,
+        order,
+        limit: CALENDAR_FEED_LIMIT,
+        offset: 0,
+    }
+}
+
+fn calendar_response(calendar: Calendar) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar.to_ics(),
+    )
+}
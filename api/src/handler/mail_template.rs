@@ -0,0 +1,43 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::mail_template::{MailPreviewQuery, MailPreviewResponse},
+};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use garde::Validate;
+use kernel::{
+    authorization::Action,
+    model::notification::{NotificationKind, ReservationNotification, DEFAULT_TIMEZONE},
+};
+use registry::AppRegistry;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{self, ErrorMessage, Language},
+};
+
+/// 通知メールのテンプレートを見本の予約で組み立てて返す（Admin only）
+/// テンプレートのない通知は、テキストのみの本文を返す
+pub async fn preview_mail_template(
+    user: AuthorizedUser,
+    Path(kind): Path<String>,
+    Query(query): Query<MailPreviewQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<MailPreviewResponse>> {
+    user.authorize(Action::PreviewMailTemplates)?;
+    query.validate(&())?;
+
+    let kind: NotificationKind = kind
+        .parse()
+        .map_err(|_| AppError::EntityNotFound(ErrorMessage::NotificationKindNotFound { kind }))?;
+    let language = query.language.map(Language::from).unwrap_or_else(i18n::current);
+    let timezone = query.timezone.as_deref().unwrap_or(DEFAULT_TIMEZONE);
+    let sample = ReservationNotification::sample(kind, language, timezone);
+
+    registry
+        .mail_renderer()
+        .render(kind, &sample)
+        .map(|mail| MailPreviewResponse::new(kind, language.into(), mail))
+        .map(Json)
+}
@@ -2,4 +2,10 @@ pub mod health;
 pub mod space;
 pub mod auth;
 pub mod user;
-pub mod reservation;
\ No newline at end of file
+pub mod reservation;
+pub mod reservation_series;
+pub mod outbox;
+pub mod waitlist;
+pub mod mail_template;
+pub mod calendar;
+pub mod webhook;
\ No newline at end of file
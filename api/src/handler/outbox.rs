@@ -0,0 +1,47 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::outbox::{OutboxListQuery, PaginatedOutboxMessageResponse},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::{authorization::Action, model::id::OutboxMessageId};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+/// 通知の outbox を一覧する（Admin only）
+/// status=dead を指定すると送信を諦めた通知のみを取得できる
+pub async fn list_outbox_messages(
+    user: AuthorizedUser,
+    Query(query): Query<OutboxListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedOutboxMessageResponse>> {
+    user.authorize(Action::ManageOutbox)?;
+    query.validate(&())?;
+
+    registry
+        .outbox_repository()
+        .find_all(query.into())
+        .await
+        .map(PaginatedOutboxMessageResponse::from)
+        .map(Json)
+}
+
+/// 送信を諦めた通知を再送対象に戻す（Admin only）
+pub async fn requeue_outbox_message(
+    user: AuthorizedUser,
+    Path(outbox_message_id): Path<OutboxMessageId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.authorize(Action::ManageOutbox)?;
+
+    registry
+        .outbox_repository()
+        .requeue(outbox_message_id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
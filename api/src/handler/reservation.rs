@@ -1,32 +1,46 @@
 use crate::{
     extractor::AuthorizedUser,
-    model::{reservation::{
-        CreateReservationRequest, 
+    model::reservation::{
+        CheckInRequest,
+        EndReservationRequest,
+        ReservationHistoryQuery,
+        CreateReservationRequest,
         UpdateReservationRequest,
+        UpdateReservationRequestWithIds,
+        ReservationListQuery,
+        PaginatedReservationResponse,
         ReservationsResponse,
         ReservationResponse
     },
-    space::UpdateSpaceRequest,
-},
+    model::reservation_series::SeriesScopeQuery,
+};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
 };
-use uuid::Uuid;
-use reqwest::Client;
-use shared::error::AppError;
-use shared::error::AppResult;
-use base64::{engine::general_purpose, Engine as _};
-use std::error::Error as StdError;
-use chrono::{DateTime, Local};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use kernel::model::{
-    reservation::event::{CreateReservation, UpdateReturned},
+    notification::NotificationKind,
+    reminder::normalize_lead_minutes,
+    reservation::{
+        event::{
+            CheckInReservation, CreateReservation, DeactivateAllSpaces, DeactivateSpace,
+            ReviewReservation,
+            UpdateReservation, UpdateReservationSeries, UpdateReturned,
+        },
+        series::SeriesScope,
+        status::{ReservationEndSource, ReservationStatus, ReviewDecision},
+        ReservationListOptions,
+    },
     id::{SpaceId, ReservationId},
 };
+use garde::Validate;
 use registry::AppRegistry;
-use kernel::model::space::event::UpdateSpace;
+use kernel::authorization::Action;
 
 pub async fn reservation_space(
     user: AuthorizedUser,
@@ -34,8 +48,13 @@ pub async fn reservation_space(
     State(registry): State<AppRegistry>,
     Json(req): Json<CreateReservationRequest>,
 ) -> AppResult<StatusCode> {
-    // reminder_at を予約開始1時間前へ
-    let reminder_at = req.reservation_start_time - chrono::Duration::hours(1);
+    req.validate(&())?;
+
+    // リマインダーは指定がなければユーザーの既定値を使う
+    let reminder_lead_minutes = normalize_lead_minutes(
+        req.reminder_lead_minutes
+            .unwrap_or_else(|| user.user.reminder_lead_minutes.clone()),
+    );
 
     let create_reservation = CreateReservation::new(
         space_id,
@@ -43,288 +62,282 @@ pub async fn reservation_space(
         chrono::Local::now(),
         req.reservation_start_time,
         req.reservation_end_time,
-        reminder_at,
-        false,
+        reminder_lead_minutes,
     );
 
     // -------------------------
-    // ① 予約作成（ここで is_active=false の場合は Err になる）
+    // 予約作成（ここで is_active=false の場合は Err になる）
+    // 予約受付の通知は予約と同じトランザクションで outbox に積まれ、
+    // バックグラウンドで送信される
     // -------------------------
-    let reservation_id=registry
+    registry
         .reservation_repository()
         .create(create_reservation)
         .await?;
 
-    // -------------------------
-    // ② スペース情報を取得（is_active の確認）
-    // -------------------------
-    let space_opt = registry.space_repository().find_by_id(space_id).await?;
-    let space = space_opt.ok_or_else(|| {
-        AppError::ExternalServiceError("Space not found".to_string())
-    })?;
-
-    // -------------------------
-    // ★ is_active = false なら Gmail を送らず終了
-    // -------------------------
-    if !space.is_active {
-        return Ok(StatusCode::CREATED);
-    }
+    Ok(StatusCode::CREATED)
+}
 
-    // -------------------------
-    // ③ Gmail送信条件を満たしているか確認
-    // -------------------------
-    let now = chrono::Local::now();
-    if now >= reminder_at {
-        return Ok(StatusCode::CREATED); // 時間が過ぎているなら送らない
-    }
+pub async fn update_reservation(
+    user: AuthorizedUser,
+    Path((space_id, reservation_id)): Path<(SpaceId, ReservationId)>,
+    Query(query): Query<SeriesScopeQuery>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateReservationRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
 
-    // -------------------------
-    // ★ ユーザー情報取得
-    // -------------------------
-    let user_opt = registry
-        .user_repository()
-        .find_current_user(user.id())
+    // 予約したユーザー本人か管理者のみ変更できる
+    let reservation = registry
+        .reservation_repository()
+        .find_by_id(reservation_id)
         .await?;
-    let user_model = user_opt.ok_or_else(|| {
-        AppError::ExternalServiceError("User not found".to_string())
+    user.authorize(Action::UpdateReservation {
+        reserved_by: reservation.reserved_by,
     })?;
 
-    // -------------------------
-    // Gmail 送信
-    // -------------------------
-    let access_token = registry.google_access_token().await?;
-    send_reminder_gmail(
-        &access_token,
-        space_id,
-        reminder_at,
-        space.space_name,
-        user_model.user_name,
-        user_model.email,
-        req.reservation_start_time,
-        req.reservation_end_time,
-    )
-    .await
-    .map_err(|e| AppError::ExternalServiceError(format!("Gmail error: {e}")))?;
-
-
-
-    // -------------------------
-    // ③ Gmail が成功したら reminder_is_already=true に
-    // -------------------------
-    registry
-        .reservation_repository()
-        .update_reminder_is_already(reservation_id,true)
-        .await?;
+    // 予約時間の変更の通知は outbox に積まれ、バックグラウンドで送信される
+    let update: UpdateReservation =
+        UpdateReservationRequestWithIds::new(space_id, reservation_id, user.id(), req).into();
+    match SeriesScope::from(query.scope) {
+        SeriesScope::This => registry.reservation_repository().update(update).await?,
+        // 繰り返し予約の場合は、指定の範囲の回にも同じ変更を適用する
+        scope => {
+            registry
+                .reservation_repository()
+                .update_series(UpdateReservationSeries::new(
+                    update.reservation_id,
+                    update.space_id,
+                    update.requested_by,
+                    scope,
+                    update.reservation_start_time,
+                    update.reservation_end_time,
+                ))
+                .await?
+        }
+    }
 
-    Ok(StatusCode::CREATED)
+    Ok(StatusCode::OK)
 }
 
+// 理由を指定する場合は本文に EndReservationRequest を渡す
 pub async fn return_space(
     user: AuthorizedUser,
     Path((space_id, reservation_id)): Path<(SpaceId, ReservationId)>,
     State(registry): State<AppRegistry>,
+    req: Option<Json<EndReservationRequest>>,
 ) -> AppResult<StatusCode> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate(&())?;
 
     // ① 予約情報を DB から取得
     let reservation = registry
         .reservation_repository()
         .find_by_id(reservation_id)
         .await?;   // Reservation を返す想定
+
+    // 予約したユーザー本人か管理者のみ終了できる
+    user.authorize(Action::EndReservation {
+        reserved_by: reservation.reserved_by,
+    })?;
+    let source = ReservationEndSource::acted_by(reservation.reserved_by, user.id());
     
+    // 予約開始前に終了した場合はキャンセル、開始後であれば利用終了とする
+    // 承認待ちのままの予約は、開始後に取り下げた場合もキャンセルとする
+    let returned_at = chrono::Local::now();
     let update_returned = UpdateReturned::new(
         reservation_id, 
         space_id, 
         user.id(), 
-        true,
-        chrono::Local::now(),
+        source,
+        req.reason,
+        ReservationStatus::ended_by(
+            reservation.status,
+            source,
+            reservation.reservation_start_time,
+            returned_at,
+        ),
+        returned_at,
         reservation.reservation_start_time,
         reservation.reservation_end_time,
-        reservation.reminder_at,
+        Some(NotificationKind::Return),
     );
 
+    // 予約終了の通知は outbox に積まれ、バックグラウンドで送信される
     registry
         .reservation_repository()
         .update_returned(update_returned)
         .await?;
-    
-
-    let access_token = registry.google_access_token().await?;
-
-    // ④ Gmail を送信
-    send_cancel_gmail(
-        &access_token,
-        space_id,
-        reservation.reminder_at,
-        reservation.space.space_name,
-        reservation.user_name,
-        reservation.email,
-        reservation.reservation_start_time,
-        reservation.reservation_end_time,
-    )
-    .await
-    .map_err(|e| AppError::ExternalServiceError(format!("Gmail error: {e}")))?;
 
     Ok(StatusCode::OK)
     
 }
 
-pub async fn cancel_space(
+// チェックインできるのは予約したユーザー本人のみ
+pub async fn check_in_reservation(
     user: AuthorizedUser,
-    Path(space_id): Path<SpaceId>,
+    Path((space_id, reservation_id)): Path<(SpaceId, ReservationId)>,
     State(registry): State<AppRegistry>,
+    Json(req): Json<CheckInRequest>,
 ) -> AppResult<StatusCode> {
-    let update_event = UpdateSpace {
-    space_id,
-    requested_user: user.id(),  // ログインユーザID
-    space_name: None,
-    is_active: Some(false),   // ★ ここを false に変更
-    description: None,
-    capacity: None,
-    equipment: None,
-    address: None,
-};
+    req.validate(&())?;
+
+    let check_in = CheckInReservation::new(
+        reservation_id,
+        space_id,
+        user.id(),
+        req.code,
+        chrono::Local::now(),
+    );
+    registry
+        .reservation_repository()
+        .check_in(check_in)
+        .await
+        .map(|_| StatusCode::OK)
+}
 
-    // spacesテーブルの該当データ（space_idから取得）のis_activeをfalseに更新
-    registry.space_repository().update_is_active(update_event).await?;   
+// 承認待ちの予約を承認する。承認できるのはスペースの承認者のみ
+// 繰り返し予約の場合は、scope の範囲の承認待ちの回もまとめて承認する
+pub async fn approve_reservation(
+    user: AuthorizedUser,
+    Path((space_id, reservation_id)): Path<(SpaceId, ReservationId)>,
+    Query(query): Query<SeriesScopeQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    // 承認の通知は outbox に積まれ、バックグラウンドで送信される
+    registry
+        .reservation_repository()
+        .review(ReviewReservation::new(
+            reservation_id,
+            space_id,
+            user.id(),
+            query.scope.into(),
+            ReviewDecision::Approve,
+            None,
+            chrono::Local::now(),
+        ))
+        .await
+        .map(|_| StatusCode::OK)
+}
 
+// 承認待ちの予約を却下する。理由を指定する場合は本文に EndReservationRequest を渡す
+pub async fn reject_reservation(
+    user: AuthorizedUser,
+    Path((space_id, reservation_id)): Path<(SpaceId, ReservationId)>,
+    Query(query): Query<SeriesScopeQuery>,
+    State(registry): State<AppRegistry>,
+    req: Option<Json<EndReservationRequest>>,
+) -> AppResult<StatusCode> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate(&())?;
 
-    // ① 予約情報をスペースIDをもとに DB から取得
-    let reservations = registry
+    // 却下の通知は outbox に積まれ、バックグラウンドで送信される
+    registry
         .reservation_repository()
-        .find_reservations_by_space_id(space_id)
-        .await?;   // Reservation を返す想定
-    
-    // 予約が無い場合は OK を返す（何も送信しない）
-    if reservations.is_empty() {
-        return Ok(StatusCode::OK);
-    }
+        .review(ReviewReservation::new(
+            reservation_id,
+            space_id,
+            user.id(),
+            query.scope.into(),
+            ReviewDecision::Reject,
+            req.reason,
+            chrono::Local::now(),
+        ))
+        .await
+        .map(|_| StatusCode::OK)
+}
 
-    let access_token = registry.google_access_token().await?;
+// スペースを停止し、そのスペースの予約をすべてキャンセルする（スペースの所有者か Admin のみ）
+// キャンセルの理由を指定する場合は本文に EndReservationRequest を渡す
+pub async fn cancel_space(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    req: Option<Json<EndReservationRequest>>,
+) -> AppResult<StatusCode> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate(&())?;
 
-    for reservation in reservations {
-        let reservation_id = reservation.reservation_id;
+    let space = registry
+        .space_repository()
+        .find_by_id(space_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })
+        })?;
+    user.authorize(Action::DeactivateSpace {
+        owner_id: space.owner.owner_id,
+    })?;
 
-        let update_canceled = UpdateReturned::new(
-            reservation_id, 
-            space_id, 
-            user.id(), 
-            true,
-            chrono::Local::now(),
-            reservation.reservation_start_time,
-            reservation.reservation_end_time,
-            reservation.reminder_at,
-        );
-    
-        registry
-            .reservation_repository()
-            .update_returned(update_canceled)
-            .await?;
-        
-    
-    
-        // ④ Gmail を送信
-        send_cancel_gmail(
-            &access_token,
+    // スペースの停止と予約のキャンセルは 1 つのトランザクションで行う
+    // キャンセルの通知は outbox に積まれ、バックグラウンドで送信される
+    registry
+        .reservation_repository()
+        .deactivate_space(DeactivateSpace::new(
             space_id,
-            reservation.reminder_at,
-            reservation.space.space_name,
-            reservation.user_name,
-            reservation.email,
-            reservation.reservation_start_time,
-            reservation.reservation_end_time,
-        )
-        .await
-        .map_err(|e| AppError::ExternalServiceError(format!("Gmail error: {e}")))?;
-    }
-Ok(StatusCode::OK)
+            user.id(),
+            ReservationStatus::cancelled_by_deactivation(user.actor()),
+            req.reason,
+            chrono::Local::now(),
+        ))
+        .await?;
+
+    Ok(StatusCode::OK)
 }
 
 
+// すべてのスペースを停止し、すべての予約をキャンセルする（Admin only）
 pub async fn cancel_all_reservation(
     user: AuthorizedUser,
     State(registry): State<AppRegistry>,
+    req: Option<Json<EndReservationRequest>>,
 ) -> AppResult<StatusCode> {
-//すべてのスペース情報を取得する
-    let all_space_info=registry
-        .space_repository()
-        .find_all_space_for_all_cancel()
+    user.authorize(Action::CancelAllReservations)?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate(&())?;
+
+    // すべてのスペースの停止と予約のキャンセルを 1 つのトランザクションで行い、
+    // 途中で失敗した場合はどのスペースも停止しない
+    // キャンセルの通知は outbox に積まれ、バックグラウンドで送信される
+    registry
+        .reservation_repository()
+        .deactivate_all_spaces(DeactivateAllSpaces::new(
+            user.id(),
+            ReservationStatus::cancelled_by_deactivation(user.actor()),
+            req.reason,
+            chrono::Local::now(),
+        ))
         .await?;
 
-    // スペースが無い場合は OK を返す（何も送信しない）
-    if all_space_info.is_empty() {
-        return Ok(StatusCode::OK);
-    }
-    
+    Ok(StatusCode::OK)
+}
 
-    for space in all_space_info{
-        let space_id = space.space_id;
-    
-        let update_event = UpdateSpace {
-        space_id,
-        requested_user: user.id(),  // ログインユーザID
-        space_name: None,
-        is_active: Some(false),   // ★ ここを false に変更
-        description: None,
-        capacity: None,
-        equipment: None,
-        address: None,
-    };
-    
-        // spacesテーブルの該当データ（space_idから取得）のis_activeをfalseに更新
-        registry.space_repository().update_is_active(update_event).await?;   
-    
-    
-        // ① 予約情報をスペースIDをもとに DB から取得
-        let reservations = registry
-            .reservation_repository()
-            .find_reservations_by_space_id(space_id)
-            .await?;   // Reservation を返す想定
-        
-        // 予約がないなら Gmail 送信はスキップ
-        if reservations.is_empty() {
-            continue;   // ★ return は絶対ダメ。ループ継続
-        }
-    
-        let access_token = registry.google_access_token().await?;
-    
-        for reservation in reservations {
-            let reservation_id = reservation.reservation_id;
-    
-            let update_canceled = UpdateReturned::new(
-                reservation_id, 
-                space_id, 
-                user.id(), 
-                true,
-                chrono::Local::now(),
-                reservation.reservation_start_time,
-                reservation.reservation_end_time,
-                reservation.reminder_at,
-            );
-        
-            registry
-                .reservation_repository()
-                .update_returned(update_canceled)
-                .await?;
-    
-        
-            // ④ Gmail を送信
-            send_cancel_gmail(
-                &access_token,
-                space_id,
-                reservation.reminder_at,
-                reservation.space.space_name,
-                reservation.user_name,
-                reservation.email,
-                reservation.reservation_start_time,
-                reservation.reservation_end_time,
-            )
-            .await
-            .map_err(|e| AppError::ExternalServiceError(format!("Gmail error: {e}")))?;
-        }
+
+/// 終わった予約も含めて、条件に合う予約を検索する
+/// 管理者はすべての予約を、それ以外のユーザーは自分の予約のみを検索できる
+pub async fn list_reservations(
+    user: AuthorizedUser,
+    Query(query): Query<ReservationListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedReservationResponse>> {
+    query.validate(&())?;
+    let mut options: ReservationListOptions = query.try_into()?;
+    // ユーザーを指定しない場合は、すべてのユーザーの予約を閲覧できる管理者以外は自分の予約に絞り込む
+    match options.user_id {
+        Some(user_id) => user.authorize(Action::ViewReservations { user_id })?,
+        None if !user.is_admin() => options.user_id = Some(user.id()),
+        None => {}
     }
-Ok(StatusCode::OK)
-}
 
+    registry
+        .reservation_repository()
+        .find_all(options)
+        .await
+        .map(PaginatedReservationResponse::from)
+        .map(Json)
+}
 
 pub async fn show_reserved_list(
     _user: AuthorizedUser,
@@ -354,124 +367,15 @@ pub async fn return_reservation_by_id(
 pub async fn reservation_history(
     _user: AuthorizedUser,
     Path(space_id): Path<SpaceId>,
+    Query(query): Query<ReservationHistoryQuery>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<ReservationsResponse>> {
+    let statuses: Vec<ReservationStatus> = query.try_into()?;
     registry
         .reservation_repository()
-        .find_history_by_space_id(space_id)
+        .find_history_by_space_id(space_id, &statuses)
         .await
         .map(ReservationsResponse::from)
         .map(Json)
 }
 
-
-// ----------------------------------------------
-// リマインダーGmail送信処理
-// ----------------------------------------------
-async fn send_reminder_gmail(
-    access_token: &str,
-    space_id: SpaceId,
-    reminder_at: DateTime<Local>,
-    space_name: String,
-    user_name: String,
-    email: String,
-    reservation_start_time: DateTime<Local>,
-    reservation_end_time: DateTime<Local>,
-) -> Result<(), Box<dyn StdError>> {
-    println!(
-        ">>> [ACTION] Triggered for space_id={} at {}",
-        space_id,
-        Local::now()
-    );
-    // let to = "horikawa0107tokyo@gmail.com";
-    let subject = format!("remind mail");
-    let body_text = format!(
-        "{}さん {}の予約の1時間前です。リマインダー時刻：{}予約時間：{} 〜{}",
-        user_name,
-        space_name,
-        reminder_at,
-        reservation_start_time.format("%Y-%m-%d %H:%M:%S"),
-        reservation_end_time.format("%Y-%m-%d %H:%M:%S")
-    );
-
-    let message_str = format!(
-        "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=UTF-8\r\n\r\n{}",
-        email, subject, body_text
-    );
-
-    let encoded_message = general_purpose::URL_SAFE_NO_PAD.encode(message_str.as_bytes());
-
-    let url = "https://gmail.googleapis.com/gmail/v1/users/me/messages/send";
-    let client = Client::new();
-
-    let res = client
-        .post(url)
-        .bearer_auth(access_token)
-        .json(&serde_json::json!({ "raw": encoded_message }))
-        .send()
-        .await?;
-
-    if res.status().is_success() {
-        println!("✅ リマインダーGmail 送信成功");
-    } else {
-        eprintln!("❌ リマインダーGmail送信失敗: {}", res.text().await?);
-    }
-
-    Ok(())
-}
-
-// ----------------------------------------------
-// キャンセルGmail送信処理
-// ----------------------------------------------
-async fn send_cancel_gmail(
-    access_token: &str,
-    space_id: SpaceId,
-    reminder_at: DateTime<Local>,
-    space_name: String,
-    user_name: String,
-    email: String,
-    reservation_start_time: DateTime<Local>,
-    reservation_end_time: DateTime<Local>,
-) -> Result<(), Box<dyn StdError>> {
-    println!(
-        ">>> [ACTION] Triggered for space_id={} at {}",
-        space_id,
-        Local::now()
-    );
-    // let to = "horikawa0107tokyo@gmail.com";
-    let subject = format!("cancel mail");
-    let body_text = format!(
-        "{}さん {}が使えなくなりました。
-        ご予約はキャンセルになります。リマインダー時刻：{}予約時間：{} 〜{}",
-        user_name,
-        space_name,
-        reminder_at,
-        reservation_start_time.format("%Y-%m-%d %H:%M:%S"),
-        reservation_end_time.format("%Y-%m-%d %H:%M:%S")
-    );
-
-    let message_str = format!(
-        "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=UTF-8\r\n\r\n{}",
-        email, subject, body_text
-    );
-
-    let encoded_message = general_purpose::URL_SAFE_NO_PAD.encode(message_str.as_bytes());
-
-    let url = "https://gmail.googleapis.com/gmail/v1/users/me/messages/send";
-    let client = Client::new();
-
-    let res = client
-        .post(url)
-        .bearer_auth(access_token)
-        .json(&serde_json::json!({ "raw": encoded_message }))
-        .send()
-        .await?;
-
-    if res.status().is_success() {
-        println!("✅ キャンセルGmail 送信成功");
-    } else {
-        eprintln!("❌ キャンセルGmail送信失敗: {}", res.text().await?);
-    }
-
-    Ok(())
-}
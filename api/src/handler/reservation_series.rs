@@ -0,0 +1,111 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        reservation::EndReservationRequest,
+        reservation_series::{
+            CreateReservationSeriesRequest, CreatedReservationSeriesResponse,
+            ReservationSeriesResponse, SeriesScopeQuery,
+        },
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::authorization::Action;
+use kernel::model::{
+    id::{ReservationId, ReservationSeriesId, SpaceId},
+    reminder::normalize_lead_minutes,
+    reservation::{
+        event::{CancelReservationSeries, CreateReservationSeries},
+        status::ReservationEndSource,
+    },
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+pub async fn create_reservation_series(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateReservationSeriesRequest>,
+) -> AppResult<(StatusCode, Json<CreatedReservationSeriesResponse>)> {
+    req.validate(&())?;
+
+    // リマインダーは指定がなければユーザーの既定値を使う
+    let reminder_lead_minutes = normalize_lead_minutes(
+        req.reminder_lead_minutes
+            .unwrap_or_else(|| user.user.reminder_lead_minutes.clone()),
+    );
+
+    let create_series = CreateReservationSeries::new(
+        space_id,
+        user.id(),
+        chrono::Local::now(),
+        req.reservation_start_time,
+        req.reservation_end_time,
+        req.recurrence.into(),
+        req.conflict_policy.into(),
+        reminder_lead_minutes,
+    );
+
+    // 作成した回と、既存の予約と重なって飛ばした回を返す
+    let created = registry
+        .reservation_repository()
+        .create_series(create_series)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(created.into())))
+}
+
+pub async fn show_reservation_series(
+    _user: AuthorizedUser,
+    Path(reservation_series_id): Path<ReservationSeriesId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ReservationSeriesResponse>> {
+    registry
+        .reservation_repository()
+        .find_series_by_id(reservation_series_id)
+        .await
+        .map(ReservationSeriesResponse::from)
+        .map(Json)
+}
+
+// キャンセルの理由を指定する場合は本文に EndReservationRequest を渡す
+pub async fn cancel_reservation(
+    user: AuthorizedUser,
+    Path((space_id, reservation_id)): Path<(SpaceId, ReservationId)>,
+    Query(query): Query<SeriesScopeQuery>,
+    State(registry): State<AppRegistry>,
+    req: Option<Json<EndReservationRequest>>,
+) -> AppResult<StatusCode> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    req.validate(&())?;
+
+    // 予約したユーザー本人か管理者のみキャンセルできる
+    let reservation = registry
+        .reservation_repository()
+        .find_by_id(reservation_id)
+        .await?;
+    user.authorize(Action::EndReservation {
+        reserved_by: reservation.reserved_by,
+    })?;
+
+    // キャンセルの通知は outbox に積まれ、バックグラウンドで送信される
+    registry
+        .reservation_repository()
+        .cancel_series(CancelReservationSeries::new(
+            reservation_id,
+            space_id,
+            user.id(),
+            query.scope.into(),
+            ReservationEndSource::acted_by(reservation.reserved_by, user.id()),
+            req.reason,
+            chrono::Local::now(),
+        ))
+        .await?;
+
+    Ok(StatusCode::OK)
+}
@@ -1,6 +1,12 @@
 use crate::{
     extractor::AuthorizedUser,
     model::space::{
+        AvailabilityQuery, AvailableSpaceQuery, CreateSpaceBlackoutRequest,
+        CreateSpaceBlackoutRequestWithIds, CreatedSpaceBlackoutResponse, SpaceAvailabilityResponse,
+        SpaceBlackoutResponse, UpdateBookingPolicyRequest, UpdateBookingPolicyRequestWithIds,
+        UpdateApprovalPolicyRequest, UpdateApprovalPolicyRequestWithIds,
+        UpdateCheckInPolicyRequest, UpdateCheckInPolicyRequestWithIds,
+        UpdateOpeningHoursRequest, UpdateOpeningHoursRequestWithIds,
         SpaceListQuery, SpaceResponse, CreateSpaceRequest, PaginatedSpaceResponse, UpdateSpaceRequest,
         UpdateSpaceRequestWithIds,
     },
@@ -10,11 +16,17 @@ use crate::{
     Json,
 };
 use garde::Validate;
-use kernel::model::{space::event::DeleteSpace, id::SpaceId};
+use kernel::authorization::Action;
+use kernel::model::{
+    space::event::{DeleteSpace, DeleteSpaceBlackout},
+    id::{SpaceBlackoutId, SpaceId},
+};
 
 use registry::AppRegistry;
-use shared::error::{AppError, AppResult};
-use thiserror::Error;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
 
 
 pub async fn register_space(
@@ -46,6 +58,35 @@ pub async fn show_space_list(
         .map(Json)
 }
 
+pub async fn show_space_availability(
+    _user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    Query(query): Query<AvailabilityQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<SpaceAvailabilityResponse>> {
+    registry
+        .space_repository()
+        .find_availability(space_id, query.try_into()?)
+        .await
+        .map(SpaceAvailabilityResponse::from)
+        .map(Json)
+}
+
+pub async fn show_available_space_list(
+    _user: AuthorizedUser,
+    Query(query): Query<AvailableSpaceQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedSpaceResponse>> {
+    query.validate(&())?;
+
+    registry
+        .space_repository()
+        .find_available(query.try_into()?)
+        .await
+        .map(PaginatedSpaceResponse::from)
+        .map(Json)
+}
+
 pub async fn show_space(
     _user: AuthorizedUser,
     Path(space_id): Path<SpaceId>,
@@ -57,7 +98,9 @@ pub async fn show_space(
         .await
         .and_then(|bc| match bc {
             Some(bc) => Ok(Json(bc.into())),
-            None => Err(AppError::EntityNotFound("not found".into())),
+            None => Err(AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })),
         })
 }
 
@@ -68,6 +111,7 @@ pub async fn update_space(
     Json(req): Json<UpdateSpaceRequest>,
 ) -> AppResult<StatusCode> {
     req.validate(&())?;
+    authorize_manage_space(&user, &registry, space_id).await?;
 
     let update_space = UpdateSpaceRequestWithIds::new(space_id, user.id(), req);
     registry
@@ -82,6 +126,8 @@ pub async fn delete_space(
     Path(space_id): Path<SpaceId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
+    authorize_manage_space(&user, &registry, space_id).await?;
+
     let delete_space = DeleteSpace {
         space_id,
         requested_user: user.id(),
@@ -91,4 +137,155 @@ pub async fn delete_space(
         .delete(delete_space)
         .await
         .map(|_| StatusCode::OK)
-}
\ No newline at end of file
+}
+
+// 営業時間の変更も、スペースの変更と同様に所有者と管理者のみが行える
+pub async fn update_opening_hours(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateOpeningHoursRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    authorize_manage_space(&user, &registry, space_id).await?;
+
+    let update_opening_hours = UpdateOpeningHoursRequestWithIds::new(space_id, user.id(), req);
+    registry
+        .space_repository()
+        .update_opening_hours(update_opening_hours.try_into()?)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+pub async fn show_space_blackout_list(
+    _user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<Vec<SpaceBlackoutResponse>>> {
+    registry
+        .space_repository()
+        .find_blackouts(space_id)
+        .await
+        .map(|blackouts| {
+            blackouts
+                .into_iter()
+                .map(SpaceBlackoutResponse::from)
+                .collect()
+        })
+        .map(Json)
+}
+
+pub async fn register_space_blackout(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateSpaceBlackoutRequest>,
+) -> AppResult<(StatusCode, Json<CreatedSpaceBlackoutResponse>)> {
+    req.validate(&())?;
+    authorize_manage_space(&user, &registry, space_id).await?;
+
+    let create_blackout = CreateSpaceBlackoutRequestWithIds::new(space_id, user.id(), req);
+    let space_blackout_id = registry
+        .space_repository()
+        .create_blackout(create_blackout.into())
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedSpaceBlackoutResponse { space_blackout_id }),
+    ))
+}
+
+pub async fn delete_space_blackout(
+    user: AuthorizedUser,
+    Path((space_id, space_blackout_id)): Path<(SpaceId, SpaceBlackoutId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    authorize_manage_space(&user, &registry, space_id).await?;
+
+    let delete_blackout = DeleteSpaceBlackout {
+        space_id,
+        space_blackout_id,
+        requested_user: user.id(),
+    };
+    registry
+        .space_repository()
+        .delete_blackout(delete_blackout)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 予約のルールの変更も、スペースの変更と同様に所有者と管理者のみが行える
+pub async fn update_booking_policy(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBookingPolicyRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    authorize_manage_space(&user, &registry, space_id).await?;
+
+    let update_booking_policy = UpdateBookingPolicyRequestWithIds::new(space_id, user.id(), req);
+    registry
+        .space_repository()
+        .update_booking_policy(update_booking_policy.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// チェックインの設定の変更も、スペースの変更と同様に所有者と管理者のみが行える
+pub async fn update_check_in_policy(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateCheckInPolicyRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    authorize_manage_space(&user, &registry, space_id).await?;
+
+    let update_check_in_policy = UpdateCheckInPolicyRequestWithIds::new(space_id, user.id(), req);
+    registry
+        .space_repository()
+        .update_check_in_policy(update_check_in_policy.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 承認が必要なスペースの予約は、承認者が承認するまで承認待ちになる
+pub async fn update_approval_policy(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateApprovalPolicyRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+    authorize_manage_space(&user, &registry, space_id).await?;
+
+    let update_approval_policy = UpdateApprovalPolicyRequestWithIds::new(space_id, user.id(), req);
+    registry
+        .space_repository()
+        .update_approval_policy(update_approval_policy.into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// スペースを変更する前に、所有者または管理者であることを確認する
+// スペースがない場合は 404、権限がない場合は 403 を返す
+async fn authorize_manage_space(
+    user: &AuthorizedUser,
+    registry: &AppRegistry,
+    space_id: SpaceId,
+) -> AppResult<()> {
+    let space = registry
+        .space_repository()
+        .find_by_id(space_id)
+        .await?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::SpaceNotFound {
+                space_id: space_id.to_string(),
+            })
+        })?;
+    user.authorize(Action::ManageSpace {
+        owner_id: space.owner.owner_id,
+    })
+}
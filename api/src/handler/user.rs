@@ -1,20 +1,29 @@
 use crate::{
     extractor::AuthorizedUser,
     model::user::{
-        CreateUserRequest, UpdateUserPasswordRequest, UpdateUserPasswordRequestWithUserId,
-        UpdateUserRoleRequest, UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
+        CreateUserRequest, UpdateLanguageRequest, UpdateLanguageRequestWithUserId,
+        UpdateReminderPreferenceRequest, UpdateTimezoneRequest, UpdateTimezoneRequestWithUserId,
+        UpdateReminderPreferenceRequestWithUserId, UpdateUserPasswordRequest,
+        UpdateUserPasswordRequestWithUserId, UpdateUserRoleRequest,
+        UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
     },
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use garde::Validate;
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::{
+    authorization::Action,
+    model::{id::UserId, user::event::DeleteUser},
+};
 use registry::AppRegistry;
-use shared::error::{AppError, AppResult};
-use crate::model::reservation::ReservationsResponse;
+use shared::error::AppResult;
+use crate::model::reservation::{
+    PaginatedReservationResponse, ReservationsResponse, UserReservationHistoryQuery,
+    UserReservationHistoryQueryWithUserId,
+};
 
 /// ユーザーを追加する（Admin only）
 pub async fn register_user(
@@ -22,10 +31,7 @@ pub async fn register_user(
     State(registry): State<AppRegistry>,
     Json(req): Json<CreateUserRequest>,
 ) -> AppResult<Json<UserResponse>> {
-    //AuthorizedUser の権限が Admin のときのみ実行可能とする
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation);
-    }
+    user.authorize(Action::ManageUsers)?;
     req.validate(&())?;
 
     let registered_user = registry.user_repository().create(req.into()).await?;
@@ -55,10 +61,7 @@ pub async fn delete_user(
     Path(user_id): Path<UserId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    //AuthorizedUser の権限が Admin のときのみ実行可能とする
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation);
-    }
+    user.authorize(Action::ManageUsers)?;
 
     registry
         .user_repository()
@@ -74,10 +77,7 @@ pub async fn change_role(
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserRoleRequest>,
 ) -> AppResult<StatusCode> {
-    //AuthorizedUser の権限が Admin のときのみ実行可能とする
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation);
-    }
+    user.authorize(Action::ManageUsers)?;
 
     registry
         .user_repository()
@@ -109,6 +109,52 @@ pub async fn change_password(
 
 }
 
+/// ユーザーが自分自身の既定のリマインダーを変更する
+pub async fn change_reminder_preference(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateReminderPreferenceRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+
+    registry
+        .user_repository()
+        .update_reminder_preference(UpdateReminderPreferenceRequestWithUserId::new(user.id(), req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// ユーザーが自分自身の通知メールの言語を変更する
+pub async fn change_language(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateLanguageRequest>,
+) -> AppResult<StatusCode> {
+    registry
+        .user_repository()
+        .update_language(UpdateLanguageRequestWithUserId::new(user.id(), req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// ユーザーが自分自身の通知メールのタイムゾーンを変更する
+pub async fn change_timezone(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateTimezoneRequest>,
+) -> AppResult<StatusCode> {
+    req.validate(&())?;
+
+    registry
+        .user_repository()
+        .update_timezone(UpdateTimezoneRequestWithUserId::new(user.id(), req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
 pub async fn get_reservations(
     user: AuthorizedUser,
     State(registry): State<AppRegistry>,
@@ -119,4 +165,38 @@ pub async fn get_reservations(
         .await
         .map(ReservationsResponse::from)
         .map(Json)
-}
\ No newline at end of file
+}
+
+/// 自分の予約履歴（終わった予約）を取得する
+pub async fn get_reservation_history(
+    user: AuthorizedUser,
+    Query(query): Query<UserReservationHistoryQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedReservationResponse>> {
+    query.validate(&())?;
+
+    registry
+        .reservation_repository()
+        .find_all(UserReservationHistoryQueryWithUserId::new(user.id(), query).try_into()?)
+        .await
+        .map(PaginatedReservationResponse::from)
+        .map(Json)
+}
+
+/// 指定したユーザーの予約履歴（終わった予約）を取得する（Admin または本人のみ）
+pub async fn get_user_reservation_history(
+    user: AuthorizedUser,
+    Path(user_id): Path<UserId>,
+    Query(query): Query<UserReservationHistoryQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedReservationResponse>> {
+    user.authorize(Action::ViewReservations { user_id })?;
+    query.validate(&())?;
+
+    registry
+        .reservation_repository()
+        .find_all(UserReservationHistoryQueryWithUserId::new(user_id, query).try_into()?)
+        .await
+        .map(PaginatedReservationResponse::from)
+        .map(Json)
+}
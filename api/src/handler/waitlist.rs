@@ -0,0 +1,84 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::waitlist::{
+        ClaimedWaitlistOfferResponse, CreateWaitlistEntryRequest,
+        CreateWaitlistEntryRequestWithIds, CreatedWaitlistEntryResponse, WaitlistEntriesResponse,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    id::{SpaceId, WaitlistEntryId},
+    waitlist::event::{CancelWaitlistEntry, ClaimWaitlistOffer},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+pub async fn join_waitlist(
+    user: AuthorizedUser,
+    Path(space_id): Path<SpaceId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateWaitlistEntryRequest>,
+) -> AppResult<(StatusCode, Json<CreatedWaitlistEntryResponse>)> {
+    req.validate(&())?;
+
+    let create_entry = CreateWaitlistEntryRequestWithIds::new(space_id, user.id(), req);
+    let waitlist_entry_id = registry
+        .reservation_repository()
+        .join_waitlist(create_entry.into())
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWaitlistEntryResponse { waitlist_entry_id }),
+    ))
+}
+
+pub async fn show_my_waitlist(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<WaitlistEntriesResponse>> {
+    registry
+        .reservation_repository()
+        .find_waitlist_by_user_id(user.id())
+        .await
+        .map(WaitlistEntriesResponse::from)
+        .map(Json)
+}
+
+pub async fn cancel_waitlist_entry(
+    user: AuthorizedUser,
+    Path(waitlist_entry_id): Path<WaitlistEntryId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .reservation_repository()
+        .cancel_waitlist_entry(CancelWaitlistEntry::new(waitlist_entry_id, user.id()))
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+// 案内のメールのリンクから、空いた時間帯の予約を確定する
+pub async fn claim_waitlist_offer(
+    user: AuthorizedUser,
+    Path(claim_token): Path<String>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<ClaimedWaitlistOfferResponse>)> {
+    let reservation_id = registry
+        .reservation_repository()
+        .claim_waitlist_offer(ClaimWaitlistOffer::new(
+            claim_token,
+            user.id(),
+            chrono::Local::now(),
+        ))
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ClaimedWaitlistOfferResponse { reservation_id }),
+    ))
+}
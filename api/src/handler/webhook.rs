@@ -0,0 +1,185 @@
+use crate::{
+    extractor::AuthorizedUser,
+    model::webhook::{
+        CreateWebhookRequest, CreatedWebhookResponse, PaginatedWebhookDeliveryResponse,
+        UpdateWebhookRequest, UpdateWebhookRequestWithId, WebhookDeliveryListQuery,
+        WebhookDeliveryListQueryWithId, WebhookDeliveryResponse, WebhookResponse,
+        WebhooksResponse,
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Local};
+use garde::Validate;
+use kernel::{
+    authorization::Action,
+    model::{
+        id::WebhookId,
+        webhook::{
+            event::{DeleteWebhook, RecordWebhookFailure},
+            WEBHOOK_LEASE_DURATION_SECS,
+        },
+    },
+};
+use registry::AppRegistry;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+/// webhook の送信先を登録する（Admin only）
+/// 署名に使う鍵はこのレスポンスでのみ返す
+pub async fn register_webhook(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateWebhookRequest>,
+) -> AppResult<(StatusCode, Json<CreatedWebhookResponse>)> {
+    user.authorize(Action::ManageWebhooks)?;
+    req.validate(&())?;
+
+    let webhook = registry.webhook_repository().create(req.into()).await?;
+
+    Ok((StatusCode::CREATED, Json(webhook.into())))
+}
+
+/// webhook の送信先を一覧する（Admin only）
+pub async fn list_webhooks(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<WebhooksResponse>> {
+    user.authorize(Action::ManageWebhooks)?;
+
+    registry
+        .webhook_repository()
+        .find_all()
+        .await
+        .map(WebhooksResponse::from)
+        .map(Json)
+}
+
+/// webhook の送信先を取得する（Admin only）
+pub async fn show_webhook(
+    user: AuthorizedUser,
+    Path(webhook_id): Path<WebhookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<WebhookResponse>> {
+    user.authorize(Action::ManageWebhooks)?;
+
+    registry
+        .webhook_repository()
+        .find_by_id(webhook_id)
+        .await?
+        .map(WebhookResponse::from)
+        .map(Json)
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::WebhookNotFound {
+                webhook_id: webhook_id.to_string(),
+            })
+        })
+}
+
+/// webhook の送信先・送る出来事を変更する（Admin only）
+/// is_active を false にすると、出来事が起きても送らなくなる
+pub async fn update_webhook(
+    user: AuthorizedUser,
+    Path(webhook_id): Path<WebhookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateWebhookRequest>,
+) -> AppResult<StatusCode> {
+    user.authorize(Action::ManageWebhooks)?;
+    req.validate(&())?;
+
+    registry
+        .webhook_repository()
+        .update(UpdateWebhookRequestWithId::new(webhook_id, req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// webhook の送信先を削除する（Admin only）
+pub async fn delete_webhook(
+    user: AuthorizedUser,
+    Path(webhook_id): Path<WebhookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    user.authorize(Action::ManageWebhooks)?;
+
+    registry
+        .webhook_repository()
+        .delete(DeleteWebhook { webhook_id })
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// webhook の送信の記録を新しい順に一覧する（Admin only）
+/// status=dead を指定すると送信を諦めたもののみを取得できる
+pub async fn list_webhook_deliveries(
+    user: AuthorizedUser,
+    Path(webhook_id): Path<WebhookId>,
+    Query(query): Query<WebhookDeliveryListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedWebhookDeliveryResponse>> {
+    user.authorize(Action::ManageWebhooks)?;
+    query.validate(&())?;
+
+    registry
+        .webhook_repository()
+        .find_deliveries(WebhookDeliveryListQueryWithId::new(webhook_id, query).into())
+        .await
+        .map(PaginatedWebhookDeliveryResponse::from)
+        .map(Json)
+}
+
+/// webhook の送信先に試験送信し、その結果を返す（Admin only）
+/// 送信先の確認のためのものなので、失敗しても再試行はしない
+pub async fn ping_webhook(
+    user: AuthorizedUser,
+    Path(webhook_id): Path<WebhookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<WebhookDeliveryResponse>> {
+    user.authorize(Action::ManageWebhooks)?;
+
+    let repository = registry.webhook_repository();
+    // 送信中に dispatcher が同じ試験送信を送らないよう、lease を付けて積む
+    let lease_until = Local::now() + Duration::seconds(WEBHOOK_LEASE_DURATION_SECS);
+    let dispatch = repository.create_ping(webhook_id, lease_until).await?;
+    let webhook_delivery_id = dispatch.delivery.webhook_delivery_id;
+
+    match registry.webhook_sender().send(&dispatch).await {
+        Ok(status) if (200..300).contains(&status) => {
+            repository
+                .mark_delivered(webhook_delivery_id, status as i32)
+                .await?
+        }
+        result => {
+            let (error, response_status) = match result {
+                Ok(status) => (format!("unexpected status: {status}"), Some(status as i32)),
+                Err(e) => (e.to_string(), None),
+            };
+            repository
+                .record_failure(RecordWebhookFailure {
+                    webhook_delivery_id,
+                    error,
+                    response_status,
+                    next_attempt_at: None,
+                })
+                .await?
+        }
+    }
+
+    repository
+        .find_delivery_by_id(webhook_delivery_id)
+        .await?
+        .map(WebhookDeliveryResponse::from)
+        .map(Json)
+        .ok_or_else(|| {
+            AppError::EntityNotFound(ErrorMessage::WebhookDeliveryNotFound {
+                webhook_delivery_id: webhook_delivery_id.to_string(),
+            })
+        })
+}
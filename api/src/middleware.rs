@@ -0,0 +1,42 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use shared::{
+    i18n::{self, Language},
+    request_id::{self, REQUEST_ID_HEADER},
+};
+use uuid::Uuid;
+
+// リクエストに ID を振り、エラーのレスポンスボディとレスポンスヘッダに含める
+// クライアントが x-request-id ヘッダを付けた場合は、その値をそのまま使う
+pub async fn request_id(req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut res = request_id::scope(id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    res
+}
+
+// Accept-Language ヘッダから、エラーのレスポンスなどに使う言語を選ぶ
+// ヘッダがない場合や、対応している言語がない場合は日本語とする
+pub async fn accept_language(req: Request, next: Next) -> Response {
+    let language = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(Language::negotiate)
+        .unwrap_or_default();
+
+    i18n::scope(language, next.run(req)).await
+}
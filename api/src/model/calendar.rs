@@ -0,0 +1,25 @@
+use chrono::{DateTime, Local};
+use kernel::model::calendar::CalendarFeedToken;
+use serde::Serialize;
+
+// 予定表を購読するためのトークン
+// カレンダーアプリには、予定表の URL に ?token= を付けて登録する
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarFeedTokenResponse {
+    pub token: String,
+    // 自分の予約の予定表を購読する URL のパス
+    pub reservations_url: String,
+    pub created_at: DateTime<Local>,
+}
+
+impl From<CalendarFeedToken> for CalendarFeedTokenResponse {
+    fn from(value: CalendarFeedToken) -> Self {
+        let CalendarFeedToken { token, created_at } = value;
+        Self {
+            reservations_url: format!("/api/v1/users/me/reservations.ics?token={token}"),
+            token,
+            created_at,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::user::LanguageName;
+use garde::Validate;
+use kernel::model::notification::{NotificationKind, RenderedMail};
+use serde::{Deserialize, Serialize};
+
+// テンプレートのプレビューで使う言語とタイムゾーン
+// language を省略した場合はリクエストの言語（Accept-Language）、
+// timezone を省略した場合は既定のタイムゾーンで組み立てる
+#[derive(Debug, Deserialize, Validate)]
+pub struct MailPreviewQuery {
+    #[garde(skip)]
+    pub language: Option<LanguageName>,
+    #[garde(length(min = 1, max = 64))]
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MailPreviewResponse {
+    pub kind: String,
+    pub language: LanguageName,
+    pub subject: String,
+    pub text: String,
+    // HTML のテンプレートがない通知では null
+    pub html: Option<String>,
+}
+
+impl MailPreviewResponse {
+    pub fn new(kind: NotificationKind, language: LanguageName, mail: RenderedMail) -> Self {
+        let RenderedMail {
+            subject,
+            text,
+            html,
+        } = mail;
+        Self {
+            kind: kind.as_ref().to_string(),
+            language,
+            subject,
+            text,
+            html,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Local};
+use garde::Validate;
+use kernel::model::{
+    id::{OutboxMessageId, ReservationId, SpaceId},
+    list::PaginatedList,
+    outbox::{OutboxListOptions, OutboxMessage, OutboxStatus},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatusName {
+    Pending,
+    Sent,
+    Dead,
+}
+
+impl From<OutboxStatus> for OutboxStatusName {
+    fn from(value: OutboxStatus) -> Self {
+        match value {
+            OutboxStatus::Pending => Self::Pending,
+            OutboxStatus::Sent => Self::Sent,
+            OutboxStatus::Dead => Self::Dead,
+        }
+    }
+}
+
+impl From<OutboxStatusName> for OutboxStatus {
+    fn from(value: OutboxStatusName) -> Self {
+        match value {
+            OutboxStatusName::Pending => Self::Pending,
+            OutboxStatusName::Sent => Self::Sent,
+            OutboxStatusName::Dead => Self::Dead,
+        }
+    }
+}
+
+// クエリで status と limit, offset を受け取るための型
+// status を省略した場合はすべての送信状態を対象とする
+#[derive(Debug, Deserialize, Validate)]
+pub struct OutboxListQuery {
+    #[garde(skip)]
+    pub status: Option<OutboxStatusName>,
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)] // default は 0
+    pub offset: i64,
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const fn default_limit() -> i64 {
+    DEFAULT_LIMIT
+}
+
+impl From<OutboxListQuery> for OutboxListOptions {
+    fn from(value: OutboxListQuery) -> Self {
+        let OutboxListQuery {
+            status,
+            limit,
+            offset,
+        } = value;
+        Self {
+            status: status.map(OutboxStatus::from),
+            limit,
+            offset,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxMessageResponse {
+    pub outbox_message_id: OutboxMessageId,
+    pub kind: String,
+    pub status: OutboxStatusName,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+    pub sent_at: Option<DateTime<Local>>,
+    // キャンセル待ちの案内など、予約に紐づかない通知では null
+    pub reservation_id: Option<ReservationId>,
+    pub space_id: SpaceId,
+    pub email: String,
+}
+
+impl From<OutboxMessage> for OutboxMessageResponse {
+    fn from(value: OutboxMessage) -> Self {
+        let OutboxMessage {
+            outbox_message_id,
+            kind,
+            status,
+            attempts,
+            last_error,
+            next_attempt_at,
+            created_at,
+            sent_at,
+            notification,
+        } = value;
+        Self {
+            outbox_message_id,
+            kind: kind.as_ref().to_string(),
+            status: status.into(),
+            attempts,
+            last_error,
+            next_attempt_at,
+            created_at,
+            sent_at,
+            reservation_id: notification.reservation_id,
+            space_id: notification.space_id,
+            email: notification.email,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedOutboxMessageResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<OutboxMessageResponse>,
+}
+
+impl From<PaginatedList<OutboxMessage>> for PaginatedOutboxMessageResponse {
+    fn from(value: PaginatedList<OutboxMessage>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(OutboxMessageResponse::from).collect(),
+        }
+    }
+}
@@ -1,13 +1,316 @@
 use chrono::{DateTime,Local};
 use kernel::model::{
-    reservation::{Reservation, ReservationSpace},
-    id::{SpaceId, ReservationId, UserId},
+    reminder::{Reminder, MAX_REMINDERS_PER_RESERVATION, MAX_REMINDER_LEAD_MINUTES},
+    reservation::{
+        event::UpdateReservation,
+        status::{
+            ReservationEndSource, ReservationEnding, ReservationOutcome, ReservationStatus,
+            MAX_END_REASON_LENGTH,
+        },
+        Reservation, ReservationListOptions, ReservationSortKey, ReservationSpace,
+    },
+    list::{PaginatedList, SortOrder},
+    space::check_in::MAX_CHECK_IN_CODE_LENGTH,
+    id::{SpaceId, ReservationId, ReminderId, ReservationSeriesId, UserId},
 
 };
+use derive_new::new;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
-use derive_new::new;
+use shared::{error::AppError, i18n::ErrorMessage};
+
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationStatusName {
+    Pending,
+    Confirmed,
+    CheckedIn,
+    Completed,
+    CancelledByUser,
+    CancelledByAdmin,
+    CancelledByOwner,
+    Cancelled,
+    NoShow,
+    Rejected,
+    Expired,
+}
+
+impl From<ReservationStatus> for ReservationStatusName {
+    fn from(value: ReservationStatus) -> Self {
+        match value {
+            ReservationStatus::Pending => Self::Pending,
+            ReservationStatus::Confirmed => Self::Confirmed,
+            ReservationStatus::CheckedIn => Self::CheckedIn,
+            ReservationStatus::Completed => Self::Completed,
+            ReservationStatus::CancelledByUser => Self::CancelledByUser,
+            ReservationStatus::CancelledByAdmin => Self::CancelledByAdmin,
+            ReservationStatus::CancelledByOwner => Self::CancelledByOwner,
+            ReservationStatus::Cancelled => Self::Cancelled,
+            ReservationStatus::NoShow => Self::NoShow,
+            ReservationStatus::Rejected => Self::Rejected,
+            ReservationStatus::Expired => Self::Expired,
+        }
+    }
+}
+
+impl From<ReservationStatusName> for ReservationStatus {
+    fn from(value: ReservationStatusName) -> Self {
+        match value {
+            ReservationStatusName::Pending => Self::Pending,
+            ReservationStatusName::Confirmed => Self::Confirmed,
+            ReservationStatusName::CheckedIn => Self::CheckedIn,
+            ReservationStatusName::Completed => Self::Completed,
+            ReservationStatusName::CancelledByUser => Self::CancelledByUser,
+            ReservationStatusName::CancelledByAdmin => Self::CancelledByAdmin,
+            ReservationStatusName::CancelledByOwner => Self::CancelledByOwner,
+            ReservationStatusName::Cancelled => Self::Cancelled,
+            ReservationStatusName::NoShow => Self::NoShow,
+            ReservationStatusName::Rejected => Self::Rejected,
+            ReservationStatusName::Expired => Self::Expired,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationEndSourceName {
+    User,
+    Admin,
+    SpaceDeactivation,
+    Watcher,
+}
+
+impl From<ReservationEndSource> for ReservationEndSourceName {
+    fn from(value: ReservationEndSource) -> Self {
+        match value {
+            ReservationEndSource::User => Self::User,
+            ReservationEndSource::Admin => Self::Admin,
+            ReservationEndSource::SpaceDeactivation => Self::SpaceDeactivation,
+            ReservationEndSource::Watcher => Self::Watcher,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationOutcomeName {
+    Returned,
+    Cancelled,
+    AutoEnded,
+}
+
+impl From<ReservationOutcome> for ReservationOutcomeName {
+    fn from(value: ReservationOutcome) -> Self {
+        match value {
+            ReservationOutcome::Returned => Self::Returned,
+            ReservationOutcome::Cancelled => Self::Cancelled,
+            ReservationOutcome::AutoEnded => Self::AutoEnded,
+        }
+    }
+}
+
+// 予約履歴の絞り込み条件をクエリで受け取るための型
+#[derive(Debug, Deserialize)]
+pub struct ReservationHistoryQuery {
+    // 状態をカンマ区切りで指定する（例: completed,no_show）。省略した場合はすべての状態
+    pub status: Option<String>,
+}
+
+impl TryFrom<ReservationHistoryQuery> for Vec<ReservationStatus> {
+    type Error = AppError;
+
+    fn try_from(value: ReservationHistoryQuery) -> Result<Self, Self::Error> {
+        parse_statuses(value.status.as_deref())
+    }
+}
+
+// カンマ区切りで指定された予約の状態を解釈する
+fn parse_statuses(value: Option<&str>) -> Result<Vec<ReservationStatus>, AppError> {
+    value
+        .iter()
+        .flat_map(|s| s.split(','))
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            ReservationStatus::try_from(s).map_err(|_| {
+                AppError::UnprocessableEntity(ErrorMessage::InvalidStatusFilter {
+                    status: s.to_string(),
+                })
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationSortKeyName {
+    #[default]
+    StartTime,
+    EndTime,
+    ReservedAt,
+}
+
+impl From<ReservationSortKeyName> for ReservationSortKey {
+    fn from(value: ReservationSortKeyName) -> Self {
+        match value {
+            ReservationSortKeyName::StartTime => Self::StartTime,
+            ReservationSortKeyName::EndTime => Self::EndTime,
+            ReservationSortKeyName::ReservedAt => Self::ReservedAt,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrderName {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl From<SortOrderName> for SortOrder {
+    fn from(value: SortOrderName) -> Self {
+        match value {
+            SortOrderName::Asc => Self::Asc,
+            SortOrderName::Desc => Self::Desc,
+        }
+    }
+}
+
+// 予約の検索条件と、並び順・ページネーションの範囲をクエリで受け取るための型
+// 終わった予約も含めて検索する。from, to は RFC 3339 形式で指定する
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservationListQuery {
+    #[garde(skip)]
+    pub space_id: Option<SpaceId>,
+    #[garde(skip)]
+    pub user_id: Option<UserId>,
+    #[garde(skip)]
+    pub from: Option<DateTime<Local>>,
+    #[garde(skip)]
+    pub to: Option<DateTime<Local>>,
+    // 状態をカンマ区切りで指定する（例: confirmed,checked_in）。省略した場合はすべての状態
+    #[garde(skip)]
+    pub status: Option<String>,
+    #[garde(skip)]
+    pub is_cancel: Option<bool>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub sort: ReservationSortKeyName,
+    #[garde(skip)]
+    #[serde(default)]
+    pub order: SortOrderName,
+    #[garde(range(min = 0, max = MAX_LIMIT))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)] // default は 0
+    pub offset: i64,
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+const fn default_limit() -> i64 {
+    DEFAULT_LIMIT
+}
+
+impl TryFrom<ReservationListQuery> for ReservationListOptions {
+    type Error = AppError;
+
+    fn try_from(value: ReservationListQuery) -> Result<Self, Self::Error> {
+        let ReservationListQuery {
+            space_id,
+            user_id,
+            from,
+            to,
+            status,
+            is_cancel,
+            sort,
+            order,
+            limit,
+            offset,
+        } = value;
+        validate_period(from, to)?;
+        Ok(Self {
+            space_id,
+            user_id,
+            from,
+            to,
+            statuses: parse_statuses(status.as_deref())?,
+            is_cancel,
+            sort: sort.into(),
+            order: order.into(),
+            limit,
+            offset,
+        })
+    }
+}
 
+// ユーザーの予約履歴の絞り込み条件と、ページネーションの範囲をクエリで受け取るための型
+// 予約開始時刻の新しい順に、終わった予約のみを返す
+#[derive(Debug, Deserialize, Validate)]
+pub struct UserReservationHistoryQuery {
+    // 予約時間が from から to の間に重なる予約に絞り込む（RFC 3339 形式）
+    #[garde(skip)]
+    pub from: Option<DateTime<Local>>,
+    #[garde(skip)]
+    pub to: Option<DateTime<Local>>,
+    #[garde(range(min = 0, max = MAX_LIMIT))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)] // default は 0
+    pub offset: i64,
+}
+
+#[derive(new)]
+pub struct UserReservationHistoryQueryWithUserId(UserId, UserReservationHistoryQuery);
+
+impl TryFrom<UserReservationHistoryQueryWithUserId> for ReservationListOptions {
+    type Error = AppError;
+
+    fn try_from(value: UserReservationHistoryQueryWithUserId) -> Result<Self, Self::Error> {
+        let UserReservationHistoryQueryWithUserId(
+            user_id,
+            UserReservationHistoryQuery {
+                from,
+                to,
+                limit,
+                offset,
+            },
+        ) = value;
+        validate_period(from, to)?;
+        Ok(Self {
+            space_id: None,
+            user_id: Some(user_id),
+            from,
+            to,
+            statuses: ReservationStatus::ENDED.to_vec(),
+            is_cancel: None,
+            sort: ReservationSortKey::StartTime,
+            order: SortOrder::Desc,
+            limit,
+            offset,
+        })
+    }
+}
+
+// 検索する期間の開始日時が、終了日時より前であることを確認する
+fn validate_period(
+    from: Option<DateTime<Local>>,
+    to: Option<DateTime<Local>>,
+) -> Result<(), AppError> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from >= to {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidSearchPeriod,
+            ));
+        }
+    }
+    Ok(())
+}
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +326,32 @@ impl From<Vec<Reservation>> for ReservationsResponse {
     }
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedReservationResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<ReservationResponse>,
+}
+
+impl From<PaginatedList<Reservation>> for PaginatedReservationResponse {
+    fn from(value: PaginatedList<Reservation>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(ReservationResponse::from).collect(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateReservationRequest {
@@ -30,9 +359,35 @@ pub struct CreateReservationRequest {
     pub reservation_start_time: DateTime<Local>,
     #[garde(skip)] 
     pub reservation_end_time: DateTime<Local>,
+    // 予約開始の何分前にリマインダーを送るか
+    // 省略した場合はユーザーの既定値を使い、空の配列の場合はリマインダーを送らない
+    #[garde(
+        inner(length(max = MAX_REMINDERS_PER_RESERVATION)),
+        inner(inner(range(min = 1, max = MAX_REMINDER_LEAD_MINUTES)))
+    )]
+    #[serde(default)]
+    pub reminder_lead_minutes: Option<Vec<i32>>,
 }
 
-// 蔵書データの更新用の型を追加する
+// チェックイン用の型。スペースにコードが設定されている場合は code が必要
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckInRequest {
+    #[garde(inner(length(min = 1, max = MAX_CHECK_IN_CODE_LENGTH)))]
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+// 予約の終了・キャンセル用の型。本文は省略できる
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct EndReservationRequest {
+    #[garde(inner(length(min = 1, max = MAX_END_REASON_LENGTH)))]
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+// 予約時間の変更用の型
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateReservationRequest {
@@ -42,6 +397,37 @@ pub struct UpdateReservationRequest {
     pub reservation_end_time: DateTime<Local>,
 }
 
+// パスパラメータからの SpaceId, ReservationId、
+// リクエスト時に AuthorizedUser から取り出す UserId、
+// UpdateReservationRequest のセットを UpdateReservation 型に変換するための一時的な型
+#[derive(new)]
+pub struct UpdateReservationRequestWithIds(
+    SpaceId,
+    ReservationId,
+    UserId,
+    UpdateReservationRequest,
+);
+impl From<UpdateReservationRequestWithIds> for UpdateReservation {
+    fn from(value: UpdateReservationRequestWithIds) -> Self {
+        let UpdateReservationRequestWithIds(
+            space_id,
+            reservation_id,
+            requested_by,
+            UpdateReservationRequest {
+                reservation_start_time,
+                reservation_end_time,
+            },
+        ) = value;
+        UpdateReservation {
+            reservation_id,
+            space_id,
+            requested_by,
+            reservation_start_time,
+            reservation_end_time,
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReservationResponse {
@@ -49,42 +435,102 @@ pub struct ReservationResponse {
     pub reserved_by: UserId,
     pub user_name: String,
     pub email: String,
-    pub reminder_is_already: bool,
     pub reserved_at: DateTime<Local>,
-    pub reminder_at: DateTime<Local>,
+    pub status: ReservationStatusName,
+    // 予約が終わった（利用終了・キャンセル・解放・却下・期限切れのいずれか）日時
     pub returned_at: Option<DateTime<Local>>,
+    // 予約がどのように終わったか。終わっていない場合は null
+    pub outcome: Option<ReservationOutcomeName>,
     pub reservation_start_time:DateTime<Local>,
     pub reservation_end_time:DateTime<Local>,
     pub space: ReservationSpaceResponse,
+    pub reminders: Vec<ReminderResponse>,
+    pub reservation_series_id: Option<ReservationSeriesId>,
+    pub confirmed_at: Option<DateTime<Local>>,
+    pub checked_in_at: Option<DateTime<Local>>,
+    pub completed_at: Option<DateTime<Local>>,
+    pub cancelled_at: Option<DateTime<Local>>,
+    pub no_show_at: Option<DateTime<Local>>,
+    pub rejected_at: Option<DateTime<Local>>,
+    pub expired_at: Option<DateTime<Local>>,
+    // 承認が必要なスペースの予約で、承認・却下したユーザー
+    pub reviewed_by: Option<UserId>,
+    // 予約を終わらせた操作。終わっていない場合や、記録がない場合は null
+    pub ending: Option<ReservationEndingResponse>,
 }
 
 impl From<Reservation> for ReservationResponse {
     fn from(value: Reservation) -> Self {
+        let returned_at = value.ended_at();
+        let outcome = value.outcome().map(ReservationOutcomeName::from);
         let Reservation {
             reservation_id,
             reserved_by,
             user_name,
             email,
-            reminder_is_already,
             reserved_at,
-            reminder_at,
-            returned_at,
+            status,
             reservation_start_time,
             reservation_end_time,
             space,
+            reminders,
+            reservation_series_id,
+            confirmed_at,
+            checked_in_at,
+            completed_at,
+            cancelled_at,
+            no_show_at,
+            rejected_at,
+            expired_at,
+            reviewed_by,
+            ending,
         } = value;
         Self {
             reservation_id,
             reserved_by,
             user_name,
             email,
-            reminder_is_already,
             reserved_at,
-            reminder_at,
+            status: status.into(),
             returned_at,
+            outcome,
             reservation_start_time,
             reservation_end_time,
             space: space.into(),
+            reminders: reminders.into_iter().map(ReminderResponse::from).collect(),
+            reservation_series_id,
+            confirmed_at,
+            checked_in_at,
+            completed_at,
+            cancelled_at,
+            no_show_at,
+            rejected_at,
+            expired_at,
+            reviewed_by,
+            ending: ending.map(ReservationEndingResponse::from),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservationEndingResponse {
+    pub ended_by: Option<UserId>,
+    pub source: ReservationEndSourceName,
+    pub reason: Option<String>,
+}
+
+impl From<ReservationEnding> for ReservationEndingResponse {
+    fn from(value: ReservationEnding) -> Self {
+        let ReservationEnding {
+            ended_by,
+            source,
+            reason,
+        } = value;
+        Self {
+            ended_by,
+            source: source.into(),
+            reason,
         }
     }
 }
@@ -111,7 +557,7 @@ impl From<ReservationSpace> for ReservationSpaceResponse {
             address,
         } = value;
         Self {
-            space_id: space_id,
+            space_id,
             space_name,
             is_active,
             capacity,
@@ -119,4 +565,30 @@ impl From<ReservationSpace> for ReservationSpaceResponse {
             address,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderResponse {
+    pub reminder_id: ReminderId,
+    pub lead_minutes: i32,
+    pub remind_at: DateTime<Local>,
+    pub is_sent: bool,
+}
+
+impl From<Reminder> for ReminderResponse {
+    fn from(value: Reminder) -> Self {
+        let Reminder {
+            reminder_id,
+            lead_minutes,
+            remind_at,
+            is_sent,
+        } = value;
+        Self {
+            reminder_id,
+            lead_minutes,
+            remind_at,
+            is_sent,
+        }
+    }
+}
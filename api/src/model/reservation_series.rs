@@ -0,0 +1,307 @@
+use crate::model::reservation::ReservationResponse;
+use chrono::{DateTime, Local, Weekday};
+use garde::Validate;
+use kernel::model::{
+    id::{ReservationId, ReservationSeriesId, SpaceId, UserId},
+    reminder::{MAX_REMINDERS_PER_RESERVATION, MAX_REMINDER_LEAD_MINUTES},
+    reservation::series::{
+        CreatedReservationSeries, Frequency, Recurrence, ReservationSeries,
+        SeriesConflictPolicy, SeriesScope, SkippedOccurrence, MAX_INTERVAL, MAX_OCCURRENCES,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrequencyName {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl From<Frequency> for FrequencyName {
+    fn from(value: Frequency) -> Self {
+        match value {
+            Frequency::Daily => Self::Daily,
+            Frequency::Weekly => Self::Weekly,
+            Frequency::Monthly => Self::Monthly,
+        }
+    }
+}
+
+impl From<FrequencyName> for Frequency {
+    fn from(value: FrequencyName) -> Self {
+        match value {
+            FrequencyName::Daily => Self::Daily,
+            FrequencyName::Weekly => Self::Weekly,
+            FrequencyName::Monthly => Self::Monthly,
+        }
+    }
+}
+
+// 曜日は iCalendar の BYDAY と同じく MO, TU, ... で表す
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WeekdayName {
+    Mo,
+    Tu,
+    We,
+    Th,
+    Fr,
+    Sa,
+    Su,
+}
+
+impl From<Weekday> for WeekdayName {
+    fn from(value: Weekday) -> Self {
+        match value {
+            Weekday::Mon => Self::Mo,
+            Weekday::Tue => Self::Tu,
+            Weekday::Wed => Self::We,
+            Weekday::Thu => Self::Th,
+            Weekday::Fri => Self::Fr,
+            Weekday::Sat => Self::Sa,
+            Weekday::Sun => Self::Su,
+        }
+    }
+}
+
+impl From<WeekdayName> for Weekday {
+    fn from(value: WeekdayName) -> Self {
+        match value {
+            WeekdayName::Mo => Self::Mon,
+            WeekdayName::Tu => Self::Tue,
+            WeekdayName::We => Self::Wed,
+            WeekdayName::Th => Self::Thu,
+            WeekdayName::Fr => Self::Fri,
+            WeekdayName::Sa => Self::Sat,
+            WeekdayName::Su => Self::Sun,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesConflictPolicyName {
+    #[default]
+    AllOrNothing,
+    SkipConflicts,
+}
+
+impl From<SeriesConflictPolicyName> for SeriesConflictPolicy {
+    fn from(value: SeriesConflictPolicyName) -> Self {
+        match value {
+            SeriesConflictPolicyName::AllOrNothing => Self::AllOrNothing,
+            SeriesConflictPolicyName::SkipConflicts => Self::SkipConflicts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesScopeName {
+    #[default]
+    This,
+    ThisAndFollowing,
+    All,
+}
+
+impl From<SeriesScopeName> for SeriesScope {
+    fn from(value: SeriesScopeName) -> Self {
+        match value {
+            SeriesScopeName::This => Self::This,
+            SeriesScopeName::ThisAndFollowing => Self::ThisAndFollowing,
+            SeriesScopeName::All => Self::All,
+        }
+    }
+}
+
+// 予約の変更・キャンセルの対象範囲をクエリで受け取るための型
+// 省略した場合は指定した回のみを対象とする
+#[derive(Debug, Default, Deserialize)]
+pub struct SeriesScopeQuery {
+    #[serde(default)]
+    pub scope: SeriesScopeName,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRequest {
+    #[garde(skip)]
+    pub frequency: FrequencyName,
+    #[garde(range(min = 1, max = MAX_INTERVAL))]
+    #[serde(default = "default_interval")]
+    pub interval: i32,
+    #[garde(length(max = 7))]
+    #[serde(default)]
+    pub by_weekday: Vec<WeekdayName>,
+    #[garde(inner(range(min = 1, max = MAX_OCCURRENCES as i32)))]
+    pub count: Option<i32>,
+    #[garde(skip)]
+    pub until: Option<DateTime<Local>>,
+}
+
+const fn default_interval() -> i32 {
+    1
+}
+
+impl From<RecurrenceRequest> for Recurrence {
+    fn from(value: RecurrenceRequest) -> Self {
+        let RecurrenceRequest {
+            frequency,
+            interval,
+            by_weekday,
+            count,
+            until,
+        } = value;
+        Self {
+            frequency: frequency.into(),
+            interval,
+            by_weekday: by_weekday.into_iter().map(Weekday::from).collect(),
+            count,
+            until,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceResponse {
+    pub frequency: FrequencyName,
+    pub interval: i32,
+    pub by_weekday: Vec<WeekdayName>,
+    pub count: Option<i32>,
+    pub until: Option<DateTime<Local>>,
+}
+
+impl From<Recurrence> for RecurrenceResponse {
+    fn from(value: Recurrence) -> Self {
+        let Recurrence {
+            frequency,
+            interval,
+            by_weekday,
+            count,
+            until,
+        } = value;
+        Self {
+            frequency: frequency.into(),
+            interval,
+            by_weekday: by_weekday.into_iter().map(WeekdayName::from).collect(),
+            count,
+            until,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReservationSeriesRequest {
+    // 初回の予約時間帯
+    #[garde(skip)]
+    pub reservation_start_time: DateTime<Local>,
+    #[garde(skip)]
+    pub reservation_end_time: DateTime<Local>,
+    #[garde(dive)]
+    pub recurrence: RecurrenceRequest,
+    // 省略した場合は、1 回でも既存の予約と重なると全体を作成しない
+    #[garde(skip)]
+    #[serde(default)]
+    pub conflict_policy: SeriesConflictPolicyName,
+    // 各回に設定するリマインダー。省略時の扱いは単発の予約と同じ
+    #[garde(
+        inner(length(max = MAX_REMINDERS_PER_RESERVATION)),
+        inner(inner(range(min = 1, max = MAX_REMINDER_LEAD_MINUTES)))
+    )]
+    #[serde(default)]
+    pub reminder_lead_minutes: Option<Vec<i32>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedReservationSeriesResponse {
+    pub reservation_series_id: ReservationSeriesId,
+    pub reservation_ids: Vec<ReservationId>,
+    pub skipped: Vec<SkippedOccurrenceResponse>,
+}
+
+impl From<CreatedReservationSeries> for CreatedReservationSeriesResponse {
+    fn from(value: CreatedReservationSeries) -> Self {
+        let CreatedReservationSeries {
+            reservation_series_id,
+            reservation_ids,
+            skipped,
+        } = value;
+        Self {
+            reservation_series_id,
+            reservation_ids,
+            skipped: skipped
+                .into_iter()
+                .map(SkippedOccurrenceResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedOccurrenceResponse {
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub conflicting_reservation_id: ReservationId,
+}
+
+impl From<SkippedOccurrence> for SkippedOccurrenceResponse {
+    fn from(value: SkippedOccurrence) -> Self {
+        let SkippedOccurrence {
+            reservation_start_time,
+            reservation_end_time,
+            conflicting_reservation_id,
+        } = value;
+        Self {
+            reservation_start_time,
+            reservation_end_time,
+            conflicting_reservation_id,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservationSeriesResponse {
+    pub reservation_series_id: ReservationSeriesId,
+    pub space_id: SpaceId,
+    pub reserved_by: UserId,
+    pub recurrence: RecurrenceResponse,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+    pub reservations: Vec<ReservationResponse>,
+}
+
+impl From<ReservationSeries> for ReservationSeriesResponse {
+    fn from(value: ReservationSeries) -> Self {
+        let ReservationSeries {
+            reservation_series_id,
+            space_id,
+            reserved_by,
+            recurrence,
+            reservation_start_time,
+            reservation_end_time,
+            created_at,
+            reservations,
+        } = value;
+        Self {
+            reservation_series_id,
+            space_id,
+            reserved_by,
+            recurrence: recurrence.into(),
+            reservation_start_time,
+            reservation_end_time,
+            created_at,
+            reservations: reservations
+                .into_iter()
+                .map(ReservationResponse::from)
+                .collect(),
+        }
+    }
+}
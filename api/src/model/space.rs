@@ -1,18 +1,33 @@
 use kernel::model::{
     space::{
-        event::{CreateSpace, UpdateSpace},
+        approval::{ApprovalPolicy, MAX_APPROVERS_PER_SPACE},
+        availability::{
+            AvailabilityOptions, AvailabilityWindow, AvailableSpaceOptions, FreeInterval,
+            SpaceAvailability,
+        },
+        check_in::{CheckInPolicy, MAX_CHECK_IN_CODE_LENGTH, MAX_CHECK_IN_GRACE_MINUTES},
+        event::{
+            CreateSpace, CreateSpaceBlackout, UpdateApprovalPolicy, UpdateBookingPolicy,
+            UpdateCheckInPolicy, UpdateOpeningHours, UpdateSpace,
+        },
+        policy::{BookingPolicy, RoleBookingPolicy, SpaceBookingPolicy},
+        schedule::{format_minutes, OpeningHours, SpaceBlackout, MINUTES_PER_DAY},
         Space, SpaceListOptions,
     },
-    id::{SpaceId, UserId},
+    id::{SpaceBlackoutId, SpaceId, UserId},
     list::PaginatedList,
 };
-use super::user::SpaceOwner;
+use super::{
+    reservation_series::WeekdayName,
+    user::{RoleName, SpaceOwner},
+};
 use derive_new::new;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
 
 use super::user::ReservationUser;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local};
+use shared::{error::AppError, i18n::ErrorMessage};
 use kernel::model::space::Reservation;
 use kernel::model::id::ReservationId;
 
@@ -129,6 +144,138 @@ impl From<SpaceListQuery> for SpaceListOptions {
     }
 }
 
+// スペースの空き時間の検索条件をクエリで受け取るための型
+// from, to は RFC 3339 形式で指定する（タイムゾーンの + は %2B とエンコードすること）
+#[derive(Debug, Deserialize)]
+pub struct AvailabilityQuery {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    // 空き時間を区切る単位。30m, 1h, 1h30m のように指定する
+    #[serde(default = "default_slot")]
+    pub slot: String,
+}
+
+fn default_slot() -> String {
+    "30m".into()
+}
+
+impl TryFrom<AvailabilityQuery> for AvailabilityOptions {
+    type Error = AppError;
+
+    fn try_from(value: AvailabilityQuery) -> Result<Self, Self::Error> {
+        let AvailabilityQuery { from, to, slot } = value;
+        let slot = parse_slot(&slot).ok_or_else(|| {
+            AppError::UnprocessableEntity(ErrorMessage::InvalidSlotFormat { slot })
+        })?;
+        AvailabilityOptions::new(AvailabilityWindow::new(from, to)?, slot)
+    }
+}
+
+// 1h30m のような、時間（h）と分（m）の組み合わせを解釈する
+fn parse_slot(value: &str) -> Option<Duration> {
+    let (hours, minutes) = match value.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<i64>().ok()?, rest),
+        None => (0, value),
+    };
+    let minutes = match minutes {
+        "" if value.ends_with('h') => 0,
+        minutes => minutes.strip_suffix('m')?.parse::<i64>().ok()?,
+    };
+    Some(Duration::hours(hours) + Duration::minutes(minutes))
+}
+
+// 予約の入っていないスペースの検索条件をクエリで受け取るための型
+#[derive(Debug, Deserialize, Validate)]
+pub struct AvailableSpaceQuery {
+    #[garde(skip)]
+    pub from: DateTime<Local>,
+    #[garde(skip)]
+    pub to: DateTime<Local>,
+    #[garde(range(min = 1))]
+    pub capacity: Option<i32>,
+    // 必要な設備をカンマ区切りで指定する
+    #[garde(skip)]
+    pub equipment: Option<String>,
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)] // default は 0
+    pub offset: i64,
+}
+
+impl TryFrom<AvailableSpaceQuery> for AvailableSpaceOptions {
+    type Error = AppError;
+
+    fn try_from(value: AvailableSpaceQuery) -> Result<Self, Self::Error> {
+        let AvailableSpaceQuery {
+            from,
+            to,
+            capacity,
+            equipment,
+            limit,
+            offset,
+        } = value;
+        Ok(Self {
+            window: AvailabilityWindow::new(from, to)?,
+            capacity,
+            equipment: equipment
+                .iter()
+                .flat_map(|e| e.split(','))
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(String::from)
+                .collect(),
+            limit,
+            offset,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceAvailabilityResponse {
+    pub space_id: SpaceId,
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+    pub slot_minutes: i64,
+    pub free_intervals: Vec<FreeIntervalResponse>,
+}
+
+impl From<SpaceAvailability> for SpaceAvailabilityResponse {
+    fn from(value: SpaceAvailability) -> Self {
+        let SpaceAvailability {
+            space_id,
+            window: AvailabilityWindow { from, to },
+            slot,
+            free_intervals,
+        } = value;
+        Self {
+            space_id,
+            from,
+            to,
+            slot_minutes: slot.num_minutes(),
+            free_intervals: free_intervals
+                .into_iter()
+                .map(FreeIntervalResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreeIntervalResponse {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+impl From<FreeInterval> for FreeIntervalResponse {
+    fn from(value: FreeInterval) -> Self {
+        let FreeInterval { start, end } = value;
+        Self { start, end }
+    }
+}
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -142,6 +289,11 @@ pub struct SpaceResponse {
     pub address: String,
     pub owner: SpaceOwner,
     pub reservation: Option<SpaceReservationResponse>,
+    pub timezone: String,
+    pub opening_hours: Vec<OpeningHoursResponse>,
+    pub booking_policy: SpaceBookingPolicyResponse,
+    pub check_in_policy: CheckInPolicyResponse,
+    pub approval_policy: ApprovalPolicyResponse,
 }
 
 impl From<Space> for SpaceResponse {
@@ -156,6 +308,11 @@ impl From<Space> for SpaceResponse {
             address,
             owner,
             reservation,
+            timezone,
+            opening_hours,
+            booking_policy,
+            check_in_policy,
+            approval_policy,
         } = value;
         Self {
             space_id,
@@ -167,6 +324,351 @@ impl From<Space> for SpaceResponse {
             address,
             owner:owner.into(),
             reservation: reservation.map(SpaceReservationResponse::from),
+            timezone,
+            opening_hours: opening_hours
+                .into_iter()
+                .map(OpeningHoursResponse::from)
+                .collect(),
+            booking_policy: booking_policy.into(),
+            check_in_policy: check_in_policy.into(),
+            approval_policy: approval_policy.into(),
+        }
+    }
+}
+
+// 営業時間の時刻は、スペースのタイムゾーンでの HH:MM 形式で表す
+// 終了時刻のみ、終日営業を表す 24:00 を指定できる
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct OpeningHoursRequest {
+    #[garde(skip)]
+    pub weekday: WeekdayName,
+    #[garde(length(min = 5, max = 5))]
+    pub open_time: String,
+    #[garde(length(min = 5, max = 5))]
+    pub close_time: String,
+}
+
+impl TryFrom<OpeningHoursRequest> for OpeningHours {
+    type Error = AppError;
+
+    fn try_from(value: OpeningHoursRequest) -> Result<Self, Self::Error> {
+        let OpeningHoursRequest {
+            weekday,
+            open_time,
+            close_time,
+        } = value;
+        Ok(Self {
+            weekday: weekday.into(),
+            open_minutes: parse_time(&open_time)?,
+            close_minutes: parse_time(&close_time)?,
+        })
+    }
+}
+
+// HH:MM 形式の時刻を 0 時からの経過分にする
+fn parse_time(value: &str) -> Result<i32, AppError> {
+    value
+        .split_once(':')
+        .and_then(|(hours, minutes)| Some((hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?)))
+        .filter(|(hours, minutes)| (0..=24).contains(hours) && (0..60).contains(minutes))
+        .map(|(hours, minutes)| hours * 60 + minutes)
+        .filter(|minutes| *minutes <= MINUTES_PER_DAY)
+        .ok_or_else(|| {
+            AppError::UnprocessableEntity(ErrorMessage::InvalidTimeFormat {
+                value: value.to_string(),
+            })
+        })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpeningHoursResponse {
+    pub weekday: WeekdayName,
+    pub open_time: String,
+    pub close_time: String,
+}
+
+impl From<OpeningHours> for OpeningHoursResponse {
+    fn from(value: OpeningHours) -> Self {
+        let OpeningHours {
+            weekday,
+            open_minutes,
+            close_minutes,
+        } = value;
+        Self {
+            weekday: weekday.into(),
+            open_time: format_minutes(open_minutes),
+            close_time: format_minutes(close_minutes),
+        }
+    }
+}
+
+// 営業時間を丸ごと置き換えるための型
+// 空の一覧を指定した場合は、終日予約できるようになる
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOpeningHoursRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub timezone: String,
+    #[garde(dive)]
+    pub opening_hours: Vec<OpeningHoursRequest>,
+}
+
+#[derive(new)]
+pub struct UpdateOpeningHoursRequestWithIds(SpaceId, UserId, UpdateOpeningHoursRequest);
+impl TryFrom<UpdateOpeningHoursRequestWithIds> for UpdateOpeningHours {
+    type Error = AppError;
+
+    fn try_from(value: UpdateOpeningHoursRequestWithIds) -> Result<Self, Self::Error> {
+        let UpdateOpeningHoursRequestWithIds(
+            space_id,
+            user_id,
+            UpdateOpeningHoursRequest {
+                timezone,
+                opening_hours,
+            },
+        ) = value;
+        Ok(UpdateOpeningHours {
+            space_id,
+            timezone,
+            opening_hours: opening_hours
+                .into_iter()
+                .map(OpeningHours::try_from)
+                .collect::<Result<_, _>>()?,
+            requested_user: user_id,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSpaceBlackoutRequest {
+    #[garde(skip)]
+    pub start_time: DateTime<Local>,
+    #[garde(skip)]
+    pub end_time: DateTime<Local>,
+    // 予約できない理由。予約時のエラーメッセージにも表示される
+    #[garde(length(min = 1, max = 255))]
+    pub reason: String,
+}
+
+#[derive(new)]
+pub struct CreateSpaceBlackoutRequestWithIds(SpaceId, UserId, CreateSpaceBlackoutRequest);
+impl From<CreateSpaceBlackoutRequestWithIds> for CreateSpaceBlackout {
+    fn from(value: CreateSpaceBlackoutRequestWithIds) -> Self {
+        let CreateSpaceBlackoutRequestWithIds(
+            space_id,
+            user_id,
+            CreateSpaceBlackoutRequest {
+                start_time,
+                end_time,
+                reason,
+            },
+        ) = value;
+        CreateSpaceBlackout {
+            space_id,
+            start_time,
+            end_time,
+            reason,
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedSpaceBlackoutResponse {
+    pub space_blackout_id: SpaceBlackoutId,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceBlackoutResponse {
+    pub space_blackout_id: SpaceBlackoutId,
+    pub start_time: DateTime<Local>,
+    pub end_time: DateTime<Local>,
+    pub reason: String,
+}
+
+impl From<SpaceBlackout> for SpaceBlackoutResponse {
+    fn from(value: SpaceBlackout) -> Self {
+        let SpaceBlackout {
+            space_blackout_id,
+            start_time,
+            end_time,
+            reason,
+        } = value;
+        Self {
+            space_blackout_id,
+            start_time,
+            end_time,
+            reason,
+        }
+    }
+}
+
+// 予約のルール。省略した項目は制限しない
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingPolicyRequest {
+    #[garde(inner(range(min = 1)))]
+    pub min_duration_minutes: Option<i32>,
+    #[garde(inner(range(min = 1)))]
+    pub max_duration_minutes: Option<i32>,
+    #[garde(inner(range(min = 1, max = MINUTES_PER_DAY)))]
+    pub slot_granularity_minutes: Option<i32>,
+    #[garde(inner(range(min = 1)))]
+    pub max_days_in_advance: Option<i32>,
+    #[garde(inner(range(min = 1)))]
+    pub max_active_reservations: Option<i32>,
+    #[garde(inner(range(min = 1)))]
+    pub max_reservations_per_week: Option<i32>,
+    #[garde(inner(range(min = 1)))]
+    pub max_recent_no_shows: Option<i32>,
+}
+
+impl From<BookingPolicyRequest> for BookingPolicy {
+    fn from(value: BookingPolicyRequest) -> Self {
+        let BookingPolicyRequest {
+            min_duration_minutes,
+            max_duration_minutes,
+            slot_granularity_minutes,
+            max_days_in_advance,
+            max_active_reservations,
+            max_reservations_per_week,
+            max_recent_no_shows,
+        } = value;
+        Self {
+            min_duration_minutes,
+            max_duration_minutes,
+            slot_granularity_minutes,
+            max_days_in_advance,
+            max_active_reservations,
+            max_reservations_per_week,
+            max_recent_no_shows,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleBookingPolicyRequest {
+    #[garde(skip)]
+    pub role: RoleName,
+    #[garde(dive)]
+    #[serde(flatten)]
+    pub policy: BookingPolicyRequest,
+}
+
+// 予約のルールを丸ごと置き換えるための型
+// roleOverrides に指定したロールのユーザーには、スペースのルールの代わりにそのルールを使う
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBookingPolicyRequest {
+    #[garde(dive)]
+    #[serde(flatten)]
+    pub policy: BookingPolicyRequest,
+    #[garde(dive)]
+    #[serde(default)]
+    pub role_overrides: Vec<RoleBookingPolicyRequest>,
+}
+
+#[derive(new)]
+pub struct UpdateBookingPolicyRequestWithIds(SpaceId, UserId, UpdateBookingPolicyRequest);
+impl From<UpdateBookingPolicyRequestWithIds> for UpdateBookingPolicy {
+    fn from(value: UpdateBookingPolicyRequestWithIds) -> Self {
+        let UpdateBookingPolicyRequestWithIds(
+            space_id,
+            user_id,
+            UpdateBookingPolicyRequest {
+                policy,
+                role_overrides,
+            },
+        ) = value;
+        UpdateBookingPolicy {
+            space_id,
+            booking_policy: SpaceBookingPolicy {
+                policy: policy.into(),
+                role_overrides: role_overrides
+                    .into_iter()
+                    .map(|role_override| RoleBookingPolicy {
+                        role: role_override.role.into(),
+                        policy: role_override.policy.into(),
+                    })
+                    .collect(),
+            },
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookingPolicyResponse {
+    pub min_duration_minutes: Option<i32>,
+    pub max_duration_minutes: Option<i32>,
+    pub slot_granularity_minutes: Option<i32>,
+    pub max_days_in_advance: Option<i32>,
+    pub max_active_reservations: Option<i32>,
+    pub max_reservations_per_week: Option<i32>,
+    pub max_recent_no_shows: Option<i32>,
+}
+
+impl From<BookingPolicy> for BookingPolicyResponse {
+    fn from(value: BookingPolicy) -> Self {
+        let BookingPolicy {
+            min_duration_minutes,
+            max_duration_minutes,
+            slot_granularity_minutes,
+            max_days_in_advance,
+            max_active_reservations,
+            max_reservations_per_week,
+            max_recent_no_shows,
+        } = value;
+        Self {
+            min_duration_minutes,
+            max_duration_minutes,
+            slot_granularity_minutes,
+            max_days_in_advance,
+            max_active_reservations,
+            max_reservations_per_week,
+            max_recent_no_shows,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleBookingPolicyResponse {
+    pub role: RoleName,
+    #[serde(flatten)]
+    pub policy: BookingPolicyResponse,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceBookingPolicyResponse {
+    #[serde(flatten)]
+    pub policy: BookingPolicyResponse,
+    pub role_overrides: Vec<RoleBookingPolicyResponse>,
+}
+
+impl From<SpaceBookingPolicy> for SpaceBookingPolicyResponse {
+    fn from(value: SpaceBookingPolicy) -> Self {
+        let SpaceBookingPolicy {
+            policy,
+            role_overrides,
+        } = value;
+        Self {
+            policy: policy.into(),
+            role_overrides: role_overrides
+                .into_iter()
+                .map(|role_override| RoleBookingPolicyResponse {
+                    role: role_override.role.into(),
+                    policy: role_override.policy.into(),
+                })
+                .collect(),
         }
     }
 }
@@ -221,4 +723,113 @@ impl From<Reservation> for SpaceReservationResponse {
             reserved_at,
         }
     }
-}
\ No newline at end of file
+}
+// チェックインの設定を丸ごと置き換えるための型
+// graceMinutes を省略した場合は、チェックインを必須としない
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckInPolicyRequest {
+    #[garde(inner(range(min = 1, max = MAX_CHECK_IN_GRACE_MINUTES)))]
+    pub grace_minutes: Option<i32>,
+    #[garde(inner(length(min = 1, max = MAX_CHECK_IN_CODE_LENGTH)))]
+    pub code: Option<String>,
+}
+
+#[derive(new)]
+pub struct UpdateCheckInPolicyRequestWithIds(SpaceId, UserId, UpdateCheckInPolicyRequest);
+impl From<UpdateCheckInPolicyRequestWithIds> for UpdateCheckInPolicy {
+    fn from(value: UpdateCheckInPolicyRequestWithIds) -> Self {
+        let UpdateCheckInPolicyRequestWithIds(
+            space_id,
+            user_id,
+            UpdateCheckInPolicyRequest {
+                grace_minutes,
+                code,
+            },
+        ) = value;
+        UpdateCheckInPolicy {
+            space_id,
+            check_in_policy: CheckInPolicy {
+                grace_minutes,
+                code,
+            },
+            requested_user: user_id,
+        }
+    }
+}
+
+// チェックインのコードそのものは返さず、コードが必要かどうかのみを返す
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckInPolicyResponse {
+    pub grace_minutes: Option<i32>,
+    pub code_required: bool,
+}
+
+impl From<CheckInPolicy> for CheckInPolicyResponse {
+    fn from(value: CheckInPolicy) -> Self {
+        let CheckInPolicy {
+            grace_minutes,
+            code,
+        } = value;
+        Self {
+            grace_minutes,
+            code_required: code.is_some(),
+        }
+    }
+}
+
+// 予約の承認の設定を丸ごと置き換えるための型
+// approverIds を省略した場合は、承認者をいなくする
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateApprovalPolicyRequest {
+    #[garde(skip)]
+    pub requires_approval: bool,
+    #[garde(length(max = MAX_APPROVERS_PER_SPACE))]
+    #[serde(default)]
+    pub approver_ids: Vec<UserId>,
+}
+
+#[derive(new)]
+pub struct UpdateApprovalPolicyRequestWithIds(SpaceId, UserId, UpdateApprovalPolicyRequest);
+impl From<UpdateApprovalPolicyRequestWithIds> for UpdateApprovalPolicy {
+    fn from(value: UpdateApprovalPolicyRequestWithIds) -> Self {
+        let UpdateApprovalPolicyRequestWithIds(
+            space_id,
+            user_id,
+            UpdateApprovalPolicyRequest {
+                requires_approval,
+                approver_ids,
+            },
+        ) = value;
+        UpdateApprovalPolicy {
+            space_id,
+            approval_policy: ApprovalPolicy {
+                requires_approval,
+                approvers: approver_ids,
+            },
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPolicyResponse {
+    pub requires_approval: bool,
+    pub approver_ids: Vec<UserId>,
+}
+
+impl From<ApprovalPolicy> for ApprovalPolicyResponse {
+    fn from(value: ApprovalPolicy) -> Self {
+        let ApprovalPolicy {
+            requires_approval,
+            approvers,
+        } = value;
+        Self {
+            requires_approval,
+            approver_ids: approvers,
+        }
+    }
+}
@@ -2,16 +2,21 @@ use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     id::UserId,
+    reminder::{normalize_lead_minutes, MAX_REMINDERS_PER_RESERVATION, MAX_REMINDER_LEAD_MINUTES},
     role::Role,
     user::{
-        event::{CreateUser, UpdateUserPassword, UpdateUserRole},
+        event::{
+            CreateUser, UpdateUserLanguage, UpdateUserPassword, UpdateUserReminderPreference,
+            UpdateUserRole, UpdateUserTimezone,
+        },
         User,
     },
 };
 use serde::{Deserialize, Serialize};
+use shared::i18n::Language;
 use strum::VariantNames;
 
-#[derive(Serialize, Deserialize, VariantNames)]
+#[derive(Debug, Serialize, Deserialize, VariantNames)]
 #[strum(serialize_all = "kebab-case")]
 pub enum RoleName {
     Admin,
@@ -36,6 +41,31 @@ impl From<RoleName> for Role {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LanguageName {
+    Ja,
+    En,
+}
+
+impl From<Language> for LanguageName {
+    fn from(value: Language) -> Self {
+        match value {
+            Language::Ja => Self::Ja,
+            Language::En => Self::En,
+        }
+    }
+}
+
+impl From<LanguageName> for Language {
+    fn from(value: LanguageName) -> Self {
+        match value {
+            LanguageName::Ja => Self::Ja,
+            LanguageName::En => Self::En,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UsersResponse {
@@ -49,6 +79,9 @@ pub struct UserResponse {
     pub user_name: String,
     pub email: String,
     pub role: RoleName,
+    pub reminder_lead_minutes: Vec<i32>,
+    pub language: LanguageName,
+    pub timezone: String,
 }
 
 impl From<User> for UserResponse {
@@ -58,12 +91,18 @@ impl From<User> for UserResponse {
             user_name,
             email,
             role,
+            reminder_lead_minutes,
+            language,
+            timezone,
         } = value;
         Self {
             user_id,
             user_name,
             email,
             role: RoleName::from(role),
+            reminder_lead_minutes,
+            language: LanguageName::from(language),
+            timezone,
         }
     }
 }
@@ -122,6 +161,71 @@ impl From<CreateUserRequest> for CreateUser {
     }
 }
 
+// 予約時に既定で設定するリマインダー（予約開始の何分前に送るか）を変更するための型
+// 空の配列を指定すると、既定ではリマインダーを送らない
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateReminderPreferenceRequest {
+    #[garde(
+        length(max = MAX_REMINDERS_PER_RESERVATION),
+        inner(range(min = 1, max = MAX_REMINDER_LEAD_MINUTES))
+    )]
+    reminder_lead_minutes: Vec<i32>,
+}
+
+#[derive(new)]
+pub struct UpdateReminderPreferenceRequestWithUserId(UserId, UpdateReminderPreferenceRequest);
+impl From<UpdateReminderPreferenceRequestWithUserId> for UpdateUserReminderPreference {
+    fn from(value: UpdateReminderPreferenceRequestWithUserId) -> Self {
+        let UpdateReminderPreferenceRequestWithUserId(
+            user_id,
+            UpdateReminderPreferenceRequest {
+                reminder_lead_minutes,
+            },
+        ) = value;
+        Self {
+            user_id,
+            reminder_lead_minutes: normalize_lead_minutes(reminder_lead_minutes),
+        }
+    }
+}
+
+// 通知メールの言語を変更するための型
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLanguageRequest {
+    language: LanguageName,
+}
+
+#[derive(new)]
+pub struct UpdateLanguageRequestWithUserId(UserId, UpdateLanguageRequest);
+impl From<UpdateLanguageRequestWithUserId> for UpdateUserLanguage {
+    fn from(value: UpdateLanguageRequestWithUserId) -> Self {
+        let UpdateLanguageRequestWithUserId(user_id, UpdateLanguageRequest { language }) = value;
+        Self {
+            user_id,
+            language: Language::from(language),
+        }
+    }
+}
+
+// 通知メールの日時を表示するタイムゾーン（IANA のタイムゾーン名）を変更するための型
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTimezoneRequest {
+    #[garde(length(min = 1, max = 64))]
+    timezone: String,
+}
+
+#[derive(new)]
+pub struct UpdateTimezoneRequestWithUserId(UserId, UpdateTimezoneRequest);
+impl From<UpdateTimezoneRequestWithUserId> for UpdateUserTimezone {
+    fn from(value: UpdateTimezoneRequestWithUserId) -> Self {
+        let UpdateTimezoneRequestWithUserId(user_id, UpdateTimezoneRequest { timezone }) = value;
+        Self { user_id, timezone }
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleRequest {
@@ -0,0 +1,163 @@
+use chrono::{DateTime, Local};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{ReservationId, SpaceId, UserId, WaitlistEntryId},
+    waitlist::{event::CreateWaitlistEntry, WaitlistEntry, WaitlistFulfillment, WaitlistStatus},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitlistFulfillmentName {
+    #[default]
+    Offer,
+    AutoBook,
+}
+
+impl From<WaitlistFulfillmentName> for WaitlistFulfillment {
+    fn from(value: WaitlistFulfillmentName) -> Self {
+        match value {
+            WaitlistFulfillmentName::Offer => Self::Offer,
+            WaitlistFulfillmentName::AutoBook => Self::AutoBook,
+        }
+    }
+}
+
+impl From<WaitlistFulfillment> for WaitlistFulfillmentName {
+    fn from(value: WaitlistFulfillment) -> Self {
+        match value {
+            WaitlistFulfillment::Offer => Self::Offer,
+            WaitlistFulfillment::AutoBook => Self::AutoBook,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitlistStatusName {
+    Waiting,
+    Offered,
+    Booked,
+    Expired,
+    Cancelled,
+}
+
+impl From<WaitlistStatus> for WaitlistStatusName {
+    fn from(value: WaitlistStatus) -> Self {
+        match value {
+            WaitlistStatus::Waiting => Self::Waiting,
+            WaitlistStatus::Offered => Self::Offered,
+            WaitlistStatus::Booked => Self::Booked,
+            WaitlistStatus::Expired => Self::Expired,
+            WaitlistStatus::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWaitlistEntryRequest {
+    #[garde(skip)]
+    pub reservation_start_time: DateTime<Local>,
+    #[garde(skip)]
+    pub reservation_end_time: DateTime<Local>,
+    // 空きが出たときの扱い。省略した場合は確定用のリンクを送る
+    #[garde(skip)]
+    #[serde(default)]
+    pub fulfillment: WaitlistFulfillmentName,
+}
+
+#[derive(new)]
+pub struct CreateWaitlistEntryRequestWithIds(SpaceId, UserId, CreateWaitlistEntryRequest);
+impl From<CreateWaitlistEntryRequestWithIds> for CreateWaitlistEntry {
+    fn from(value: CreateWaitlistEntryRequestWithIds) -> Self {
+        let CreateWaitlistEntryRequestWithIds(
+            space_id,
+            user_id,
+            CreateWaitlistEntryRequest {
+                reservation_start_time,
+                reservation_end_time,
+                fulfillment,
+            },
+        ) = value;
+        CreateWaitlistEntry {
+            space_id,
+            requested_by: user_id,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment: fulfillment.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWaitlistEntryResponse {
+    pub waitlist_entry_id: WaitlistEntryId,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimedWaitlistOfferResponse {
+    pub reservation_id: ReservationId,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistEntriesResponse {
+    pub items: Vec<WaitlistEntryResponse>,
+}
+
+impl From<Vec<WaitlistEntry>> for WaitlistEntriesResponse {
+    fn from(value: Vec<WaitlistEntry>) -> Self {
+        Self {
+            items: value.into_iter().map(WaitlistEntryResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitlistEntryResponse {
+    pub waitlist_entry_id: WaitlistEntryId,
+    pub space_id: SpaceId,
+    pub space_name: String,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub fulfillment: WaitlistFulfillmentName,
+    pub status: WaitlistStatusName,
+    pub offer_expires_at: Option<DateTime<Local>>,
+    pub reservation_id: Option<ReservationId>,
+    pub created_at: DateTime<Local>,
+}
+
+impl From<WaitlistEntry> for WaitlistEntryResponse {
+    fn from(value: WaitlistEntry) -> Self {
+        let WaitlistEntry {
+            waitlist_entry_id,
+            space_id,
+            space_name,
+            user_id: _,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment,
+            status,
+            offer_expires_at,
+            reservation_id,
+            created_at,
+        } = value;
+        Self {
+            waitlist_entry_id,
+            space_id,
+            space_name,
+            reservation_start_time,
+            reservation_end_time,
+            fulfillment: fulfillment.into(),
+            status: status.into(),
+            offer_expires_at,
+            reservation_id,
+            created_at,
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use chrono::{DateTime, Local};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{WebhookDeliveryId, WebhookId},
+    list::PaginatedList,
+    webhook::{
+        event::{CreateWebhook, UpdateWebhook},
+        Webhook, WebhookDelivery, WebhookDeliveryListOptions, WebhookDeliveryStatus,
+        WebhookEvent,
+    },
+};
+use serde::{Deserialize, Serialize};
+use shared::i18n::FieldErrorMessage;
+
+// 送信先として受け付ける URL の長さの上限
+const MAX_WEBHOOK_URL_LENGTH: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEventName {
+    #[serde(rename = "reservation.created")]
+    ReservationCreated,
+    #[serde(rename = "reservation.updated")]
+    ReservationUpdated,
+    #[serde(rename = "reservation.returned")]
+    ReservationReturned,
+    #[serde(rename = "reservation.cancelled")]
+    ReservationCancelled,
+    #[serde(rename = "space.deactivated")]
+    SpaceDeactivated,
+    // 試験送信は購読できないため、送信の記録を返すときにのみ使う
+    #[serde(rename = "ping", skip_deserializing)]
+    Ping,
+}
+
+impl From<WebhookEvent> for WebhookEventName {
+    fn from(value: WebhookEvent) -> Self {
+        match value {
+            WebhookEvent::ReservationCreated => Self::ReservationCreated,
+            WebhookEvent::ReservationUpdated => Self::ReservationUpdated,
+            WebhookEvent::ReservationReturned => Self::ReservationReturned,
+            WebhookEvent::ReservationCancelled => Self::ReservationCancelled,
+            WebhookEvent::SpaceDeactivated => Self::SpaceDeactivated,
+            WebhookEvent::Ping => Self::Ping,
+        }
+    }
+}
+
+impl From<WebhookEventName> for WebhookEvent {
+    fn from(value: WebhookEventName) -> Self {
+        match value {
+            WebhookEventName::ReservationCreated => Self::ReservationCreated,
+            WebhookEventName::ReservationUpdated => Self::ReservationUpdated,
+            WebhookEventName::ReservationReturned => Self::ReservationReturned,
+            WebhookEventName::ReservationCancelled => Self::ReservationCancelled,
+            WebhookEventName::SpaceDeactivated => Self::SpaceDeactivated,
+            WebhookEventName::Ping => Self::Ping,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatusName {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl From<WebhookDeliveryStatus> for WebhookDeliveryStatusName {
+    fn from(value: WebhookDeliveryStatus) -> Self {
+        match value {
+            WebhookDeliveryStatus::Pending => Self::Pending,
+            WebhookDeliveryStatus::Delivered => Self::Delivered,
+            WebhookDeliveryStatus::Dead => Self::Dead,
+        }
+    }
+}
+
+impl From<WebhookDeliveryStatusName> for WebhookDeliveryStatus {
+    fn from(value: WebhookDeliveryStatusName) -> Self {
+        match value {
+            WebhookDeliveryStatusName::Pending => Self::Pending,
+            WebhookDeliveryStatusName::Delivered => Self::Delivered,
+            WebhookDeliveryStatusName::Dead => Self::Dead,
+        }
+    }
+}
+
+// 送信先は http または https の URL に限る
+fn validate_webhook_url(value: &str, _: &()) -> garde::Result {
+    let valid = ["http://", "https://"].iter().any(|scheme| {
+        value
+            .get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            && value.len() > scheme.len()
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(garde::Error::new(FieldErrorMessage::INVALID_HTTP_URL))
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    #[garde(length(max = MAX_WEBHOOK_URL_LENGTH), custom(validate_webhook_url))]
+    pub url: String,
+    #[garde(length(min = 1))]
+    pub events: Vec<WebhookEventName>,
+    #[garde(skip)]
+    pub description: Option<String>,
+}
+
+impl From<CreateWebhookRequest> for CreateWebhook {
+    fn from(value: CreateWebhookRequest) -> Self {
+        let CreateWebhookRequest {
+            url,
+            events,
+            description,
+        } = value;
+        CreateWebhook {
+            url,
+            events: dedup_events(events),
+            description: description.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookRequest {
+    #[garde(length(max = MAX_WEBHOOK_URL_LENGTH), custom(validate_webhook_url))]
+    pub url: String,
+    #[garde(length(min = 1))]
+    pub events: Vec<WebhookEventName>,
+    #[garde(skip)]
+    pub description: Option<String>,
+    #[garde(skip)]
+    pub is_active: bool,
+}
+
+// パスパラメータからの WebhookId と UpdateWebhookRequest を UpdateWebhook 型に変換するための一時的な型
+#[derive(new)]
+pub struct UpdateWebhookRequestWithId(WebhookId, UpdateWebhookRequest);
+impl From<UpdateWebhookRequestWithId> for UpdateWebhook {
+    fn from(value: UpdateWebhookRequestWithId) -> Self {
+        let UpdateWebhookRequestWithId(
+            webhook_id,
+            UpdateWebhookRequest {
+                url,
+                events,
+                description,
+                is_active,
+            },
+        ) = value;
+        UpdateWebhook {
+            webhook_id,
+            url,
+            events: dedup_events(events),
+            description: description.unwrap_or_default(),
+            is_active,
+        }
+    }
+}
+
+// 同じ出来事が重複して指定されても、送信は 1 回にする
+fn dedup_events(events: Vec<WebhookEventName>) -> Vec<WebhookEvent> {
+    let mut res: Vec<WebhookEvent> = Vec::with_capacity(events.len());
+    for event in events.into_iter().map(WebhookEvent::from) {
+        if !res.contains(&event) {
+            res.push(event);
+        }
+    }
+    res
+}
+
+// 署名に使う鍵は登録時にのみ返す
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResponse {
+    pub webhook_id: WebhookId,
+    pub url: String,
+    pub events: Vec<WebhookEventName>,
+    pub description: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(value: Webhook) -> Self {
+        let Webhook {
+            webhook_id,
+            url,
+            secret: _,
+            events,
+            description,
+            is_active,
+            created_at,
+            updated_at,
+        } = value;
+        Self {
+            webhook_id,
+            url,
+            events: events.into_iter().map(WebhookEventName::from).collect(),
+            description,
+            is_active,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhooksResponse {
+    pub items: Vec<WebhookResponse>,
+}
+
+impl From<Vec<Webhook>> for WebhooksResponse {
+    fn from(value: Vec<Webhook>) -> Self {
+        Self {
+            items: value.into_iter().map(WebhookResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    // 受信側で署名を検証するための鍵
+    pub secret: String,
+}
+
+impl From<Webhook> for CreatedWebhookResponse {
+    fn from(value: Webhook) -> Self {
+        let secret = value.secret.clone();
+        Self {
+            webhook: value.into(),
+            secret,
+        }
+    }
+}
+
+// クエリで status と limit, offset を受け取るための型
+// status を省略した場合はすべての送信状態を対象とする
+#[derive(Debug, Deserialize, Validate)]
+pub struct WebhookDeliveryListQuery {
+    #[garde(skip)]
+    pub status: Option<WebhookDeliveryStatusName>,
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)] // default は 0
+    pub offset: i64,
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const fn default_limit() -> i64 {
+    DEFAULT_LIMIT
+}
+
+#[derive(new)]
+pub struct WebhookDeliveryListQueryWithId(WebhookId, WebhookDeliveryListQuery);
+impl From<WebhookDeliveryListQueryWithId> for WebhookDeliveryListOptions {
+    fn from(value: WebhookDeliveryListQueryWithId) -> Self {
+        let WebhookDeliveryListQueryWithId(
+            webhook_id,
+            WebhookDeliveryListQuery {
+                status,
+                limit,
+                offset,
+            },
+        ) = value;
+        Self {
+            webhook_id,
+            status: status.map(WebhookDeliveryStatus::from),
+            limit,
+            offset,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryResponse {
+    pub webhook_delivery_id: WebhookDeliveryId,
+    pub webhook_id: WebhookId,
+    pub event: WebhookEventName,
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatusName,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    // レスポンスを受け取れなかった場合は null
+    pub response_status: Option<i32>,
+    pub next_attempt_at: DateTime<Local>,
+    pub delivered_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(value: WebhookDelivery) -> Self {
+        let WebhookDelivery {
+            webhook_delivery_id,
+            webhook_id,
+            event,
+            payload,
+            status,
+            attempts,
+            last_error,
+            response_status,
+            next_attempt_at,
+            delivered_at,
+            created_at,
+        } = value;
+        Self {
+            webhook_delivery_id,
+            webhook_id,
+            event: event.into(),
+            payload,
+            status: status.into(),
+            attempts,
+            last_error,
+            response_status,
+            next_attempt_at,
+            delivered_at,
+            created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedWebhookDeliveryResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<WebhookDeliveryResponse>,
+}
+
+impl From<PaginatedList<WebhookDelivery>> for PaginatedWebhookDeliveryResponse {
+    fn from(value: PaginatedList<WebhookDelivery>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(WebhookDeliveryResponse::from).collect(),
+        }
+    }
+}
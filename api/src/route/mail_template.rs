@@ -0,0 +1,7 @@
+use crate::handler::mail_template::preview_mail_template;
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+pub fn build_mail_template_router() -> Router<AppRegistry> {
+    Router::new().route("/mail-templates/:kind/preview", get(preview_mail_template))
+}
@@ -2,4 +2,9 @@ pub mod health;
 pub mod space;
 pub mod auth;
 pub mod user;
+pub mod outbox;
+pub mod waitlist;
+pub mod reservation;
+pub mod mail_template;
+pub mod webhook;
 pub mod v1;
\ No newline at end of file
@@ -0,0 +1,12 @@
+use crate::handler::outbox::{list_outbox_messages, requeue_outbox_message};
+use axum::{
+    routing::{get, put},
+    Router,
+};
+use registry::AppRegistry;
+
+pub fn build_outbox_router() -> Router<AppRegistry> {
+    Router::new()
+        .route("/outbox", get(list_outbox_messages))
+        .route("/outbox/:outbox_message_id/requeue", put(requeue_outbox_message))
+}
@@ -0,0 +1,7 @@
+use crate::handler::reservation::list_reservations;
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+pub fn build_reservation_router() -> Router<AppRegistry> {
+    Router::new().route("/reservations", get(list_reservations))
+}
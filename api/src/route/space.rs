@@ -5,33 +5,88 @@ use axum::{
 use registry::AppRegistry;
 
 use crate::handler::{
-    space::{delete_space, register_space, show_space, show_space_list, update_space},
+    space::{
+        delete_space, delete_space_blackout, register_space, register_space_blackout,
+        show_available_space_list, show_space, show_space_availability,
+        show_space_blackout_list, show_space_list, update_booking_policy,
+        update_approval_policy, update_check_in_policy, update_opening_hours, update_space,
+    },
     reservation::{
         return_reservation_by_id,
         reservation_space, 
+        update_reservation,
         reservation_history, 
         return_space,
+        check_in_reservation,
+        approve_reservation,
+        reject_reservation,
         cancel_space, 
         cancel_all_reservation,
         show_reserved_list},
+    reservation_series::{
+        cancel_reservation, create_reservation_series, show_reservation_series,
+    },
+    waitlist::join_waitlist,
+    calendar::show_space_calendar,
 };
 
 pub fn build_space_routers() -> Router<AppRegistry> {
     let spaces_routers = Router::new()
         .route("/", post(register_space))
         .route("/", get(show_space_list))
+        .route("/available", get(show_available_space_list))
         .route("/:space_id", get(show_space))
         .route("/:space_id", put(update_space))
-        .route("/:space_id", delete(delete_space));
+        .route("/:space_id", delete(delete_space))
+        .route("/:space_id/availability", get(show_space_availability))
+        .route("/:space_id/opening-hours", put(update_opening_hours))
+        .route("/:space_id/booking-policy", put(update_booking_policy))
+        .route("/:space_id/check-in-policy", put(update_check_in_policy))
+        .route("/:space_id/approval-policy", put(update_approval_policy))
+        .route("/:space_id/blackouts", get(show_space_blackout_list))
+        .route("/:space_id/blackouts", post(register_space_blackout))
+        .route(
+            "/:space_id/blackouts/:space_blackout_id",
+            delete(delete_space_blackout),
+        );
 
     let reservation_router = Router::new()
         .route("/reservations", get(show_reserved_list))
         .route("/reservations/:reservation_id", get(return_reservation_by_id))
         .route("/:space_id/reservations", post(reservation_space))
+        .route("/:space_id/waitlist", post(join_waitlist))
+        .route(
+            "/:space_id/reservations/:reservation_id",
+            put(update_reservation),
+        )
         .route(
             "/:space_id/reservations/:reservation_id/returned",
             put(return_space),
         )
+        .route(
+            "/:space_id/reservations/:reservation_id/check-in",
+            post(check_in_reservation),
+        )
+        .route(
+            "/:space_id/reservations/:reservation_id/approve",
+            post(approve_reservation),
+        )
+        .route(
+            "/:space_id/reservations/:reservation_id/reject",
+            post(reject_reservation),
+        )
+        .route(
+            "/:space_id/reservations/:reservation_id/canceled",
+            put(cancel_reservation),
+        )
+        .route(
+            "/:space_id/reservation-series",
+            post(create_reservation_series),
+        )
+        .route(
+            "/reservation-series/:reservation_series_id",
+            get(show_reservation_series),
+        )
         .route(
             "/:space_id/canceled",
             put(cancel_space),
@@ -40,7 +95,8 @@ pub fn build_space_routers() -> Router<AppRegistry> {
             "/all/canceled",
             put(cancel_all_reservation),
         )
-        .route("/:space_id/reservation-history", get(reservation_history));
+        .route("/:space_id/reservation-history", get(reservation_history))
+        .route("/:space_id/calendar.ics", get(show_space_calendar));
 
     // merge メソッドで router を結合する
     Router::new().nest("/spaces", spaces_routers.merge(reservation_router))
@@ -1,6 +1,12 @@
+use crate::handler::calendar::{
+    issue_calendar_feed_token, revoke_calendar_feed_token, show_calendar_feed_token,
+    show_my_reservation_calendar,
+};
+use crate::handler::waitlist::show_my_waitlist;
 use crate::handler::user::{
-    change_password, change_role, delete_user, get_reservations, get_current_user, list_users,
-    register_user,
+    change_language, change_password, change_reminder_preference, change_role, change_timezone,
+    delete_user, get_current_user, get_reservation_history, get_reservations,
+    get_user_reservation_history, list_users, register_user,
 };
 use axum::{
     routing::{delete, get, put},
@@ -12,8 +18,24 @@ pub fn build_user_router() -> Router<AppRegistry> {
     Router::new()
         .route("/users/me", get(get_current_user))
         .route("/users/me/password", put(change_password))
+        .route("/users/me/reminder-preferences", put(change_reminder_preference))
+        .route("/users/me/language", put(change_language))
+        .route("/users/me/timezone", put(change_timezone))
         .route("/users/me/reservations", get(get_reservations))
+        .route("/users/me/reservations.ics", get(show_my_reservation_calendar))
+        .route(
+            "/users/me/calendar-feed-token",
+            get(show_calendar_feed_token)
+                .post(issue_calendar_feed_token)
+                .delete(revoke_calendar_feed_token),
+        )
+        .route("/users/me/reservation-history", get(get_reservation_history))
+        .route("/users/me/waitlist", get(show_my_waitlist))
         .route("/users", get(list_users).post(register_user))
         .route("/users/:user_id", delete(delete_user))
         .route("/users/:user_id/role", put(change_role))
+        .route(
+            "/users/:user_id/reservation-history",
+            get(get_user_reservation_history),
+        )
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use super::{
     space::build_space_routers, health::build_health_check_routers, user::build_user_router,
+    outbox::build_outbox_router, waitlist::build_waitlist_router,
+    reservation::build_reservation_router, mail_template::build_mail_template_router,
+    webhook::build_webhook_router,
 };
 use axum::Router;
 use registry::AppRegistry;
@@ -8,6 +11,11 @@ pub fn routes() -> Router<AppRegistry> {
     let router = Router::new()
         .merge(build_health_check_routers())
         .merge(build_space_routers())
-        .merge(build_user_router());
+        .merge(build_user_router())
+        .merge(build_outbox_router())
+        .merge(build_waitlist_router())
+        .merge(build_reservation_router())
+        .merge(build_mail_template_router())
+        .merge(build_webhook_router());
     Router::new().nest("/api/v1", router)
 }
\ No newline at end of file
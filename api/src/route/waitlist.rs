@@ -0,0 +1,14 @@
+use crate::handler::waitlist::{cancel_waitlist_entry, claim_waitlist_offer};
+use axum::{
+    routing::{delete, post},
+    Router,
+};
+use registry::AppRegistry;
+
+pub fn build_waitlist_router() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/:waitlist_entry_id", delete(cancel_waitlist_entry))
+        .route("/claims/:claim_token", post(claim_waitlist_offer));
+
+    Router::new().nest("/waitlist", routers)
+}
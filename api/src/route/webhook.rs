@@ -0,0 +1,22 @@
+use crate::handler::webhook::{
+    delete_webhook, list_webhook_deliveries, list_webhooks, ping_webhook, register_webhook,
+    show_webhook, update_webhook,
+};
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use registry::AppRegistry;
+
+pub fn build_webhook_router() -> Router<AppRegistry> {
+    let webhook_routers = Router::new()
+        .route("/", get(list_webhooks).post(register_webhook))
+        .route(
+            "/:webhook_id",
+            get(show_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .route("/:webhook_id/deliveries", get(list_webhook_deliveries))
+        .route("/:webhook_id/ping", post(ping_webhook));
+
+    Router::new().nest("/webhooks", webhook_routers)
+}
@@ -0,0 +1,175 @@
+use crate::model::{id::UserId, role::Role};
+use shared::error::{AppError, AppResult};
+
+// 操作するユーザー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Actor {
+    pub user_id: UserId,
+    pub role: Role,
+}
+
+impl Actor {
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+}
+
+// 権限を確認する操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    // 予約時間の変更
+    UpdateReservation { reserved_by: UserId },
+    // 予約の利用終了・キャンセル（繰り返し予約のまとめてのキャンセルを含む）
+    EndReservation { reserved_by: UserId },
+    // スペースの停止と、そのスペースの予約の一括キャンセル
+    DeactivateSpace { owner_id: UserId },
+    // スペースの内容・営業時間・利用停止期間・予約のルール・チェックインと承認の設定の変更と、スペースの削除
+    ManageSpace { owner_id: UserId },
+    // すべてのスペースの停止と、すべての予約の一括キャンセル
+    CancelAllReservations,
+    // 予約・予約履歴の閲覧
+    ViewReservations { user_id: UserId },
+    // ユーザーの登録・削除・一覧・権限の変更
+    ManageUsers,
+    // 通知の outbox の閲覧・再送
+    ManageOutbox,
+    // 通知メールのテンプレートのプレビュー
+    PreviewMailTemplates,
+    // webhook の送信先の登録・変更・削除と、送信の記録の閲覧
+    ManageWebhooks,
+}
+
+// 誰がどの操作を行えるかを決める
+// handler は、データを変更する前にここで権限を確認する
+pub struct AuthorizationPolicy;
+
+impl AuthorizationPolicy {
+    pub fn is_allowed(actor: Actor, action: Action) -> bool {
+        // 管理者はすべての操作を行える
+        if actor.is_admin() {
+            return true;
+        }
+        match action {
+            // 予約は、予約したユーザー本人のみ変更・終了できる
+            Action::UpdateReservation { reserved_by } | Action::EndReservation { reserved_by } => {
+                reserved_by == actor.user_id
+            }
+            // スペースは、所有者のみ変更・停止できる
+            Action::DeactivateSpace { owner_id } | Action::ManageSpace { owner_id } => {
+                owner_id == actor.user_id
+            }
+            Action::ViewReservations { user_id } => user_id == actor.user_id,
+            Action::CancelAllReservations
+            | Action::ManageUsers
+            | Action::ManageOutbox
+            | Action::PreviewMailTemplates
+            | Action::ManageWebhooks => false,
+        }
+    }
+
+    // 操作を行えない場合は 403 を返す
+    pub fn authorize(actor: Actor, action: Action) -> AppResult<()> {
+        if Self::is_allowed(actor, action) {
+            Ok(())
+        } else {
+            Err(AppError::ForbiddenOperation)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actors() -> (Actor, Actor, Actor) {
+        let owner = Actor {
+            user_id: UserId::new(),
+            role: Role::User,
+        };
+        let other = Actor {
+            user_id: UserId::new(),
+            role: Role::User,
+        };
+        let admin = Actor {
+            user_id: UserId::new(),
+            role: Role::Admin,
+        };
+        (owner, other, admin)
+    }
+
+    fn assert_allowed(action: Action, owner: Actor, other: Actor, admin: Actor) {
+        assert!(AuthorizationPolicy::authorize(owner, action).is_ok());
+        assert!(matches!(
+            AuthorizationPolicy::authorize(other, action),
+            Err(AppError::ForbiddenOperation)
+        ));
+        assert!(AuthorizationPolicy::authorize(admin, action).is_ok());
+    }
+
+    #[test]
+    fn test_update_reservation() {
+        let (owner, other, admin) = actors();
+        let action = Action::UpdateReservation {
+            reserved_by: owner.user_id,
+        };
+        assert_allowed(action, owner, other, admin);
+    }
+
+    #[test]
+    fn test_end_reservation() {
+        let (owner, other, admin) = actors();
+        let action = Action::EndReservation {
+            reserved_by: owner.user_id,
+        };
+        assert_allowed(action, owner, other, admin);
+    }
+
+    #[test]
+    fn test_deactivate_space() {
+        let (owner, other, admin) = actors();
+        let action = Action::DeactivateSpace {
+            owner_id: owner.user_id,
+        };
+        assert_allowed(action, owner, other, admin);
+    }
+
+    #[test]
+    fn test_manage_space() {
+        let (owner, other, admin) = actors();
+        let action = Action::ManageSpace {
+            owner_id: owner.user_id,
+        };
+        assert_allowed(action, owner, other, admin);
+        // 他のユーザーが所有するスペースは変更できない
+        assert!(!AuthorizationPolicy::is_allowed(
+            owner,
+            Action::ManageSpace {
+                owner_id: other.user_id,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_view_reservations() {
+        let (owner, other, admin) = actors();
+        let action = Action::ViewReservations {
+            user_id: owner.user_id,
+        };
+        assert_allowed(action, owner, other, admin);
+    }
+
+    #[test]
+    fn test_admin_only_actions() {
+        let (user, _, admin) = actors();
+        for action in [
+            Action::CancelAllReservations,
+            Action::ManageUsers,
+            Action::ManageOutbox,
+            Action::PreviewMailTemplates,
+            Action::ManageWebhooks,
+        ] {
+            assert!(!AuthorizationPolicy::is_allowed(user, action));
+            assert!(AuthorizationPolicy::is_allowed(admin, action));
+        }
+    }
+}
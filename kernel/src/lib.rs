@@ -1,2 +1,5 @@
+pub mod authorization;
 pub mod model;
+pub mod notifier;
 pub mod repository;
+pub mod webhook;
@@ -0,0 +1,271 @@
+use crate::model::{
+    id::ReservationId,
+    notification::ReservationNotification,
+    reservation::{
+        status::{ReservationOutcome, ReservationStatus},
+        Reservation,
+    },
+};
+use chrono::{DateTime, Local, Utc};
+
+// 予定表に含める予約の範囲。予約時間が現在よりこの日数以上前に終わった予約は含めない
+pub const CALENDAR_FEED_PAST_DAYS: i64 = 90;
+// 予定表に含める予約の数の上限。終わった予約と今後の予約それぞれに適用する
+pub const CALENDAR_FEED_LIMIT: i64 = 1000;
+
+const PRODUCT_ID: &str = "-//sp2025//Space Reservation//JA";
+// RFC 5545 では、1 行を改行を除いて 75 オクテット以内に折り返す
+const MAX_LINE_OCTETS: usize = 75;
+
+// カレンダーアプリから予定表を購読するための、ユーザーごとのトークン
+#[derive(Debug, Clone)]
+pub struct CalendarFeedToken {
+    pub token: String,
+    pub created_at: DateTime<Local>,
+}
+
+// 予定の状態（RFC 5545 の STATUS）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarEventStatus {
+    Tentative,
+    Confirmed,
+    Cancelled,
+}
+
+impl CalendarEventStatus {
+    // 承認待ちの予約は仮の予定とする
+    // 終わった予約のうち、時間どおりに利用を終えたもの以外（利用終了・キャンセル・解放・却下・期限切れ）は取り消した予定とする
+    pub fn of(status: ReservationStatus, outcome: Option<ReservationOutcome>) -> Self {
+        match (status, outcome) {
+            (ReservationStatus::Pending, _) => Self::Tentative,
+            (_, None) => Self::Confirmed,
+            (ReservationStatus::Completed, Some(ReservationOutcome::AutoEnded)) => Self::Confirmed,
+            (_, Some(_)) => Self::Cancelled,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Tentative => "TENTATIVE",
+            Self::Confirmed => "CONFIRMED",
+            Self::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+// 予定表の 1 件の予定（VEVENT）
+// UID は予約 ID から作るため、同じ予約は何度取得しても同じ予定として扱われる
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub reservation_id: ReservationId,
+    pub summary: String,
+    pub location: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub status: CalendarEventStatus,
+    // 予定の通知（VALARM）を出す日時。予約のリマインダーの送信時刻
+    pub alarms: Vec<DateTime<Local>>,
+}
+
+impl CalendarEvent {
+    // 予約を予定にする。取り消した予定には通知を付けない
+    pub fn from_reservation(reservation: &Reservation, summary: String) -> Self {
+        let status = CalendarEventStatus::of(reservation.status, reservation.outcome());
+        let alarms = match status {
+            CalendarEventStatus::Cancelled => Vec::new(),
+            _ => reservation.reminders.iter().map(|r| r.remind_at).collect(),
+        };
+        Self {
+            reservation_id: reservation.reservation_id,
+            summary,
+            location: reservation.space.address.clone(),
+            start: reservation.reservation_start_time,
+            end: reservation.reservation_end_time,
+            status,
+            alarms,
+        }
+    }
+
+    // 確定した予約の通知に添付する予定
+    // 予約がまだない通知（キャンセル待ちの案内）では None
+    pub fn from_notification(n: &ReservationNotification) -> Option<Self> {
+        Some(Self {
+            reservation_id: n.reservation_id?,
+            summary: n.space_name.clone(),
+            location: n.space_address.clone(),
+            start: n.reservation_start_time,
+            end: n.reservation_end_time,
+            status: CalendarEventStatus::Confirmed,
+            alarms: n.reminder_times.clone(),
+        })
+    }
+
+    pub fn uid(&self) -> String {
+        format!("reservation-{}", self.reservation_id)
+    }
+}
+
+// RFC 5545 形式の予定表（VCALENDAR）
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    // カレンダーアプリに表示する予定表の名前
+    pub name: String,
+    pub events: Vec<CalendarEvent>,
+}
+
+impl Calendar {
+    pub fn new(name: impl Into<String>, events: Vec<CalendarEvent>) -> Self {
+        Self {
+            name: name.into(),
+            events,
+        }
+    }
+
+    // iCalendar 形式の文字列にする。日時はすべて UTC で書き出す
+    pub fn to_ics(&self) -> String {
+        let stamp = format_utc(Utc::now());
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{PRODUCT_ID}"),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", escape_text(&self.name)),
+        ];
+        for event in &self.events {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", event.uid()));
+            lines.push(format!("DTSTAMP:{stamp}"));
+            lines.push(format!("DTSTART:{}", format_utc(event.start)));
+            lines.push(format!("DTEND:{}", format_utc(event.end)));
+            lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+            if !event.location.is_empty() {
+                lines.push(format!("LOCATION:{}", escape_text(&event.location)));
+            }
+            lines.push(format!("STATUS:{}", event.status.as_str()));
+            for alarm in &event.alarms {
+                lines.push("BEGIN:VALARM".to_string());
+                lines.push("ACTION:DISPLAY".to_string());
+                lines.push(format!("DESCRIPTION:{}", escape_text(&event.summary)));
+                lines.push(format!("TRIGGER;VALUE=DATE-TIME:{}", format_utc(*alarm)));
+                lines.push("END:VALARM".to_string());
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| fold_line(line)).collect()
+    }
+}
+
+fn format_utc<Tz: chrono::TimeZone>(at: DateTime<Tz>) -> String {
+    at.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// TEXT 型の値で意味を持つ文字をエスケープする
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 75 オクテットを超える行を、文字の途中で切らないように折り返し、CRLF で終える
+// 折り返した続きの行は空白 1 文字で始める
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn event(status: CalendarEventStatus) -> CalendarEvent {
+        let start = Utc.with_ymd_and_hms(2025, 12, 15, 1, 0, 0).unwrap();
+        CalendarEvent {
+            reservation_id: ReservationId::new(),
+            summary: "会議室A, 3F; 窓側".into(),
+            location: "東京都千代田区".into(),
+            start: start.into(),
+            end: (start + Duration::hours(1)).into(),
+            status,
+            alarms: vec![(start - Duration::minutes(30)).into()],
+        }
+    }
+
+    #[test]
+    fn test_event_status() {
+        use ReservationStatus::*;
+        assert_eq!(
+            CalendarEventStatus::of(Pending, None),
+            CalendarEventStatus::Tentative
+        );
+        assert_eq!(
+            CalendarEventStatus::of(Confirmed, None),
+            CalendarEventStatus::Confirmed
+        );
+        assert_eq!(
+            CalendarEventStatus::of(Completed, Some(ReservationOutcome::AutoEnded)),
+            CalendarEventStatus::Confirmed
+        );
+        assert_eq!(
+            CalendarEventStatus::of(Completed, Some(ReservationOutcome::Returned)),
+            CalendarEventStatus::Cancelled
+        );
+        assert_eq!(
+            CalendarEventStatus::of(CancelledByUser, Some(ReservationOutcome::Cancelled)),
+            CalendarEventStatus::Cancelled
+        );
+        assert_eq!(
+            CalendarEventStatus::of(NoShow, Some(ReservationOutcome::AutoEnded)),
+            CalendarEventStatus::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_to_ics() {
+        let event = event(CalendarEventStatus::Confirmed);
+        let ics = Calendar::new("予約", vec![event.clone()]).to_ics();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains(&format!("UID:reservation-{}\r\n", event.reservation_id)));
+        assert!(ics.contains("DTSTART:20251215T010000Z\r\n"));
+        assert!(ics.contains("DTEND:20251215T020000Z\r\n"));
+        assert!(ics.contains("SUMMARY:会議室A\\, 3F\\; 窓側\r\n"));
+        assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+        assert!(ics.contains("TRIGGER;VALUE=DATE-TIME:20251215T003000Z\r\n"));
+        // 改行は CRLF のみ
+        assert!(!ics.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("SUMMARY:{}", "予".repeat(40));
+        let folded = fold_line(&line);
+        for part in folded.trim_end_matches("\r\n").split("\r\n") {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), format!("{line}\r\n"));
+    }
+}
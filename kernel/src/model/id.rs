@@ -55,3 +55,9 @@ define_id!(UserId);
 define_id!(SpaceId);
 define_id!(ReservationId);
 define_id!(ReminderId);
+define_id!(OutboxMessageId);
+define_id!(ReservationSeriesId);
+define_id!(SpaceBlackoutId);
+define_id!(WaitlistEntryId);
+define_id!(WebhookId);
+define_id!(WebhookDeliveryId);
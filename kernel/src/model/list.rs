@@ -10,4 +10,11 @@ impl<T> PaginatedList<T> {
     pub fn into_inner(self) -> Vec<T> {
         self.items
     }
-}
\ No newline at end of file
+}
+// 一覧の並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
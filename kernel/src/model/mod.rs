@@ -4,4 +4,10 @@ pub mod auth;
 pub mod user;
 pub mod role;
 pub mod list;
-pub mod reservation;
\ No newline at end of file
+pub mod reservation;
+pub mod notification;
+pub mod outbox;
+pub mod reminder;pub mod waitlist;
+
+pub mod calendar;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,157 @@
+use crate::model::{
+    id::{ReservationId, SpaceId},
+    reservation::{status::ReservationEndSource, Reservation},
+};
+use chrono::{DateTime, Duration, DurationRound, Local};
+use shared::i18n::Language;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+// ユーザーがタイムゾーンを設定していない場合に使うタイムゾーン
+pub const DEFAULT_TIMEZONE: &str = "Asia/Tokyo";
+
+// 予約に関して利用者へ送る通知の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationKind {
+    // 予約開始前のリマインダー
+    Reminder,
+    // 予約受付の確認
+    Confirmation,
+    // 予約時間の変更
+    Reschedule,
+    // スペース停止などによる予約のキャンセル
+    Cancellation,
+    // 利用者自身による予約終了
+    Return,
+    // キャンセル待ちの時間帯に空きが出たことの案内
+    WaitlistOffer,
+    // キャンセル待ちの時間帯の自動予約
+    WaitlistBooked,
+    // チェックインがなかったことによる予約の解放
+    NoShow,
+    // 承認者への、承認待ちの予約の承認依頼
+    ApprovalRequest,
+    // 予約したユーザーへの、承認待ちとして受け付けたことの通知
+    ApprovalPending,
+    // 承認待ちの予約の承認
+    Approved,
+    // 承認待ちの予約の却下
+    Rejected,
+    // 予約開始時刻までに承認・却下されなかったことによる期限切れ
+    ApprovalExpired,
+}
+
+// 通知の本文を組み立てるために必要な予約情報
+#[derive(Debug, Clone)]
+pub struct ReservationNotification {
+    // キャンセル待ちの案内では、まだ予約がないため None
+    pub reservation_id: Option<ReservationId>,
+    pub space_id: SpaceId,
+    pub space_name: String,
+    pub space_address: String,
+    pub space_equipment: String,
+    pub user_name: String,
+    pub email: String,
+    // リマインダーの送信時刻。リマインダー以外の通知では None
+    pub reminder_at: Option<DateTime<Local>>,
+    // 添付する予定の通知（VALARM）に使う、予約のリマインダーの送信時刻
+    pub reminder_times: Vec<DateTime<Local>>,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    // キャンセル待ちの案内で、予約を確定するためのトークンとその期限
+    pub claim_token: Option<String>,
+    pub claim_expires_at: Option<DateTime<Local>>,
+    // 予約の終了・キャンセルの通知で、終わらせた操作の出どころと理由
+    pub end_source: Option<ReservationEndSource>,
+    pub end_reason: Option<String>,
+    // 本文に使う言語。宛先のユーザーの言語
+    pub language: Language,
+    // 本文の日時を表示するタイムゾーン。宛先のユーザーのタイムゾーン
+    pub timezone: String,
+}
+
+impl ReservationNotification {
+    // テンプレートのプレビューと、起動時のテンプレートの検証に使う見本の通知
+    // 予約開始は翌日の同じ時（分以下は切り捨て）から 1 時間とする
+    pub fn sample(kind: NotificationKind, language: Language, timezone: &str) -> Self {
+        let now = Local::now();
+        let start = now.duration_trunc(Duration::hours(1)).unwrap_or(now) + Duration::days(1);
+        let (space_name, space_address, space_equipment, user_name, reason) = match language {
+            Language::Ja => (
+                "会議室A",
+                "東京都千代田区丸の内1-1-1 3F",
+                "プロジェクター、ホワイトボード",
+                "山田 太郎",
+                "設備点検のため",
+            ),
+            Language::En => (
+                "Meeting Room A",
+                "3F, 1-1-1 Marunouchi, Chiyoda-ku, Tokyo",
+                "Projector, Whiteboard",
+                "Taro Yamada",
+                "Scheduled equipment inspection",
+            ),
+        };
+        let end_source = match kind {
+            NotificationKind::Cancellation | NotificationKind::Rejected => {
+                Some(ReservationEndSource::Admin)
+            }
+            NotificationKind::Return => Some(ReservationEndSource::User),
+            _ => None,
+        };
+        let is_waitlist_offer = kind == NotificationKind::WaitlistOffer;
+        Self {
+            reservation_id: (!is_waitlist_offer).then(ReservationId::new),
+            space_id: SpaceId::new(),
+            space_name: space_name.into(),
+            space_address: space_address.into(),
+            space_equipment: space_equipment.into(),
+            user_name: user_name.into(),
+            email: "user@example.com".into(),
+            reminder_at: (kind == NotificationKind::Reminder).then(|| start - Duration::hours(1)),
+            reminder_times: vec![start - Duration::hours(1)],
+            reservation_start_time: start,
+            reservation_end_time: start + Duration::hours(1),
+            claim_token: is_waitlist_offer.then(|| "sample-claim-token".into()),
+            claim_expires_at: is_waitlist_offer.then(|| now + Duration::minutes(30)),
+            end_source,
+            end_reason: (end_source == Some(ReservationEndSource::Admin)).then(|| reason.into()),
+            language,
+            timezone: timezone.into(),
+        }
+    }
+}
+
+// 通知の件名と本文を組み立てた結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedMail {
+    pub subject: String,
+    pub text: String,
+    // HTML のテンプレートがない通知では None
+    pub html: Option<String>,
+}
+
+impl From<&Reservation> for ReservationNotification {
+    fn from(value: &Reservation) -> Self {
+        Self {
+            reservation_id: Some(value.reservation_id),
+            space_id: value.space.space_id,
+            space_name: value.space.space_name.clone(),
+            space_address: value.space.address.clone(),
+            space_equipment: value.space.equipment.clone(),
+            user_name: value.user_name.clone(),
+            email: value.email.clone(),
+            reminder_at: None,
+            reminder_times: value.reminders.iter().map(|r| r.remind_at).collect(),
+            reservation_start_time: value.reservation_start_time,
+            reservation_end_time: value.reservation_end_time,
+            claim_token: None,
+            claim_expires_at: None,
+            end_source: value.ending.as_ref().map(|e| e.source),
+            end_reason: value.ending.as_ref().and_then(|e| e.reason.clone()),
+            // 予約はユーザーの言語とタイムゾーンを持たないため、既定の値とする
+            language: Language::default(),
+            timezone: DEFAULT_TIMEZONE.into(),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::model::id::OutboxMessageId;
+use chrono::{DateTime, Local};
+
+// 送信に失敗した outbox メッセージを記録する
+// next_attempt_at が None の場合は再試行せず dead とする
+#[derive(Debug)]
+pub struct RecordOutboxFailure {
+    pub outbox_message_id: OutboxMessageId,
+    pub error: String,
+    pub next_attempt_at: Option<DateTime<Local>>,
+}
@@ -0,0 +1,41 @@
+use crate::model::{
+    id::OutboxMessageId,
+    notification::{NotificationKind, ReservationNotification},
+};
+use chrono::{DateTime, Local};
+use strum::{AsRefStr, EnumString};
+
+pub mod event;
+
+// outbox に積まれた通知の送信状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum OutboxStatus {
+    // 未送信（再試行待ちを含む）
+    Pending,
+    // 送信済み
+    Sent,
+    // 再試行の上限に達し、送信を諦めたもの
+    Dead,
+}
+
+#[derive(Debug)]
+pub struct OutboxMessage {
+    pub outbox_message_id: OutboxMessageId,
+    pub kind: NotificationKind,
+    pub status: OutboxStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+    pub sent_at: Option<DateTime<Local>>,
+    pub notification: ReservationNotification,
+}
+
+// ページネーションの範囲と、絞り込む送信状態を指定するための設定値
+#[derive(Debug)]
+pub struct OutboxListOptions {
+    pub status: Option<OutboxStatus>,
+    pub limit: i64,
+    pub offset: i64,
+}
@@ -0,0 +1,25 @@
+use crate::model::id::ReminderId;
+use chrono::{DateTime, Local};
+
+// 予約開始前に送るリマインダー
+// 1 つの予約に対して、送るタイミング（lead_minutes）ごとに 1 件ずつ持つ
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub reminder_id: ReminderId,
+    // 予約開始の何分前に送るか
+    pub lead_minutes: i32,
+    pub remind_at: DateTime<Local>,
+    pub is_sent: bool,
+}
+
+// リマインダーは予約開始の 1 週間前まで設定できる
+pub const MAX_REMINDER_LEAD_MINUTES: i32 = 60 * 24 * 7;
+// 1 つの予約に設定できるリマインダーの数
+pub const MAX_REMINDERS_PER_RESERVATION: usize = 5;
+
+// 同じタイミングのリマインダーを重複して登録しないよう、早い順に並べて重複を取り除く
+pub fn normalize_lead_minutes(mut lead_minutes: Vec<i32>) -> Vec<i32> {
+    lead_minutes.sort_unstable_by(|a, b| b.cmp(a));
+    lead_minutes.dedup();
+    lead_minutes
+}
@@ -1,4 +1,14 @@
-use crate::model::id::{SpaceId, ReservationId, UserId};
+// 予約関連のイベントは項目が多く、derive(new) で生成される関数の引数も多くなる
+#![allow(clippy::too_many_arguments)]
+
+use crate::model::{
+    id::{SpaceId, ReservationId, UserId},
+    notification::NotificationKind,
+    reservation::{
+        series::{Recurrence, SeriesConflictPolicy, SeriesScope},
+        status::{ReservationEndSource, ReservationStatus, ReviewDecision},
+    },
+};
 use chrono::{DateTime, Local};
 use derive_new::new;
 
@@ -9,8 +19,17 @@ pub struct CreateReservation {
     pub reserved_at: DateTime<Local>,
     pub reservation_start_time: DateTime<Local>,
     pub reservation_end_time: DateTime<Local>,
-    pub reminder_at: DateTime<Local>,
-    pub reminder_is_already: bool,
+    // 予約開始の何分前にリマインダーを送るか。空の場合はリマインダーを送らない
+    pub reminder_lead_minutes: Vec<i32>,
+}
+
+#[derive(new)]
+pub struct UpdateReservation {
+    pub reservation_id: ReservationId,
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
 }
 
 #[derive(new)]
@@ -18,9 +37,97 @@ pub struct UpdateReturned {
     pub reservation_id: ReservationId,
     pub space_id: SpaceId,
     pub returned_by: UserId,
-    pub is_cancel: bool,
+    pub source: ReservationEndSource,
+    // 終了・キャンセルの理由
+    pub reason: Option<String>,
+    // 予約を終わらせた後の状態。利用終了またはキャンセルのいずれか
+    pub status: ReservationStatus,
     pub returned_at: DateTime<Local>,
     pub reservation_start_time: DateTime<Local>,
     pub reservation_end_time: DateTime<Local>,
-    pub reminder_at: DateTime<Local>,
-}
\ No newline at end of file
+    // 予約終了と同じトランザクションで outbox に積む通知。None の場合は通知しない
+    pub notification: Option<NotificationKind>,
+}
+
+// スペースを停止し、そのスペースの終わっていない予約をすべてキャンセルする
+#[derive(new)]
+pub struct DeactivateSpace {
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    // キャンセルした予約の状態。停止したユーザーに応じて管理者または所有者によるキャンセルとする
+    pub status: ReservationStatus,
+    // キャンセルの理由
+    pub reason: Option<String>,
+    pub deactivated_at: DateTime<Local>,
+}
+
+// すべてのスペースを停止し、すべての終わっていない予約をキャンセルする
+#[derive(new)]
+pub struct DeactivateAllSpaces {
+    pub requested_by: UserId,
+    // キャンセルした予約の状態
+    pub status: ReservationStatus,
+    // キャンセルの理由
+    pub reason: Option<String>,
+    pub deactivated_at: DateTime<Local>,
+}
+#[derive(new)]
+pub struct CreateReservationSeries {
+    pub space_id: SpaceId,
+    pub reserved_by: UserId,
+    pub reserved_at: DateTime<Local>,
+    // 初回の予約時間帯
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub recurrence: Recurrence,
+    pub conflict_policy: SeriesConflictPolicy,
+    // 各回に設定するリマインダー
+    pub reminder_lead_minutes: Vec<i32>,
+}
+
+// 指定した回の予約時間の変更を、scope の範囲の回にも同じだけずらして適用する
+#[derive(new)]
+pub struct UpdateReservationSeries {
+    pub reservation_id: ReservationId,
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    pub scope: SeriesScope,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+}
+
+#[derive(new)]
+pub struct CancelReservationSeries {
+    pub reservation_id: ReservationId,
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    pub scope: SeriesScope,
+    pub source: ReservationEndSource,
+    // キャンセルの理由
+    pub reason: Option<String>,
+    pub cancelled_at: DateTime<Local>,
+}
+
+#[derive(new)]
+pub struct CheckInReservation {
+    pub reservation_id: ReservationId,
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    // スペースにチェックインのコードが設定されている場合に入力されたコード
+    pub code: Option<String>,
+    pub checked_in_at: DateTime<Local>,
+}
+
+// 承認待ちの予約を承認・却下する
+// 繰り返し予約の場合は、scope の範囲の承認待ちの回にも同じ判断を適用する
+#[derive(new)]
+pub struct ReviewReservation {
+    pub reservation_id: ReservationId,
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    pub scope: SeriesScope,
+    pub decision: ReviewDecision,
+    // 却下の理由。承認の場合は使わない
+    pub reason: Option<String>,
+    pub reviewed_at: DateTime<Local>,
+}
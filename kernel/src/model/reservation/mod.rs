@@ -1,7 +1,16 @@
-use crate::model::id::{SpaceId, ReservationId, UserId};
+use crate::model::{
+    id::{SpaceId, ReservationId, ReservationSeriesId, UserId},
+    list::SortOrder,
+    reminder::Reminder,
+};
 use chrono::{DateTime, Local};
+use strum::{AsRefStr, EnumString};
 
 pub mod event;
+pub mod series;
+pub mod status;
+
+use status::{ReservationEnding, ReservationOutcome, ReservationStatus};
 
 #[derive(Debug)]
 pub struct Reservation {
@@ -9,13 +18,74 @@ pub struct Reservation {
     pub reserved_by: UserId,
     pub user_name:String,
     pub email:String,
-    pub reminder_is_already: bool,
     pub reserved_at: DateTime<Local>,
-    pub reminder_at:DateTime<Local>,
-    pub returned_at: Option<DateTime<Local>>,
+    pub status: ReservationStatus,
     pub reservation_start_time: DateTime<Local>,
     pub reservation_end_time: DateTime<Local>,
     pub space: ReservationSpace,
+    pub reminders: Vec<Reminder>,
+    // 繰り返し予約の 1 回である場合は、その繰り返し予約の ID
+    pub reservation_series_id: Option<ReservationSeriesId>,
+    // 各状態になった日時。その状態を経ていない場合は None
+    pub confirmed_at: Option<DateTime<Local>>,
+    pub checked_in_at: Option<DateTime<Local>>,
+    pub completed_at: Option<DateTime<Local>>,
+    pub cancelled_at: Option<DateTime<Local>>,
+    pub no_show_at: Option<DateTime<Local>>,
+    pub rejected_at: Option<DateTime<Local>>,
+    pub expired_at: Option<DateTime<Local>>,
+    // 承認が必要なスペースの予約で、承認・却下したユーザー
+    pub reviewed_by: Option<UserId>,
+    // 予約を終わらせた操作。終わっていない場合や、記録がない場合は None
+    pub ending: Option<ReservationEnding>,
+}
+
+impl Reservation {
+    // 予約が終わった（利用終了・キャンセル・解放・却下・期限切れのいずれか）日時。終わっていない場合は None
+    pub fn ended_at(&self) -> Option<DateTime<Local>> {
+        self.completed_at
+            .or(self.cancelled_at)
+            .or(self.no_show_at)
+            .or(self.rejected_at)
+            .or(self.expired_at)
+    }
+
+    // 予約履歴に表示する、予約がどのように終わったかの区分。終わっていない場合は None
+    pub fn outcome(&self) -> Option<ReservationOutcome> {
+        ReservationOutcome::of(self.status, self.ending.as_ref().map(|e| e.source))
+    }
+}
+
+// 予約を並べ替えるときの基準
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ReservationSortKey {
+    // 予約開始時刻
+    #[default]
+    StartTime,
+    // 予約終了時刻
+    EndTime,
+    // 予約を受け付けた日時
+    ReservedAt,
+}
+
+// 予約の検索条件と、並び順・ページネーションの範囲を指定するための設定値
+// 終わった予約も含めて検索する
+#[derive(Debug)]
+pub struct ReservationListOptions {
+    pub space_id: Option<SpaceId>,
+    pub user_id: Option<UserId>,
+    // 予約時間が from から to の間に重なる予約に絞り込む。省略した側は制限しない
+    pub from: Option<DateTime<Local>>,
+    pub to: Option<DateTime<Local>>,
+    // いずれかの状態の予約に絞り込む。空の場合はすべての状態
+    pub statuses: Vec<ReservationStatus>,
+    // true の場合はキャンセルされた予約のみ、false の場合はキャンセルされていない予約のみ
+    pub is_cancel: Option<bool>,
+    pub sort: ReservationSortKey,
+    pub order: SortOrder,
+    pub limit: i64,
+    pub offset: i64,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,297 @@
+use crate::model::{
+    id::{ReservationId, ReservationSeriesId, SpaceId, UserId},
+    reservation::Reservation,
+};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Weekday};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+use strum::{AsRefStr, EnumString};
+
+// 1 つの繰り返し予約で作成できる予約の上限
+pub const MAX_OCCURRENCES: usize = 100;
+// 繰り返しの間隔の上限
+pub const MAX_INTERVAL: i32 = 99;
+
+// 繰り返しの単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+// 繰り返しのルール（iCalendar の RRULE の一部に相当する）
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    // 何日・何週・何か月ごとに繰り返すか
+    pub interval: i32,
+    // 毎週の繰り返しで予約する曜日。空の場合は初回の予約と同じ曜日とする
+    pub by_weekday: Vec<Weekday>,
+    // 繰り返す回数。until と両方指定した場合は先に達した方で終わる
+    pub count: Option<i32>,
+    // この日時までに始まる回を予約する
+    pub until: Option<DateTime<Local>>,
+}
+
+impl Recurrence {
+    // 初回の予約時間帯から、各回の予約時間帯を開始時刻の早い順に求める
+    // 毎月の繰り返しで該当する日がない月（31 日など）や、
+    // 夏時間の切り替えで存在しない時刻になる回は飛ばす
+    pub fn occurrences(
+        &self,
+        reservation_start_time: DateTime<Local>,
+        reservation_end_time: DateTime<Local>,
+    ) -> AppResult<Vec<(DateTime<Local>, DateTime<Local>)>> {
+        self.validate(reservation_start_time)?;
+
+        let duration = reservation_end_time - reservation_start_time;
+        let first = reservation_start_time.naive_local();
+        let limit = self
+            .count
+            .map_or(MAX_OCCURRENCES, |count| count as usize);
+
+        let mut occurrences = Vec::new();
+        for period in 0.. {
+            let Some((period_start, dates)) = self.dates_in_period(first.date(), period) else {
+                break;
+            };
+            if self.until.is_some_and(|until| period_start > until.date_naive()) {
+                break;
+            }
+            for date in dates.into_iter().filter(|date| *date >= first.date()) {
+                let Some(start) = Local
+                    .from_local_datetime(&date.and_time(first.time()))
+                    .earliest()
+                else {
+                    continue;
+                };
+                if self.until.is_some_and(|until| start > until) {
+                    break;
+                }
+                if occurrences.len() == limit {
+                    if self.count.is_some() {
+                        return Ok(occurrences);
+                    }
+                    return Err(AppError::UnprocessableEntity(
+                        ErrorMessage::TooManyOccurrences {
+                            max: MAX_OCCURRENCES as i64,
+                        },
+                    ));
+                }
+                occurrences.push((start, start + duration));
+            }
+        }
+        Ok(occurrences)
+    }
+
+    fn validate(&self, reservation_start_time: DateTime<Local>) -> AppResult<()> {
+        if !(1..=MAX_INTERVAL).contains(&self.interval) {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::RecurrenceIntervalOutOfRange {
+                    max: MAX_INTERVAL.into(),
+                },
+            ));
+        }
+        if self.count.is_none() && self.until.is_none() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::RecurrenceEndRequired,
+            ));
+        }
+        if self
+            .count
+            .is_some_and(|count| count < 1 || count as usize > MAX_OCCURRENCES)
+        {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::OccurrenceCountOutOfRange {
+                    max: MAX_OCCURRENCES as i64,
+                },
+            ));
+        }
+        if self.until.is_some_and(|until| until < reservation_start_time) {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::RecurrenceEndBeforeStart,
+            ));
+        }
+        if self.frequency != Frequency::Weekly && !self.by_weekday.is_empty() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::WeekdayRequiresWeekly,
+            ));
+        }
+        Ok(())
+    }
+
+    // period 番目の繰り返しの期間について、期間の初日と予約する日付を返す
+    // 日付が表現できる範囲を超えた場合は None を返す
+    fn dates_in_period(&self, first: NaiveDate, period: i32) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let step = period.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => {
+                let date = first.checked_add_signed(Duration::days(step as i64))?;
+                Some((date, vec![date]))
+            }
+            Frequency::Weekly => {
+                let week_start = first
+                    .checked_sub_signed(Duration::days(
+                        first.weekday().num_days_from_monday() as i64,
+                    ))?
+                    .checked_add_signed(Duration::weeks(step as i64))?;
+                let mut weekdays = if self.by_weekday.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    self.by_weekday.clone()
+                };
+                weekdays.sort_by_key(Weekday::num_days_from_monday);
+                weekdays.dedup();
+                let dates = weekdays
+                    .into_iter()
+                    .filter_map(|weekday| {
+                        week_start.checked_add_signed(Duration::days(
+                            weekday.num_days_from_monday() as i64,
+                        ))
+                    })
+                    .collect();
+                Some((week_start, dates))
+            }
+            Frequency::Monthly => {
+                let months = first.month0() as i32 + step;
+                let year = first.year().checked_add(months.div_euclid(12))?;
+                let month = months.rem_euclid(12) as u32 + 1;
+                let month_start = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let dates = NaiveDate::from_ymd_opt(year, month, first.day())
+                    .into_iter()
+                    .collect();
+                Some((month_start, dates))
+            }
+        }
+    }
+}
+
+// 繰り返しの各回が既存の予約と重なった場合の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeriesConflictPolicy {
+    // 1 回でも重なった場合は、繰り返し予約全体を作成しない
+    #[default]
+    AllOrNothing,
+    // 重なった回のみ飛ばして作成する
+    SkipConflicts,
+}
+
+// 繰り返し予約の変更・キャンセルの対象範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeriesScope {
+    // 指定した回のみ
+    #[default]
+    This,
+    // 指定した回と、それ以降のまだ始まっていない回
+    ThisAndFollowing,
+    // まだ始まっていないすべての回
+    All,
+}
+
+#[derive(Debug)]
+pub struct ReservationSeries {
+    pub reservation_series_id: ReservationSeriesId,
+    pub space_id: SpaceId,
+    pub reserved_by: UserId,
+    pub recurrence: Recurrence,
+    // 初回の予約時間帯
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+    // 予約中の回
+    pub reservations: Vec<Reservation>,
+}
+
+// 繰り返し予約の作成結果
+#[derive(Debug)]
+pub struct CreatedReservationSeries {
+    pub reservation_series_id: ReservationSeriesId,
+    pub reservation_ids: Vec<ReservationId>,
+    // SkipConflicts で飛ばした回
+    pub skipped: Vec<SkippedOccurrence>,
+}
+
+#[derive(Debug)]
+pub struct SkippedOccurrence {
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub conflicting_reservation_id: ReservationId,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_weekly_occurrences_by_weekday() {
+        // 2030-01-02 は水曜日
+        let recurrence = Recurrence {
+            frequency: Frequency::Weekly,
+            interval: 2,
+            by_weekday: vec![Weekday::Mon, Weekday::Wed],
+            count: Some(4),
+            until: None,
+        };
+        let starts = recurrence
+            .occurrences(at(2030, 1, 2, 10), at(2030, 1, 2, 11))
+            .unwrap()
+            .into_iter()
+            .map(|(start, _)| start)
+            .collect::<Vec<_>>();
+        // 初回より前の月曜日は含めず、隔週の月曜日と水曜日を予約する
+        assert_eq!(
+            starts,
+            vec![
+                at(2030, 1, 2, 10),
+                at(2030, 1, 14, 10),
+                at(2030, 1, 16, 10),
+                at(2030, 1, 28, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monthly_occurrences_skip_missing_days_until() {
+        let recurrence = Recurrence {
+            frequency: Frequency::Monthly,
+            interval: 1,
+            by_weekday: vec![],
+            count: None,
+            until: Some(at(2030, 5, 31, 10)),
+        };
+        let occurrences = recurrence
+            .occurrences(at(2030, 1, 31, 10), at(2030, 1, 31, 12))
+            .unwrap();
+        assert_eq!(
+            occurrences,
+            vec![
+                (at(2030, 1, 31, 10), at(2030, 1, 31, 12)),
+                (at(2030, 3, 31, 10), at(2030, 3, 31, 12)),
+                (at(2030, 5, 31, 10), at(2030, 5, 31, 12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_occurrences_over_limit_is_rejected() {
+        let recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_weekday: vec![],
+            count: None,
+            until: Some(at(2031, 1, 1, 0)),
+        };
+        assert!(matches!(
+            recurrence.occurrences(at(2030, 1, 1, 10), at(2030, 1, 1, 11)),
+            Err(AppError::UnprocessableEntity(_))
+        ));
+    }
+}
@@ -0,0 +1,350 @@
+use crate::{authorization::Actor, model::id::UserId};
+use chrono::{DateTime, Local};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+use strum::{AsRefStr, EnumString};
+
+// 予約の状態
+// pending → confirmed → checked_in → completed の順に進み、
+// 終わっていない状態からは、キャンセルまたはチェックインしなかったことによる解放で終わる
+// 承認待ちの予約は、却下または期限切れでも終わる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ReservationStatus {
+    // 承認待ち
+    Pending,
+    // 予約確定
+    Confirmed,
+    // チェックイン済み
+    CheckedIn,
+    // 利用終了
+    Completed,
+    // 予約したユーザーによるキャンセル
+    CancelledByUser,
+    // スペースの停止など、管理者によるキャンセル
+    CancelledByAdmin,
+    // スペースの所有者がスペースを停止したことによるキャンセル
+    CancelledByOwner,
+    // 誰がキャンセルしたかの記録がないキャンセル。状態を移す前の予約のみ
+    Cancelled,
+    // チェックインがないまま猶予時間を過ぎたため解放
+    NoShow,
+    // 承認者による却下
+    Rejected,
+    // 予約開始時刻までに承認・却下されなかったため期限切れ
+    Expired,
+}
+
+impl ReservationStatus {
+    // スペースの時間帯を押さえている、まだ終わっていない状態
+    pub const ACTIVE: [Self; 3] = [Self::Pending, Self::Confirmed, Self::CheckedIn];
+
+    // 終わった状態。予約履歴に表示する
+    pub const ENDED: [Self; 8] = [
+        Self::Completed,
+        Self::CancelledByUser,
+        Self::CancelledByAdmin,
+        Self::CancelledByOwner,
+        Self::Cancelled,
+        Self::NoShow,
+        Self::Rejected,
+        Self::Expired,
+    ];
+
+    pub fn is_active(self) -> bool {
+        Self::ACTIVE.contains(&self)
+    }
+
+    pub fn is_cancelled(self) -> bool {
+        matches!(
+            self,
+            Self::CancelledByUser
+                | Self::CancelledByAdmin
+                | Self::CancelledByOwner
+                | Self::Cancelled
+        )
+    }
+
+    // スペースの停止により予約をキャンセルした場合の状態
+    // 管理者が停止した場合は管理者による、それ以外（所有者）が停止した場合は所有者によるキャンセルとする
+    pub fn cancelled_by_deactivation(actor: Actor) -> Self {
+        if actor.is_admin() {
+            Self::CancelledByAdmin
+        } else {
+            Self::CancelledByOwner
+        }
+    }
+
+    // 利用者が current の状態の予約を終了した場合の状態
+    // 予約開始前であればキャンセル、開始後であれば利用終了とする
+    pub fn ended_by_user(
+        current: Self,
+        reservation_start_time: DateTime<Local>,
+        ended_at: DateTime<Local>,
+    ) -> Self {
+        Self::ended_by(
+            current,
+            ReservationEndSource::User,
+            reservation_start_time,
+            ended_at,
+        )
+    }
+
+    // source による操作で current の状態の予約を終了した場合の状態
+    // 予約開始前であれば source に応じたキャンセル、開始後であれば利用終了とする
+    // 承認待ちのまま確定していない予約は利用されていないため、開始後でもキャンセルとする
+    pub fn ended_by(
+        current: Self,
+        source: ReservationEndSource,
+        reservation_start_time: DateTime<Local>,
+        ended_at: DateTime<Local>,
+    ) -> Self {
+        if current == Self::Pending || ended_at < reservation_start_time {
+            source.cancelled_status()
+        } else {
+            Self::Completed
+        }
+    }
+
+    pub fn can_transition_to(self, next: Self) -> bool {
+        use ReservationStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Confirmed)
+                | (Confirmed, CheckedIn)
+                | (Confirmed | CheckedIn, Completed)
+                | (Confirmed, NoShow)
+                | (Pending, Rejected | Expired)
+                | (
+                    Pending | Confirmed | CheckedIn,
+                    CancelledByUser | CancelledByAdmin | CancelledByOwner
+                )
+        )
+    }
+
+    // 状態を変えられない場合は 422 を返す
+    pub fn transition_to(self, next: Self) -> AppResult<Self> {
+        if !self.can_transition_to(next) {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidStatusTransition {
+                    from: self.as_ref().into(),
+                    to: next.as_ref().into(),
+                },
+            ));
+        }
+        Ok(next)
+    }
+}
+
+// 予約履歴に表示する、予約がどのように終わったかの区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationOutcome {
+    // 利用者または管理者が利用終了にした
+    Returned,
+    // 予約開始前にキャンセル・却下された
+    Cancelled,
+    // 終了時刻の経過・チェックインなし・承認の期限切れにより、自動で終わった
+    AutoEnded,
+}
+
+impl ReservationOutcome {
+    // 終わった予約の区分。終わっていない場合は None
+    // 利用終了は、バックグラウンドの処理で終わった場合のみ自動で終わったものとする
+    pub fn of(status: ReservationStatus, source: Option<ReservationEndSource>) -> Option<Self> {
+        use ReservationStatus::*;
+        match status {
+            Pending | Confirmed | CheckedIn => None,
+            Completed if source == Some(ReservationEndSource::Watcher) => Some(Self::AutoEnded),
+            Completed => Some(Self::Returned),
+            CancelledByUser | CancelledByAdmin | CancelledByOwner | Cancelled | Rejected => {
+                Some(Self::Cancelled)
+            }
+            NoShow | Expired => Some(Self::AutoEnded),
+        }
+    }
+}
+
+// 承認待ちの予約に対する承認者の判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approve,
+    Reject,
+}
+
+impl ReviewDecision {
+    // 判断した後の予約の状態
+    pub fn next_status(self) -> ReservationStatus {
+        match self {
+            Self::Approve => ReservationStatus::Confirmed,
+            Self::Reject => ReservationStatus::Rejected,
+        }
+    }
+}
+
+// 予約の終了・キャンセルの理由として入力できる文字数の上限
+pub const MAX_END_REASON_LENGTH: usize = 500;
+
+// 予約を終わらせた操作の出どころ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ReservationEndSource {
+    // 予約したユーザー本人
+    User,
+    // 予約したユーザー以外（管理者）
+    Admin,
+    // スペースの停止
+    SpaceDeactivation,
+    // バックグラウンドの処理（利用終了・チェックインしなかった予約の解放など）
+    Watcher,
+}
+
+impl ReservationEndSource {
+    // 操作したユーザーが予約したユーザー本人であれば利用者、そうでなければ管理者による操作とする
+    pub fn acted_by(reserved_by: UserId, acting_user: UserId) -> Self {
+        if reserved_by == acting_user {
+            Self::User
+        } else {
+            Self::Admin
+        }
+    }
+
+    // この操作で予約をキャンセルした場合の状態
+    pub fn cancelled_status(self) -> ReservationStatus {
+        match self {
+            Self::User => ReservationStatus::CancelledByUser,
+            _ => ReservationStatus::CancelledByAdmin,
+        }
+    }
+}
+
+// 予約を終わらせた操作の記録
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReservationEnding {
+    // 操作したユーザー。バックグラウンドの処理で終わった場合は None
+    pub ended_by: Option<UserId>,
+    pub source: ReservationEndSource,
+    // 操作したユーザーが入力した理由
+    pub reason: Option<String>,
+}
+
+impl ReservationEnding {
+    // バックグラウンドの処理で終わった場合の記録
+    pub fn watcher() -> Self {
+        Self {
+            ended_by: None,
+            source: ReservationEndSource::Watcher,
+            reason: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_status_transitions() {
+        use ReservationStatus::*;
+        assert!(Pending.can_transition_to(Confirmed));
+        assert!(Confirmed.can_transition_to(CheckedIn));
+        assert!(CheckedIn.can_transition_to(Completed));
+        assert!(Confirmed.can_transition_to(NoShow));
+        assert!(!CheckedIn.can_transition_to(NoShow));
+        assert!(!Pending.can_transition_to(CheckedIn));
+        assert!(Pending.can_transition_to(Rejected));
+        assert!(Pending.can_transition_to(Expired));
+        assert!(!Confirmed.can_transition_to(Rejected));
+        // 誰がキャンセルしたかの記録がないキャンセルには、新しく変えることはできない
+        assert!(Cancelled.is_cancelled());
+        assert!(!Confirmed.can_transition_to(Cancelled));
+        for ended in ReservationStatus::ENDED {
+            assert!(!ended.is_active());
+            assert!(ended.transition_to(CancelledByUser).is_err());
+        }
+
+        let start = Local.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        assert_eq!(
+            ReservationStatus::ended_by_user(Confirmed, start, start - Duration::minutes(1)),
+            CancelledByUser
+        );
+        assert_eq!(
+            ReservationStatus::ended_by_user(Confirmed, start, start),
+            Completed
+        );
+        assert_eq!(
+            ReservationStatus::ended_by_user(CheckedIn, start, start + Duration::minutes(30)),
+            Completed
+        );
+        // 承認されないまま開始時刻を過ぎた予約は、利用終了ではなくキャンセルとする
+        let ended = ReservationStatus::ended_by_user(Pending, start, start + Duration::minutes(1));
+        assert_eq!(ended, CancelledByUser);
+        assert!(Pending.can_transition_to(ended));
+        assert_eq!(
+            ReservationStatus::ended_by(
+                Pending,
+                ReservationEndSource::Admin,
+                start,
+                start + Duration::minutes(1)
+            ),
+            CancelledByAdmin
+        );
+        assert_eq!(
+            ReservationStatus::ended_by(
+                Confirmed,
+                ReservationEndSource::Admin,
+                start,
+                start - Duration::minutes(1)
+            ),
+            CancelledByAdmin
+        );
+    }
+
+    #[test]
+    fn test_reservation_outcome() {
+        use ReservationStatus::*;
+        for active in ReservationStatus::ACTIVE {
+            assert_eq!(ReservationOutcome::of(active, None), None);
+        }
+        for ended in ReservationStatus::ENDED {
+            assert!(ReservationOutcome::of(ended, None).is_some());
+        }
+        assert_eq!(
+            ReservationOutcome::of(Completed, Some(ReservationEndSource::User)),
+            Some(ReservationOutcome::Returned)
+        );
+        assert_eq!(
+            ReservationOutcome::of(Completed, Some(ReservationEndSource::Watcher)),
+            Some(ReservationOutcome::AutoEnded)
+        );
+        assert_eq!(
+            ReservationOutcome::of(Rejected, Some(ReservationEndSource::Admin)),
+            Some(ReservationOutcome::Cancelled)
+        );
+        assert_eq!(
+            ReservationOutcome::of(NoShow, Some(ReservationEndSource::Watcher)),
+            Some(ReservationOutcome::AutoEnded)
+        );
+    }
+
+    #[test]
+    fn test_cancelled_by_deactivation() {
+        use crate::model::role::Role;
+        let actor = |role| Actor {
+            user_id: UserId::new(),
+            role,
+        };
+        assert_eq!(
+            ReservationStatus::cancelled_by_deactivation(actor(Role::Admin)),
+            ReservationStatus::CancelledByAdmin
+        );
+        assert_eq!(
+            ReservationStatus::cancelled_by_deactivation(actor(Role::User)),
+            ReservationStatus::CancelledByOwner
+        );
+        assert!(ReservationStatus::CancelledByOwner.is_cancelled());
+        assert!(ReservationStatus::Confirmed.can_transition_to(ReservationStatus::CancelledByOwner));
+    }
+}
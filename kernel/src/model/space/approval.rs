@@ -0,0 +1,75 @@
+use crate::model::id::UserId;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+// 1 つのスペースに設定できる承認者の数の上限
+pub const MAX_APPROVERS_PER_SPACE: usize = 20;
+
+// スペースの予約の承認の設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApprovalPolicy {
+    // true の場合、予約は承認待ちとして作成され、承認者が承認すると確定する
+    pub requires_approval: bool,
+    // 予約を承認・却下できるユーザー
+    // 承認を不要に戻しても、残っている承認待ちの予約を扱えるよう、設定はそのまま残す
+    pub approvers: Vec<UserId>,
+}
+
+impl ApprovalPolicy {
+    // 設定値として正しいかを確認する
+    pub fn validate(&self) -> AppResult<()> {
+        if self.requires_approval && self.approvers.is_empty() {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::ApproversRequired,
+            ));
+        }
+        if self.approvers.len() > MAX_APPROVERS_PER_SPACE {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::TooManyApprovers {
+                    max: MAX_APPROVERS_PER_SPACE as i64,
+                },
+            ));
+        }
+        for (i, approver) in self.approvers.iter().enumerate() {
+            if self.approvers[..i].contains(approver) {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::DuplicateApprover {
+                        approver: approver.to_string(),
+                    },
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_approval_policy() {
+        let approver = UserId::new();
+        assert!(ApprovalPolicy::default().validate().is_ok());
+        assert!(ApprovalPolicy {
+            requires_approval: true,
+            approvers: vec![approver],
+        }
+        .validate()
+        .is_ok());
+        assert!(ApprovalPolicy {
+            requires_approval: true,
+            approvers: vec![],
+        }
+        .validate()
+        .is_err());
+        assert!(ApprovalPolicy {
+            requires_approval: false,
+            approvers: vec![approver, approver],
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::model::id::SpaceId;
+use chrono::{DateTime, Duration, Local};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+// 一度に検索できる期間の上限（日）
+pub const MAX_AVAILABILITY_WINDOW_DAYS: i64 = 31;
+// 空き時間を区切る単位の下限と上限（分）
+pub const MIN_SLOT_MINUTES: i64 = 5;
+pub const MAX_SLOT_MINUTES: i64 = 60 * 24;
+
+// 空き状況を調べる期間。from を含み to を含まない
+#[derive(Debug, Clone, Copy)]
+pub struct AvailabilityWindow {
+    pub from: DateTime<Local>,
+    pub to: DateTime<Local>,
+}
+
+impl AvailabilityWindow {
+    pub fn new(from: DateTime<Local>, to: DateTime<Local>) -> AppResult<Self> {
+        if from >= to {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidSearchPeriod,
+            ));
+        }
+        if to - from > Duration::days(MAX_AVAILABILITY_WINDOW_DAYS) {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::SearchPeriodTooLong {
+                    max_days: MAX_AVAILABILITY_WINDOW_DAYS,
+                },
+            ));
+        }
+        Ok(Self { from, to })
+    }
+}
+
+// スペースの空き時間の検索条件
+#[derive(Debug, Clone, Copy)]
+pub struct AvailabilityOptions {
+    pub window: AvailabilityWindow,
+    // 空き時間を区切る単位。from から slot ごとに区切った枠のうち、空いている枠を返す
+    pub slot: Duration,
+}
+
+impl AvailabilityOptions {
+    pub fn new(window: AvailabilityWindow, slot: Duration) -> AppResult<Self> {
+        if slot < Duration::minutes(MIN_SLOT_MINUTES) || slot > Duration::minutes(MAX_SLOT_MINUTES)
+        {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::SlotMinutesOutOfRange {
+                    min: MIN_SLOT_MINUTES,
+                    max: MAX_SLOT_MINUTES,
+                },
+            ));
+        }
+        Ok(Self { window, slot })
+    }
+
+    // 予約済みの時間帯（開始時刻の早い順に並べたもの。互いに重なっていてもよい）から空き時間を求める
+    // 開始時刻が now 以前の枠は予約できないため空きとしない
+    // opening が指定された場合は、そのいずれかの時間帯に収まる枠のみを空きとする
+    // 連続する空き枠は 1 つの時間帯にまとめる
+    pub fn free_intervals(
+        &self,
+        now: DateTime<Local>,
+        reserved: &[(DateTime<Local>, DateTime<Local>)],
+        opening: Option<&[(DateTime<Local>, DateTime<Local>)]>,
+    ) -> Vec<FreeInterval> {
+        let AvailabilityWindow { from, to } = self.window;
+        let mut free_intervals: Vec<FreeInterval> = Vec::new();
+        let mut reserved = reserved.iter().peekable();
+        let mut slot_start = from;
+        while slot_start + self.slot <= to {
+            let slot_end = slot_start + self.slot;
+            // この枠より前に終わる予約は、以降の枠とも重ならない
+            while reserved.next_if(|(_, end)| *end <= slot_start).is_some() {}
+            let is_reserved = reserved.peek().is_some_and(|(start, _)| *start < slot_end);
+            let is_open = opening.map_or(true, |opening| {
+                opening
+                    .iter()
+                    .any(|(open, close)| *open <= slot_start && slot_end <= *close)
+            });
+
+            if slot_start > now && !is_reserved && is_open {
+                match free_intervals.last_mut() {
+                    Some(last) if last.end == slot_start => last.end = slot_end,
+                    _ => free_intervals.push(FreeInterval {
+                        start: slot_start,
+                        end: slot_end,
+                    }),
+                }
+            }
+            slot_start = slot_end;
+        }
+        free_intervals
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeInterval {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+}
+
+#[derive(Debug)]
+pub struct SpaceAvailability {
+    pub space_id: SpaceId,
+    pub window: AvailabilityWindow,
+    pub slot: Duration,
+    pub free_intervals: Vec<FreeInterval>,
+}
+
+// 指定の期間に予約が入っていないスペースの検索条件
+#[derive(Debug)]
+pub struct AvailableSpaceOptions {
+    pub window: AvailabilityWindow,
+    // 収容人数がこの値以上のスペースに絞り込む
+    pub capacity: Option<i32>,
+    // 設備にこれらの文字列をすべて含むスペースに絞り込む
+    pub equipment: Vec<String>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2030, 1, 1, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_free_intervals_merge_slots_around_reservations() {
+        let options = AvailabilityOptions::new(
+            AvailabilityWindow::new(at(9, 0), at(12, 0)).unwrap(),
+            Duration::minutes(30),
+        )
+        .unwrap();
+        // 9:30 より前の枠は過去として扱う
+        let free = options.free_intervals(
+            at(9, 15),
+            &[(at(10, 0), at(10, 45)), (at(11, 30), at(12, 30))],
+            None,
+        );
+        assert_eq!(
+            free,
+            vec![
+                FreeInterval {
+                    start: at(9, 30),
+                    end: at(10, 0),
+                },
+                FreeInterval {
+                    start: at(11, 0),
+                    end: at(11, 30),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_free_intervals_within_opening_hours() {
+        let options = AvailabilityOptions::new(
+            AvailabilityWindow::new(at(8, 0), at(14, 0)).unwrap(),
+            Duration::hours(1),
+        )
+        .unwrap();
+        // 重なり合う予約済みの時間帯と、昼休みを挟む営業時間
+        let free = options.free_intervals(
+            at(0, 0),
+            &[(at(9, 0), at(9, 30)), (at(9, 15), at(10, 0))],
+            Some(&[(at(9, 0), at(12, 0)), (at(13, 0), at(18, 0))]),
+        );
+        assert_eq!(
+            free,
+            vec![
+                FreeInterval {
+                    start: at(10, 0),
+                    end: at(12, 0),
+                },
+                FreeInterval {
+                    start: at(13, 0),
+                    end: at(14, 0),
+                },
+            ]
+        );
+    }
+}
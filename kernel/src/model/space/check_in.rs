@@ -0,0 +1,126 @@
+use chrono::{DateTime, Duration, Local};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+// 予約開始の何分前からチェックインできるか
+pub const CHECK_IN_OPENS_BEFORE_MINUTES: i64 = 15;
+// チェックインの猶予時間の上限（分）
+pub const MAX_CHECK_IN_GRACE_MINUTES: i32 = 60 * 4;
+// チェックインのコードの長さ
+pub const MIN_CHECK_IN_CODE_LENGTH: usize = 4;
+pub const MAX_CHECK_IN_CODE_LENGTH: usize = 32;
+// 予約のルールで、チェックインしなかった回数を数える期間（日）
+pub const NO_SHOW_LOOKBACK_DAYS: i64 = 30;
+
+// スペースのチェックインの設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckInPolicy {
+    // 予約開始からチェックインを待つ時間（分）
+    // None の場合はチェックインを必須とせず、予約を解放しない
+    pub grace_minutes: Option<i32>,
+    // チェックイン時に入力してもらうコード。None の場合はコードなしでチェックインできる
+    pub code: Option<String>,
+}
+
+impl CheckInPolicy {
+    // 設定値として正しいかを確認する
+    pub fn validate(&self) -> AppResult<()> {
+        if self
+            .grace_minutes
+            .is_some_and(|minutes| !(1..=MAX_CHECK_IN_GRACE_MINUTES).contains(&minutes))
+        {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::CheckInGraceOutOfRange {
+                    max: MAX_CHECK_IN_GRACE_MINUTES.into(),
+                },
+            ));
+        }
+        if self.code.as_ref().is_some_and(|code| {
+            !(MIN_CHECK_IN_CODE_LENGTH..=MAX_CHECK_IN_CODE_LENGTH).contains(&code.len())
+                || !code.chars().all(|c| c.is_ascii_alphanumeric())
+        }) {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::CheckInCodeLength {
+                    min: MIN_CHECK_IN_CODE_LENGTH as i64,
+                    max: MAX_CHECK_IN_CODE_LENGTH as i64,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    // 猶予時間を過ぎてもチェックインがない場合に予約を解放する時刻
+    pub fn deadline(&self, reservation_start_time: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.grace_minutes
+            .map(|minutes| reservation_start_time + Duration::minutes(minutes as i64))
+    }
+
+    // チェックインできるかを確認する
+    // - 予約開始の CHECK_IN_OPENS_BEFORE_MINUTES 分前から、予約終了（猶予時間がある場合はその期限）まで
+    // - コードが設定されている場合は、コードが一致する
+    pub fn verify(
+        &self,
+        now: DateTime<Local>,
+        reservation_start_time: DateTime<Local>,
+        reservation_end_time: DateTime<Local>,
+        code: Option<&str>,
+    ) -> AppResult<()> {
+        let opens_at = reservation_start_time - Duration::minutes(CHECK_IN_OPENS_BEFORE_MINUTES);
+        if now < opens_at {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::CheckInNotOpenYet { opens_at },
+            ));
+        }
+        let closes_at = self
+            .deadline(reservation_start_time)
+            .map_or(reservation_end_time, |deadline| {
+                deadline.min(reservation_end_time)
+            });
+        if now >= closes_at {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::CheckInClosed { closes_at },
+            ));
+        }
+        if let Some(expected) = &self.code {
+            if code != Some(expected.as_str()) {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::InvalidCheckInCode,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_verify_check_in() {
+        let start = Local.with_ymd_and_hms(2030, 1, 1, 10, 0, 0).unwrap();
+        let end = start + Duration::hours(1);
+        let policy = CheckInPolicy {
+            grace_minutes: Some(10),
+            code: Some("ROOM1".into()),
+        };
+        let verify = |minutes: i64, code| {
+            policy.verify(start + Duration::minutes(minutes), start, end, code)
+        };
+
+        assert!(verify(-15, Some("ROOM1")).is_ok());
+        assert!(verify(9, Some("ROOM1")).is_ok());
+        assert!(verify(-16, Some("ROOM1")).is_err());
+        assert!(verify(10, Some("ROOM1")).is_err());
+        assert!(verify(0, Some("ROOM2")).is_err());
+        assert!(verify(0, None).is_err());
+
+        // 猶予時間がない場合は予約終了までチェックインできる
+        let policy = CheckInPolicy::default();
+        assert!(policy.verify(end - Duration::minutes(1), start, end, None).is_ok());
+        assert!(policy.verify(end, start, end, None).is_err());
+    }
+}
@@ -1,4 +1,11 @@
-use crate::model::id::{SpaceId, UserId};
+use crate::model::{
+    id::{SpaceBlackoutId, SpaceId, UserId},
+    space::{
+        approval::ApprovalPolicy, check_in::CheckInPolicy, policy::SpaceBookingPolicy,
+        schedule::OpeningHours,
+    },
+};
+use chrono::{DateTime, Local};
 
 
 pub struct CreateSpace {
@@ -26,4 +33,53 @@ pub struct UpdateSpace {
 pub struct DeleteSpace {
     pub space_id: SpaceId,
     pub requested_user: UserId,
-}
\ No newline at end of file
+}
+// 営業時間を、タイムゾーンとあわせて丸ごと置き換える
+#[derive(Debug)]
+pub struct UpdateOpeningHours {
+    pub space_id: SpaceId,
+    pub timezone: String,
+    // 空の場合は終日予約できる
+    pub opening_hours: Vec<OpeningHours>,
+    pub requested_user: UserId,
+}
+
+#[derive(Debug)]
+pub struct CreateSpaceBlackout {
+    pub space_id: SpaceId,
+    pub start_time: DateTime<Local>,
+    pub end_time: DateTime<Local>,
+    pub reason: String,
+    pub requested_user: UserId,
+}
+
+#[derive(Debug)]
+pub struct DeleteSpaceBlackout {
+    pub space_id: SpaceId,
+    pub space_blackout_id: SpaceBlackoutId,
+    pub requested_user: UserId,
+}
+
+// 予約のルールを、ロールごとのルールとあわせて丸ごと置き換える
+#[derive(Debug)]
+pub struct UpdateBookingPolicy {
+    pub space_id: SpaceId,
+    pub booking_policy: SpaceBookingPolicy,
+    pub requested_user: UserId,
+}
+
+// チェックインの設定を置き換える
+#[derive(Debug)]
+pub struct UpdateCheckInPolicy {
+    pub space_id: SpaceId,
+    pub check_in_policy: CheckInPolicy,
+    pub requested_user: UserId,
+}
+
+// 予約の承認の設定を、承認者の一覧とあわせて丸ごと置き換える
+#[derive(Debug)]
+pub struct UpdateApprovalPolicy {
+    pub space_id: SpaceId,
+    pub approval_policy: ApprovalPolicy,
+    pub requested_user: UserId,
+}
@@ -1,6 +1,15 @@
+pub mod approval;
+pub mod availability;
+pub mod check_in;
 pub mod event;
+pub mod policy;
+pub mod schedule;
 use super::{id::{SpaceId,ReservationId}, user::{SpaceOwner,ReservationUser}};
 use chrono::{DateTime,Local};
+use approval::ApprovalPolicy;
+use check_in::CheckInPolicy;
+use policy::SpaceBookingPolicy;
+use schedule::OpeningHours;
 
 #[derive(Debug)]
 pub struct Space {
@@ -13,6 +22,16 @@ pub struct Space {
     pub address: String,
     pub owner: SpaceOwner,
     pub reservation: Option<Reservation>,
+    // 営業時間を解釈するタイムゾーン（IANA のタイムゾーン名）
+    pub timezone: String,
+    // 曜日ごとの営業時間。空の場合は終日予約できる
+    pub opening_hours: Vec<OpeningHours>,
+    // 予約の長さや件数などのルール
+    pub booking_policy: SpaceBookingPolicy,
+    // チェックインの猶予時間とコード
+    pub check_in_policy: CheckInPolicy,
+    // 予約に承認が必要かと、承認者
+    pub approval_policy: ApprovalPolicy,
 }
 
 // ページネーションの範囲を指定するための設定値を格納する型
@@ -0,0 +1,241 @@
+use crate::model::{
+    role::Role,
+    space::{check_in::NO_SHOW_LOOKBACK_DAYS, schedule::MINUTES_PER_DAY},
+};
+use chrono::{DateTime, Duration, Local};
+use shared::{
+    error::{AppError, AppResult, ErrorCode},
+    i18n::{BookingRule, ErrorMessage},
+};
+
+// スペースの予約に関するルール
+// 値が None の項目は制限しない
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookingPolicy {
+    // 1 回の予約の長さの下限と上限（分）
+    pub min_duration_minutes: Option<i32>,
+    pub max_duration_minutes: Option<i32>,
+    // 予約の開始時刻と長さの単位（分）。開始時刻はスペースのタイムゾーンでの 0 時からこの単位で区切る
+    pub slot_granularity_minutes: Option<i32>,
+    // 何日先まで予約できるか。予約の終了時刻がこの範囲に収まっている必要がある
+    pub max_days_in_advance: Option<i32>,
+    // 1 人のユーザーがこのスペースに同時に持てる、まだ終わっていない予約の数
+    pub max_active_reservations: Option<i32>,
+    // 1 人のユーザーがこのスペースに 1 週間（月曜日始まり）に入れられる予約の数
+    pub max_reservations_per_week: Option<i32>,
+    // 直近 NO_SHOW_LOOKBACK_DAYS 日間にチェックインしなかった回数がこの値に達したユーザーは予約できない
+    // チェックインしなかった回数は、すべてのスペースの予約について数える
+    pub max_recent_no_shows: Option<i32>,
+}
+
+// 予約がルールに合っているかを確認するために必要な値
+#[derive(Debug)]
+pub struct BookingRequest {
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub now: DateTime<Local>,
+    // 予約開始時刻の、スペースのタイムゾーンでの 0 時からの経過秒
+    pub start_seconds_of_day: i64,
+    // この予約を除いた、ユーザーのまだ終わっていない予約の数
+    pub active_reservations: i64,
+    // この予約を除いた、予約開始日と同じ週のユーザーの予約の数
+    pub reservations_in_week: i64,
+    // 直近 NO_SHOW_LOOKBACK_DAYS 日間に、ユーザーがチェックインしなかった予約の数
+    pub recent_no_shows: i64,
+}
+
+impl BookingPolicy {
+    // ルールの設定値として正しいかを確認する
+    pub fn validate(&self) -> AppResult<()> {
+        let values = [
+            (BookingRule::MinDuration, self.min_duration_minutes),
+            (BookingRule::MaxDuration, self.max_duration_minutes),
+            (BookingRule::SlotGranularity, self.slot_granularity_minutes),
+            (BookingRule::MaxDaysInAdvance, self.max_days_in_advance),
+            (BookingRule::MaxActiveReservations, self.max_active_reservations),
+            (BookingRule::MaxReservationsPerWeek, self.max_reservations_per_week),
+            (BookingRule::MaxRecentNoShows, self.max_recent_no_shows),
+        ];
+        for (rule, value) in values {
+            if value.is_some_and(|value| value < 1) {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::BookingRuleNotPositive { rule },
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_duration_minutes, self.max_duration_minutes) {
+            if min > max {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::MinDurationExceedsMax,
+                ));
+            }
+        }
+        if self
+            .slot_granularity_minutes
+            .is_some_and(|granularity| MINUTES_PER_DAY % granularity != 0)
+        {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::SlotGranularityNotDivisor,
+            ));
+        }
+        Ok(())
+    }
+
+    // 予約がルールに合っているかを確認し、合っていない場合はルールの名前を含めて 422 を返す
+    pub fn check(&self, request: &BookingRequest) -> AppResult<()> {
+        let duration = request.reservation_end_time - request.reservation_start_time;
+
+        if let Some(min) = self.min_duration_minutes {
+            if duration < Duration::minutes(min as i64) {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::DurationTooShort { min: min.into() },
+                ));
+            }
+        }
+        if let Some(max) = self.max_duration_minutes {
+            if duration > Duration::minutes(max as i64) {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::DurationTooLong { max: max.into() },
+                ));
+            }
+        }
+        if let Some(granularity) = self.slot_granularity_minutes {
+            let granularity_seconds = granularity as i64 * 60;
+            if request.start_seconds_of_day % granularity_seconds != 0
+                || duration.num_seconds() % granularity_seconds != 0
+            {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::SlotGranularityMismatch {
+                        granularity: granularity.into(),
+                    },
+                ));
+            }
+        }
+        if let Some(days) = self.max_days_in_advance {
+            if request.reservation_end_time > request.now + Duration::days(days as i64) {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::TooFarInAdvance { days: days.into() },
+                ));
+            }
+        }
+        if let Some(max) = self.max_active_reservations {
+            if request.active_reservations >= max as i64 {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::TooManyActiveReservations { max: max.into() },
+                ));
+            }
+        }
+        if let Some(max) = self.max_reservations_per_week {
+            if request.reservations_in_week >= max as i64 {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::TooManyReservationsPerWeek { max: max.into() },
+                ));
+            }
+        }
+        if let Some(max) = self.max_recent_no_shows {
+            if request.recent_no_shows >= max as i64 {
+                return Err(AppError::rule_violation(
+                    ErrorCode::BookingPolicyViolation,
+                    ErrorMessage::TooManyRecentNoShows {
+                        days: NO_SHOW_LOOKBACK_DAYS,
+                        max: max.into(),
+                    },
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ロールごとに、スペースのルールの代わりに使うルール
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleBookingPolicy {
+    pub role: Role,
+    pub policy: BookingPolicy,
+}
+
+// スペースに設定された予約のルール
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpaceBookingPolicy {
+    pub policy: BookingPolicy,
+    pub role_overrides: Vec<RoleBookingPolicy>,
+}
+
+impl SpaceBookingPolicy {
+    pub fn validate(&self) -> AppResult<()> {
+        self.policy.validate()?;
+        for (i, role_override) in self.role_overrides.iter().enumerate() {
+            role_override.policy.validate()?;
+            if self.role_overrides[..i]
+                .iter()
+                .any(|other| other.role == role_override.role)
+            {
+                return Err(AppError::UnprocessableEntity(
+                    ErrorMessage::DuplicateRoleRule {
+                        role: role_override.role.as_ref().into(),
+                    },
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_check_booking_policy() {
+        let policy = BookingPolicy {
+            min_duration_minutes: Some(30),
+            max_duration_minutes: Some(240),
+            slot_granularity_minutes: Some(15),
+            max_days_in_advance: Some(14),
+            max_active_reservations: Some(2),
+            max_reservations_per_week: None,
+            max_recent_no_shows: Some(3),
+        };
+        let now = Local.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let request = |start: DateTime<Local>, minutes: i64, active_reservations: i64| {
+            BookingRequest {
+                reservation_start_time: start,
+                reservation_end_time: start + Duration::minutes(minutes),
+                now,
+                start_seconds_of_day: (start - now).num_seconds() + 9 * 3600,
+                active_reservations,
+                reservations_in_week: 0,
+                recent_no_shows: 0,
+            }
+        };
+        let rule = |res: AppResult<()>| match res {
+            Err(AppError::RuleViolation {
+                code: ErrorCode::BookingPolicyViolation,
+                message,
+            }) => message.to_string(),
+            res => panic!("unexpected result: {res:?}"),
+        };
+
+        assert!(policy.check(&request(now + Duration::hours(1), 60, 1)).is_ok());
+        assert!(rule(policy.check(&request(now + Duration::hours(1), 15, 0))).contains("最短予約時間"));
+        assert!(rule(policy.check(&request(now + Duration::hours(1), 300, 0))).contains("最長予約時間"));
+        assert!(rule(policy.check(&request(now + Duration::minutes(70), 60, 0)))
+            .contains("予約時間の単位"));
+        assert!(rule(policy.check(&request(now + Duration::days(14), 60, 0)))
+            .contains("予約可能な日数"));
+        assert!(rule(policy.check(&request(now + Duration::hours(1), 60, 2)))
+            .contains("同時に持てる予約数"));
+        assert!(rule(policy.check(&BookingRequest {
+            recent_no_shows: 3,
+            ..request(now + Duration::hours(1), 60, 0)
+        }))
+        .contains("チェックインしなかった回数"));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::model::id::SpaceBlackoutId;
+use chrono::{DateTime, Local, Weekday};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::ErrorMessage,
+};
+
+// 24:00 を表す、0 時からの経過分
+pub const MINUTES_PER_DAY: i32 = 60 * 24;
+
+// 曜日ごとの営業時間
+// 時刻はスペースのタイムゾーンでの 0 時からの経過分で持つ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpeningHours {
+    pub weekday: Weekday,
+    pub open_minutes: i32,
+    pub close_minutes: i32,
+}
+
+// 営業時間の一覧として正しいかを確認する
+// - 開始が終了より前で、いずれも 0:00 〜 24:00 の範囲にある
+// - 同じ曜日の時間帯どうしが重ならない
+pub fn validate_opening_hours(opening_hours: &[OpeningHours]) -> AppResult<()> {
+    for hours in opening_hours {
+        if !(0 <= hours.open_minutes
+            && hours.open_minutes < hours.close_minutes
+            && hours.close_minutes <= MINUTES_PER_DAY)
+        {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::InvalidOpeningHours {
+                    weekday: hours.weekday,
+                    open: format_minutes(hours.open_minutes),
+                    close: format_minutes(hours.close_minutes),
+                },
+            ));
+        }
+    }
+    let mut sorted = opening_hours.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|h| (h.weekday.num_days_from_monday(), h.open_minutes));
+    for pair in sorted.windows(2) {
+        if pair[0].weekday == pair[1].weekday && pair[1].open_minutes < pair[0].close_minutes {
+            return Err(AppError::UnprocessableEntity(
+                ErrorMessage::OverlappingOpeningHours {
+                    weekday: pair[0].weekday,
+                },
+            ));
+        }
+    }
+    Ok(())
+}
+
+// 0 時からの経過分を HH:MM 形式にする
+pub fn format_minutes(minutes: i32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+// 清掃やメンテナンスなどで予約を受け付けない期間
+#[derive(Debug)]
+pub struct SpaceBlackout {
+    pub space_blackout_id: SpaceBlackoutId,
+    pub start_time: DateTime<Local>,
+    pub end_time: DateTime<Local>,
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_opening_hours() {
+        let hours = |weekday, open_minutes, close_minutes| OpeningHours {
+            weekday,
+            open_minutes,
+            close_minutes,
+        };
+        assert!(validate_opening_hours(&[
+            hours(Weekday::Mon, 9 * 60, 12 * 60),
+            hours(Weekday::Mon, 13 * 60, 18 * 60),
+            hours(Weekday::Tue, 0, MINUTES_PER_DAY),
+        ])
+        .is_ok());
+        assert!(validate_opening_hours(&[
+            hours(Weekday::Mon, 13 * 60, 18 * 60),
+            hours(Weekday::Mon, 9 * 60, 14 * 60),
+        ])
+        .is_err());
+        assert!(validate_opening_hours(&[hours(Weekday::Wed, 18 * 60, 9 * 60)]).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::model::{id::UserId, role::Role};
+use shared::i18n::Language;
 
 #[derive(Debug)]
 pub struct CreateUser {
@@ -23,4 +24,22 @@ pub struct UpdateUserPassword {
 #[derive(Debug)]
 pub struct DeleteUser {
     pub user_id: UserId,
-}
\ No newline at end of file
+}
+
+#[derive(Debug)]
+pub struct UpdateUserReminderPreference {
+    pub user_id: UserId,
+    pub reminder_lead_minutes: Vec<i32>,
+}
+
+#[derive(Debug)]
+pub struct UpdateUserLanguage {
+    pub user_id: UserId,
+    pub language: Language,
+}
+
+#[derive(Debug)]
+pub struct UpdateUserTimezone {
+    pub user_id: UserId,
+    pub timezone: String,
+}
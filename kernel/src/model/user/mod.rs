@@ -1,5 +1,6 @@
 // kernel/src/model/user/mod.rs
 use crate::model::{id::UserId, role::Role};
+use shared::i18n::Language;
 pub mod event;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -8,6 +9,12 @@ pub struct User {
     pub user_name: String,
     pub email: String,
     pub role: Role,
+    // 予約時に既定で設定するリマインダー（予約開始の何分前に送るか）
+    pub reminder_lead_minutes: Vec<i32>,
+    // 通知メールに使う言語
+    pub language: Language,
+    // 通知メールの日時を表示するタイムゾーン（IANA のタイムゾーン名）
+    pub timezone: String,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,29 @@
+use crate::model::{
+    id::{SpaceId, UserId, WaitlistEntryId},
+    waitlist::WaitlistFulfillment,
+};
+use chrono::{DateTime, Local};
+use derive_new::new;
+
+#[derive(new)]
+pub struct CreateWaitlistEntry {
+    pub space_id: SpaceId,
+    pub requested_by: UserId,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub fulfillment: WaitlistFulfillment,
+}
+
+#[derive(new)]
+pub struct CancelWaitlistEntry {
+    pub waitlist_entry_id: WaitlistEntryId,
+    pub requested_by: UserId,
+}
+
+// 案内のメールに含まれるトークンで、空いた時間帯の予約を確定する
+#[derive(new)]
+pub struct ClaimWaitlistOffer {
+    pub claim_token: String,
+    pub requested_by: UserId,
+    pub claimed_at: DateTime<Local>,
+}
@@ -0,0 +1,59 @@
+use crate::model::id::{ReservationId, SpaceId, UserId, WaitlistEntryId};
+use chrono::{DateTime, Local};
+use strum::{AsRefStr, EnumString};
+
+pub mod event;
+
+// 空きが出たことを案内してから、予約を確定できる時間（分）
+// この時間を過ぎると、次に待っているユーザーに案内する
+pub const WAITLIST_OFFER_HOLD_MINUTES: i64 = 30;
+
+// 空きが出たときの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum WaitlistFulfillment {
+    // 予約を確定するためのリンクを送り、期限内に確定してもらう
+    Offer,
+    // そのまま予約し、予約したことを知らせる
+    AutoBook,
+}
+
+// キャンセル待ちの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum WaitlistStatus {
+    // 空きが出るのを待っている
+    Waiting,
+    // 空きを案内し、予約の確定を待っている
+    Offered,
+    // 予約が確定した
+    Booked,
+    // 案内の期限、または希望の時間帯を過ぎた
+    Expired,
+    // ユーザーが取り下げた
+    Cancelled,
+}
+
+impl WaitlistStatus {
+    // 空きが出たときの案内の対象になりうる状態かどうか
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Waiting | Self::Offered)
+    }
+}
+
+#[derive(Debug)]
+pub struct WaitlistEntry {
+    pub waitlist_entry_id: WaitlistEntryId,
+    pub space_id: SpaceId,
+    pub space_name: String,
+    pub user_id: UserId,
+    pub reservation_start_time: DateTime<Local>,
+    pub reservation_end_time: DateTime<Local>,
+    pub fulfillment: WaitlistFulfillment,
+    pub status: WaitlistStatus,
+    // 案内中の場合の、予約を確定できる期限
+    pub offer_expires_at: Option<DateTime<Local>>,
+    // 予約が確定した場合の予約 ID
+    pub reservation_id: Option<ReservationId>,
+    pub created_at: DateTime<Local>,
+}
@@ -0,0 +1,37 @@
+use super::WebhookEvent;
+use crate::model::id::{WebhookDeliveryId, WebhookId};
+use chrono::{DateTime, Local};
+
+// webhook の送信先を登録する
+// 署名に使う鍵は登録時に生成する
+#[derive(Debug)]
+pub struct CreateWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub description: String,
+}
+
+#[derive(Debug)]
+pub struct UpdateWebhook {
+    pub webhook_id: WebhookId,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub description: String,
+    pub is_active: bool,
+}
+
+#[derive(Debug)]
+pub struct DeleteWebhook {
+    pub webhook_id: WebhookId,
+}
+
+// 送信に失敗した webhook の送信を記録する
+// next_attempt_at が None の場合は再試行せず dead とする
+#[derive(Debug)]
+pub struct RecordWebhookFailure {
+    pub webhook_delivery_id: WebhookDeliveryId,
+    pub error: String,
+    // レスポンスを受け取れなかった場合は None
+    pub response_status: Option<i32>,
+    pub next_attempt_at: Option<DateTime<Local>>,
+}
@@ -0,0 +1,160 @@
+use crate::model::{
+    id::{WebhookDeliveryId, WebhookId},
+    reservation::status::ReservationStatus,
+};
+use chrono::{DateTime, Local};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+pub mod event;
+
+// 送信のために取得した webhook を、他の dispatcher から見えなくしておく時間（秒）
+// 送信のタイムアウトよりも十分長くしておく
+pub const WEBHOOK_LEASE_DURATION_SECS: i64 = 300;
+
+// webhook で知らせる出来事
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+pub enum WebhookEvent {
+    // 予約の作成（承認待ちを含む）
+    #[strum(serialize = "reservation.created")]
+    ReservationCreated,
+    // 予約時間の変更と、承認・チェックインによる状態の変化
+    #[strum(serialize = "reservation.updated")]
+    ReservationUpdated,
+    // 利用終了
+    #[strum(serialize = "reservation.returned")]
+    ReservationReturned,
+    // キャンセル・チェックインしなかったことによる解放・却下・期限切れ
+    #[strum(serialize = "reservation.cancelled")]
+    ReservationCancelled,
+    // スペースの停止
+    #[strum(serialize = "space.deactivated")]
+    SpaceDeactivated,
+    // 送信先の確認のための試験送信。購読するものではなく、指定した webhook にのみ送る
+    #[strum(serialize = "ping")]
+    Ping,
+}
+
+impl WebhookEvent {
+    // 予約の状態を next に変えたときに知らせる出来事
+    pub fn for_status(next: ReservationStatus) -> Self {
+        use ReservationStatus::*;
+        match next {
+            Pending | Confirmed | CheckedIn => Self::ReservationUpdated,
+            Completed => Self::ReservationReturned,
+            CancelledByUser | CancelledByAdmin | CancelledByOwner | Cancelled | NoShow
+            | Rejected | Expired => Self::ReservationCancelled,
+        }
+    }
+
+    // webhook の登録時に、送る出来事として指定できるか
+    pub fn is_subscribable(self) -> bool {
+        self != Self::Ping
+    }
+}
+
+// webhook の送信先
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub webhook_id: WebhookId,
+    pub url: String,
+    // ペイロードの署名に使う鍵
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub description: String,
+    // false の場合は出来事が起きても送らない
+    pub is_active: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+// webhook の送信状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    // 未送信（再試行待ちを含む）
+    Pending,
+    // 送信先が 2xx を返した
+    Delivered,
+    // 再試行の上限に達し、送信を諦めたもの
+    Dead,
+}
+
+// webhook の送信 1 件分と、その結果の記録
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
+    pub webhook_delivery_id: WebhookDeliveryId,
+    pub webhook_id: WebhookId,
+    pub event: WebhookEvent,
+    // 出来事が起きた時点の予約・スペースの値
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    // 最後に受け取ったレスポンスの HTTP ステータス。レスポンスを受け取れなかった場合は None
+    pub response_status: Option<i32>,
+    pub next_attempt_at: DateTime<Local>,
+    pub delivered_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
+
+// 送信するために取得した webhook の送信と、その送信先
+#[derive(Debug, Clone)]
+pub struct WebhookDispatch {
+    pub delivery: WebhookDelivery,
+    pub url: String,
+    pub secret: String,
+}
+
+// ページネーションの範囲と、絞り込む送信状態を指定するための設定値
+#[derive(Debug)]
+pub struct WebhookDeliveryListOptions {
+    pub webhook_id: WebhookId,
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_event_names() {
+        for event in WebhookEvent::iter() {
+            assert_eq!(event.as_ref().parse::<WebhookEvent>().ok(), Some(event));
+        }
+        assert_eq!(
+            "reservation.created".parse::<WebhookEvent>().ok(),
+            Some(WebhookEvent::ReservationCreated)
+        );
+        assert!(!WebhookEvent::Ping.is_subscribable());
+    }
+
+    #[test]
+    fn test_event_for_status() {
+        use ReservationStatus::*;
+        assert_eq!(
+            WebhookEvent::for_status(Confirmed),
+            WebhookEvent::ReservationUpdated
+        );
+        assert_eq!(
+            WebhookEvent::for_status(Completed),
+            WebhookEvent::ReservationReturned
+        );
+        for status in [
+            CancelledByUser,
+            CancelledByAdmin,
+            CancelledByOwner,
+            Cancelled,
+            NoShow,
+            Rejected,
+            Expired,
+        ] {
+            assert_eq!(
+                WebhookEvent::for_status(status),
+                WebhookEvent::ReservationCancelled
+            );
+        }
+    }
+}
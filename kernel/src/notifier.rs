@@ -0,0 +1,84 @@
+use crate::model::notification::{NotificationKind, RenderedMail, ReservationNotification};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// 利用者への通知（メール等）の送信手段を抽象化したトレイト
+// 送信手段は adapter 側で Gmail API・SMTP・ファイル出力などとして実装する
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<()>;
+
+    // 予約開始前のリマインダーを送る
+    async fn send_reminder(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Reminder, notification).await
+    }
+    // 予約受付の確認を送る
+    async fn send_confirmation(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Confirmation, notification)
+            .await
+    }
+    // 予約時間の変更を送る
+    async fn send_reschedule(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Reschedule, notification).await
+    }
+    // 予約キャンセルを送る
+    async fn send_cancellation(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Cancellation, notification)
+            .await
+    }
+    // 予約終了を送る
+    async fn send_return(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Return, notification).await
+    }
+    // キャンセル待ちの時間帯に空きが出たことを案内する
+    async fn send_waitlist_offer(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::WaitlistOffer, notification)
+            .await
+    }
+    // キャンセル待ちの時間帯を自動で予約したことを送る
+    async fn send_waitlist_booked(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::WaitlistBooked, notification)
+            .await
+    }
+    // チェックインがなかったため予約を解放したことを送る
+    async fn send_no_show(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::NoShow, notification).await
+    }
+    // 承認者に承認待ちの予約の承認を依頼する
+    async fn send_approval_request(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::ApprovalRequest, notification)
+            .await
+    }
+    // 予約を承認待ちとして受け付けたことを送る
+    async fn send_approval_pending(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::ApprovalPending, notification)
+            .await
+    }
+    // 予約が承認されたことを送る
+    async fn send_approved(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Approved, notification).await
+    }
+    // 予約が却下されたことを送る
+    async fn send_rejected(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::Rejected, notification).await
+    }
+    // 承認されないまま予約開始時刻を過ぎたため、予約が期限切れになったことを送る
+    async fn send_approval_expired(&self, notification: &ReservationNotification) -> AppResult<()> {
+        self.notify(NotificationKind::ApprovalExpired, notification)
+            .await
+    }
+}
+
+// 通知の件名と本文をテンプレートから組み立てる
+// 管理者がテンプレートをプレビューする際にも使う
+pub trait MailRenderer: Send + Sync {
+    fn render(
+        &self,
+        kind: NotificationKind,
+        notification: &ReservationNotification,
+    ) -> AppResult<RenderedMail>;
+}
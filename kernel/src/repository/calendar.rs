@@ -0,0 +1,16 @@
+use crate::model::{calendar::CalendarFeedToken, id::UserId};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[async_trait]
+pub trait CalendarFeedRepository: Send + Sync {
+    // 予定表を購読するためのトークンを発行する
+    // 発行済みの場合は新しいトークンに置き換え、以前のトークンは使えなくなる
+    async fn issue_token(&self, user_id: UserId) -> AppResult<CalendarFeedToken>;
+    // 発行済みのトークンを取得する
+    async fn find_token(&self, user_id: UserId) -> AppResult<Option<CalendarFeedToken>>;
+    // トークンを取り消す
+    async fn revoke_token(&self, user_id: UserId) -> AppResult<()>;
+    // トークンが紐づくユーザー ID を取得する
+    async fn find_user_id_by_token(&self, token: &str) -> AppResult<Option<UserId>>;
+}
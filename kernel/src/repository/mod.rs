@@ -2,4 +2,8 @@ pub mod health;
 pub mod space;
 pub mod auth;
 pub mod user;
-pub mod reservation;
\ No newline at end of file
+pub mod reservation;
+pub mod outbox;
+pub mod reminder;
+pub mod calendar;
+pub mod webhook;
\ No newline at end of file
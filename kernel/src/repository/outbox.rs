@@ -0,0 +1,27 @@
+use crate::model::{
+    id::OutboxMessageId,
+    list::PaginatedList,
+    outbox::{event::RecordOutboxFailure, OutboxListOptions, OutboxMessage},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use shared::error::AppResult;
+
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    // 送信時刻を迎えた未送信メッセージを取得し、lease_until まで他の dispatcher から見えなくする
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Local>,
+    ) -> AppResult<Vec<OutboxMessage>>;
+    // 送信済みにする
+    async fn mark_sent(&self, outbox_message_id: OutboxMessageId) -> AppResult<()>;
+    // 送信失敗を記録する
+    async fn record_failure(&self, event: RecordOutboxFailure) -> AppResult<()>;
+    // outbox メッセージの一覧を取得する
+    async fn find_all(&self, options: OutboxListOptions)
+        -> AppResult<PaginatedList<OutboxMessage>>;
+    // dead になったメッセージを再送対象に戻す
+    async fn requeue(&self, outbox_message_id: OutboxMessageId) -> AppResult<()>;
+}
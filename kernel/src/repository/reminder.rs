@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use shared::error::AppResult;
+
+#[async_trait]
+pub trait ReminderRepository: Send + Sync {
+    // 送信時刻を迎えたリマインダーを最大 limit 件取得して outbox に積み、
+    // 送信済みとして記録する。積んだ件数を返す
+    async fn enqueue_due(&self, limit: i64) -> AppResult<usize>;
+    // 未送信のリマインダーのうち、最も早い送信時刻を取得する
+    async fn find_next_due_at(&self) -> AppResult<Option<DateTime<Local>>>;
+}
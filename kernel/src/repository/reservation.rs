@@ -1,9 +1,19 @@
 use crate::model::{
     reservation::{
-        event::{CreateReservation, UpdateReturned},
-        Reservation,
+        event::{
+            CancelReservationSeries, CheckInReservation, CreateReservation, CreateReservationSeries,
+            DeactivateAllSpaces, DeactivateSpace, ReviewReservation, UpdateReservation, UpdateReservationSeries, UpdateReturned,
+        },
+        series::{CreatedReservationSeries, ReservationSeries},
+        status::ReservationStatus,
+        Reservation, ReservationListOptions,
+    },
+    list::PaginatedList,
+    id::{ SpaceId, UserId,ReservationId, ReservationSeriesId, WaitlistEntryId},
+    waitlist::{
+        event::{CancelWaitlistEntry, ClaimWaitlistOffer, CreateWaitlistEntry},
+        WaitlistEntry,
     },
-    id::{ SpaceId, UserId,ReservationId},
 };
 use async_trait::async_trait;
 use shared::error::AppResult;
@@ -12,8 +22,38 @@ use shared::error::AppResult;
 pub trait ReservationRepository: Send + Sync {
     // 予約操作を行う
     async fn create(&self, event: CreateReservation) -> AppResult<ReservationId>;
-    // 予約終了操作を行う
+    // 予約時間を変更する
+    async fn update(&self, event: UpdateReservation) -> AppResult<()>;
+    // 繰り返し予約を作成する
+    async fn create_series(
+        &self,
+        event: CreateReservationSeries,
+    ) -> AppResult<CreatedReservationSeries>;
+    // 繰り返し予約の予約時間を、指定の範囲の回についてまとめて変更する
+    async fn update_series(&self, event: UpdateReservationSeries) -> AppResult<()>;
+    // 繰り返し予約を、指定の範囲の回についてまとめてキャンセルする
+    async fn cancel_series(&self, event: CancelReservationSeries) -> AppResult<()>;
+    // 予約を利用終了またはキャンセルの状態にする
     async fn update_returned(&self, event: UpdateReturned) -> AppResult<()>;
+    // スペースを停止し、そのスペースの終わっていない予約をすべてキャンセルして、キャンセルした件数を返す
+    // 停止と予約のキャンセル・通知は、まとめて 1 つのトランザクションで行う
+    async fn deactivate_space(&self, event: DeactivateSpace) -> AppResult<usize>;
+    // すべてのスペースを停止し、すべての終わっていない予約をキャンセルして、キャンセルした件数を返す
+    // 途中で失敗した場合に一部のスペースだけが停止したままにならないよう、まとめて 1 つのトランザクションで行う
+    async fn deactivate_all_spaces(&self, event: DeactivateAllSpaces) -> AppResult<usize>;
+    // 終了時刻を過ぎた予約を最大 limit 件利用終了の状態にし、処理した件数を返す
+    async fn archive_ended(&self, limit: i64) -> AppResult<usize>;
+    // 予約の利用開始時にチェックインする
+    async fn check_in(&self, event: CheckInReservation) -> AppResult<()>;
+    // チェックインがないまま猶予時間を過ぎた予約を最大 limit 件 no_show の状態にして解放し、処理した件数を返す
+    async fn release_no_shows(&self, limit: i64) -> AppResult<usize>;
+    // 承認待ちの予約を承認または却下する
+    async fn review(&self, event: ReviewReservation) -> AppResult<()>;
+    // 予約開始時刻を過ぎても承認・却下されていない予約を最大 limit 件期限切れにし、処理した件数を返す
+    async fn expire_pending_reviews(&self, limit: i64) -> AppResult<usize>;
+    // 終わった予約も含めて、条件に合う予約を並べ替えて取得する
+    async fn find_all(&self, options: ReservationListOptions)
+        -> AppResult<PaginatedList<Reservation>>;
     // すべての現在の予約情報を取得する
     async fn find_unreturned_all(&self) -> AppResult<Vec<Reservation>>;
     // reservation_idからReservation型のデータを渡す
@@ -22,8 +62,25 @@ pub trait ReservationRepository: Send + Sync {
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Reservation>>;
     //スペース　ID　に紐づく予約中の予約一覧を取得する
     async fn find_reservations_by_space_id(&self, space_id: SpaceId) -> AppResult<Vec<Reservation>>;
-    //リザーブテーション　ID　に紐づくreminder_is_alreadyを更新する
-    async fn update_reminder_is_already(&self, reservatio_id:ReservationId,reminder_is_already:bool) -> AppResult<()>;
-    // 予約履歴を取得する
-    async fn find_history_by_space_id(&self, space_id:  SpaceId) -> AppResult<Vec<Reservation>>;
-}
\ No newline at end of file
+    // 繰り返し予約と、その予約中の回を取得する
+    async fn find_series_by_id(
+        &self,
+        reservation_series_id: ReservationSeriesId,
+    ) -> AppResult<ReservationSeries>;
+    // 予約履歴を取得する。statuses を指定した場合は、そのいずれかの状態の予約に絞り込む
+    async fn find_history_by_space_id(
+        &self,
+        space_id: SpaceId,
+        statuses: &[ReservationStatus],
+    ) -> AppResult<Vec<Reservation>>;
+    // 予約の入っている時間帯のキャンセル待ちに登録する
+    async fn join_waitlist(&self, event: CreateWaitlistEntry) -> AppResult<WaitlistEntryId>;
+    // ユーザー ID に紐づくキャンセル待ちを、登録の新しい順に取得する
+    async fn find_waitlist_by_user_id(&self, user_id: UserId) -> AppResult<Vec<WaitlistEntry>>;
+    // キャンセル待ちを取り下げる。案内中だった場合は次に待っているユーザーに案内する
+    async fn cancel_waitlist_entry(&self, event: CancelWaitlistEntry) -> AppResult<()>;
+    // 案内された時間帯の予約を確定する
+    async fn claim_waitlist_offer(&self, event: ClaimWaitlistOffer) -> AppResult<ReservationId>;
+    // 期限を過ぎた案内と、希望の時間帯を過ぎたキャンセル待ちを最大 limit 件期限切れにし、処理した件数を返す
+    async fn expire_waitlist_entries(&self, limit: i64) -> AppResult<usize>;
+}
@@ -2,8 +2,14 @@ use async_trait::async_trait;
 use shared::error::AppResult;
 
 use crate::model::{
-    id::{SpaceId,UserId},
-    space::{event::{CreateSpace, DeleteSpace, UpdateSpace},
+    id::{SpaceBlackoutId, SpaceId,UserId},
+    space::{event::{
+            CreateSpace, CreateSpaceBlackout, DeleteSpace, DeleteSpaceBlackout,
+            UpdateApprovalPolicy, UpdateBookingPolicy, UpdateCheckInPolicy, UpdateOpeningHours,
+            UpdateSpace,
+        },
+        schedule::SpaceBlackout,
+        availability::{AvailabilityOptions, AvailableSpaceOptions, SpaceAvailability},
         Space, SpaceListOptions,},
     list::PaginatedList,
 };
@@ -13,8 +19,32 @@ pub trait SpaceRepository: Send + Sync {
     async fn create(&self, event: CreateSpace,user_id: UserId) -> AppResult<()>;
     async fn find_all(&self,options: SpaceListOptions) -> AppResult<PaginatedList<Space>>;
     async fn find_by_id(&self, space_id: SpaceId) -> AppResult<Option<Space>>;
+    // スペースの空き時間を取得する
+    async fn find_availability(
+        &self,
+        space_id: SpaceId,
+        options: AvailabilityOptions,
+    ) -> AppResult<SpaceAvailability>;
+    // 指定の期間に予約が入っていない、利用可能なスペースを取得する
+    async fn find_available(
+        &self,
+        options: AvailableSpaceOptions,
+    ) -> AppResult<PaginatedList<Space>>;
     async fn find_all_space_for_all_cancel(&self) -> AppResult<Vec<Space>>;
     async fn update(&self, event: UpdateSpace) -> AppResult<()>;
+    // スペースの有効・停止を切り替える。権限の確認は呼び出し側で AuthorizationPolicy により行う
     async fn update_is_active(&self, event: UpdateSpace) -> AppResult<()>;
     async fn delete(&self, event: DeleteSpace) -> AppResult<()>;
+    // 営業時間とタイムゾーンを置き換える
+    async fn update_opening_hours(&self, event: UpdateOpeningHours) -> AppResult<()>;
+    // まだ終わっていない、予約を受け付けない期間を開始の早い順に取得する
+    async fn find_blackouts(&self, space_id: SpaceId) -> AppResult<Vec<SpaceBlackout>>;
+    async fn create_blackout(&self, event: CreateSpaceBlackout) -> AppResult<SpaceBlackoutId>;
+    async fn delete_blackout(&self, event: DeleteSpaceBlackout) -> AppResult<()>;
+    // 予約のルールを置き換える
+    async fn update_booking_policy(&self, event: UpdateBookingPolicy) -> AppResult<()>;
+    // チェックインの猶予時間とコードを置き換える
+    async fn update_check_in_policy(&self, event: UpdateCheckInPolicy) -> AppResult<()>;
+    // 予約に承認が必要かと、承認者を置き換える
+    async fn update_approval_policy(&self, event: UpdateApprovalPolicy) -> AppResult<()>;
 }
@@ -1,7 +1,10 @@
 use crate::model::{
     id::UserId,
     user::{
-        event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+        event::{
+            CreateUser, DeleteUser, UpdateUserLanguage, UpdateUserPassword,
+            UpdateUserReminderPreference, UpdateUserRole, UpdateUserTimezone,
+        },
         User,
     },
 };
@@ -15,5 +18,13 @@ pub trait UserRepository: Send + Sync {
     async fn create(&self, event: CreateUser) -> AppResult<User>;
     async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()>;
+    async fn update_reminder_preference(
+        &self,
+        event: UpdateUserReminderPreference,
+    ) -> AppResult<()>;
+    // 通知メールに使う言語を変更する
+    async fn update_language(&self, event: UpdateUserLanguage) -> AppResult<()>;
+    // 通知メールの日時を表示するタイムゾーンを変更する
+    async fn update_timezone(&self, event: UpdateUserTimezone) -> AppResult<()>;
     async fn delete(&self, event: DeleteUser) -> AppResult<()>;
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use crate::model::{
+    id::{WebhookDeliveryId, WebhookId},
+    list::PaginatedList,
+    webhook::{
+        event::{CreateWebhook, DeleteWebhook, RecordWebhookFailure, UpdateWebhook},
+        Webhook, WebhookDelivery, WebhookDeliveryListOptions, WebhookDispatch,
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Local};
+use shared::error::AppResult;
+
+#[async_trait]
+pub trait WebhookRepository: Send + Sync {
+    // webhook の送信先を登録する
+    async fn create(&self, event: CreateWebhook) -> AppResult<Webhook>;
+    // webhook の送信先を一覧する
+    async fn find_all(&self) -> AppResult<Vec<Webhook>>;
+    async fn find_by_id(&self, webhook_id: WebhookId) -> AppResult<Option<Webhook>>;
+    async fn update(&self, event: UpdateWebhook) -> AppResult<()>;
+    // 送信先を削除する。送信の記録もあわせて削除する
+    async fn delete(&self, event: DeleteWebhook) -> AppResult<()>;
+    // 試験送信を積み、lease_until まで dispatcher から見えなくした状態で返す
+    async fn create_ping(
+        &self,
+        webhook_id: WebhookId,
+        lease_until: DateTime<Local>,
+    ) -> AppResult<WebhookDispatch>;
+    // 送信時刻を迎えた未送信の webhook を取得し、lease_until まで他の dispatcher から見えなくする
+    async fn claim_due(
+        &self,
+        limit: i64,
+        lease_until: DateTime<Local>,
+    ) -> AppResult<Vec<WebhookDispatch>>;
+    // 送信済みにする
+    async fn mark_delivered(
+        &self,
+        webhook_delivery_id: WebhookDeliveryId,
+        response_status: i32,
+    ) -> AppResult<()>;
+    // 送信失敗を記録する
+    async fn record_failure(&self, event: RecordWebhookFailure) -> AppResult<()>;
+    // webhook の送信の記録を新しい順に取得する
+    async fn find_deliveries(
+        &self,
+        options: WebhookDeliveryListOptions,
+    ) -> AppResult<PaginatedList<WebhookDelivery>>;
+    // 送信の記録を 1 件取得する
+    async fn find_delivery_by_id(
+        &self,
+        webhook_delivery_id: WebhookDeliveryId,
+    ) -> AppResult<Option<WebhookDelivery>>;
+}
@@ -0,0 +1,12 @@
+use crate::model::webhook::WebhookDispatch;
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+// webhook の送信手段を抽象化したトレイト
+// adapter 側で、署名を付けた HTTP リクエストとして実装する
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    // 送信先にペイロードを送り、レスポンスの HTTP ステータスを返す
+    // 2xx 以外のステータスもそのまま返す。レスポンスを受け取れなかった場合はエラーとする
+    async fn send(&self, dispatch: &WebhookDispatch) -> AppResult<u16>;
+}
@@ -5,22 +5,35 @@ use adapter::{database::ConnectionPool, repository::health::HealthCheckRepositor
 use adapter::repository::auth::AuthRepositoryImpl;
 use adapter::repository::user::UserRepositoryImpl;
 use adapter::repository::reservation::ReservationRepositoryImpl;
-use anyhow::Result;
-use yup_oauth2::{
-    read_application_secret, InstalledFlowAuthenticator, InstalledFlowReturnMethod
+use adapter::repository::outbox::OutboxRepositoryImpl;
+use adapter::repository::reminder::ReminderRepositoryImpl;
+use adapter::repository::calendar::CalendarFeedRepositoryImpl;
+use adapter::repository::webhook::WebhookRepositoryImpl;
+use adapter::webhook::HttpWebhookSender;
+use adapter::notifier::{
+    gmail::{build_gmail_authenticator, GmailNotifier},
+    message::parse_sender,
+    sink::{DisabledNotifier, FileNotifier, InMemoryNotifier},
+    smtp::SmtpNotifier,
+    template::MailTemplates,
 };
-use tokio::sync::Mutex;
-use shared::error::{AppError, AppResult};
+use anyhow::{Context, Result};
 
 
 use adapter::redis::RedisClient;
+use kernel::notifier::{MailRenderer, Notifier};
 use kernel::repository::health::HealthCheckRepository;
 use kernel::repository::space::SpaceRepository;
 use kernel::repository::auth::AuthRepository;
 use kernel::repository::user::UserRepository;
 use kernel::repository::reservation::ReservationRepository;
+use kernel::repository::outbox::OutboxRepository;
+use kernel::repository::reminder::ReminderRepository;
+use kernel::repository::calendar::CalendarFeedRepository;
+use kernel::repository::webhook::WebhookRepository;
+use kernel::webhook::WebhookSender;
 
-use shared::config::AppConfig;
+use shared::config::{AppConfig, MailConfig, MailTransport};
 
 #[derive(Clone)]
 pub struct AppRegistry {
@@ -29,20 +42,20 @@ pub struct AppRegistry {
     auth_repository: Arc<dyn AuthRepository>,
     user_repository: Arc<dyn UserRepository>,
     reservation_repository: Arc<dyn ReservationRepository>,
+    outbox_repository: Arc<dyn OutboxRepository>,
+    reminder_repository: Arc<dyn ReminderRepository>,
+    calendar_feed_repository: Arc<dyn CalendarFeedRepository>,
+    webhook_repository: Arc<dyn WebhookRepository>,
+    notifier: Arc<dyn Notifier>,
+    mail_renderer: Arc<dyn MailRenderer>,
+    webhook_sender: Arc<dyn WebhookSender>,
 }
 
-const SECRET_PATH: &str =
-    "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/client_secret_483730081753-qm9ujsmkcgfpag17j2iv618fspsjpgou.apps.googleusercontent.com.json";
-
-const TOKEN_PATH: &str =
-    "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/token.json";
-
-
 impl AppRegistry {
-    pub fn new(pool: ConnectionPool,
+    pub async fn new(pool: ConnectionPool,
         redis_client: Arc<RedisClient>,
         app_config: AppConfig,
-    ) -> Self {
+    ) -> Result<Self> {
         let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(pool.clone()));
         let space_repository = Arc::new(SpaceRepositoryImpl::new(pool.clone()));
         let auth_repository = Arc::new(AuthRepositoryImpl::new(
@@ -52,15 +65,39 @@ impl AppRegistry {
         ));
         let user_repository = Arc::new(UserRepositoryImpl::new(pool.clone()));
         let reservation_repository = Arc::new(ReservationRepositoryImpl::new(pool.clone()));
-
-
-        Self {
+        let outbox_repository = Arc::new(OutboxRepositoryImpl::new(pool.clone()));
+        let reminder_repository = Arc::new(ReminderRepositoryImpl::new(pool.clone()));
+        let calendar_feed_repository = Arc::new(CalendarFeedRepositoryImpl::new(pool.clone()));
+        let webhook_repository = Arc::new(WebhookRepositoryImpl::new(pool.clone()));
+        // テンプレートはメール送信が無効な場合もプレビューに使うため、常に読み込む
+        let mail_templates = Arc::new(
+            MailTemplates::load(
+                &app_config.mail.template_dir,
+                app_config.mail.waitlist_claim_url.clone(),
+            )
+            .context("failed to load mail templates")?,
+        );
+        let notifier = build_notifier(&app_config.mail, mail_templates.clone()).await?;
+        let webhook_sender = Arc::new(
+            HttpWebhookSender::new(app_config.webhook.timeout_secs)
+                .context("failed to build webhook sender")?,
+        );
+
+
+        Ok(Self {
             health_check_repository,
             space_repository,
             auth_repository,
             user_repository,
             reservation_repository,
-        }
+            outbox_repository,
+            reminder_repository,
+            calendar_feed_repository,
+            webhook_repository,
+            notifier,
+            mail_renderer: mail_templates,
+            webhook_sender,
+        })
     }
 
     pub fn health_check_repository(&self) -> Arc<dyn HealthCheckRepository> {
@@ -82,26 +119,72 @@ impl AppRegistry {
         self.reservation_repository.clone()
     }
 
-    pub async fn google_access_token(&self) -> AppResult<String> {
-        let secret_path = "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/client_secret_483730081753-qm9ujsmkcgfpag17j2iv618fspsjpgou.apps.googleusercontent.com.json";
-        let token_path = "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/token.json";
-    
-        // let secret = yup_oauth2::read_application_secret(secret_path).await?;
-        let secret = yup_oauth2::read_application_secret(secret_path)
-            .await
-            .map_err(|e| AppError::ExternalServiceError(e.to_string()))?;
-        
-        let auth = InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::Interactive)
-            .persist_tokens_to_disk(token_path)
-            .build()
-            .await?;
-    
-        let token = auth
-            .token(&["https://www.googleapis.com/auth/gmail.send"])
-            .await?;
-        let access_token = token.token().unwrap().to_string();
-        
-
-        Ok(access_token)
+    pub fn outbox_repository(&self) -> Arc<dyn OutboxRepository> {
+        self.outbox_repository.clone()
     }
+
+    pub fn reminder_repository(&self) -> Arc<dyn ReminderRepository> {
+        self.reminder_repository.clone()
+    }
+
+    pub fn calendar_feed_repository(&self) -> Arc<dyn CalendarFeedRepository> {
+        self.calendar_feed_repository.clone()
+    }
+
+    pub fn webhook_repository(&self) -> Arc<dyn WebhookRepository> {
+        self.webhook_repository.clone()
+    }
+
+    pub fn notifier(&self) -> Arc<dyn Notifier> {
+        self.notifier.clone()
+    }
+
+    pub fn mail_renderer(&self) -> Arc<dyn MailRenderer> {
+        self.mail_renderer.clone()
+    }
+
+    pub fn webhook_sender(&self) -> Arc<dyn WebhookSender> {
+        self.webhook_sender.clone()
+    }
+}
+
+// MailConfig の送信手段に応じて Notifier の実装を選ぶ
+// 設定に誤りがある場合は起動時にエラーとする
+async fn build_notifier(
+    config: &MailConfig,
+    templates: Arc<MailTemplates>,
+) -> Result<Arc<dyn Notifier>> {
+    let sender = parse_sender(&config.sender).context("MAIL_SENDER is invalid")?;
+    let notifier: Arc<dyn Notifier> = match config.transport {
+        MailTransport::Disabled => {
+            tracing::warn!("mail notifications are disabled (MAIL_TRANSPORT is not set or disabled)");
+            Arc::new(DisabledNotifier)
+        }
+        MailTransport::Gmail => {
+            let gmail = config.gmail.as_ref().context("Gmail settings are missing")?;
+            let authenticator = build_gmail_authenticator(gmail, &sender)
+                .await
+                .context("failed to set up Gmail authentication")?;
+            Arc::new(GmailNotifier::new(
+                sender,
+                templates,
+                Arc::new(authenticator),
+            ))
+        }
+        MailTransport::Smtp => {
+            let smtp = config.smtp.as_ref().context("SMTP settings are missing")?;
+            Arc::new(SmtpNotifier::new(smtp, sender, templates)?)
+        }
+        MailTransport::File => {
+            let path = config
+                .sink_path
+                .as_ref()
+                .context("mail sink path is missing")?;
+            Arc::new(FileNotifier::new(path, sender, templates))
+        }
+        MailTransport::Memory => {
+            Arc::new(InMemoryNotifier::new(templates))
+        }
+    };
+    Ok(notifier)
 }
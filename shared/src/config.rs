@@ -1,9 +1,15 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use strum::EnumString;
 
 pub struct AppConfig {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub auth: AuthConfig,
+    pub mail: MailConfig,
+    pub outbox: OutboxConfig,
+    pub reminder: ReminderConfig,
+    pub watcher: WatcherConfig,
+    pub webhook: WebhookConfig,
 }
 
 impl AppConfig {
@@ -23,9 +29,19 @@ impl AppConfig {
         let auth = AuthConfig {
             ttl: std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
         };
+        let mail = MailConfig::from_env()?;
+        let outbox = OutboxConfig::from_env()?;
+        let reminder = ReminderConfig::from_env()?;
+        let watcher = WatcherConfig::from_env()?;
+        let webhook = WebhookConfig::from_env()?;
         Ok(Self { database,
             redis,
-            auth, })
+            auth,
+            mail,
+            outbox,
+            reminder,
+            watcher,
+            webhook, })
     }
 }
 
@@ -44,4 +60,246 @@ pub struct RedisConfig {
 
 pub struct AuthConfig {
     pub ttl: u64,
-}
\ No newline at end of file
+}
+
+// メールの送信手段
+// MAIL_TRANSPORT 環境変数で切り替え、未設定の場合はメール送信を行わない
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum MailTransport {
+    #[default]
+    Disabled,
+    Gmail,
+    Smtp,
+    File,
+    Memory,
+}
+
+// Gmail API へのアクセストークンを取得する OAuth フロー
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum GoogleAuthFlow {
+    // OAuth クライアント（インストール済みアプリ）として認可を受ける
+    #[default]
+    Installed,
+    // サービスアカウントのドメイン全体の委任で送信者になりすます
+    ServiceAccount,
+}
+
+pub struct MailConfig {
+    pub transport: MailTransport,
+    pub sender: String,
+    pub gmail: Option<GmailConfig>,
+    pub smtp: Option<SmtpConfig>,
+    pub sink_path: Option<String>,
+    // キャンセル待ちの案内に載せる、予約を確定するためのリンクの URL
+    // 末尾にトークンを付けて使う
+    pub waitlist_claim_url: String,
+    // 通知メールのテンプレートを置いたディレクトリ。起動時に読み込む
+    pub template_dir: String,
+}
+
+impl MailConfig {
+    fn from_env() -> Result<Self> {
+        let transport = match std::env::var("MAIL_TRANSPORT") {
+            Err(_) => MailTransport::default(),
+            Ok(v) if v.is_empty() => MailTransport::default(),
+            Ok(v) => v
+                .parse()
+                .with_context(|| format!("unknown MAIL_TRANSPORT: {v}"))?,
+        };
+
+        // 実際にメールを送る手段では送信者アドレスを必須とする
+        let sender = match transport {
+            MailTransport::Gmail | MailTransport::Smtp => std::env::var("MAIL_SENDER")
+                .context("MAIL_SENDER must be set when mail is enabled")?,
+            _ => std::env::var("MAIL_SENDER").unwrap_or_else(|_| "noreply@localhost".into()),
+        };
+
+        // Gmail の認証情報は MAIL_TRANSPORT=gmail のときのみ必須とする
+        let gmail = match transport {
+            MailTransport::Gmail => Some(GmailConfig {
+                secret_path: std::env::var("GMAIL_SECRET_PATH")
+                    .context("GMAIL_SECRET_PATH must be set when MAIL_TRANSPORT=gmail")?,
+                token_cache_path: std::env::var("GMAIL_TOKEN_CACHE_PATH").ok(),
+                flow: match std::env::var("GMAIL_AUTH_FLOW") {
+                    Err(_) => GoogleAuthFlow::default(),
+                    Ok(v) => v
+                        .parse()
+                        .with_context(|| format!("unknown GMAIL_AUTH_FLOW: {v}"))?,
+                },
+            }),
+            _ => None,
+        };
+        if let Some(GmailConfig {
+            flow: GoogleAuthFlow::Installed,
+            token_cache_path: None,
+            ..
+        }) = &gmail
+        {
+            anyhow::bail!("GMAIL_TOKEN_CACHE_PATH must be set when GMAIL_AUTH_FLOW=installed");
+        }
+
+        // SMTP の接続先は MAIL_TRANSPORT=smtp のときのみ必須とする
+        let smtp = match transport {
+            MailTransport::Smtp => Some(SmtpConfig {
+                host: std::env::var("SMTP_HOST")
+                    .context("SMTP_HOST must be set when MAIL_TRANSPORT=smtp")?,
+                port: match std::env::var("SMTP_PORT") {
+                    Err(_) => 25,
+                    Ok(v) => v.parse::<u16>()?,
+                },
+                username: std::env::var("SMTP_USERNAME").ok(),
+                password: std::env::var("SMTP_PASSWORD").ok(),
+            }),
+            _ => None,
+        };
+
+        // ファイル出力先は MAIL_TRANSPORT=file のときのみ必須とする
+        let sink_path = match transport {
+            MailTransport::File => Some(
+                std::env::var("MAIL_SINK_PATH")
+                    .context("MAIL_SINK_PATH must be set when MAIL_TRANSPORT=file")?,
+            ),
+            _ => None,
+        };
+
+        let waitlist_claim_url = std::env::var("WAITLIST_CLAIM_URL")
+            .unwrap_or_else(|_| "http://localhost:8080/api/v1/waitlist/claims".into());
+
+        let template_dir =
+            std::env::var("MAIL_TEMPLATE_DIR").unwrap_or_else(|_| "adapter/templates/mail".into());
+
+        Ok(Self {
+            transport,
+            sender,
+            gmail,
+            smtp,
+            sink_path,
+            waitlist_claim_url,
+            template_dir,
+        })
+    }
+}
+
+pub struct GmailConfig {
+    // OAuth クライアントシークレット、またはサービスアカウントキーの JSON ファイル
+    pub secret_path: String,
+    // 取得したトークンを保存するファイル
+    pub token_cache_path: Option<String>,
+    pub flow: GoogleAuthFlow,
+}
+
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+// outbox に積まれた通知を送信する dispatcher の設定
+// いずれも環境変数が未設定の場合は既定値を使う
+#[derive(Debug, Clone, Copy)]
+pub struct OutboxConfig {
+    // 送信を諦めて dead とするまでの試行回数
+    pub max_attempts: i32,
+    // 再試行までの待ち時間の初期値（秒）。失敗するたびに 2 倍にする
+    pub backoff_base_secs: u64,
+    // 再試行までの待ち時間の上限（秒）
+    pub backoff_max_secs: u64,
+    // 送信対象がない場合に次に確認するまでの間隔（秒）
+    pub poll_interval_secs: u64,
+    // 一度に取得するメッセージの件数
+    pub batch_size: i64,
+}
+
+impl OutboxConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            max_attempts: var_or("OUTBOX_MAX_ATTEMPTS", 8)?,
+            backoff_base_secs: var_or("OUTBOX_BACKOFF_BASE_SECS", 30)?,
+            backoff_max_secs: var_or("OUTBOX_BACKOFF_MAX_SECS", 3600)?,
+            poll_interval_secs: var_or("OUTBOX_POLL_INTERVAL_SECS", 10)?,
+            batch_size: var_or("OUTBOX_BATCH_SIZE", 20)?,
+        })
+    }
+}
+
+// 送信時刻を迎えたリマインダーを outbox に積む scheduler の設定
+#[derive(Debug, Clone, Copy)]
+pub struct ReminderConfig {
+    // 一度に処理する予約の件数
+    pub batch_size: i64,
+    // 次のリマインダーの送信時刻まで待つ時間の上限（秒）
+    // 待機中に追加された予約のリマインダーも、この間隔で拾われる
+    pub max_idle_secs: u64,
+}
+
+impl ReminderConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            batch_size: var_or("REMINDER_BATCH_SIZE", 50)?,
+            max_idle_secs: var_or("REMINDER_MAX_IDLE_SECS", 60)?,
+        })
+    }
+}
+
+// 終了時刻を過ぎた予約を利用終了にする watcher の設定
+#[derive(Debug, Clone, Copy)]
+pub struct WatcherConfig {
+    // 一度に処理する予約の件数
+    pub batch_size: i64,
+    // 処理の間隔（秒）
+    pub interval_secs: u64,
+}
+
+impl WatcherConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            batch_size: var_or("WATCHER_BATCH_SIZE", 100)?,
+            interval_secs: var_or("WATCHER_INTERVAL_SECS", 30)?,
+        })
+    }
+}
+
+// webhook を送信する dispatcher の設定
+// いずれも環境変数が未設定の場合は既定値を使う
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookConfig {
+    // 送信を諦めて dead とするまでの試行回数
+    pub max_attempts: i32,
+    // 再試行までの待ち時間の初期値（秒）。失敗するたびに 2 倍にする
+    pub backoff_base_secs: u64,
+    // 再試行までの待ち時間の上限（秒）
+    pub backoff_max_secs: u64,
+    // 送信対象がない場合に次に確認するまでの間隔（秒）
+    pub poll_interval_secs: u64,
+    // 一度に取得する送信の件数
+    pub batch_size: i64,
+    // 送信先がレスポンスを返すまで待つ時間（秒）
+    pub timeout_secs: u64,
+}
+
+impl WebhookConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            max_attempts: var_or("WEBHOOK_MAX_ATTEMPTS", 8)?,
+            backoff_base_secs: var_or("WEBHOOK_BACKOFF_BASE_SECS", 10)?,
+            backoff_max_secs: var_or("WEBHOOK_BACKOFF_MAX_SECS", 3600)?,
+            poll_interval_secs: var_or("WEBHOOK_POLL_INTERVAL_SECS", 5)?,
+            batch_size: var_or("WEBHOOK_BATCH_SIZE", 20)?,
+            timeout_secs: var_or("WEBHOOK_TIMEOUT_SECS", 10)?,
+        })
+    }
+}
+
+// 環境変数が未設定の場合は既定値を使う
+fn var_or<T: std::str::FromStr>(key: &str, default: T) -> Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(key) {
+        Err(_) => Ok(default),
+        Ok(v) => v.parse().with_context(|| format!("invalid {key}: {v}")),
+    }
+}
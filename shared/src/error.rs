@@ -1,4 +1,11 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use crate::i18n::{self, ErrorMessage, FieldErrorMessage, Language};
+use crate::request_id;
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,9 +17,22 @@ pub enum AppError {
     #[error("Other error: {0}")]
     AnyhowError(#[from] anyhow::Error),
     #[error("{0}")]
-    UnprocessableEntity(String),
+    UnprocessableEntity(ErrorMessage),
     #[error("{0}")]
-    EntityNotFound(String),
+    EntityNotFound(ErrorMessage),
+    // 予約時刻やスペースのルールなど、クライアントが理由ごとに処理を分けたい 422 のエラー
+    #[error("{message}")]
+    RuleViolation {
+        code: ErrorCode,
+        message: ErrorMessage,
+    },
+    // 他の予約と時間帯が重なる、または同時に行われた操作と競合した
+    // 競合相手の予約が特定できた場合はその ID を持つ
+    #[error("{message}")]
+    Conflict {
+        message: ErrorMessage,
+        conflicting_reservation_id: Option<uuid::Uuid>,
+    },
     #[error("OAuth error: {0}")]
     ExternalServiceError(String),
     #[error("{0}")]
@@ -39,19 +59,101 @@ pub enum AppError {
     ForbiddenOperation,
     #[error("{0}")]
     ConversionEntityError(String),
+    #[error("Mail template error: {0}")]
+    MailTemplateError(String),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let status_code = match self {
-            AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
-            AppError::EntityNotFound(_) => StatusCode::NOT_FOUND,
-            AppError::ValidationError(_) | AppError::ConvertToUuidError(_) => {
-                StatusCode::BAD_REQUEST
-            }
-            AppError::UnauthenticatedError | AppError::ForbiddenOperation => StatusCode::FORBIDDEN,
-            AppError::UnauthorizedError => StatusCode::UNAUTHORIZED,
-            e @ (AppError::TransactionError(_)
+// クライアントが処理を分けたり、表示する文言を選んだりするためのエラーコード
+// 一度公開したコードは変えないこと
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    // リクエストの値が入力チェックを通らなかった
+    ValidationFailed,
+    // ID の形式が正しくない
+    InvalidId,
+    // リクエストの内容では処理できない（予約開始時刻が過去である、など）
+    UnprocessableEntity,
+    // 指定したデータが存在しない
+    NotFound,
+    // 他の予約と時間帯が重なる、または同時に行われた操作と競合した
+    ReservationConflict,
+    // 予約開始時刻が現在時刻より前である
+    ReservationStartInPast,
+    // 予約開始時刻が予約終了時刻より前でない
+    InvalidReservationPeriod,
+    // 予約がすでに開始しているため変更できない
+    ReservationAlreadyStarted,
+    // スペースが停止されている
+    SpaceInactive,
+    // スペースの営業時間外である
+    OutsideOpeningHours,
+    // スペースの利用停止期間と重なる
+    SpaceBlackout,
+    // 予約の長さや件数など、スペースの予約のルールを満たしていない
+    BookingPolicyViolation,
+    // ログインしていない、またはログインに失敗した
+    Unauthenticated,
+    // 認可情報が誤っている
+    Unauthorized,
+    // 操作を行う権限がない
+    Forbidden,
+    // サーバー内部のエラー
+    InternalError,
+}
+
+// 入力チェックを通らなかった項目と、その理由
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+// エラーのレスポンスボディ（application/problem+json）
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorResponse {
+    pub status: u16,
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub field_errors: Vec<FieldError>,
+    // 競合相手の予約が特定できた場合は、その ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicting_reservation_id: Option<uuid::Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl AppError {
+    pub fn rule_violation(code: ErrorCode, message: ErrorMessage) -> Self {
+        Self::RuleViolation { code, message }
+    }
+
+    // 個々のエラーの詳しい文言。ない場合はエラーコードごとの文言を使う
+    fn message(&self) -> Option<&ErrorMessage> {
+        match self {
+            AppError::UnprocessableEntity(message)
+            | AppError::EntityNotFound(message)
+            | AppError::RuleViolation { message, .. }
+            | AppError::Conflict { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::ValidationError(_) => ErrorCode::ValidationFailed,
+            AppError::ConvertToUuidError(_) => ErrorCode::InvalidId,
+            AppError::UnprocessableEntity(_) => ErrorCode::UnprocessableEntity,
+            AppError::RuleViolation { code, .. } => *code,
+            AppError::EntityNotFound(_) => ErrorCode::NotFound,
+            AppError::Conflict { .. } => ErrorCode::ReservationConflict,
+            AppError::UnauthenticatedError => ErrorCode::Unauthenticated,
+            AppError::UnauthorizedError => ErrorCode::Unauthorized,
+            AppError::ForbiddenOperation => ErrorCode::Forbidden,
+            AppError::TransactionError(_)
             | AppError::ExternalServiceError(_)
             | AppError::DbQueryError(_)
             | AppError::IoError(_)
@@ -61,18 +163,174 @@ impl IntoResponse for AppError {
             | AppError::NoRowsAffectedError(_)
             | AppError::KeyValueStoreError(_)
             | AppError::BcryptError(_)
-            | AppError::ConversionEntityError(_)) => {
-                tracing::error!(
-                error.cause_chain = ?e,
-                error.message = %e,
-                "Unexpected error happened"
-                );
-                StatusCode::INTERNAL_SERVER_ERROR
-            }
+            | AppError::ConversionEntityError(_)
+            | AppError::MailTemplateError(_) => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self.code() {
+            ErrorCode::ValidationFailed | ErrorCode::InvalidId => StatusCode::BAD_REQUEST,
+            ErrorCode::UnprocessableEntity
+            | ErrorCode::ReservationStartInPast
+            | ErrorCode::InvalidReservationPeriod
+            | ErrorCode::ReservationAlreadyStarted
+            | ErrorCode::SpaceInactive
+            | ErrorCode::OutsideOpeningHours
+            | ErrorCode::SpaceBlackout
+            | ErrorCode::BookingPolicyViolation => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::ReservationConflict => StatusCode::CONFLICT,
+            ErrorCode::Unauthenticated | ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // レスポンスボディを組み立てる。request_id は処理中のリクエストの ID
+    // 文言はいずれもカタログから、リクエストの言語で組み立てる
+    // 個々のエラーの詳しい文言があればその文言を、なければエラーコードごとの文言を返す
+    pub fn to_response_body(
+        &self,
+        request_id: Option<String>,
+        language: Language,
+    ) -> ErrorResponse {
+        let code = self.code();
+        let message = match self.message() {
+            Some(message) => message.localize(language),
+            None => i18n::error_message(code, language).to_string(),
+        };
+        let field_errors = match self {
+            AppError::ValidationError(report) => report
+                .iter()
+                .map(|(path, error)| FieldError {
+                    field: path.to_string(),
+                    message: FieldErrorMessage::parse(error.message()).localize(language),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let conflicting_reservation_id = match self {
+            AppError::Conflict {
+                conflicting_reservation_id,
+                ..
+            } => *conflicting_reservation_id,
+            _ => None,
         };
-        status_code.into_response()
+        ErrorResponse {
+            status: self.status_code().as_u16(),
+            code,
+            message,
+            field_errors,
+            conflicting_reservation_id,
+            request_id,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        if self.code() == ErrorCode::InternalError {
+            tracing::error!(
+            error.cause_chain = ?self,
+            error.message = %self,
+            "Unexpected error happened"
+            );
+        }
+        let body = self.to_response_body(request_id::current(), i18n::current());
+        (
+            self.status_code(),
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/problem+json"),
+            )],
+            Json(body),
+        )
+            .into_response()
     }
 }
 
 // エラー型が `AppError` なものを扱える `Result` 型
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use garde::Validate;
+
+    #[derive(Validate)]
+    struct Request {
+        #[garde(length(min = 1))]
+        name: String,
+    }
+
+    #[test]
+    fn test_error_response_body() {
+        let report = Request { name: "".into() }.validate(&()).unwrap_err();
+        let body = AppError::from(report).to_response_body(Some("req-1".into()), Language::Ja);
+        assert_eq!(body.status, 400);
+        assert_eq!(body.code, ErrorCode::ValidationFailed);
+        assert_eq!(body.field_errors.len(), 1);
+        assert_eq!(body.field_errors[0].field, "name");
+        assert_eq!(
+            body.field_errors[0].message,
+            "長さは 1 以上で指定してください。"
+        );
+        assert_eq!(body.request_id.as_deref(), Some("req-1"));
+
+        let reservation_id = uuid::Uuid::new_v4();
+        let body = AppError::Conflict {
+            message: ErrorMessage::ReservationOverlap {
+                space_id: "space1".into(),
+            },
+            conflicting_reservation_id: Some(reservation_id),
+        }
+        .to_response_body(None, Language::Ja);
+        assert_eq!(body.status, 409);
+        assert_eq!(body.code, ErrorCode::ReservationConflict);
+        assert_eq!(
+            body.message,
+            "スペース（space1）は指定時間帯にすでに予約が存在します。"
+        );
+        assert_eq!(body.conflicting_reservation_id, Some(reservation_id));
+
+        // サーバー内部のエラーは、原因をクライアントに返さない
+        let body =
+            AppError::NoRowsAffectedError("secret".into()).to_response_body(None, Language::Ja);
+        assert_eq!(body.status, 500);
+        assert_eq!(body.code, ErrorCode::InternalError);
+        assert_eq!(body.message, "サーバー内部でエラーが発生しました。");
+    }
+
+    #[test]
+    fn test_localized_error_response_body() {
+        let error = AppError::rule_violation(
+            ErrorCode::BookingPolicyViolation,
+            ErrorMessage::DurationTooLong { max: 120 },
+        );
+        let body = error.to_response_body(None, Language::En);
+        assert_eq!(body.status, 422);
+        assert_eq!(body.code, ErrorCode::BookingPolicyViolation);
+        assert_eq!(
+            body.message,
+            "The reservation must be at most 120 minutes long (maximum duration)."
+        );
+        let body = error.to_response_body(None, Language::Ja);
+        assert_eq!(
+            body.message,
+            "予約時間は 120 分以下である必要があります（最長予約時間）。"
+        );
+
+        // 詳しい文言のないエラーは、エラーコードごとの文言をカタログから返す
+        let body = AppError::ForbiddenOperation.to_response_body(None, Language::En);
+        assert_eq!(
+            body.message,
+            "You are not allowed to perform this operation."
+        );
+
+        // 入力チェックの理由も、リクエストの言語で返す
+        let report = Request { name: "".into() }.validate(&()).unwrap_err();
+        let body = AppError::from(report).to_response_body(None, Language::En);
+        assert_eq!(body.field_errors[0].message, "Length must be at least 1.");
+    }
+}
@@ -0,0 +1,860 @@
+use super::Language;
+use chrono::{DateTime, Local, Weekday};
+use std::fmt;
+
+// スペースの予約のルールの項目。ルールの設定値やルール違反の文言で、項目名として使う
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookingRule {
+    MinDuration,
+    MaxDuration,
+    SlotGranularity,
+    MaxDaysInAdvance,
+    MaxActiveReservations,
+    MaxReservationsPerWeek,
+    MaxRecentNoShows,
+}
+
+impl BookingRule {
+    pub fn name(self, language: Language) -> &'static str {
+        use BookingRule::*;
+        match language {
+            Language::Ja => match self {
+                MinDuration => "最短予約時間",
+                MaxDuration => "最長予約時間",
+                SlotGranularity => "予約時間の単位",
+                MaxDaysInAdvance => "予約可能な日数",
+                MaxActiveReservations => "同時に持てる予約数",
+                MaxReservationsPerWeek => "1 週間の予約数",
+                MaxRecentNoShows => "チェックインしなかった回数",
+            },
+            Language::En => match self {
+                MinDuration => "minimum duration",
+                MaxDuration => "maximum duration",
+                SlotGranularity => "slot granularity",
+                MaxDaysInAdvance => "booking window",
+                MaxActiveReservations => "active reservation limit",
+                MaxReservationsPerWeek => "weekly reservation limit",
+                MaxRecentNoShows => "no-show limit",
+            },
+        }
+    }
+}
+
+// 個々のエラーの詳しい文言
+// 文言に埋め込む値を持ち、言語ごとの文言は localize でカタログから組み立てる
+// ID は表示用の文字列で持つ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorMessage {
+    // 見つからない
+    SpaceNotFound {
+        space_id: String,
+    },
+    ReservationNotFound {
+        reservation_id: String,
+    },
+    ReservationNotFoundInSpace {
+        reservation_id: String,
+        space_id: String,
+    },
+    ReservationSeriesNotFound {
+        reservation_series_id: String,
+    },
+    SpaceBlackoutNotFound {
+        space_blackout_id: String,
+    },
+    UserNotFound {
+        user_id: String,
+    },
+    WaitlistEntryNotFound {
+        waitlist_entry_id: String,
+    },
+    WaitlistOfferNotFound,
+    OutboxMessageNotFound {
+        outbox_message_id: String,
+    },
+    RetryableOutboxMessageNotFound {
+        outbox_message_id: String,
+    },
+    WebhookNotFound {
+        webhook_id: String,
+    },
+    WebhookDeliveryNotFound {
+        webhook_delivery_id: String,
+    },
+    CalendarFeedTokenNotFound,
+    NotificationKindNotFound {
+        kind: String,
+    },
+
+    // リクエストの内容では処理できない
+    InvalidEndStatus {
+        status: String,
+    },
+    InvalidDeactivationStatus {
+        status: String,
+    },
+    InvalidStatusTransition {
+        from: String,
+        to: String,
+    },
+    InvalidStatusFilter {
+        status: String,
+    },
+    InvalidSearchPeriod,
+    SearchPeriodTooLong {
+        max_days: i64,
+    },
+    SlotMinutesOutOfRange {
+        min: i64,
+        max: i64,
+    },
+    InvalidSlotFormat {
+        slot: String,
+    },
+    InvalidTimeFormat {
+        value: String,
+    },
+    InvalidTimezone {
+        timezone: String,
+    },
+    AlreadyCheckedIn {
+        checked_in_at: DateTime<Local>,
+    },
+    CheckInNotOpenYet {
+        opens_at: DateTime<Local>,
+    },
+    CheckInClosed {
+        closes_at: DateTime<Local>,
+    },
+    InvalidCheckInCode,
+    CheckInGraceOutOfRange {
+        max: i64,
+    },
+    CheckInCodeLength {
+        min: i64,
+        max: i64,
+    },
+    AllOccurrencesConflict,
+    NotReservationSeries {
+        reservation_id: String,
+    },
+    TooManyOccurrences {
+        max: i64,
+    },
+    RecurrenceIntervalOutOfRange {
+        max: i64,
+    },
+    RecurrenceEndRequired,
+    OccurrenceCountOutOfRange {
+        max: i64,
+    },
+    RecurrenceEndBeforeStart,
+    WeekdayRequiresWeekly,
+    InvalidOpeningHours {
+        weekday: Weekday,
+        open: String,
+        close: String,
+    },
+    OverlappingOpeningHours {
+        weekday: Weekday,
+    },
+    InvalidBlackoutPeriod,
+    BookingRuleNotPositive {
+        rule: BookingRule,
+    },
+    MinDurationExceedsMax,
+    SlotGranularityNotDivisor,
+    DuplicateRoleRule {
+        role: String,
+    },
+    ApproversRequired,
+    TooManyApprovers {
+        max: i64,
+    },
+    DuplicateApprover {
+        approver: String,
+    },
+    UnknownApprover,
+    NoApprovers,
+    NotPendingReview {
+        reservation_id: String,
+    },
+    ReviewAfterStart {
+        reservation_id: String,
+    },
+    WaitlistOwnReservation,
+    WaitlistSlotAvailable,
+    WaitlistEntryClosed {
+        waitlist_entry_id: String,
+    },
+    WaitlistOfferUsed,
+    WaitlistOfferExpired {
+        expires_at: DateTime<Local>,
+    },
+
+    // 予約時刻やスペースのルールに合わない
+    ReservationAlreadyStarted {
+        reservation_id: String,
+    },
+    InvalidReservationPeriod,
+    ReservationStartInPast,
+    SpaceInactive {
+        space_id: String,
+    },
+    SpaceBlackout {
+        space_id: String,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        reason: String,
+    },
+    // opening_hours はその曜日の営業時間の開始と終了（HH:MM）。空の場合は休業日
+    OutsideOpeningHours {
+        space_id: String,
+        weekday: Weekday,
+        opening_hours: Vec<(String, String)>,
+        timezone: String,
+    },
+    DurationTooShort {
+        min: i64,
+    },
+    DurationTooLong {
+        max: i64,
+    },
+    SlotGranularityMismatch {
+        granularity: i64,
+    },
+    TooFarInAdvance {
+        days: i64,
+    },
+    TooManyActiveReservations {
+        max: i64,
+    },
+    TooManyReservationsPerWeek {
+        max: i64,
+    },
+    TooManyRecentNoShows {
+        days: i64,
+        max: i64,
+    },
+
+    // 他の予約や操作と競合した
+    ReservationOverlap {
+        space_id: String,
+    },
+    ConcurrentOperation,
+    AlreadyOnWaitlist,
+    OfferedToWaitlist {
+        space_id: String,
+        offer_expires_at: DateTime<Local>,
+    },
+}
+
+impl ErrorMessage {
+    pub fn localize(&self, language: Language) -> String {
+        match language {
+            Language::Ja => self.japanese(),
+            Language::En => self.english(),
+        }
+    }
+
+    fn japanese(&self) -> String {
+        use ErrorMessage::*;
+        let rule = |rule: BookingRule| rule.name(Language::Ja);
+        match self {
+            SpaceNotFound { space_id } => format!("スペース（{space_id}）が見つかりませんでした。"),
+            ReservationNotFound { reservation_id } => {
+                format!("予約（ID={reservation_id}）が見つかりませんでした。")
+            }
+            ReservationNotFoundInSpace {
+                reservation_id,
+                space_id,
+            } => format!("予約（ID={reservation_id}）がスペース（{space_id}）に存在しません。"),
+            ReservationSeriesNotFound {
+                reservation_series_id,
+            } => format!("繰り返し予約（ID={reservation_series_id}）が見つかりませんでした。"),
+            SpaceBlackoutNotFound { space_blackout_id } => {
+                format!("利用停止期間（ID={space_blackout_id}）が見つかりませんでした。")
+            }
+            UserNotFound { user_id } => format!("ユーザー（ID={user_id}）が見つかりませんでした。"),
+            WaitlistEntryNotFound { waitlist_entry_id } => {
+                format!("キャンセル待ち（ID={waitlist_entry_id}）が見つかりませんでした。")
+            }
+            WaitlistOfferNotFound => "キャンセル待ちの案内が見つかりませんでした。".into(),
+            OutboxMessageNotFound { outbox_message_id } => {
+                format!("outbox メッセージ（{outbox_message_id}）が見つかりませんでした。")
+            }
+            RetryableOutboxMessageNotFound { outbox_message_id } => {
+                format!("再送できる outbox メッセージ（{outbox_message_id}）が見つかりませんでした。")
+            }
+            WebhookNotFound { webhook_id } => {
+                format!("webhook（ID={webhook_id}）が見つかりませんでした。")
+            }
+            WebhookDeliveryNotFound {
+                webhook_delivery_id,
+            } => format!("webhook の送信（ID={webhook_delivery_id}）が見つかりませんでした。"),
+            CalendarFeedTokenNotFound => "予定表のトークンが発行されていません。".into(),
+            NotificationKindNotFound { kind } => format!("通知の種類（{kind}）がありません。"),
+
+            InvalidEndStatus { status } => {
+                format!("予約を {status} の状態で終了することはできません。")
+            }
+            InvalidDeactivationStatus { status } => {
+                format!("スペースの停止による予約の状態を {status} にすることはできません。")
+            }
+            InvalidStatusTransition { from, to } => {
+                format!("予約の状態を {from} から {to} に変更することはできません。")
+            }
+            InvalidStatusFilter { status } => format!("予約の状態（{status}）が正しくありません。"),
+            InvalidSearchPeriod => "検索の開始日時は終了日時より前である必要があります。".into(),
+            SearchPeriodTooLong { max_days } => format!("検索できる期間は {max_days} 日までです。"),
+            SlotMinutesOutOfRange { min, max } => {
+                format!("枠の長さは {min} 分以上 {max} 分以下で指定してください。")
+            }
+            InvalidSlotFormat { slot } => format!("枠の長さ（{slot}）の形式が正しくありません。"),
+            InvalidTimeFormat { value } => format!("時刻（{value}）の形式が正しくありません。"),
+            InvalidTimezone { timezone } => format!("タイムゾーン（{timezone}）が正しくありません。"),
+            AlreadyCheckedIn { checked_in_at } => format!(
+                "この予約は {} にチェックイン済みです。",
+                format_datetime(checked_in_at)
+            ),
+            CheckInNotOpenYet { opens_at } => {
+                format!("チェックインは {} から受け付けます。", format_datetime(opens_at))
+            }
+            CheckInClosed { closes_at } => {
+                format!("チェックインの期限（{}）を過ぎています。", format_datetime(closes_at))
+            }
+            InvalidCheckInCode => "チェックインのコードが正しくありません。".into(),
+            CheckInGraceOutOfRange { max } => {
+                format!("チェックインの猶予時間は 1 分以上 {max} 分以下で指定してください。")
+            }
+            CheckInCodeLength { min, max } => {
+                format!("チェックインのコードは {min} 〜 {max} 文字の英数字で指定してください。")
+            }
+            AllOccurrencesConflict => {
+                "すべての回が既存の予約と重なっているため、予約できませんでした。".into()
+            }
+            NotReservationSeries { reservation_id } => {
+                format!("予約（ID={reservation_id}）は繰り返し予約ではありません。")
+            }
+            TooManyOccurrences { max } => format!("繰り返し予約は {max} 回までです。"),
+            RecurrenceIntervalOutOfRange { max } => {
+                format!("繰り返しの間隔は 1 以上 {max} 以下で指定してください。")
+            }
+            RecurrenceEndRequired => {
+                "繰り返しの回数か終了日時のどちらかを指定してください。".into()
+            }
+            OccurrenceCountOutOfRange { max } => {
+                format!("繰り返しの回数は 1 以上 {max} 以下で指定してください。")
+            }
+            RecurrenceEndBeforeStart => {
+                "繰り返しの終了日時は初回の予約開始時刻より後である必要があります。".into()
+            }
+            WeekdayRequiresWeekly => "曜日の指定は毎週の繰り返しでのみ使用できます。".into(),
+            InvalidOpeningHours {
+                weekday,
+                open,
+                close,
+            } => format!(
+                "{}の営業時間（{open}〜{close}）が正しくありません。",
+                weekday_name(*weekday, Language::Ja)
+            ),
+            OverlappingOpeningHours { weekday } => format!(
+                "{}の営業時間が重なっています。",
+                weekday_name(*weekday, Language::Ja)
+            ),
+            InvalidBlackoutPeriod => {
+                "利用停止の開始日時は終了日時より前である必要があります。".into()
+            }
+            BookingRuleNotPositive { rule: r } => format!("{}は 1 以上で指定してください。", rule(*r)),
+            MinDurationExceedsMax => "最短予約時間は最長予約時間以下で指定してください。".into(),
+            SlotGranularityNotDivisor => {
+                "予約時間の単位は 1 日（1440 分）を割り切れる値で指定してください。".into()
+            }
+            DuplicateRoleRule { role } => format!("ロール（{role}）のルールが重複しています。"),
+            ApproversRequired => {
+                "予約に承認が必要な場合は、承認者を 1 人以上指定してください。".into()
+            }
+            TooManyApprovers { max } => format!("承認者は {max} 人まで指定できます。"),
+            DuplicateApprover { approver } => format!("承認者（{approver}）が重複しています。"),
+            UnknownApprover => "承認者に存在しないユーザーが含まれています。".into(),
+            NoApprovers => "このスペースには承認者がいないため、予約できません。".into(),
+            NotPendingReview { reservation_id } => {
+                format!("予約（ID={reservation_id}）は承認待ちではありません。")
+            }
+            ReviewAfterStart { reservation_id } => format!(
+                "予約（ID={reservation_id}）は予約開始時刻を過ぎているため、承認・却下できません。"
+            ),
+            WaitlistOwnReservation => "指定の時間帯には、すでにご自身の予約があります。".into(),
+            WaitlistSlotAvailable => {
+                "指定の時間帯には予約が入っていません。キャンセル待ちではなく、予約を作成してください。"
+                    .into()
+            }
+            WaitlistEntryClosed { waitlist_entry_id } => {
+                format!("キャンセル待ち（ID={waitlist_entry_id}）はすでに終了しています。")
+            }
+            WaitlistOfferUsed => "この案内はすでに使われたか、取り下げられています。".into(),
+            WaitlistOfferExpired { expires_at } => format!(
+                "案内の期限（{}）を過ぎているため、予約できません。",
+                format_datetime(expires_at)
+            ),
+
+            ReservationAlreadyStarted { reservation_id } => {
+                format!("予約（ID={reservation_id}）はすでに開始しているため変更できません。")
+            }
+            InvalidReservationPeriod => {
+                "予約開始時刻は予約終了時刻より前である必要があります。".into()
+            }
+            ReservationStartInPast => "予約開始時刻は現在時刻より後である必要があります。".into(),
+            SpaceInactive { space_id } => {
+                format!("スペース（{space_id}）は現在利用できません（is_active = false）")
+            }
+            SpaceBlackout {
+                space_id,
+                start,
+                end,
+                reason,
+            } => format!(
+                "スペース（{space_id}）は {} から {} まで利用できません（{reason}）。",
+                format_datetime(start),
+                format_datetime(end)
+            ),
+            OutsideOpeningHours {
+                space_id,
+                weekday,
+                opening_hours,
+                timezone,
+            } => {
+                let weekday = weekday_name(*weekday, Language::Ja);
+                let description = if opening_hours.is_empty() {
+                    format!("{weekday}は休業日です（{timezone}）。")
+                } else {
+                    let ranges = opening_hours
+                        .iter()
+                        .map(|(open, close)| format!("{open}〜{close}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{weekday}の営業時間は {ranges} です（{timezone}）。")
+                };
+                format!("スペース（{space_id}）の営業時間外です。{description}")
+            }
+            DurationTooShort { min } => format!(
+                "予約時間は {min} 分以上である必要があります（{}）。",
+                rule(BookingRule::MinDuration)
+            ),
+            DurationTooLong { max } => format!(
+                "予約時間は {max} 分以下である必要があります（{}）。",
+                rule(BookingRule::MaxDuration)
+            ),
+            SlotGranularityMismatch { granularity } => format!(
+                "予約の開始時刻と長さは {granularity} 分単位で指定してください（{}）。",
+                rule(BookingRule::SlotGranularity)
+            ),
+            TooFarInAdvance { days } => format!(
+                "予約できるのは {days} 日先までです（{}）。",
+                rule(BookingRule::MaxDaysInAdvance)
+            ),
+            TooManyActiveReservations { max } => format!(
+                "このスペースに同時に持てる予約は {max} 件までです（{}）。",
+                rule(BookingRule::MaxActiveReservations)
+            ),
+            TooManyReservationsPerWeek { max } => format!(
+                "このスペースに 1 週間に入れられる予約は {max} 件までです（{}）。",
+                rule(BookingRule::MaxReservationsPerWeek)
+            ),
+            TooManyRecentNoShows { days, max } => format!(
+                "直近 {days} 日間にチェックインしなかった予約が {max} 件以上あるため、このスペースは予約できません（{}）。",
+                rule(BookingRule::MaxRecentNoShows)
+            ),
+
+            ReservationOverlap { space_id } => {
+                format!("スペース（{space_id}）は指定時間帯にすでに予約が存在します。")
+            }
+            ConcurrentOperation => "他の予約操作と競合しました。再度お試しください。".into(),
+            AlreadyOnWaitlist => "同じ時間帯のキャンセル待ちにすでに登録しています。".into(),
+            OfferedToWaitlist {
+                space_id,
+                offer_expires_at,
+            } => format!(
+                "スペース（{space_id}）の指定時間帯は、キャンセル待ちの方に {} までご案内中です。",
+                format_datetime(offer_expires_at)
+            ),
+        }
+    }
+
+    fn english(&self) -> String {
+        use ErrorMessage::*;
+        let rule = |rule: BookingRule| rule.name(Language::En);
+        match self {
+            SpaceNotFound { space_id } => format!("Space ({space_id}) was not found."),
+            ReservationNotFound { reservation_id } => {
+                format!("Reservation (ID={reservation_id}) was not found.")
+            }
+            ReservationNotFoundInSpace {
+                reservation_id,
+                space_id,
+            } => format!("Reservation (ID={reservation_id}) does not exist in space ({space_id})."),
+            ReservationSeriesNotFound {
+                reservation_series_id,
+            } => format!("Recurring reservation (ID={reservation_series_id}) was not found."),
+            SpaceBlackoutNotFound { space_blackout_id } => {
+                format!("Blackout period (ID={space_blackout_id}) was not found.")
+            }
+            UserNotFound { user_id } => format!("User (ID={user_id}) was not found."),
+            WaitlistEntryNotFound { waitlist_entry_id } => {
+                format!("Waitlist entry (ID={waitlist_entry_id}) was not found.")
+            }
+            WaitlistOfferNotFound => "The waitlist offer was not found.".into(),
+            OutboxMessageNotFound { outbox_message_id } => {
+                format!("Outbox message ({outbox_message_id}) was not found.")
+            }
+            RetryableOutboxMessageNotFound { outbox_message_id } => {
+                format!("No retryable outbox message ({outbox_message_id}) was found.")
+            }
+            WebhookNotFound { webhook_id } => format!("Webhook (ID={webhook_id}) was not found."),
+            WebhookDeliveryNotFound {
+                webhook_delivery_id,
+            } => format!("Webhook delivery (ID={webhook_delivery_id}) was not found."),
+            CalendarFeedTokenNotFound => "No calendar feed token has been issued.".into(),
+            NotificationKindNotFound { kind } => format!("Unknown notification kind ({kind})."),
+
+            InvalidEndStatus { status } => {
+                format!("A reservation cannot be ended with the status {status}.")
+            }
+            InvalidDeactivationStatus { status } => format!(
+                "Reservations cannot be set to {status} when the space is deactivated."
+            ),
+            InvalidStatusTransition { from, to } => {
+                format!("The reservation status cannot change from {from} to {to}.")
+            }
+            InvalidStatusFilter { status } => format!("Invalid reservation status ({status})."),
+            InvalidSearchPeriod => "The search period must start before it ends.".into(),
+            SearchPeriodTooLong { max_days } => {
+                format!("The search period can be at most {max_days} days.")
+            }
+            SlotMinutesOutOfRange { min, max } => {
+                format!("The slot length must be between {min} and {max} minutes.")
+            }
+            InvalidSlotFormat { slot } => format!("The slot length ({slot}) is not valid."),
+            InvalidTimeFormat { value } => format!("The time ({value}) is not valid."),
+            InvalidTimezone { timezone } => format!("The time zone ({timezone}) is not valid."),
+            AlreadyCheckedIn { checked_in_at } => format!(
+                "This reservation was already checked in at {}.",
+                format_datetime(checked_in_at)
+            ),
+            CheckInNotOpenYet { opens_at } => {
+                format!("Check-in opens at {}.", format_datetime(opens_at))
+            }
+            CheckInClosed { closes_at } => format!(
+                "The check-in deadline ({}) has passed.",
+                format_datetime(closes_at)
+            ),
+            InvalidCheckInCode => "The check-in code is incorrect.".into(),
+            CheckInGraceOutOfRange { max } => {
+                format!("The check-in grace period must be between 1 and {max} minutes.")
+            }
+            CheckInCodeLength { min, max } => format!(
+                "The check-in code must be {min} to {max} alphanumeric characters."
+            ),
+            AllOccurrencesConflict => {
+                "Every occurrence overlaps an existing reservation, so nothing was booked.".into()
+            }
+            NotReservationSeries { reservation_id } => {
+                format!("Reservation (ID={reservation_id}) is not part of a recurring reservation.")
+            }
+            TooManyOccurrences { max } => {
+                format!("A recurring reservation can have at most {max} occurrences.")
+            }
+            RecurrenceIntervalOutOfRange { max } => {
+                format!("The recurrence interval must be between 1 and {max}.")
+            }
+            RecurrenceEndRequired => {
+                "Specify either the number of occurrences or the end date of the recurrence."
+                    .into()
+            }
+            OccurrenceCountOutOfRange { max } => {
+                format!("The number of occurrences must be between 1 and {max}.")
+            }
+            RecurrenceEndBeforeStart => {
+                "The recurrence must end after the first reservation starts.".into()
+            }
+            WeekdayRequiresWeekly => "Weekdays can only be specified for weekly recurrences.".into(),
+            InvalidOpeningHours {
+                weekday,
+                open,
+                close,
+            } => format!(
+                "The opening hours on {} ({open}-{close}) are not valid.",
+                weekday_name(*weekday, Language::En)
+            ),
+            OverlappingOpeningHours { weekday } => format!(
+                "The opening hours on {} overlap.",
+                weekday_name(*weekday, Language::En)
+            ),
+            InvalidBlackoutPeriod => "The blackout period must start before it ends.".into(),
+            BookingRuleNotPositive { rule: r } => format!("The {} must be at least 1.", rule(*r)),
+            MinDurationExceedsMax => {
+                "The minimum duration must not exceed the maximum duration.".into()
+            }
+            SlotGranularityNotDivisor => {
+                "The slot granularity must evenly divide one day (1440 minutes).".into()
+            }
+            DuplicateRoleRule { role } => format!("The rules for role ({role}) are duplicated."),
+            ApproversRequired => {
+                "Specify at least one approver when reservations require approval.".into()
+            }
+            TooManyApprovers { max } => format!("You can specify up to {max} approvers."),
+            DuplicateApprover { approver } => format!("The approver ({approver}) is duplicated."),
+            UnknownApprover => "The approvers include a user who does not exist.".into(),
+            NoApprovers => "This space has no approvers, so it cannot be reserved.".into(),
+            NotPendingReview { reservation_id } => {
+                format!("Reservation (ID={reservation_id}) is not awaiting approval.")
+            }
+            ReviewAfterStart { reservation_id } => format!(
+                "Reservation (ID={reservation_id}) has already started and can no longer be approved or rejected."
+            ),
+            WaitlistOwnReservation => "You already have a reservation in this time slot.".into(),
+            WaitlistSlotAvailable => {
+                "This time slot is not booked. Create a reservation instead of joining the waitlist."
+                    .into()
+            }
+            WaitlistEntryClosed { waitlist_entry_id } => {
+                format!("Waitlist entry (ID={waitlist_entry_id}) has already ended.")
+            }
+            WaitlistOfferUsed => "This offer has already been used or withdrawn.".into(),
+            WaitlistOfferExpired { expires_at } => format!(
+                "The offer expired at {}, so it can no longer be booked.",
+                format_datetime(expires_at)
+            ),
+
+            ReservationAlreadyStarted { reservation_id } => format!(
+                "Reservation (ID={reservation_id}) has already started and can no longer be changed."
+            ),
+            InvalidReservationPeriod => "The reservation must start before it ends.".into(),
+            ReservationStartInPast => "The reservation must start in the future.".into(),
+            SpaceInactive { space_id } => format!("Space ({space_id}) is currently unavailable."),
+            SpaceBlackout {
+                space_id,
+                start,
+                end,
+                reason,
+            } => format!(
+                "Space ({space_id}) is unavailable from {} to {} ({reason}).",
+                format_datetime(start),
+                format_datetime(end)
+            ),
+            OutsideOpeningHours {
+                space_id,
+                weekday,
+                opening_hours,
+                timezone,
+            } => {
+                let weekday = weekday_name(*weekday, Language::En);
+                let description = if opening_hours.is_empty() {
+                    format!("It is closed on {weekday} ({timezone}).")
+                } else {
+                    let ranges = opening_hours
+                        .iter()
+                        .map(|(open, close)| format!("{open}-{close}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("The opening hours on {weekday} are {ranges} ({timezone}).")
+                };
+                format!("The reservation is outside the opening hours of space ({space_id}). {description}")
+            }
+            DurationTooShort { min } => format!(
+                "The reservation must be at least {min} minutes long ({}).",
+                rule(BookingRule::MinDuration)
+            ),
+            DurationTooLong { max } => format!(
+                "The reservation must be at most {max} minutes long ({}).",
+                rule(BookingRule::MaxDuration)
+            ),
+            SlotGranularityMismatch { granularity } => format!(
+                "The start time and length must be in {granularity}-minute steps ({}).",
+                rule(BookingRule::SlotGranularity)
+            ),
+            TooFarInAdvance { days } => format!(
+                "Reservations can be made up to {days} days in advance ({}).",
+                rule(BookingRule::MaxDaysInAdvance)
+            ),
+            TooManyActiveReservations { max } => format!(
+                "You can hold at most {max} reservations for this space at a time ({}).",
+                rule(BookingRule::MaxActiveReservations)
+            ),
+            TooManyReservationsPerWeek { max } => format!(
+                "You can make at most {max} reservations for this space per week ({}).",
+                rule(BookingRule::MaxReservationsPerWeek)
+            ),
+            TooManyRecentNoShows { days, max } => format!(
+                "You missed check-in for {max} or more reservations in the last {days} days, so this space cannot be reserved ({}).",
+                rule(BookingRule::MaxRecentNoShows)
+            ),
+
+            ReservationOverlap { space_id } => {
+                format!("Space ({space_id}) is already reserved during this time.")
+            }
+            ConcurrentOperation => {
+                "The request conflicted with another reservation operation. Please try again."
+                    .into()
+            }
+            AlreadyOnWaitlist => "You are already on the waitlist for this time slot.".into(),
+            OfferedToWaitlist {
+                space_id,
+                offer_expires_at,
+            } => format!(
+                "This time slot of space ({space_id}) is offered to a waitlisted user until {}.",
+                format_datetime(offer_expires_at)
+            ),
+        }
+    }
+}
+
+// ログなどに出すときは既定の言語の文言とする
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(Language::default()))
+    }
+}
+
+// 入力チェックで通らなかった理由
+// garde の文言を解釈して、言語ごとの文言にする
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldErrorMessage {
+    TooShort(i64),
+    TooLong(i64),
+    TooSmall(i64),
+    TooLarge(i64),
+    InvalidEmail,
+    InvalidHttpUrl,
+    // 解釈できなかった文言
+    Invalid,
+}
+
+impl FieldErrorMessage {
+    // 独自の入力チェックで、garde のエラーにする文言
+    pub const INVALID_HTTP_URL: &'static str = "must be an http or https URL";
+
+    pub fn parse(message: &str) -> Self {
+        let number = |rest: &str| rest.trim().parse().ok();
+        if let Some(n) = message
+            .strip_prefix("length is lower than ")
+            .and_then(number)
+        {
+            Self::TooShort(n)
+        } else if let Some(n) = message
+            .strip_prefix("length is greater than ")
+            .and_then(number)
+        {
+            Self::TooLong(n)
+        } else if let Some(n) = message.strip_prefix("lower than ").and_then(number) {
+            Self::TooSmall(n)
+        } else if let Some(n) = message.strip_prefix("greater than ").and_then(number) {
+            Self::TooLarge(n)
+        } else if message.starts_with("not a valid email") {
+            Self::InvalidEmail
+        } else if message == Self::INVALID_HTTP_URL {
+            Self::InvalidHttpUrl
+        } else {
+            Self::Invalid
+        }
+    }
+
+    pub fn localize(self, language: Language) -> String {
+        use FieldErrorMessage::*;
+        match language {
+            Language::Ja => match self {
+                TooShort(n) => format!("長さは {n} 以上で指定してください。"),
+                TooLong(n) => format!("長さは {n} 以下で指定してください。"),
+                TooSmall(n) => format!("{n} 以上の値を指定してください。"),
+                TooLarge(n) => format!("{n} 以下の値を指定してください。"),
+                InvalidEmail => "メールアドレスの形式が正しくありません。".into(),
+                InvalidHttpUrl => "http または https の URL を指定してください。".into(),
+                Invalid => "値が正しくありません。".into(),
+            },
+            Language::En => match self {
+                TooShort(n) => format!("Length must be at least {n}."),
+                TooLong(n) => format!("Length must be at most {n}."),
+                TooSmall(n) => format!("Must be at least {n}."),
+                TooLarge(n) => format!("Must be at most {n}."),
+                InvalidEmail => "Must be a valid email address.".into(),
+                InvalidHttpUrl => "Must be an http or https URL.".into(),
+                Invalid => "The value is invalid.".into(),
+            },
+        }
+    }
+}
+
+fn weekday_name(weekday: Weekday, language: Language) -> &'static str {
+    match language {
+        Language::Ja => match weekday {
+            Weekday::Mon => "月曜日",
+            Weekday::Tue => "火曜日",
+            Weekday::Wed => "水曜日",
+            Weekday::Thu => "木曜日",
+            Weekday::Fri => "金曜日",
+            Weekday::Sat => "土曜日",
+            Weekday::Sun => "日曜日",
+        },
+        Language::En => match weekday {
+            Weekday::Mon => "Monday",
+            Weekday::Tue => "Tuesday",
+            Weekday::Wed => "Wednesday",
+            Weekday::Thu => "Thursday",
+            Weekday::Fri => "Friday",
+            Weekday::Sat => "Saturday",
+            Weekday::Sun => "Sunday",
+        },
+    }
+}
+
+fn format_datetime(at: &DateTime<Local>) -> String {
+    at.format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_error_message() {
+        let message = ErrorMessage::OutsideOpeningHours {
+            space_id: "space1".into(),
+            weekday: Weekday::Mon,
+            opening_hours: vec![("09:00".into(), "18:00".into())],
+            timezone: "Asia/Tokyo".into(),
+        };
+        assert_eq!(
+            message.localize(Language::Ja),
+            "スペース（space1）の営業時間外です。月曜日の営業時間は 09:00〜18:00 です（Asia/Tokyo）。"
+        );
+        assert_eq!(
+            message.localize(Language::En),
+            "The reservation is outside the opening hours of space (space1). The opening hours on Monday are 09:00-18:00 (Asia/Tokyo)."
+        );
+        assert_eq!(message.to_string(), message.localize(Language::Ja));
+    }
+
+    #[test]
+    fn test_field_error_message() {
+        assert_eq!(
+            FieldErrorMessage::parse("length is lower than 1"),
+            FieldErrorMessage::TooShort(1)
+        );
+        assert_eq!(
+            FieldErrorMessage::parse("greater than 100"),
+            FieldErrorMessage::TooLarge(100)
+        );
+        assert_eq!(
+            FieldErrorMessage::parse(FieldErrorMessage::INVALID_HTTP_URL),
+            FieldErrorMessage::InvalidHttpUrl
+        );
+        assert_eq!(
+            FieldErrorMessage::parse("something else"),
+            FieldErrorMessage::Invalid
+        );
+        assert_eq!(
+            FieldErrorMessage::TooShort(1).localize(Language::Ja),
+            "長さは 1 以上で指定してください。"
+        );
+    }
+}
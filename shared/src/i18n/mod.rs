@@ -0,0 +1,116 @@
+use crate::error::ErrorCode;
+use std::future::Future;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+mod message;
+pub use message::{BookingRule, ErrorMessage, FieldErrorMessage};
+
+// API のレスポンスと通知メールで使う言語
+// 言語を追加する場合は、ここに追加したうえで、各カタログに文言を追加する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumString, AsRefStr, EnumIter)]
+#[strum(serialize_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Language {
+    // Accept-Language ヘッダの値から、対応している言語のうち最も優先度の高いものを選ぶ
+    // 対応している言語がない場合は既定の言語（日本語）とする
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut best: Option<(Self, f32)> = None;
+        for item in accept_language.split(',') {
+            let mut parts = item.split(';').map(str::trim);
+            let tag = parts.next().unwrap_or_default();
+            let quality = parts
+                .find_map(|p| p.strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            // en-US のような地域付きの指定は、主言語（en）で判定する
+            let primary = tag.split('-').next().unwrap_or_default().to_ascii_lowercase();
+            let Ok(language) = primary.parse::<Self>() else {
+                continue;
+            };
+            if quality > 0.0 && best.map_or(true, |(_, q)| quality > q) {
+                best = Some((language, quality));
+            }
+        }
+        best.map(|(language, _)| language).unwrap_or_default()
+    }
+}
+
+tokio::task_local! {
+    static LANGUAGE: Language;
+}
+
+// リクエストの処理中は、エラーのレスポンスなどからリクエストの言語を参照できるようにする
+pub async fn scope<F: Future>(language: Language, f: F) -> F::Output {
+    LANGUAGE.scope(language, f).await
+}
+
+// 処理中のリクエストの言語。リクエストの処理外から呼んだ場合は既定の言語
+pub fn current() -> Language {
+    LANGUAGE.try_with(|language| *language).unwrap_or_default()
+}
+
+// エラーコードごとの文言のカタログ
+pub fn error_message(code: ErrorCode, language: Language) -> &'static str {
+    use ErrorCode::*;
+    match language {
+        Language::Ja => match code {
+            ValidationFailed => "入力内容に誤りがあります。",
+            InvalidId => "ID の形式が正しくありません。",
+            UnprocessableEntity => "リクエストの内容では処理できません。",
+            NotFound => "指定したデータが見つかりません。",
+            ReservationConflict => "他の予約と時間帯が重なっています。",
+            ReservationStartInPast => "予約開始時刻は現在時刻より後である必要があります。",
+            InvalidReservationPeriod => "予約開始時刻は予約終了時刻より前である必要があります。",
+            ReservationAlreadyStarted => "予約はすでに開始しているため変更できません。",
+            SpaceInactive => "スペースは現在利用できません。",
+            OutsideOpeningHours => "スペースの営業時間外です。",
+            SpaceBlackout => "スペースはこの時間帯に利用できません。",
+            BookingPolicyViolation => "スペースの予約のルールを満たしていません。",
+            Unauthenticated => "ログインに失敗しました",
+            Unauthorized => "認可情報が誤っています",
+            Forbidden => "許可されていない操作です",
+            InternalError => "サーバー内部でエラーが発生しました。",
+        },
+        Language::En => match code {
+            ValidationFailed => "Some of the input values are invalid.",
+            InvalidId => "The ID is not in a valid format.",
+            UnprocessableEntity => "The request could not be processed.",
+            NotFound => "The requested resource was not found.",
+            ReservationConflict => "The time slot overlaps with another reservation.",
+            ReservationStartInPast => "The reservation must start in the future.",
+            InvalidReservationPeriod => "The reservation must start before it ends.",
+            ReservationAlreadyStarted => {
+                "The reservation has already started and can no longer be changed."
+            }
+            SpaceInactive => "The space is currently unavailable.",
+            OutsideOpeningHours => "The reservation is outside the space's opening hours.",
+            SpaceBlackout => "The space is unavailable during this time.",
+            BookingPolicyViolation => "The reservation does not meet the space's booking rules.",
+            Unauthenticated => "Login failed.",
+            Unauthorized => "The credentials are invalid.",
+            Forbidden => "You are not allowed to perform this operation.",
+            InternalError => "An internal server error occurred.",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Language::negotiate("en-US,en;q=0.9,ja;q=0.8"), Language::En);
+        assert_eq!(Language::negotiate("ja,en;q=0.5"), Language::Ja);
+        assert_eq!(Language::negotiate("fr-FR, en;q=0.3"), Language::En);
+        assert_eq!(Language::negotiate("en;q=0.2, ja;q=0.7"), Language::Ja);
+        assert_eq!(Language::negotiate("en;q=0"), Language::Ja);
+        assert_eq!(Language::negotiate("fr"), Language::Ja);
+        assert_eq!(Language::negotiate(""), Language::Ja);
+    }
+}
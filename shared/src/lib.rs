@@ -1,3 +1,5 @@
 pub mod config;
 pub mod env;
 pub mod error;
+pub mod request_id;
+pub mod i18n;
@@ -0,0 +1,19 @@
+use std::future::Future;
+
+// リクエストごとに振る ID のヘッダ名
+// クライアントが指定した場合はその値を、指定しない場合はサーバーで振った値を使う
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+// リクエストの処理中は、エラーのレスポンスなどからリクエスト ID を参照できるようにする
+pub async fn scope<F: Future>(request_id: String, f: F) -> F::Output {
+    REQUEST_ID.scope(request_id, f).await
+}
+
+// 処理中のリクエストの ID。リクエストの処理外から呼んだ場合は None
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
@@ -1,31 +1,21 @@
 use adapter::{database::connect_database_with,redis::RedisClient};
-use anyhow::{Error, Result};
-use adapter::{
-    database::{
-        ConnectionPool,
-    },
+use adapter::scheduler::{
+    outbox::OutboxDispatcher, reminder::ReminderScheduler, watcher::ReservationEndWatcher,
+    webhook::WebhookDispatcher,
 };
-use api::handler::reservation::{return_space};
+use anyhow::Result;
+use api::middleware::{accept_language, request_id};
 use api::route::{
     v1,
     auth};
 use axum::{Router,http::Method};
 use registry::AppRegistry;
 use shared::config::AppConfig;
-use std::error::Error as StdError;
 use std::{
     net::{Ipv4Addr, SocketAddr},
     sync::Arc};
 use tokio::net::TcpListener;
 
-use base64::{engine::general_purpose, Engine as _};
-use chrono::{DateTime, Duration as ChronoDuration, Local};
-use reqwest::Client;
-use sqlx::{postgres::PgPoolOptions, FromRow, PgPool};
-use std::{collections::HashSet, time::Duration};
-use tokio::time::sleep;
-use uuid::Uuid;
-use yup_oauth2::{InstalledFlowAuthenticator, InstalledFlowReturnMethod};
 
 use shared::env::{which, Environment};
 use tracing_subscriber::layer::SubscriberExt;
@@ -40,22 +30,6 @@ use tower_http::cors::{self, CorsLayer};
 
 
 
-#[derive(Debug, FromRow)]
-struct Space {
-    reservation_id: Uuid,
-    is_active: bool,
-    space_id: Uuid,
-    user_id: Uuid,
-    reminder_is_already: bool,
-    reservation_start_time: DateTime<Local>,
-    reservation_end_time: DateTime<Local>,
-    reserved_at: DateTime<Local>,
-    reminder_at: DateTime<Local>,
-    email: String,
-    user_name: String,
-    space_name: String,
-}
-
 // cors 関数を追加
 fn cors() -> CorsLayer {
     CorsLayer::new()
@@ -70,184 +44,6 @@ async fn main() -> Result<()> {
     bootstrap().await
 }
 
-pub async fn reminder_loop(pool: ConnectionPool) -> Result<(), Box<dyn StdError>> {
-    // --------------------------
-    // Gmail OAuth2 認証
-    // --------------------------
-    let secret_path = "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/client_secret_483730081753-qm9ujsmkcgfpag17j2iv618fspsjpgou.apps.googleusercontent.com.json";
-    let token_path = "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/token.json";
-
-    let secret = yup_oauth2::read_application_secret(secret_path).await?;
-    let auth = InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::Interactive)
-        .persist_tokens_to_disk(token_path)
-        .build()
-        .await?;
-
-    let token = auth
-        .token(&["https://www.googleapis.com/auth/gmail.send"])
-        .await?;
-    let access_token = token.token().unwrap().to_string();
-
-    // let pool = PgPoolOptions::new()
-    //     .connect("postgresql://localhost:5432/app?user=app&password=passwd")
-    //     .await?;
-
-    
-
-    loop {
-        println!("Polling database at: {}", Local::now());
-
-        let rows =
-            sqlx::query_as::<_, Space>("SELECT 
-            r.reservation_id AS reservation_id,
-                    r.space_id AS space_id,
-                    s.is_active AS is_active,
-                    r.reminder_is_already AS reminder_is_already,
-                    r.reserved_at AS reserved_at,
-                    r.reminder_at AS reminder_at,
-                    r.reservation_start_time AS reservation_start_time,
-                    r.reservation_end_time AS reservation_end_time,
-                    u.user_id AS user_id,
-                    u.email AS email,
-                    u.user_name AS user_name,
-                    s.space_name AS space_name
-                FROM reservations AS r
-                INNER JOIN users AS u 
-                ON r.user_id = u.user_id
-                INNER JOIN spaces AS s 
-                ON r.space_id = s.space_id;
-                ")
-                .fetch_all(pool.inner_ref())
-                .await?;
-
-        let now = Local::now();
-
-        for row in rows {
-            // すでに実行済みならスキップ
-
-
-            if row.reminder_is_already == true || row.is_active == false{
-                continue;
-            }
-            
-
-            // let target_time = row.created_at + ChronoDuration::minutes(5);
-
-            if now >= row.reminder_at {
-                // 実行
-                send_gmail(
-                    &access_token, 
-                    row.space_id, 
-                    row.reminder_at,
-                    row.space_name,
-                    row.user_name,
-                    row.email,
-                    row.reservation_start_time,
-                    row.reservation_end_time,
-                ).await;
-
-                // DB を更新（reminder_is_already = true）
-            if let Err(err) = pool.mark_reminder_as_done(row.reservation_id).await {
-                eprintln!("Failed to update reminder flag: {:?}", err);
-            }
-
-
-            }
-        }
-
-        // 10秒待機
-        sleep(Duration::from_secs(30)).await;
-    }
-    // Ok(()) は到達しないが、Rust 的には必要
-    #[allow(unreachable_code)]
-    Ok(())
-}
-
-pub async fn reservation_watcher_loop(pool: ConnectionPool) -> Result<(), Box<dyn StdError>> {
-    loop {
-        println!("[EndWatcher] Polling database at: {}", Local::now());
-
-        let mut tx = pool.begin().await?;
-
-
-        let rows = sqlx::query_as::<_, Space>(
-            r#"
-            SELECT
-                r.reservation_id AS reservation_id,
-                r.space_id AS space_id,
-                s.is_active AS is_active,
-                r.reminder_is_already AS reminder_is_already,
-                r.reserved_at AS reserved_at,
-                r.reminder_at AS reminder_at,
-                r.reservation_start_time AS reservation_start_time,
-                r.reservation_end_time AS reservation_end_time,
-                u.user_id AS user_id,
-                u.email AS email,
-                u.user_name AS user_name,
-                s.space_name AS space_name
-            FROM reservations AS r
-            INNER JOIN users AS u 
-                ON r.user_id = u.user_id
-            INNER JOIN spaces AS s 
-                ON r.space_id = s.space_id
-            FOR UPDATE SKIP LOCKED;
-            "#
-        )
-        .fetch_all(pool.inner_ref())
-        .await?;
-
-        let now = Local::now();
-
-        for row in rows {
-            if now >= row.reservation_end_time {
-                println!(
-                    "Reservation {} has ended. Moving to returned_reservations...",
-                    row.reservation_id
-                );
-
-                // returned_reservations に追加
-                sqlx::query(
-                    r#"
-                    INSERT INTO returned_reservations
-                        (reservation_id, 
-                        user_id, 
-                        space_id, 
-                        is_cancel,
-                        reminder_is_already,
-                        reservation_start_time,
-                        reservation_end_time,
-                        reserved_at,
-                        returned_at,
-                        reminder_at
-                        )
-                    VALUES ($1, $2, $3,false, $4, $5,$6,$7, NOW(),$8)
-                    "#,
-                )
-                .bind(row.reservation_id)
-                .bind(row.user_id)
-                .bind(row.space_id)
-                .bind(row.reminder_is_already)
-                .bind(row.reservation_start_time)
-                .bind(row.reservation_end_time)
-                .bind(row.reserved_at)
-                .bind(row.reminder_at)
-                .execute(pool.inner_ref())
-                .await?;
-
-                // reservations から削除（または update is_active = false）
-                sqlx::query("DELETE FROM reservations WHERE reservation_id = $1")
-                    .bind(row.reservation_id)
-                    .execute(pool.inner_ref())
-                    .await?;
-
-                println!("Reservation {} moved to returned_reservations.", row.reservation_id);
-            }
-        }
-
-        sleep(Duration::from_secs(30)).await;
-    }
-}
-
 fn init_logger() -> anyhow::Result<()> {
     let log_level = match which() {
         Environment::Development => "debug",
@@ -275,31 +71,45 @@ async fn bootstrap() -> Result<()> {
     let pool = connect_database_with(&app_config.database);
     let kv = Arc::new(RedisClient::new(&app_config.redis)?);
 
-    
-    // プールとトークンを clone
-    let pg_pool_for_loop = pool.clone();
-
-    tokio::spawn(async move {
-        if let Err(e) = reminder_loop(pg_pool_for_loop).await {
-            eprintln!("reminder_loop error: {:?}", e);
-        }
-    });
-
-    // reservation_end_time の監視ループ
-    let pg_pool_for_endwatcher = pool.clone();
-    tokio::spawn(async move {
-        if let Err(e) = reservation_watcher_loop(pg_pool_for_endwatcher).await {
-            eprintln!("reservation_watcher_loop error: {:?}", e);
-        }
-    });
-
-    let registry = AppRegistry::new(pool, kv, app_config);
-
+    let outbox_config = app_config.outbox;
+    let reminder_config = app_config.reminder;
+    let watcher_config = app_config.watcher;
+    let webhook_config = app_config.webhook;
+    let registry = AppRegistry::new(pool.clone(), kv, app_config).await?;
+
+    // outbox に積まれた通知を送信する dispatcher
+    let dispatcher = OutboxDispatcher::new(
+        registry.outbox_repository(),
+        registry.notifier(),
+        outbox_config,
+    );
+    tokio::spawn(dispatcher.run());
+
+    // 送信時刻を迎えたリマインダーを outbox に積む scheduler
+    let reminder_scheduler =
+        ReminderScheduler::new(registry.reminder_repository(), reminder_config);
+    tokio::spawn(reminder_scheduler.run());
+
+    // 終了時刻を過ぎた予約を利用終了にする watcher
+    let end_watcher =
+        ReservationEndWatcher::new(registry.reservation_repository(), watcher_config);
+    tokio::spawn(end_watcher.run());
+
+    // 積まれた webhook を送信する dispatcher
+    let webhook_dispatcher = WebhookDispatcher::new(
+        registry.webhook_repository(),
+        registry.webhook_sender(),
+        webhook_config,
+    );
+    tokio::spawn(webhook_dispatcher.run());
 
 
     let app = Router::new()
         .merge(v1::routes())
         .merge(auth::routes())
+        // エラーのレスポンスからリクエストを追えるよう、リクエストごとに ID を振る
+        .layer(axum::middleware::from_fn(request_id))
+        .layer(axum::middleware::from_fn(accept_language))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
@@ -325,75 +135,3 @@ async fn bootstrap() -> Result<()> {
             )
         })
 }
-
-// ----------------------------------------------
-// Gmail送信処理
-// ----------------------------------------------
-async fn send_gmail(
-    access_token: &str,
-    space_id: Uuid,
-    reminder_at: DateTime<Local>,
-    space_name: String,
-    user_name: String,
-    email: String,
-    reservation_start_time: DateTime<Local>,
-    reservation_end_time: DateTime<Local>,
-) -> Result<(), Box<dyn StdError>> {
-    println!(
-        ">>> [ACTION] Triggered for space_id={} at {}",
-        space_id,
-        Local::now()
-    );
-    // let to = "horikawa0107tokyo@gmail.com";
-    let subject = format!("remind mail");
-    let body_text = format!(
-        "{}さん {} の予約の1時間前です。リマインダー時刻：{}予約時間：{} 〜{}",
-        user_name,
-        space_name,
-        reminder_at,
-        reservation_start_time,
-        reservation_end_time
-    );
-
-    let message_str = format!(
-        "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=UTF-8\r\n\r\n{}",
-        email, subject, body_text
-    );
-
-    let encoded_message = general_purpose::URL_SAFE_NO_PAD.encode(message_str.as_bytes());
-
-    let url = "https://gmail.googleapis.com/gmail/v1/users/me/messages/send";
-    let client = Client::new();
-
-    let res = client
-        .post(url)
-        .bearer_auth(access_token)
-        .json(&serde_json::json!({ "raw": encoded_message }))
-        .send()
-        .await?;
-
-    if res.status().is_success() {
-        println!("✅ Gmail 送信成功");
-    } else {
-        eprintln!("❌ Gmail送信失敗: {}", res.text().await?);
-    }
-
-    Ok(())
-}
-
-
-async fn get_gmail_access_token() -> Result<String, Box<dyn std::error::Error>> {
-    let secret_path = "/Users/horikawafuka2/Documents/class_2025/sp/test_gmail/client_secret.json";
-    let token_path = "/Users/horikawafuka2/Documents/class_2025/sp/sp2025_dev/token.json";
-
-    let secret = yup_oauth2::read_application_secret(secret_path).await?;
-    let auth = InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::Interactive)
-        .persist_tokens_to_disk(token_path)
-        .build()
-        .await?;
-
-    let token = auth
-        .token(&["https://www.googleapis.com/auth/gmail.send"])
-        .await?;
-    Ok(token.token().unwrap().to_string())
-}
\ No newline at end of file